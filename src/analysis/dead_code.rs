@@ -0,0 +1,192 @@
+use crate::code_graph::{CodeGraph, CodeNode, NodeType, RelationshipType};
+use log::{debug, info};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+// Which nodes count as entry points when looking for dead code
+#[derive(Debug, Clone)]
+pub struct EntryPointRules {
+    // Functions named `main`
+    pub main: bool,
+    // Nodes whose `visibility` metadata is public
+    pub public: bool,
+    // Test functions and anything defined in test files
+    pub tests: bool,
+    // Functions that look like HTTP or event handlers
+    pub handlers: bool,
+    // Additional node IDs to treat as entry points
+    pub extra: Vec<String>,
+}
+
+impl Default for EntryPointRules {
+    fn default() -> Self {
+        EntryPointRules {
+            main: true,
+            public: true,
+            tests: true,
+            handlers: true,
+            extra: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadSymbol {
+    pub id: String,
+    pub name: String,
+    pub node_type: NodeType,
+    pub line_range: (usize, usize),
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeadCodeReport {
+    pub entry_points: usize,
+    pub reachable: usize,
+    pub unreachable: usize,
+    // Unreachable symbols grouped by file, ordered by line
+    pub files: BTreeMap<String, Vec<DeadSymbol>>,
+}
+
+// Path fragments of files that only hold tests
+const TEST_PATH_MARKERS: &[&str] = &[
+    "/tests/", "/test/", "/spec/", "_test.", ".test.", ".spec.", "/test_",
+];
+
+// Decorators and annotations that register a function as a route handler
+const HANDLER_MARKERS: &[&str] = &[
+    "@app.",
+    "@router.",
+    "@blueprint.",
+    "@GetMapping",
+    "@PostMapping",
+    "@PutMapping",
+    "@DeleteMapping",
+    "@RequestMapping",
+    "#[get(",
+    "#[post(",
+    "#[put(",
+    "#[delete(",
+    "#[route(",
+];
+
+// Prototypes are never called themselves; their definitions are
+fn is_code(node: &CodeNode) -> bool {
+    matches!(node.node_type, NodeType::Function | NodeType::Method)
+        && !node.metadata.contains_key("declaration")
+}
+
+fn bare_name(node: &CodeNode) -> &str {
+    node.name.rsplit("::").next().unwrap_or(&node.name)
+}
+
+// Heuristic: test naming conventions or a file that only holds tests
+pub fn is_test(node: &CodeNode) -> bool {
+    let name = bare_name(node);
+    let path = format!("/{}", node.file_path.replace('\\', "/"));
+    name.starts_with("test_")
+        || (name.starts_with("Test") && node.file_path.ends_with("_test.go"))
+        || node.metadata.contains_key("test")
+        || TEST_PATH_MARKERS.iter().any(|marker| path.contains(marker))
+}
+
+fn is_handler(node: &CodeNode) -> bool {
+    let name = bare_name(node).to_lowercase();
+    name.starts_with("handle")
+        || name.ends_with("handler")
+        || name.starts_with("on_")
+        || HANDLER_MARKERS
+            .iter()
+            .any(|marker| node.content.contains(marker))
+}
+
+impl EntryPointRules {
+    pub fn is_entry_point(&self, node: &CodeNode) -> bool {
+        (self.main && bare_name(node) == "main" && node.node_type == NodeType::Function)
+            || (self.public && node.is_public())
+            || (self.tests && is_test(node))
+            || (self.handlers && is_code(node) && is_handler(node))
+            || self.extra.contains(&node.id)
+    }
+}
+
+// Walk Calls and Contains edges from every entry point, set `reachable`
+// metadata on functions and methods and report the ones never reached.
+// Functions nested in a reachable function, such as callbacks passed as
+// arguments, are reachable with it.
+pub fn find_dead_code(graph: &mut CodeGraph, rules: &EntryPointRules) -> DeadCodeReport {
+    info!("Looking for unreachable code...");
+
+    let entry_points: Vec<&str> = graph
+        .all_nodes()
+        .filter(|node| rules.is_entry_point(node))
+        .map(|node| node.id.as_str())
+        .collect();
+    debug!("Found {} entry points", entry_points.len());
+
+    let mut report = DeadCodeReport {
+        entry_points: entry_points.len(),
+        ..Default::default()
+    };
+
+    let mut reachable: HashSet<&str> = entry_points.iter().copied().collect();
+    let mut to_visit = entry_points;
+    while let Some(id) = to_visit.pop() {
+        if let Some(node) = graph.get_node(id).filter(|node| is_code(node)) {
+            let (start, end) = node.line_range;
+            for nested in graph.find_nodes_overlapping(&node.file_path, start, end) {
+                if is_code(nested)
+                    && nested.line_range.0 >= start
+                    && nested.line_range.1 <= end
+                    && reachable.insert(&nested.id)
+                {
+                    to_visit.push(&nested.id);
+                }
+            }
+        }
+        for rel in graph.outgoing_relationships(id) {
+            if matches!(
+                rel.relationship_type,
+                RelationshipType::Calls | RelationshipType::Contains
+            ) && reachable.insert(&rel.to_id)
+            {
+                to_visit.push(&rel.to_id);
+            }
+        }
+    }
+
+    let reachable: HashSet<String> = reachable.into_iter().map(str::to_string).collect();
+    for node in graph.all_nodes_mut().filter(|node| is_code(node)) {
+        let is_reachable = reachable.contains(&node.id);
+        node.add_metadata("reachable".to_string(), is_reachable.to_string());
+
+        if is_reachable {
+            report.reachable += 1;
+            continue;
+        }
+
+        report.unreachable += 1;
+        report
+            .files
+            .entry(node.file_path.clone())
+            .or_default()
+            .push(DeadSymbol {
+                id: node.id.clone(),
+                name: node.name.clone(),
+                node_type: node.node_type.clone(),
+                line_range: node.line_range,
+            });
+    }
+
+    for symbols in report.files.values_mut() {
+        symbols.sort_by_key(|symbol| symbol.line_range);
+    }
+
+    info!(
+        "{} of {} functions and methods are unreachable from {} entry points",
+        report.unreachable,
+        report.reachable + report.unreachable,
+        report.entry_points
+    );
+
+    report
+}
@@ -0,0 +1,209 @@
+use crate::code_graph::{
+    CodeGraph, CodeNode, NodeType, Relationship, RelationshipType, provenance,
+};
+use log::{debug, info};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+
+// Tokens hashed together into one shingle
+const SHINGLE_SIZE: usize = 5;
+// Shingles per winnowing window; one fingerprint is kept from each window
+const WINDOW_SIZE: usize = 4;
+// Fingerprints shared by more functions than this are boilerplate and are
+// not used to find candidate pairs
+const MAX_FINGERPRINT_FUNCTIONS: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct DuplicateOptions {
+    // Share of fingerprints two functions must have in common (0 to 1)
+    pub threshold: f64,
+    // Functions with fewer tokens than this are too small to report
+    pub min_tokens: usize,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        DuplicateOptions {
+            threshold: 0.8,
+            min_tokens: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateSymbol {
+    pub id: String,
+    pub name: String,
+    pub file_path: String,
+    pub line_range: (usize, usize),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicatePair {
+    pub duplicate: DuplicateSymbol,
+    pub original: DuplicateSymbol,
+    pub similarity: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DuplicateReport {
+    // Functions and methods large enough to be compared
+    pub compared: usize,
+    // Most similar pairs first
+    pub pairs: Vec<DuplicatePair>,
+}
+
+// Tokens of a function body with whitespace and comments dropped, and
+// literals replaced by placeholders so copies that only differ in constants
+// still match
+fn normalized_tokens(content: &str, token_regex: &Regex) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            !["//", "#", "--", "/*", "*", "%"]
+                .iter()
+                .any(|comment| line.starts_with(comment))
+        })
+        .flat_map(|line| token_regex.find_iter(line))
+        .map(|token| {
+            let token = token.as_str();
+            if token.starts_with('"') || token.starts_with('\'') {
+                "STR".to_string()
+            } else if token.starts_with(|c: char| c.is_ascii_digit()) {
+                "NUM".to_string()
+            } else {
+                token.to_string()
+            }
+        })
+        .collect()
+}
+
+// Winnowing: hash every run of SHINGLE_SIZE tokens and keep the smallest
+// hash of each window of WINDOW_SIZE shingles
+fn fingerprints(tokens: &[String]) -> BTreeSet<u64> {
+    let shingles: Vec<u64> = tokens
+        .windows(SHINGLE_SIZE)
+        .map(|shingle| {
+            let mut hasher = DefaultHasher::new();
+            shingle.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+
+    if shingles.len() < WINDOW_SIZE {
+        return shingles.into_iter().collect();
+    }
+    shingles
+        .windows(WINDOW_SIZE)
+        .filter_map(|window| window.iter().min().copied())
+        .collect()
+}
+
+fn symbol(node: &CodeNode) -> DuplicateSymbol {
+    DuplicateSymbol {
+        id: node.id.clone(),
+        name: node.name.clone(),
+        file_path: node.file_path.clone(),
+        line_range: node.line_range,
+    }
+}
+
+// Compare the fingerprints of every function and method body, adding a
+// DuplicateOf edge from each later copy to the earlier one (by file and
+// line) when they are at least `threshold` similar
+pub fn find_duplicates(graph: &mut CodeGraph, options: &DuplicateOptions) -> DuplicateReport {
+    info!("Looking for duplicated functions...");
+
+    // String literals, identifiers, numbers and single punctuation characters
+    let token_regex =
+        Regex::new(r#""(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'|[A-Za-z_][A-Za-z0-9_]*|\d[\w.]*|\S"#)
+            .expect("valid token pattern");
+
+    let mut functions: Vec<(&CodeNode, BTreeSet<u64>)> = graph
+        .all_nodes()
+        .filter(|node| matches!(node.node_type, NodeType::Function | NodeType::Method))
+        .filter_map(|node| {
+            let tokens = normalized_tokens(&node.content, &token_regex);
+            (tokens.len() >= options.min_tokens).then(|| (node, fingerprints(&tokens)))
+        })
+        .collect();
+    functions.sort_by(|(a, _), (b, _)| {
+        (&a.file_path, a.line_range, &a.id).cmp(&(&b.file_path, b.line_range, &b.id))
+    });
+
+    let mut functions_by_fingerprint: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (index, (_, prints)) in functions.iter().enumerate() {
+        for print in prints {
+            functions_by_fingerprint
+                .entry(*print)
+                .or_default()
+                .push(index);
+        }
+    }
+
+    // Fingerprints shared by each candidate pair, earlier function first
+    let mut shared: HashMap<(usize, usize), usize> = HashMap::new();
+    for indices in functions_by_fingerprint.values() {
+        if indices.len() > MAX_FINGERPRINT_FUNCTIONS {
+            continue;
+        }
+        for (position, &first) in indices.iter().enumerate() {
+            for &second in &indices[position + 1..] {
+                *shared.entry((first, second)).or_default() += 1;
+            }
+        }
+    }
+    debug!(
+        "Comparing {} candidate pairs of {} functions",
+        shared.len(),
+        functions.len()
+    );
+
+    let mut report = DuplicateReport {
+        compared: functions.len(),
+        ..Default::default()
+    };
+    for ((original, duplicate), count) in shared {
+        let (original, original_prints) = &functions[original];
+        let (duplicate, duplicate_prints) = &functions[duplicate];
+        let similarity =
+            count as f64 / (original_prints.len() + duplicate_prints.len() - count) as f64;
+        if similarity >= options.threshold {
+            report.pairs.push(DuplicatePair {
+                duplicate: symbol(duplicate),
+                original: symbol(original),
+                similarity,
+            });
+        }
+    }
+    report.pairs.sort_by(|a, b| {
+        b.similarity
+            .total_cmp(&a.similarity)
+            .then_with(|| a.duplicate.id.cmp(&b.duplicate.id))
+            .then_with(|| a.original.id.cmp(&b.original.id))
+    });
+
+    for pair in &report.pairs {
+        graph.add_relationship(
+            Relationship::new(
+                RelationshipType::DuplicateOf,
+                pair.duplicate.id.clone(),
+                pair.original.id.clone(),
+            )
+            .with_metadata("similarity".to_string(), format!("{:.2}", pair.similarity))
+            .with_provenance(provenance::DUPLICATE_CODE),
+        );
+    }
+
+    info!(
+        "Found {} duplicated pairs among {} functions and methods",
+        report.pairs.len(),
+        report.compared
+    );
+
+    report
+}
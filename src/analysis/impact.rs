@@ -0,0 +1,33 @@
+use crate::code_graph::{CodeGraph, CodeNode};
+use crate::utils::diff::ChangedLines;
+use std::collections::HashSet;
+
+// Nodes whose line ranges intersect the changed lines. Diff paths are
+// repository-relative, so they match graph files that end with them.
+pub fn changed_nodes<'a>(graph: &'a CodeGraph, changes: &[ChangedLines]) -> Vec<&'a CodeNode> {
+    let files: Vec<&str> = graph.files().collect();
+    let mut seen = HashSet::new();
+    let mut nodes = Vec::new();
+
+    for change in changes {
+        for file in files.iter().filter(|file| same_file(file, &change.path)) {
+            for node in graph.find_nodes_overlapping(file, change.start, change.end) {
+                if seen.insert(node.id.as_str()) {
+                    nodes.push(node);
+                }
+            }
+        }
+    }
+
+    nodes.sort_by(|a, b| (&a.file_path, a.line_range).cmp(&(&b.file_path, b.line_range)));
+    nodes
+}
+
+fn same_file(graph_path: &str, diff_path: &str) -> bool {
+    let graph_path = graph_path.replace('\\', "/");
+    let graph_path = graph_path.trim_start_matches("./");
+    graph_path == diff_path
+        || graph_path
+            .strip_suffix(diff_path)
+            .is_some_and(|prefix| prefix.ends_with('/'))
+}
@@ -0,0 +1,160 @@
+use crate::code_graph::{
+    CodeGraph, CodeNode, NodeType, Relationship, RelationshipType, provenance,
+};
+use log::{debug, info};
+use std::collections::{HashMap, HashSet};
+
+// PageRank parameters; scores usually settle well before the iteration cap
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-9;
+
+// Calls edges between symbols, leaving out the ones the directory hierarchy
+// rolls up between files and directories
+fn is_symbol_call(rel: &Relationship) -> bool {
+    rel.relationship_type == RelationshipType::Calls
+        && rel.provenance.as_deref() != Some(provenance::DIRECTORY_HIERARCHY)
+}
+
+// Distinct callers and callees of every node that takes part in a call
+fn call_degrees(graph: &CodeGraph) -> HashMap<&str, (usize, usize)> {
+    let mut callers: HashMap<&str, HashSet<&str>> = HashMap::new();
+    let mut callees: HashMap<&str, HashSet<&str>> = HashMap::new();
+
+    for rel in graph.all_relationships() {
+        if is_symbol_call(rel) && rel.from_id != rel.to_id {
+            callers.entry(&rel.to_id).or_default().insert(&rel.from_id);
+            callees.entry(&rel.from_id).or_default().insert(&rel.to_id);
+        }
+    }
+
+    graph
+        .all_nodes()
+        .map(|node| {
+            let fan_in = callers.get(node.id.as_str()).map_or(0, |ids| ids.len());
+            let fan_out = callees.get(node.id.as_str()).map_or(0, |ids| ids.len());
+            (node.id.as_str(), (fan_in, fan_out))
+        })
+        .collect()
+}
+
+// PageRank over Calls edges weighted by their call count: a function scores
+// high when it is called by other high scoring functions. Scores sum to 1
+// across the graph.
+pub fn pagerank(graph: &CodeGraph) -> HashMap<String, f64> {
+    let ids: Vec<&str> = graph.all_nodes().map(|node| node.id.as_str()).collect();
+    let count = ids.len();
+    if count == 0 {
+        return HashMap::new();
+    }
+
+    let index_of: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    // Callees with the number of call sites; a callee called from several
+    // places gets a larger share of the caller's score
+    let mut callees: Vec<Vec<(usize, f64)>> = vec![Vec::new(); count];
+    for rel in graph.all_relationships() {
+        if !is_symbol_call(rel) {
+            continue;
+        }
+        let weight = rel
+            .metadata
+            .get("count")
+            .and_then(|c| c.parse::<f64>().ok())
+            .unwrap_or(1.0);
+        if let (Some(&from), Some(&to)) = (
+            index_of.get(rel.from_id.as_str()),
+            index_of.get(rel.to_id.as_str()),
+        ) && !callees[from].iter().any(|(callee, _)| *callee == to)
+        {
+            callees[from].push((to, weight));
+        }
+    }
+
+    let base = (1.0 - DAMPING) / count as f64;
+    let mut scores = vec![1.0 / count as f64; count];
+
+    for iteration in 0..MAX_ITERATIONS {
+        // Nodes without callees spread their score evenly
+        let dangling: f64 = (0..count)
+            .filter(|&i| callees[i].is_empty())
+            .map(|i| scores[i])
+            .sum();
+        let mut next = vec![base + DAMPING * dangling / count as f64; count];
+
+        for (from, targets) in callees.iter().enumerate() {
+            if targets.is_empty() {
+                continue;
+            }
+            let total_weight: f64 = targets.iter().map(|(_, weight)| weight).sum();
+            for &(to, weight) in targets {
+                next[to] += DAMPING * scores[from] * weight / total_weight;
+            }
+        }
+
+        let delta: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if delta < TOLERANCE {
+            debug!("PageRank converged after {} iterations", iteration + 1);
+            break;
+        }
+    }
+
+    ids.into_iter().map(str::to_string).zip(scores).collect()
+}
+
+// Store `fan_in`, `fan_out` and `pagerank` as metadata on every node
+pub fn compute_call_metrics(graph: &mut CodeGraph) {
+    info!("Computing call graph metrics...");
+
+    let degrees: HashMap<String, (usize, usize)> = call_degrees(graph)
+        .into_iter()
+        .map(|(id, degrees)| (id.to_string(), degrees))
+        .collect();
+    let scores = pagerank(graph);
+
+    for node in graph.all_nodes_mut() {
+        let (fan_in, fan_out) = degrees.get(&node.id).copied().unwrap_or_default();
+        node.add_metadata("fan_in".to_string(), fan_in.to_string());
+        node.add_metadata("fan_out".to_string(), fan_out.to_string());
+        if let Some(score) = scores.get(&node.id) {
+            node.add_metadata("pagerank".to_string(), format!("{:.6}", score));
+        }
+    }
+
+    info!("Call graph metrics complete");
+}
+
+// The `n` nodes with the highest PageRank. When the graph carries stored
+// scores only the nodes that have one are ranked, so File and Directory
+// nodes added after the metrics pass stay out; otherwise scores are computed
+// and only functions and methods are ranked.
+pub fn top_n_by_centrality(graph: &CodeGraph, n: usize) -> Vec<(&CodeNode, f64)> {
+    let stored: Vec<(&CodeNode, f64)> = graph
+        .all_nodes()
+        .filter_map(|node| {
+            node.metadata
+                .get("pagerank")
+                .and_then(|score| score.parse().ok())
+                .map(|score| (node, score))
+        })
+        .collect();
+
+    let mut ranked = if stored.is_empty() {
+        let scores = pagerank(graph);
+        graph
+            .all_nodes()
+            .filter(|node| matches!(node.node_type, NodeType::Function | NodeType::Method))
+            .map(|node| (node, scores.get(&node.id).copied().unwrap_or(0.0)))
+            .collect()
+    } else {
+        stored
+    };
+
+    ranked.sort_by(|(a, a_score), (b, b_score)| {
+        b_score
+            .total_cmp(a_score)
+            .then_with(|| (&a.file_path, a.line_range).cmp(&(&b.file_path, b.line_range)))
+    });
+    ranked.truncate(n);
+    ranked
+}
@@ -0,0 +1,15 @@
+pub mod dead_code;
+pub mod duplicates;
+pub mod impact;
+pub mod metrics;
+pub mod search;
+pub mod secrets;
+pub mod unresolved;
+
+pub use dead_code::{DeadCodeReport, EntryPointRules, find_dead_code, is_test};
+pub use duplicates::{DuplicateOptions, DuplicateReport, find_duplicates};
+pub use impact::changed_nodes;
+pub use metrics::{compute_call_metrics, pagerank, top_n_by_centrality};
+pub use search::{SearchHit, SearchOptions, search};
+pub use secrets::{RedactionReport, RedactionRules, redact_secrets};
+pub use unresolved::{Severity, UnresolvedReport, unresolved_report};
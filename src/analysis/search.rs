@@ -0,0 +1,269 @@
+use crate::analysis::metrics::pagerank;
+use crate::code_graph::embeddings::identifier_words;
+use crate::code_graph::{CodeGraph, CodeNode, NodeType};
+use log::debug;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io;
+
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    pub limit: usize,
+    // Shares of the score from name matches, content matches and PageRank
+    pub name_weight: f64,
+    pub content_weight: f64,
+    pub centrality_weight: f64,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            limit: 20,
+            name_weight: 0.5,
+            content_weight: 0.35,
+            centrality_weight: 0.15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub id: String,
+    pub name: String,
+    pub node_type: NodeType,
+    pub file_path: String,
+    pub line_range: (usize, usize),
+    pub score: f64,
+    // Each signal scaled to 0..1 before weighting
+    pub name_score: f64,
+    pub content_score: f64,
+    pub centrality: f64,
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != *b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+// How well a query term matches one word of a name: exactly, as a prefix,
+// inside it, or within a typo or two
+fn term_similarity(term: &str, word: &str) -> f64 {
+    if term == word {
+        1.0
+    } else if word.starts_with(term) || (term.len() >= 3 && term.starts_with(word)) {
+        0.8
+    } else if word.contains(term) {
+        0.6
+    } else {
+        let longest = term.chars().count().max(word.chars().count());
+        let distance = edit_distance(term, word);
+        if longest >= 4 && distance * 4 <= longest {
+            0.5
+        } else {
+            0.0
+        }
+    }
+}
+
+// Share of the query terms found in the name, each by its best match
+fn name_score(terms: &[String], node: &CodeNode) -> f64 {
+    let words = identifier_words(&node.name);
+    if words.is_empty() || terms.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = terms
+        .iter()
+        .map(|term| {
+            words
+                .iter()
+                .map(|word| term_similarity(term, word))
+                .fold(0.0, f64::max)
+        })
+        .sum();
+    total / terms.len() as f64
+}
+
+// BM25 over the identifier words of node content
+#[cfg(not(feature = "tantivy"))]
+fn content_scores<'a>(
+    nodes: &[&'a CodeNode],
+    terms: &[String],
+) -> io::Result<HashMap<&'a str, f64>> {
+    const K1: f64 = 1.2;
+    const B: f64 = 0.75;
+
+    let documents: Vec<Vec<String>> = nodes
+        .iter()
+        .map(|node| identifier_words(&node.content))
+        .collect();
+    let average_length =
+        documents.iter().map(Vec::len).sum::<usize>() as f64 / documents.len().max(1) as f64;
+
+    let mut scores = HashMap::new();
+    for term in terms {
+        let frequencies: Vec<usize> = documents
+            .iter()
+            .map(|words| words.iter().filter(|word| *word == term).count())
+            .collect();
+        let containing = frequencies.iter().filter(|count| **count > 0).count() as f64;
+        if containing == 0.0 {
+            continue;
+        }
+        let idf = ((nodes.len() as f64 - containing + 0.5) / (containing + 0.5) + 1.0).ln();
+        for ((node, words), frequency) in nodes.iter().zip(&documents).zip(frequencies) {
+            if frequency == 0 {
+                continue;
+            }
+            let frequency = frequency as f64;
+            let length = words.len() as f64 / average_length.max(1.0);
+            let score = idf * frequency * (K1 + 1.0) / (frequency + K1 * (1.0 - B + B * length));
+            *scores.entry(node.id.as_str()).or_insert(0.0) += score;
+        }
+    }
+
+    Ok(scores)
+}
+
+// Tantivy's BM25 over the identifier words of node content, from an index
+// built in memory for the query
+#[cfg(feature = "tantivy")]
+fn content_scores<'a>(
+    nodes: &[&'a CodeNode],
+    terms: &[String],
+) -> io::Result<HashMap<&'a str, f64>> {
+    use tantivy::collector::TopDocs;
+    use tantivy::query::QueryParser;
+    use tantivy::schema::{STORED, Schema, TEXT, Value};
+    use tantivy::{Index, IndexWriter, TantivyDocument, doc};
+
+    // Memory for the indexing threads
+    const WRITER_MEMORY: usize = 50_000_000;
+
+    let mut builder = Schema::builder();
+    let index_field = builder.add_u64_field("index", STORED);
+    let content_field = builder.add_text_field("content", TEXT);
+    let index = Index::create_in_ram(builder.build());
+    let mut writer: IndexWriter = index.writer(WRITER_MEMORY).map_err(io::Error::other)?;
+    for (position, node) in nodes.iter().enumerate() {
+        writer
+            .add_document(doc!(
+                index_field => position as u64,
+                content_field => identifier_words(&node.content).join(" "),
+            ))
+            .map_err(io::Error::other)?;
+    }
+    writer.commit().map_err(io::Error::other)?;
+
+    let searcher = index.reader().map_err(io::Error::other)?.searcher();
+    let (query, _) =
+        QueryParser::for_index(&index, vec![content_field]).parse_query_lenient(&terms.join(" "));
+    let top = searcher
+        .search(&query, &TopDocs::with_limit(nodes.len().max(1)))
+        .map_err(io::Error::other)?;
+
+    let mut scores = HashMap::new();
+    for (score, address) in top {
+        let document: TantivyDocument = searcher.doc(address).map_err(io::Error::other)?;
+        if let Some(position) = document.get_first(index_field).and_then(|v| v.as_u64()) {
+            scores.insert(nodes[position as usize].id.as_str(), score as f64);
+        }
+    }
+
+    Ok(scores)
+}
+
+// Rank nodes for a free-text query by how well their names match the query
+// terms (allowing prefixes and typos), how relevant their content is, and
+// their PageRank, so central code comes first among similar matches. Only
+// nodes matching by name or content are returned, best first.
+pub fn search(
+    graph: &CodeGraph,
+    query: &str,
+    options: &SearchOptions,
+) -> io::Result<Vec<SearchHit>> {
+    let terms = identifier_words(query);
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut nodes: Vec<&CodeNode> = graph
+        .all_nodes()
+        .filter(|node| {
+            !matches!(
+                node.node_type,
+                NodeType::File | NodeType::Directory | NodeType::ExternalSymbol
+            )
+        })
+        .collect();
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let content = content_scores(&nodes, &terms)?;
+    let max_content = content.values().copied().fold(0.0, f64::max);
+
+    // Stored scores from the metrics pass are used when present
+    let stored: HashMap<&str, f64> = nodes
+        .iter()
+        .filter_map(|node| {
+            let score = node.metadata.get("pagerank")?.parse().ok()?;
+            Some((node.id.as_str(), score))
+        })
+        .collect();
+    let computed;
+    let centrality: HashMap<&str, f64> = if stored.len() == nodes.len() {
+        stored
+    } else {
+        computed = pagerank(graph);
+        computed
+            .iter()
+            .map(|(id, score)| (id.as_str(), *score))
+            .collect()
+    };
+    let max_centrality = centrality.values().copied().fold(0.0, f64::max);
+
+    let mut hits: Vec<SearchHit> = nodes
+        .into_iter()
+        .filter_map(|node| {
+            let name_score = name_score(&terms, node);
+            let content_score = match content.get(node.id.as_str()) {
+                Some(score) if max_content > 0.0 => score / max_content,
+                _ => 0.0,
+            };
+            if name_score == 0.0 && content_score == 0.0 {
+                return None;
+            }
+            let centrality = match centrality.get(node.id.as_str()) {
+                Some(score) if max_centrality > 0.0 => score / max_centrality,
+                _ => 0.0,
+            };
+            Some(SearchHit {
+                id: node.id.clone(),
+                name: node.name.clone(),
+                node_type: node.node_type.clone(),
+                file_path: node.file_path.clone(),
+                line_range: node.line_range,
+                score: options.name_weight * name_score
+                    + options.content_weight * content_score
+                    + options.centrality_weight * centrality,
+                name_score,
+                content_score,
+                centrality,
+            })
+        })
+        .collect();
+    debug!("Search for {:?} matched {} nodes", terms, hits.len());
+
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+    hits.truncate(options.limit);
+
+    Ok(hits)
+}
@@ -0,0 +1,355 @@
+use crate::code_graph::CodeGraph;
+use log::{debug, info};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// Credential formats masked by default. A `secret` group limits the mask to
+// that part of the match, so `password = "..."` keeps the key name.
+const BUILTIN_PATTERNS: &[(&str, &str)] = &[
+    (
+        "private_key",
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+    ),
+    ("aws_access_key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+    (
+        "github_token",
+        r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{50,})",
+    ),
+    ("slack_token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+    ("google_api_key", r"\bAIza[0-9A-Za-z_-]{35}"),
+    ("stripe_key", r"\b[rs]k_live_[0-9A-Za-z]{24,}"),
+    (
+        "jwt",
+        r"\beyJ[A-Za-z0-9_-]{10,}\.eyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}",
+    ),
+    (
+        "connection_string",
+        r#"\b[a-zA-Z][a-zA-Z0-9+.-]*://[^\s:/@'"]+:(?P<secret>[^\s@'"/]+)@"#,
+    ),
+    (
+        "credential_assignment",
+        r#"(?i)\b\w*(?:api_?key|secret|passwd|password|token|access_?key)\w*["']?\s*(?::|=|:=|=>)\s*["'](?P<secret>[^"'\s]{8,})["']"#,
+    ),
+];
+
+// Quoted strings checked for high entropy; shorter ones are too common
+const ENTROPY_CANDIDATE: &str = r#"["']([A-Za-z0-9+/=_\-]{32,})["']"#;
+
+#[derive(Debug, Clone)]
+pub struct RedactionRules {
+    // Kind reported for a match and the expression that finds it
+    pub patterns: Vec<(String, Regex)>,
+    // Quoted strings with at least this many bits of entropy per character
+    // are masked as `high_entropy_string`; None turns the check off
+    pub min_entropy: Option<f64>,
+}
+
+impl Default for RedactionRules {
+    fn default() -> Self {
+        RedactionRules {
+            patterns: BUILTIN_PATTERNS
+                .iter()
+                .map(|(kind, pattern)| {
+                    (
+                        kind.to_string(),
+                        Regex::new(pattern).expect("valid builtin pattern"),
+                    )
+                })
+                .collect(),
+            min_entropy: Some(4.5),
+        }
+    }
+}
+
+impl RedactionRules {
+    pub fn add_pattern(&mut self, kind: &str, pattern: &str) -> Result<(), regex::Error> {
+        self.patterns.push((kind.to_string(), Regex::new(pattern)?));
+        Ok(())
+    }
+}
+
+// One masked value; the secret itself is never part of the report
+#[derive(Debug, Clone, Serialize)]
+pub struct Redaction {
+    // The node, or the source of the relationship, the value belongs to
+    pub id: String,
+    pub file_path: String,
+    pub line: usize,
+    pub kind: String,
+    // `content`, `name`, `summary`, `parent_class`, `declared_type`,
+    // `signature.parameter_types.<index>` or `metadata.<key>`
+    pub field: String,
+    // Target of the relationship whose metadata held the value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RedactionReport {
+    pub redactions: usize,
+    // Nodes with at least one masked value
+    pub nodes: usize,
+    // Relationships with at least one masked value, such as a call
+    // expression passing a literal credential
+    pub relationships: usize,
+    pub kinds: BTreeMap<String, usize>,
+    pub findings: Vec<Redaction>,
+}
+
+// Mask credentials in node content, names, summaries, typed fields and
+// metadata, and in relationship metadata such as call expressions, with
+// `[REDACTED:<kind>]`. Newlines inside a match are kept so line numbers
+// derived from the content stay correct.
+pub fn redact_secrets(graph: &mut CodeGraph, rules: &RedactionRules) -> RedactionReport {
+    info!("Scanning node content for secrets...");
+
+    let entropy_candidate = Regex::new(ENTROPY_CANDIDATE).expect("valid entropy pattern");
+    let mut report = RedactionReport::default();
+    // Names are applied afterwards so the name index follows them
+    let mut renames = Vec::new();
+
+    for node in graph.all_nodes_mut() {
+        let mut fields: Vec<(String, &str)> = vec![
+            ("content".to_string(), &node.content),
+            ("name".to_string(), &node.name),
+        ];
+        if let Some(summary) = &node.summary {
+            fields.push(("summary".to_string(), summary));
+        }
+        if let Some(parent_class) = &node.parent_class {
+            fields.push(("parent_class".to_string(), parent_class));
+        }
+        if let Some(declared_type) = &node.declared_type {
+            fields.push(("declared_type".to_string(), declared_type));
+        }
+        if let Some(signature) = &node.signature {
+            for (index, parameter_type) in signature.parameter_types.iter().enumerate() {
+                fields.push((
+                    format!("signature.parameter_types.{}", index),
+                    parameter_type,
+                ));
+            }
+        }
+        for (key, value) in &node.metadata {
+            fields.push((format!("metadata.{}", key), value));
+        }
+
+        let mut changed = Vec::new();
+        for (field, text) in fields {
+            let matches = find_secrets(text, rules, &entropy_candidate);
+            if matches.is_empty() {
+                continue;
+            }
+            for (start, _, kind) in &matches {
+                let line = if field == "content" {
+                    node.line_range.0 + text[..*start].matches('\n').count()
+                } else {
+                    node.line_range.0
+                };
+                *report.kinds.entry(kind.clone()).or_default() += 1;
+                report.findings.push(Redaction {
+                    id: node.id.clone(),
+                    file_path: node.file_path.clone(),
+                    line,
+                    kind: kind.clone(),
+                    field: field.clone(),
+                    target: None,
+                });
+            }
+            changed.push((field, mask(text, &matches)));
+        }
+        if changed.is_empty() {
+            continue;
+        }
+
+        for (field, text) in changed {
+            match field.as_str() {
+                "content" => node.content = text,
+                "name" => renames.push((node.id.clone(), text)),
+                "summary" => node.summary = Some(text),
+                "parent_class" => node.parent_class = Some(text),
+                "declared_type" => node.declared_type = Some(text),
+                field => {
+                    if let Some(key) = field.strip_prefix("metadata.") {
+                        node.metadata.insert(key.to_string(), text);
+                    } else if let Some(parameter_type) = field
+                        .strip_prefix("signature.parameter_types.")
+                        .and_then(|index| index.parse::<usize>().ok())
+                        .and_then(|index| {
+                            node.signature
+                                .as_mut()
+                                .and_then(|signature| signature.parameter_types.get_mut(index))
+                        })
+                    {
+                        *parameter_type = text;
+                    }
+                }
+            }
+        }
+        node.add_metadata("redacted".to_string(), "true".to_string());
+        report.nodes += 1;
+    }
+    for (id, name) in renames {
+        graph.rename_node(&id, name);
+    }
+
+    redact_relationships(graph, rules, &entropy_candidate, &mut report);
+
+    report.redactions = report.findings.len();
+    report.findings.sort_by(|a, b| {
+        (&a.file_path, a.line, &a.id, &a.field, &a.target).cmp(&(
+            &b.file_path,
+            b.line,
+            &b.id,
+            &b.field,
+            &b.target,
+        ))
+    });
+
+    info!(
+        "Redacted {} secrets in {} nodes and {} relationships",
+        report.redactions, report.nodes, report.relationships
+    );
+    report
+}
+
+// Mask relationship metadata. Both stored copies of a relationship are
+// masked, but each is reported once.
+fn redact_relationships(
+    graph: &mut CodeGraph,
+    rules: &RedactionRules,
+    entropy_candidate: &Regex,
+    report: &mut RedactionReport,
+) {
+    let sources: HashMap<String, (String, usize)> = graph
+        .all_nodes()
+        .map(|node| (node.id.clone(), (node.file_path.clone(), node.line_range.0)))
+        .collect();
+    let mut reported = HashSet::new();
+
+    for rel in graph.all_relationships_mut() {
+        let mut changed = Vec::new();
+        for (key, value) in &rel.metadata {
+            let matches = find_secrets(value, rules, entropy_candidate);
+            if matches.is_empty() {
+                continue;
+            }
+            changed.push((key.clone(), mask(value, &matches)));
+
+            let edge = (
+                rel.from_id.clone(),
+                rel.to_id.clone(),
+                rel.relationship_type.clone(),
+            );
+            if !reported.insert((edge, key.clone())) {
+                continue;
+            }
+            let (file_path, start_line) = sources.get(&rel.from_id).cloned().unwrap_or_default();
+            // Call sites record their own line
+            let line = rel
+                .metadata
+                .get("line")
+                .and_then(|line| line.parse().ok())
+                .unwrap_or(start_line);
+            for (_, _, kind) in &matches {
+                *report.kinds.entry(kind.clone()).or_default() += 1;
+                report.findings.push(Redaction {
+                    id: rel.from_id.clone(),
+                    file_path: file_path.clone(),
+                    line,
+                    kind: kind.clone(),
+                    field: format!("metadata.{}", key),
+                    target: Some(rel.to_id.clone()),
+                });
+            }
+        }
+        if changed.is_empty() {
+            continue;
+        }
+        for (key, text) in changed {
+            rel.metadata.insert(key, text);
+        }
+    }
+
+    report.relationships = reported
+        .iter()
+        .map(|(edge, _)| edge)
+        .collect::<HashSet<_>>()
+        .len();
+}
+
+// Byte ranges to mask with their kind, without overlaps and in order
+fn find_secrets(
+    text: &str,
+    rules: &RedactionRules,
+    entropy_candidate: &Regex,
+) -> Vec<(usize, usize, String)> {
+    let mut found: Vec<(usize, usize, String)> = Vec::new();
+    for (kind, regex) in &rules.patterns {
+        for captures in regex.captures_iter(text) {
+            let secret = captures
+                .name("secret")
+                .or_else(|| captures.get(0))
+                .expect("a match has a whole-match group");
+            found.push((secret.start(), secret.end(), kind.clone()));
+        }
+    }
+    if let Some(min_entropy) = rules.min_entropy {
+        for captures in entropy_candidate.captures_iter(text) {
+            let value = &captures[1];
+            if shannon_entropy(value) >= min_entropy {
+                let secret = captures.get(1).expect("entropy pattern has a group");
+                found.push((
+                    secret.start(),
+                    secret.end(),
+                    "high_entropy_string".to_string(),
+                ));
+            }
+        }
+    }
+
+    // The earliest and then longest match wins where matches overlap
+    found.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+    let mut kept: Vec<(usize, usize, String)> = Vec::new();
+    for range in found {
+        if kept.last().is_none_or(|last| range.0 >= last.1) {
+            kept.push(range);
+        }
+    }
+    if !kept.is_empty() {
+        debug!("Found {} secrets", kept.len());
+    }
+    kept
+}
+
+fn mask(text: &str, ranges: &[(usize, usize, String)]) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut position = 0;
+    for (start, end, kind) in ranges {
+        masked.push_str(&text[position..*start]);
+        masked.push_str(&format!("[REDACTED:{}]", kind));
+        for _ in text[*start..*end].matches('\n') {
+            masked.push('\n');
+        }
+        position = *end;
+    }
+    masked.push_str(&text[position..]);
+    masked
+}
+
+// Bits of entropy per character of the string's own character distribution
+fn shannon_entropy(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let length = value.chars().count() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / length;
+            -p * p.log2()
+        })
+        .sum()
+}
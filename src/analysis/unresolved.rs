@@ -0,0 +1,131 @@
+use crate::code_graph::{CodeGraph, NodeType};
+use log::debug;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+// How likely an unresolved reference points at a gap in the graph rather
+// than at code that was never meant to be indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    // Standard library and declared third-party packages
+    Low,
+    // Origin unknown
+    Medium,
+    // Calls through `self` and crate-relative imports that should resolve
+    High,
+}
+
+impl Severity {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "low" => Some(Severity::Low),
+            "medium" => Some(Severity::Medium),
+            "high" => Some(Severity::High),
+            _ => None,
+        }
+    }
+
+    fn of_origin(origin: &str) -> Self {
+        match origin {
+            "internal" => Severity::High,
+            "stdlib" | "third_party" => Severity::Low,
+            _ => Severity::Medium,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnresolvedSymbol {
+    pub id: String,
+    pub name: String,
+    pub language: String,
+    pub origin: String,
+    pub severity: Severity,
+    // Call sites and imports pointing at the symbol
+    pub references: usize,
+    // Files those references come from
+    pub files: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UnresolvedReport {
+    pub symbols: usize,
+    pub references: usize,
+    // Number of symbols per origin
+    pub origins: BTreeMap<String, usize>,
+    // Most-referenced symbols at or above the requested severity
+    pub top: Vec<UnresolvedSymbol>,
+}
+
+// Summarise the ExternalSymbol nodes of a graph indexed with external
+// symbols enabled: how many there are per origin and which ones are
+// referenced most, keeping the `limit` most-referenced symbols of at least
+// `min_severity`.
+pub fn unresolved_report(
+    graph: &CodeGraph,
+    limit: usize,
+    min_severity: Severity,
+) -> UnresolvedReport {
+    let mut report = UnresolvedReport::default();
+    let mut symbols = Vec::new();
+
+    for node in graph.all_nodes() {
+        if node.node_type != NodeType::ExternalSymbol {
+            continue;
+        }
+        let origin = node
+            .metadata
+            .get("origin")
+            .cloned()
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let mut references = 0;
+        let mut files = HashSet::new();
+        for rel in graph.incoming_relationships(&node.id) {
+            // Merged call edges record how many call sites they stand for
+            references += rel
+                .metadata
+                .get("count")
+                .and_then(|count| count.parse().ok())
+                .unwrap_or(1);
+            if let Some(caller) = graph.get_node(&rel.from_id) {
+                files.insert(caller.file_path.as_str());
+            }
+        }
+
+        report.symbols += 1;
+        report.references += references;
+        *report.origins.entry(origin.clone()).or_insert(0) += 1;
+
+        let severity = Severity::of_origin(&origin);
+        if severity >= min_severity {
+            symbols.push(UnresolvedSymbol {
+                id: node.id.clone(),
+                name: node.name.clone(),
+                language: node.language.clone().unwrap_or_default(),
+                origin,
+                severity,
+                references,
+                files: files.len(),
+            });
+        }
+    }
+    debug!(
+        "{} of {} unresolved symbols at {:?} severity or above",
+        symbols.len(),
+        report.symbols,
+        min_severity
+    );
+
+    symbols.sort_by(|a, b| {
+        b.references
+            .cmp(&a.references)
+            .then_with(|| b.severity.cmp(&a.severity))
+            .then_with(|| (&a.name, &a.language).cmp(&(&b.name, &b.language)))
+    });
+    symbols.truncate(limit);
+    report.top = symbols;
+
+    report
+}
@@ -0,0 +1,161 @@
+use super::{CodeGraph, CodeNode, NodeType, Relationship, RelationshipType};
+use log::debug;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+// Built-in groupings for `CodeGraph::aggregate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    File,
+    Directory,
+    // Methods with their class; other symbols stay on their own
+    Class,
+}
+
+impl Aggregation {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "file" => Some(Aggregation::File),
+            "directory" => Some(Aggregation::Directory),
+            "class" => Some(Aggregation::Class),
+            _ => None,
+        }
+    }
+
+    fn key(&self, node: &CodeNode) -> String {
+        match self {
+            Aggregation::File => node.file_path.clone(),
+            Aggregation::Directory => Path::new(&node.file_path)
+                .parent()
+                .map_or(String::new(), |dir| dir.to_string_lossy().into_owned()),
+            Aggregation::Class => {
+                let class = match node.node_type {
+                    NodeType::Method => node.parent_class.as_ref().unwrap_or(&node.name),
+                    _ => &node.name,
+                };
+                format!("{}::{}", node.file_path, class)
+            }
+        }
+    }
+
+    fn node_type(&self) -> NodeType {
+        match self {
+            Aggregation::File => NodeType::File,
+            Aggregation::Directory => NodeType::Directory,
+            Aggregation::Class => NodeType::Class,
+        }
+    }
+}
+
+impl CodeGraph {
+    // Collapse the graph to one node per file, directory or class
+    pub fn aggregate(&self, aggregation: Aggregation) -> CodeGraph {
+        self.aggregate_nodes(|node| Some(aggregation.key(node)), aggregation.node_type())
+    }
+
+    // Collapse every node into the group named by `key`, dropping nodes for
+    // which it returns None. Each group becomes one Module node whose ID is
+    // the key, with a `members` count; edges between groups are merged per
+    // type with the number of calls or edges they stand for as `count`, and
+    // edges inside a group are dropped.
+    pub fn aggregate_by<F>(&self, key: F) -> CodeGraph
+    where
+        F: Fn(&CodeNode) -> Option<String>,
+    {
+        self.aggregate_nodes(key, NodeType::Module)
+    }
+
+    fn aggregate_nodes<F>(&self, key: F, node_type: NodeType) -> CodeGraph
+    where
+        F: Fn(&CodeNode) -> Option<String>,
+    {
+        let mut groups: BTreeMap<String, Vec<&CodeNode>> = BTreeMap::new();
+        let mut group_of: HashMap<&str, String> = HashMap::new();
+        for node in self.nodes.values() {
+            if let Some(group) = key(node) {
+                group_of.insert(&node.id, group.clone());
+                groups.entry(group).or_default().push(node);
+            }
+        }
+
+        let mut aggregated = CodeGraph::new();
+        aggregated.provenance = self.provenance.clone();
+        for (group, members) in &groups {
+            // File and lines are kept when every member shares a file
+            let first = members[0];
+            let single_file = members
+                .iter()
+                .all(|member| member.file_path == first.file_path);
+            let (file_path, line_range) = if single_file {
+                let start = members.iter().map(|m| m.line_range.0).min().unwrap_or(0);
+                let end = members.iter().map(|m| m.line_range.1).max().unwrap_or(0);
+                (first.file_path.clone(), (start, end))
+            } else {
+                (String::new(), (0, 0))
+            };
+
+            let mut node = CodeNode::new(
+                group.clone(),
+                node_type.clone(),
+                group.clone(),
+                file_path,
+                line_range,
+                String::new(),
+            )
+            .with_metadata("members".to_string(), members.len().to_string());
+            if members.iter().all(|member| member.repo == first.repo) {
+                node.repo = first.repo.clone();
+            }
+            if members
+                .iter()
+                .all(|member| member.namespace == first.namespace)
+            {
+                node.namespace = first.namespace.clone();
+            }
+            if members
+                .iter()
+                .all(|member| member.language == first.language)
+            {
+                node.language = first.language.clone();
+            }
+            aggregated.add_node(node);
+        }
+
+        let mut edges: BTreeMap<(RelationshipType, &str, &str), usize> = BTreeMap::new();
+        for rel in self.all_relationships() {
+            let (Some(from), Some(to)) = (
+                group_of.get(rel.from_id.as_str()),
+                group_of.get(rel.to_id.as_str()),
+            ) else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+            // Merged call edges already carry the number of call sites
+            let weight = rel
+                .metadata
+                .get("count")
+                .and_then(|count| count.parse().ok())
+                .unwrap_or(1);
+            *edges
+                .entry((rel.relationship_type.clone(), from, to))
+                .or_default() += weight;
+        }
+        for ((relationship_type, from, to), count) in edges {
+            aggregated.add_relationship(
+                Relationship::new(relationship_type, from.to_string(), to.to_string())
+                    .with_metadata("count".to_string(), count.to_string()),
+            );
+        }
+
+        debug!(
+            "Aggregated {} nodes into {} groups with {} relationships",
+            group_of.len(),
+            aggregated.node_count(),
+            aggregated.relationship_count()
+        );
+
+        aggregated
+    }
+}
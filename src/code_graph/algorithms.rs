@@ -0,0 +1,212 @@
+use super::{CodeGraph, CodeNode, RelationshipType};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+// A set of nodes that all reach each other over the chosen relationship
+// types, ordered by file and position
+pub type Cycle<'a> = Vec<&'a CodeNode>;
+
+// Strongly connected components with more than one node, found with
+// Tarjan's algorithm over the given relationship types (all types when
+// empty). Self-loops such as direct recursion are not reported.
+pub fn find_cycles<'a>(graph: &'a CodeGraph, rel_types: &[RelationshipType]) -> Vec<Cycle<'a>> {
+    let mut nodes: Vec<&CodeNode> = graph.all_nodes().collect();
+    nodes.sort_by(|a, b| {
+        (&a.file_path, a.line_range, &a.id).cmp(&(&b.file_path, b.line_range, &b.id))
+    });
+
+    let index_of: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (node.id.as_str(), index))
+        .collect();
+
+    let successors: Vec<Vec<usize>> = nodes
+        .iter()
+        .map(|node| {
+            graph
+                .outgoing_relationships(&node.id)
+                .iter()
+                .filter(|rel| rel_types.is_empty() || rel_types.contains(&rel.relationship_type))
+                .filter_map(|rel| index_of.get(rel.to_id.as_str()).copied())
+                .collect()
+        })
+        .collect();
+
+    let mut cycles: Vec<Cycle> = strongly_connected_components(&successors)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .map(|mut component| {
+            component.sort_unstable();
+            component.into_iter().map(|index| nodes[index]).collect()
+        })
+        .collect();
+
+    cycles.sort_by(|a, b| {
+        b.len().cmp(&a.len()).then_with(|| {
+            (&a[0].file_path, a[0].line_range).cmp(&(&b[0].file_path, b[0].line_range))
+        })
+    });
+    cycles
+}
+
+// Source files in the order their imports need them
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ModuleOrder<'a> {
+    // Every module after the modules it imports, members of an import cycle
+    // next to each other; ties are broken by path
+    pub order: Vec<&'a str>,
+    // Groups of modules that import each other, sorted by path
+    pub cycles: Vec<Vec<&'a str>>,
+}
+
+// Order the files of the graph so that each comes after the files it
+// imports from, as a build or a bottom-up pass over the codebase needs.
+// A module is a source file; nodes without one are ignored.
+pub fn topo_order_modules(graph: &CodeGraph) -> ModuleOrder<'_> {
+    let mut files: Vec<&str> = graph.files().filter(|file| !file.is_empty()).collect();
+    files.sort_unstable();
+    let index_of: HashMap<&str, usize> = files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| (*file, index))
+        .collect();
+
+    let successors: Vec<Vec<usize>> = files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| {
+            let imported: BTreeSet<usize> = graph
+                .find_nodes_in_file(file)
+                .into_iter()
+                .flat_map(|node| graph.outgoing_relationships(&node.id))
+                .filter(|rel| rel.relationship_type == RelationshipType::Imports)
+                .filter_map(|rel| graph.get_node(&rel.to_id))
+                .filter_map(|node| index_of.get(node.file_path.as_str()).copied())
+                .filter(|imported| *imported != index)
+                .collect();
+            imported.into_iter().collect()
+        })
+        .collect();
+
+    let mut components = strongly_connected_components(&successors);
+    for component in &mut components {
+        component.sort_unstable();
+    }
+    let mut component_of = vec![0; files.len()];
+    for (component, members) in components.iter().enumerate() {
+        for member in members {
+            component_of[*member] = component;
+        }
+    }
+
+    // Kahn's algorithm over the components, taking the one with the first
+    // path whenever several are ready
+    let mut pending = vec![0; components.len()];
+    let mut dependents: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); components.len()];
+    for (component, members) in components.iter().enumerate() {
+        let imported: BTreeSet<usize> = members
+            .iter()
+            .flat_map(|member| &successors[*member])
+            .map(|imported| component_of[*imported])
+            .filter(|imported| *imported != component)
+            .collect();
+        pending[component] = imported.len();
+        for imported in imported {
+            dependents[imported].insert(component);
+        }
+    }
+    let mut ready: BTreeSet<(usize, usize)> = components
+        .iter()
+        .enumerate()
+        .filter(|(component, _)| pending[*component] == 0)
+        .map(|(component, members)| (members[0], component))
+        .collect();
+
+    let mut modules = ModuleOrder::default();
+    while let Some((_, component)) = ready.pop_first() {
+        let members = &components[component];
+        modules
+            .order
+            .extend(members.iter().map(|member| files[*member]));
+        if members.len() > 1 {
+            modules
+                .cycles
+                .push(members.iter().map(|member| files[*member]).collect());
+        }
+        for dependent in &dependents[component] {
+            pending[*dependent] -= 1;
+            if pending[*dependent] == 0 {
+                ready.insert((components[*dependent][0], *dependent));
+            }
+        }
+    }
+
+    modules.cycles.sort();
+    modules
+}
+
+// Iterative Tarjan SCC over an adjacency list, so deep call chains cannot
+// overflow the stack
+fn strongly_connected_components(successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+
+    let count = successors.len();
+    let mut index = vec![UNVISITED; count];
+    let mut lowlink = vec![0; count];
+    let mut on_stack = vec![false; count];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    for root in 0..count {
+        if index[root] != UNVISITED {
+            continue;
+        }
+
+        // (node, position of the next successor to look at)
+        let mut call_stack = vec![(root, 0)];
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((node, position)) = call_stack.last_mut() {
+            let node = *node;
+            if let Some(&next) = successors[node].get(*position) {
+                *position += 1;
+                if index[next] == UNVISITED {
+                    index[next] = next_index;
+                    lowlink[next] = next_index;
+                    next_index += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    call_stack.push((next, 0));
+                } else if on_stack[next] {
+                    lowlink[node] = lowlink[node].min(index[next]);
+                }
+                continue;
+            }
+
+            call_stack.pop();
+            if let Some(&(parent, _)) = call_stack.last() {
+                lowlink[parent] = lowlink[parent].min(lowlink[node]);
+            }
+
+            if lowlink[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
@@ -0,0 +1,135 @@
+use super::{CodeGraph, CodeNode, Relationship, RelationshipType};
+use log::debug;
+use std::io;
+
+// Relationships listed in the error of a failed build
+const REPORTED_PROBLEMS: usize = 5;
+
+// Assembles a graph from nodes and relationships made by other tools.
+// Node IDs must be unique; relationship endpoints may be node IDs or
+// unique node names and are resolved by `build`, which fails rather than
+// leave an edge pointing at nothing.
+#[derive(Debug, Default)]
+pub struct CodeGraphBuilder {
+    graph: CodeGraph,
+    relationships: Vec<Relationship>,
+}
+
+impl CodeGraph {
+    pub fn builder() -> CodeGraphBuilder {
+        CodeGraphBuilder::new()
+    }
+}
+
+impl CodeGraphBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Add a node, failing when its ID is empty or already taken
+    pub fn add_node(&mut self, node: CodeNode) -> io::Result<&mut Self> {
+        if node.id.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} node {} has no ID", node.node_type, node.name),
+            ));
+        }
+        if let Some(existing) = self.graph.get_node(&node.id) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "Duplicate node ID {}: {} and {}",
+                    node.id, existing.name, node.name
+                ),
+            ));
+        }
+        self.graph.add_node(node);
+        Ok(self)
+    }
+
+    // Relate two nodes, each given by ID or by a name only one node has
+    pub fn connect(
+        &mut self,
+        from: &str,
+        to: &str,
+        relationship_type: RelationshipType,
+    ) -> &mut Self {
+        self.add_relationship(Relationship::new(
+            relationship_type,
+            from.to_string(),
+            to.to_string(),
+        ))
+    }
+
+    // Add a relationship whose endpoints are node IDs or unique names, for
+    // edges that carry provenance or metadata
+    pub fn add_relationship(&mut self, relationship: Relationship) -> &mut Self {
+        self.relationships.push(relationship);
+        self
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    // ID of the node an endpoint refers to
+    fn resolve(&self, endpoint: &str) -> Result<String, String> {
+        if self.graph.get_node(endpoint).is_some() {
+            return Ok(endpoint.to_string());
+        }
+        match self.graph.find_nodes_by_name(endpoint).as_slice() {
+            [node] => Ok(node.id.clone()),
+            [] => Err(format!("no node with ID or name {}", endpoint)),
+            nodes => Err(format!("{} nodes are named {}", nodes.len(), endpoint)),
+        }
+    }
+
+    // The graph, once every relationship endpoint resolves to one node
+    pub fn build(self) -> io::Result<CodeGraph> {
+        let mut resolved = Vec::with_capacity(self.relationships.len());
+        let mut problems = Vec::new();
+        for mut relationship in self.relationships.iter().cloned() {
+            match (
+                self.resolve(&relationship.from_id),
+                self.resolve(&relationship.to_id),
+            ) {
+                (Ok(from), Ok(to)) => {
+                    relationship.from_id = from;
+                    relationship.to_id = to;
+                    resolved.push(relationship);
+                }
+                (Err(problem), _) | (_, Err(problem)) => problems.push(format!(
+                    "{:?} {} -> {}: {}",
+                    relationship.relationship_type,
+                    relationship.from_id,
+                    relationship.to_id,
+                    problem
+                )),
+            }
+        }
+
+        if !problems.is_empty() {
+            let mut message = format!(
+                "{} of {} relationships do not resolve to a node: {}",
+                problems.len(),
+                self.relationships.len(),
+                problems[..problems.len().min(REPORTED_PROBLEMS)].join("; ")
+            );
+            if problems.len() > REPORTED_PROBLEMS {
+                message.push_str("; ...");
+            }
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+
+        let mut graph = self.graph;
+        for relationship in resolved {
+            graph.add_relationship(relationship);
+        }
+        debug!(
+            "Built graph with {} nodes and {} relationships",
+            graph.node_count(),
+            graph.relationship_count()
+        );
+        Ok(graph)
+    }
+}
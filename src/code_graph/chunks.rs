@@ -0,0 +1,211 @@
+use super::tokens::{ApproximateTokenizer, Tokenizer};
+use super::{CodeGraph, CodeNode, NodeType};
+use log::debug;
+use serde::Serialize;
+use std::sync::Arc;
+
+// Longest caller or callee signature injected into a chunk, in characters
+const MAX_SIGNATURE_LENGTH: usize = 200;
+
+#[derive(Clone)]
+pub struct ChunkOptions {
+    // Budget of every chunk, context included
+    pub max_tokens: usize,
+    // Types of the nodes to chunk; empty for every node with content
+    pub node_types: Vec<NodeType>,
+    // Caller and callee signatures injected per direction
+    pub max_neighbors: usize,
+    pub tokenizer: Arc<dyn Tokenizer>,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        ChunkOptions {
+            max_tokens: 512,
+            node_types: Vec::new(),
+            max_neighbors: 5,
+            tokenizer: Arc::new(ApproximateTokenizer),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Chunk {
+    // Node ID, with the part number when the node was split
+    pub id: String,
+    pub node_id: String,
+    pub name: String,
+    pub node_type: NodeType,
+    pub file_path: String,
+    // Lines of the node's content in this chunk
+    pub line_range: (usize, usize),
+    // Context header followed by the content
+    pub text: String,
+    pub token_count: usize,
+}
+
+// First line of a declaration, without the opening of its body
+pub(super) fn signature(node: &CodeNode) -> String {
+    let line = node
+        .content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or(&node.name);
+    let line = line.trim_end_matches('{').trim_end();
+    match line.char_indices().nth(MAX_SIGNATURE_LENGTH) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+fn neighbor_signatures(nodes: Vec<&CodeNode>, limit: usize) -> Vec<String> {
+    let mut nodes = nodes;
+    nodes.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
+    nodes.dedup_by(|a, b| a.id == b.id);
+    nodes.into_iter().take(limit).map(signature).collect()
+}
+
+// Context lines for a node, most important first
+fn context_lines(graph: &CodeGraph, node: &CodeNode, options: &ChunkOptions) -> Vec<String> {
+    let mut lines = vec![
+        format!("{:?} {}::{}", node.node_type, node.file_path, node.name),
+        format!(
+            "File: {} (lines {}-{})",
+            node.file_path, node.line_range.0, node.line_range.1
+        ),
+    ];
+    if let Some(parent_class) = &node.parent_class {
+        lines.push(format!("Class: {}", parent_class));
+    }
+    let callers = neighbor_signatures(graph.find_callers(&node.id), options.max_neighbors);
+    let callees = neighbor_signatures(graph.find_called_functions(&node.id), options.max_neighbors);
+    lines.extend(callers.iter().map(|sig| format!("Called by: {}", sig)));
+    lines.extend(callees.iter().map(|sig| format!("Calls: {}", sig)));
+    lines
+}
+
+// Longest prefix of `text` within `budget` tokens, at a char boundary
+pub(super) fn fitting_prefix<'a>(
+    text: &'a str,
+    budget: usize,
+    tokenizer: &dyn Tokenizer,
+) -> &'a str {
+    let boundaries: Vec<usize> = text
+        .char_indices()
+        .map(|(index, _)| index)
+        .skip(1)
+        .chain([text.len()])
+        .collect();
+    let fitting = boundaries.partition_point(|&end| tokenizer.count_tokens(&text[..end]) <= budget);
+    // At least one character, so splitting always makes progress
+    &text[..boundaries[fitting.saturating_sub(1)]]
+}
+
+// Split a node into chunks that each start with its context. Context lines
+// that do not fit in half the budget are left out, and content is split at
+// line boundaries, or inside lines too long for a chunk of their own.
+fn chunk_node(graph: &CodeGraph, node: &CodeNode, options: &ChunkOptions) -> Vec<Chunk> {
+    let tokenizer = options.tokenizer.as_ref();
+    let mut header = String::new();
+    for line in context_lines(graph, node, options) {
+        let extended = format!("{}{}\n", header, line);
+        if tokenizer.count_tokens(&extended) > options.max_tokens / 2 {
+            break;
+        }
+        header = extended;
+    }
+    header.push('\n');
+    let budget = options
+        .max_tokens
+        .saturating_sub(tokenizer.count_tokens(&header))
+        .max(1);
+
+    // Pieces of content with the line they come from
+    let mut pieces: Vec<(usize, String)> = Vec::new();
+    for (offset, line) in node.content.lines().enumerate() {
+        let line_number = node.line_range.0 + offset;
+        let mut rest = format!("{}\n", line);
+        while tokenizer.count_tokens(&rest) > budget {
+            let prefix = fitting_prefix(&rest, budget, tokenizer).to_string();
+            rest = rest[prefix.len()..].to_string();
+            pieces.push((line_number, prefix));
+        }
+        pieces.push((line_number, rest));
+    }
+
+    // Piece counts are summed, as the tokens of adjacent lines barely
+    // interact
+    let mut parts: Vec<Vec<(usize, String)>> = Vec::new();
+    let mut current: Vec<(usize, String)> = Vec::new();
+    let mut used = 0;
+    for (line_number, piece) in pieces {
+        let tokens = tokenizer.count_tokens(&piece);
+        if !current.is_empty() && used + tokens > budget {
+            parts.push(std::mem::take(&mut current));
+            used = 0;
+        }
+        used += tokens;
+        current.push((line_number, piece));
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    let split = parts.len() > 1;
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, part)| {
+            let start = part.first().map_or(node.line_range.0, |(line, _)| *line);
+            let end = part.last().map_or(node.line_range.1, |(line, _)| *line);
+            let content: String = part.into_iter().map(|(_, piece)| piece).collect();
+            let text = format!("{}{}", header, content.trim_end());
+            Chunk {
+                id: if split {
+                    format!("{}#{}", node.id, index + 1)
+                } else {
+                    node.id.clone()
+                },
+                node_id: node.id.clone(),
+                name: node.name.clone(),
+                node_type: node.node_type.clone(),
+                file_path: node.file_path.clone(),
+                line_range: (start, end),
+                token_count: tokenizer.count_tokens(&text),
+                text,
+            }
+        })
+        .collect()
+}
+
+// Retrieval-ready chunks for the nodes of a graph: each node's content
+// headed by its qualified name, file, parent class and the signatures of its
+// direct callers and callees, split so no chunk exceeds the token budget.
+// Chunks are ordered by file and line.
+pub fn chunk_graph(graph: &CodeGraph, options: &ChunkOptions) -> Vec<Chunk> {
+    let mut nodes: Vec<&CodeNode> = graph
+        .all_nodes()
+        .filter(|node| !node.content.trim().is_empty())
+        .filter(|node| {
+            options.node_types.is_empty() || options.node_types.contains(&node.node_type)
+        })
+        .collect();
+    nodes.sort_by(|a, b| {
+        (&a.file_path, a.line_range, &a.id).cmp(&(&b.file_path, b.line_range, &b.id))
+    });
+
+    let node_count = nodes.len();
+    let chunks: Vec<Chunk> = nodes
+        .into_iter()
+        .flat_map(|node| chunk_node(graph, node, options))
+        .collect();
+    debug!(
+        "Split {} nodes into {} chunks of at most {} tokens",
+        node_count,
+        chunks.len(),
+        options.max_tokens
+    );
+
+    chunks
+}
@@ -0,0 +1,201 @@
+use super::{CodeGraph, CodeNode, Relationship, RelationshipType};
+use log::info;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::mem::size_of;
+
+// What a call to `CodeGraph::compact` cleaned up
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CompactionStats {
+    pub duplicate_edges: usize,
+    pub dangling_edges: usize,
+    pub stale_index_entries: usize,
+    pub bytes_before: usize,
+    pub bytes_after: usize,
+}
+
+impl CompactionStats {
+    pub fn reclaimed_bytes(&self) -> usize {
+        self.bytes_before.saturating_sub(self.bytes_after)
+    }
+}
+
+impl CodeGraph {
+    // Rebuild edge lists and indices from the node table: drops duplicate
+    // edges and edges to missing nodes, clears stale index entries and
+    // releases spare capacity. Meant for graphs that live for a long time.
+    pub fn compact(&mut self) -> CompactionStats {
+        let mut stats = CompactionStats {
+            bytes_before: self.approximate_heap_size(),
+            ..Default::default()
+        };
+
+        let mut seen: HashSet<(String, String, RelationshipType)> = HashSet::new();
+        let mut outgoing: HashMap<String, Vec<Relationship>> = HashMap::new();
+        let mut incoming: HashMap<String, Vec<Relationship>> = HashMap::new();
+        for id in self.nodes.keys() {
+            outgoing.insert(id.clone(), Vec::new());
+            incoming.insert(id.clone(), Vec::new());
+        }
+
+        for rel in std::mem::take(&mut self.outgoing_edges)
+            .into_values()
+            .flatten()
+        {
+            if !self.nodes.contains_key(&rel.from_id) || !self.nodes.contains_key(&rel.to_id) {
+                stats.dangling_edges += 1;
+                continue;
+            }
+
+            let key = (
+                rel.from_id.clone(),
+                rel.to_id.clone(),
+                rel.relationship_type.clone(),
+            );
+            if !seen.insert(key) {
+                stats.duplicate_edges += 1;
+                continue;
+            }
+
+            incoming
+                .entry(rel.to_id.clone())
+                .or_default()
+                .push(rel.clone());
+            outgoing.entry(rel.from_id.clone()).or_default().push(rel);
+        }
+
+        for rels in outgoing.values_mut().chain(incoming.values_mut()) {
+            rels.shrink_to_fit();
+        }
+        outgoing.shrink_to_fit();
+        incoming.shrink_to_fit();
+        self.outgoing_edges = outgoing;
+        self.incoming_edges = incoming;
+
+        stats.stale_index_entries = self.stale_index_entry_count();
+        self.rebuild_indexes();
+        let nodes = &self.nodes;
+        self.embeddings.retain(|id, _| nodes.contains_key(id));
+
+        self.nodes.shrink_to_fit();
+        for ids in self
+            .nodes_by_type
+            .values_mut()
+            .chain(self.nodes_by_file.values_mut())
+            .chain(self.nodes_by_name.values_mut())
+            .chain(self.nodes_by_language.values_mut())
+        {
+            ids.shrink_to_fit();
+        }
+        self.nodes_by_type.shrink_to_fit();
+        self.nodes_by_file.shrink_to_fit();
+        self.nodes_by_name.shrink_to_fit();
+        self.nodes_by_language.shrink_to_fit();
+
+        stats.bytes_after = self.approximate_heap_size();
+
+        info!(
+            "Compacted graph: removed {} duplicate and {} dangling edges, {} stale index entries, reclaimed ~{} bytes",
+            stats.duplicate_edges,
+            stats.dangling_edges,
+            stats.stale_index_entries,
+            stats.reclaimed_bytes()
+        );
+
+        stats
+    }
+
+    // Index entries whose node is gone or no longer has the indexed key
+    pub(super) fn stale_index_entry_count(&self) -> usize {
+        let stale = |ids: &HashSet<String>, matches: &dyn Fn(&CodeNode) -> bool| {
+            ids.iter()
+                .filter(|id| !self.nodes.get(*id).is_some_and(matches))
+                .count()
+        };
+
+        let by_type: usize = self
+            .nodes_by_type
+            .iter()
+            .map(|(node_type, ids)| stale(ids, &|node| &node.node_type == node_type))
+            .sum();
+        let by_file: usize = self
+            .nodes_by_file
+            .iter()
+            .map(|(file, ids)| stale(ids, &|node| &node.file_path == file))
+            .sum();
+        let by_name: usize = self
+            .nodes_by_name
+            .iter()
+            .map(|(name, ids)| stale(ids, &|node| &node.name == name))
+            .sum();
+        let by_language: usize = self
+            .nodes_by_language
+            .iter()
+            .map(|(language, ids)| stale(ids, &|node| node.language.as_ref() == Some(language)))
+            .sum();
+
+        by_type + by_file + by_name + by_language
+    }
+
+    // Index every node afresh, for graphs whose indices were emptied or
+    // written by a version that lacked some of them
+    pub(super) fn rebuild_indexes(&mut self) {
+        self.line_index.take();
+        self.nodes_by_type.clear();
+        self.nodes_by_file.clear();
+        self.nodes_by_name.clear();
+        self.nodes_by_language.clear();
+        let nodes: Vec<CodeNode> = std::mem::take(&mut self.nodes).into_values().collect();
+        for node in nodes {
+            self.index_node(&node);
+            self.nodes.insert(node.id.clone(), node);
+        }
+    }
+
+    // Rough heap footprint based on container capacities; string contents
+    // of nodes are counted once, index keys and ids are counted by capacity
+    pub fn approximate_heap_size(&self) -> usize {
+        let string_set = |ids: &HashSet<String>| {
+            ids.capacity() * size_of::<String>() + ids.iter().map(|id| id.capacity()).sum::<usize>()
+        };
+        let edge_list = |rels: &Vec<Relationship>| {
+            rels.capacity() * size_of::<Relationship>()
+                + rels
+                    .iter()
+                    .map(|rel| rel.from_id.capacity() + rel.to_id.capacity())
+                    .sum::<usize>()
+        };
+
+        let nodes = self.nodes.capacity() * (size_of::<String>() + size_of::<CodeNode>())
+            + self
+                .nodes
+                .iter()
+                .map(|(id, node)| id.capacity() + node.id.capacity() + node.content.capacity())
+                .sum::<usize>();
+
+        let edges = (self.outgoing_edges.capacity() + self.incoming_edges.capacity())
+            * (size_of::<String>() + size_of::<Vec<Relationship>>())
+            + self
+                .outgoing_edges
+                .values()
+                .chain(self.incoming_edges.values())
+                .map(edge_list)
+                .sum::<usize>();
+
+        let indices = self.nodes_by_type.capacity() * size_of::<HashSet<String>>()
+            + (self.nodes_by_file.capacity()
+                + self.nodes_by_name.capacity()
+                + self.nodes_by_language.capacity())
+                * (size_of::<String>() + size_of::<HashSet<String>>())
+            + self
+                .nodes_by_type
+                .values()
+                .chain(self.nodes_by_file.values())
+                .chain(self.nodes_by_name.values())
+                .chain(self.nodes_by_language.values())
+                .map(string_set)
+                .sum::<usize>();
+
+        nodes + edges + indices
+    }
+}
@@ -0,0 +1,58 @@
+use super::CodeGraph;
+use log::info;
+
+// How much source text nodes keep when a graph is exported. Structure-only
+// exports drop it; `content_hash` still identifies what was there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentLimit {
+    #[default]
+    Full,
+    Omit,
+    // Content longer than this many bytes is cut at a character boundary
+    MaxBytes(usize),
+}
+
+impl ContentLimit {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "full" => Some(ContentLimit::Full),
+            "none" => Some(ContentLimit::Omit),
+            _ => value
+                .strip_prefix("max-bytes:")
+                .and_then(|n| n.parse().ok())
+                .map(ContentLimit::MaxBytes),
+        }
+    }
+}
+
+impl CodeGraph {
+    // Drop or shorten node content, recording what was kept in
+    // `content_scope`. Returns the number of nodes that changed.
+    pub fn limit_content(&mut self, limit: ContentLimit) -> usize {
+        let (length, scope) = match limit {
+            ContentLimit::Full => return 0,
+            ContentLimit::Omit => (0, "none"),
+            ContentLimit::MaxBytes(max) => (max, "bytes"),
+        };
+
+        let mut changed = 0;
+        for node in self.nodes.values_mut() {
+            if node.content.len() <= length {
+                continue;
+            }
+
+            let mut end = length;
+            while !node.content.is_char_boundary(end) {
+                end -= 1;
+            }
+            node.content.truncate(end);
+            node.add_metadata("content_scope".to_string(), scope.to_string());
+            changed += 1;
+        }
+
+        if changed > 0 {
+            info!("Limited the content of {} nodes to {:?}", changed, limit);
+        }
+        changed
+    }
+}
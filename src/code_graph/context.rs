@@ -0,0 +1,159 @@
+use super::chunks::{fitting_prefix, signature};
+use super::tokens::{ApproximateTokenizer, Tokenizer};
+use super::{CodeGraph, CodeNode, RelationshipType};
+use log::debug;
+use std::collections::BTreeSet;
+
+// Callers and callees listed per direction
+const MAX_CONTEXT_NEIGHBORS: usize = 10;
+// Imported symbols listed
+const MAX_CONTEXT_IMPORTS: usize = 20;
+
+// Callers or callees by name, as signatures alone such as `fn new() -> Self`
+// do not tell them apart
+fn named_signatures(mut nodes: Vec<&CodeNode>) -> Vec<String> {
+    nodes.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
+    nodes.dedup_by(|a, b| a.id == b.id);
+    nodes
+        .into_iter()
+        .take(MAX_CONTEXT_NEIGHBORS)
+        .map(|node| format!("{}: {}", node.name, signature(node)))
+        .collect()
+}
+
+impl CodeGraph {
+    // Everything an agent needs to explain a node, as one prompt-ready text of
+    // at most `budget` approximate tokens: where it is, its signature and
+    // class, its direct callers and callees, what it imports, and then as
+    // much of its code as still fits. None when the node does not exist.
+    pub fn context_for(&self, node_id: &str, budget: usize) -> Option<String> {
+        self.context_for_with_tokenizer(node_id, budget, &ApproximateTokenizer)
+    }
+
+    // `context_for` with tokens counted for a particular model
+    pub fn context_for_with_tokenizer(
+        &self,
+        node_id: &str,
+        budget: usize,
+        tokenizer: &dyn Tokenizer,
+    ) -> Option<String> {
+        let node = self.get_node(node_id)?;
+
+        // Facts about the node take at most half the budget, most important
+        // first, so the code always has room
+        let mut context = String::new();
+        for line in self.context_facts(node) {
+            let extended = format!("{}{}\n", context, line);
+            if tokenizer.count_tokens(&extended) > budget / 2 {
+                break;
+            }
+            context = extended;
+        }
+
+        let opening = format!("\n```{}\n", node.language.as_deref().unwrap_or_default());
+        let closing = "```\n";
+        // Line counts are summed, as in chunks, and room for the marker
+        // saying how much was cut is kept until the last line
+        let reserve = tokenizer.count_tokens("... 99999 more lines\n");
+        let mut available = budget.saturating_sub(
+            tokenizer.count_tokens(&context)
+                + tokenizer.count_tokens(&opening)
+                + tokenizer.count_tokens(closing),
+        );
+        let lines: Vec<&str> = node.content.trim_end().lines().collect();
+        let mut code = String::new();
+        for (index, line) in lines.iter().enumerate() {
+            let line = format!("{}\n", line);
+            let tokens = tokenizer.count_tokens(&line);
+            let needed = if index + 1 == lines.len() { 0 } else { reserve };
+            if tokens + needed <= available {
+                available -= tokens;
+                code.push_str(&line);
+                continue;
+            }
+            if available < reserve {
+                break;
+            }
+            // A first line too long on its own is cut instead
+            if code.is_empty() {
+                code.push_str(fitting_prefix(&line, available - reserve, tokenizer));
+                code.push('\n');
+            }
+            code.push_str(&format!("... {} more lines\n", lines.len() - index));
+            break;
+        }
+        if !code.is_empty() {
+            context.push_str(&opening);
+            context.push_str(&code);
+            context.push_str(closing);
+        }
+
+        debug!(
+            "Assembled {} tokens of context for {}",
+            tokenizer.count_tokens(&context),
+            node.name
+        );
+        Some(context)
+    }
+
+    // Lines describing a node, most important first
+    fn context_facts(&self, node: &CodeNode) -> Vec<String> {
+        let mut facts = vec![format!("{:?} {}", node.node_type, node.name)];
+        let mut location = format!(
+            "File: {} (lines {}-{})",
+            node.file_path, node.line_range.0, node.line_range.1
+        );
+        if let Some(language) = &node.language {
+            location.push_str(&format!(", {}", language));
+        }
+        facts.push(location);
+        if !node.content.trim().is_empty() {
+            facts.push(format!("Signature: {}", signature(node)));
+        }
+        if let Some(visibility) = node.visibility {
+            facts.push(format!("Visibility: {}", visibility.as_str()));
+        }
+        if let Some(declared_type) = &node.declared_type {
+            facts.push(format!("Type: {}", declared_type));
+        }
+
+        let parent = node
+            .parent_class_id
+            .as_deref()
+            .and_then(|id| self.get_node(id));
+        match (parent, &node.parent_class) {
+            (Some(parent), _) => facts.push(format!("Class: {}", signature(parent))),
+            (None, Some(parent_class)) => facts.push(format!("Class: {}", parent_class)),
+            (None, None) => {}
+        }
+        if let Some(summary) = &node.summary {
+            facts.push(format!("Summary: {}", summary));
+        }
+
+        let callers = named_signatures(self.find_callers(&node.id));
+        let callees = named_signatures(self.find_called_functions(&node.id));
+        facts.extend(callers.iter().map(|sig| format!("Called by: {}", sig)));
+        facts.extend(callees.iter().map(|sig| format!("Calls: {}", sig)));
+
+        // Imports of the file reach every node in it, so the node's own
+        // edges are the file's imports
+        let imports: BTreeSet<String> = self
+            .outgoing_relationships(&node.id)
+            .iter()
+            .filter(|rel| rel.relationship_type == RelationshipType::Imports)
+            .filter_map(|rel| self.get_node(&rel.to_id))
+            .map(|imported| match imported.file_path.as_str() {
+                "" => imported.name.clone(),
+                file_path => format!("{} ({})", imported.name, file_path),
+            })
+            .collect();
+        facts.extend(
+            imports
+                .into_iter()
+                .take(MAX_CONTEXT_IMPORTS)
+                .map(|import| format!("Imports: {}", import)),
+        );
+
+        facts
+    }
+}
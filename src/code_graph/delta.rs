@@ -0,0 +1,90 @@
+use super::{CodeGraph, CodeNode, Relationship};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// One change between two versions of a graph, as sent to event sinks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GraphEvent {
+    NodeAdded { node: CodeNode },
+    NodeUpdated { node: CodeNode },
+    NodeRemoved { id: String },
+    RelationshipAdded { relationship: Relationship },
+    RelationshipRemoved { relationship: Relationship },
+}
+
+// Edges are identified by their type, endpoints and provenance
+fn relationship_key(rel: &Relationship) -> (String, &str, &str, &str) {
+    (
+        format!("{:?}", rel.relationship_type),
+        rel.from_id.as_str(),
+        rel.to_id.as_str(),
+        rel.provenance.as_deref().unwrap_or_default(),
+    )
+}
+
+fn relationships_by_key(graph: &CodeGraph) -> BTreeMap<(String, &str, &str, &str), &Relationship> {
+    graph
+        .all_relationships()
+        .map(|rel| (relationship_key(rel), rel))
+        .collect()
+}
+
+impl CodeGraph {
+    // Events that turn `previous` into this graph: removals first, then
+    // additions and updates, each in ID order. An edge whose metadata
+    // changed is removed and added again.
+    pub fn changes_since(&self, previous: &CodeGraph) -> Vec<GraphEvent> {
+        let mut events = Vec::new();
+
+        let current_edges = relationships_by_key(self);
+        let previous_edges = relationships_by_key(previous);
+        for (key, rel) in &previous_edges {
+            if current_edges
+                .get(key)
+                .is_none_or(|current| current.metadata != rel.metadata)
+            {
+                events.push(GraphEvent::RelationshipRemoved {
+                    relationship: (*rel).clone(),
+                });
+            }
+        }
+
+        let mut removed: Vec<&String> = previous
+            .nodes
+            .keys()
+            .filter(|id| !self.nodes.contains_key(*id))
+            .collect();
+        removed.sort();
+        events.extend(
+            removed
+                .into_iter()
+                .map(|id| GraphEvent::NodeRemoved { id: id.clone() }),
+        );
+
+        let mut nodes: Vec<&CodeNode> = self.nodes.values().collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        for node in nodes {
+            match previous.nodes.get(&node.id) {
+                None => events.push(GraphEvent::NodeAdded { node: node.clone() }),
+                Some(old) if old != node => {
+                    events.push(GraphEvent::NodeUpdated { node: node.clone() })
+                }
+                Some(_) => {}
+            }
+        }
+
+        for (key, rel) in &current_edges {
+            if previous_edges
+                .get(key)
+                .is_none_or(|old| old.metadata != rel.metadata)
+            {
+                events.push(GraphEvent::RelationshipAdded {
+                    relationship: (*rel).clone(),
+                });
+            }
+        }
+
+        events
+    }
+}
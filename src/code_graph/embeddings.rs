@@ -0,0 +1,381 @@
+use super::{CodeGraph, CodeNode};
+use log::{debug, info};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+use std::io;
+
+// Turns node content and search queries into vectors in the same space
+pub trait Embedder: Send + Sync {
+    fn embed(&self, text: &str) -> io::Result<Vec<f32>>;
+
+    // Models served over the network usually take batches
+    fn embed_batch(&self, texts: &[&str]) -> io::Result<Vec<Vec<f32>>> {
+        texts.iter().map(|text| self.embed(text)).collect()
+    }
+}
+
+// Bag of identifier words hashed into a fixed number of dimensions, so
+// search works without a model: `parseConfig` and `parse_config` both count
+// as `parse` and `config`
+#[derive(Debug, Clone, Copy)]
+pub struct HashingEmbedder {
+    pub dimensions: usize,
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        HashingEmbedder { dimensions: 256 }
+    }
+}
+
+// Words of identifiers split at underscores and case changes
+pub(crate) fn identifier_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for token in text.split(|c: char| !c.is_alphanumeric()) {
+        let mut word = String::new();
+        let mut previous_lower = false;
+        for c in token.chars() {
+            if c.is_uppercase() && previous_lower {
+                words.push(std::mem::take(&mut word));
+            }
+            previous_lower = c.is_lowercase() || c.is_ascii_digit();
+            word.extend(c.to_lowercase());
+        }
+        words.push(word);
+    }
+    words.retain(|word| word.chars().count() > 1);
+    words
+}
+
+// 64-bit FNV-1a, fixed across builds and Rust versions so vectors stored
+// with a graph still match the ones computed for queries later
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl Embedder for HashingEmbedder {
+    fn embed(&self, text: &str) -> io::Result<Vec<f32>> {
+        let mut vector = vec![0.0; self.dimensions.max(1)];
+        for word in identifier_words(text) {
+            let hash = fnv1a(word.as_bytes());
+            let bucket = (hash % vector.len() as u64) as usize;
+            // The sign halves collisions between unrelated words
+            vector[bucket] += if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        }
+        Ok(normalized(vector))
+    }
+}
+
+fn normalized(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let norms = dot(a, a).sqrt() * dot(b, b).sqrt();
+    if norms > 0.0 { dot(a, b) / norms } else { 0.0 }
+}
+
+// Text a node is embedded from
+fn embedding_text(node: &CodeNode) -> String {
+    format!("{}\n{}", node.name, node.content)
+}
+
+// Neighbours kept per point above the bottom layer; the bottom keeps twice
+// as many
+const HNSW_NEIGHBORS: usize = 16;
+const HNSW_EF_CONSTRUCTION: usize = 100;
+const HNSW_EF_SEARCH: usize = 64;
+
+// Point with its distance to a query, ordered by distance
+#[derive(Clone, Copy, PartialEq)]
+struct Candidate {
+    distance: f32,
+    point: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.point.cmp(&other.point))
+    }
+}
+
+// Approximate nearest neighbour index over the embeddings of a graph
+// (hierarchical navigable small world graph). Worth building for graphs
+// with many thousands of embedded nodes, where scanning every vector for
+// each query gets slow.
+pub struct HnswIndex {
+    ids: Vec<String>,
+    // Unit length, so the distance is one minus the dot product
+    vectors: Vec<Vec<f32>>,
+    // Neighbours of every point on each layer it is part of
+    neighbors: Vec<Vec<Vec<usize>>>,
+    entry_point: Option<usize>,
+}
+
+impl HnswIndex {
+    pub fn build(graph: &CodeGraph) -> Self {
+        let mut embeddings: Vec<(&String, &Vec<f32>)> = graph
+            .embeddings
+            .iter()
+            .filter(|(id, _)| graph.get_node(id).is_some())
+            .collect();
+        embeddings.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut index = HnswIndex {
+            ids: Vec::new(),
+            vectors: Vec::new(),
+            neighbors: Vec::new(),
+            entry_point: None,
+        };
+        for (id, vector) in embeddings {
+            index.insert(id.clone(), normalized(vector.clone()));
+        }
+        debug!("Built an HNSW index over {} embeddings", index.ids.len());
+
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    // Layer from a hash of the ID, so the same graph always gives the same
+    // index: each layer holds about 1/HNSW_NEIGHBORS of the one below
+    fn level(id: &str) -> usize {
+        let uniform = (fnv1a(id.as_bytes()) >> 11) as f64 / (1u64 << 53) as f64;
+        let level = -(1.0 - uniform).ln() / (HNSW_NEIGHBORS as f64).ln();
+        level.floor() as usize
+    }
+
+    fn distance(&self, query: &[f32], point: usize) -> f32 {
+        1.0 - dot(query, &self.vectors[point])
+    }
+
+    fn max_neighbors(layer: usize) -> usize {
+        if layer == 0 {
+            HNSW_NEIGHBORS * 2
+        } else {
+            HNSW_NEIGHBORS
+        }
+    }
+
+    // The `ef` points closest to the query found on a layer, nearest first
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[usize],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<usize> = entry_points.iter().copied().collect();
+        let mut candidates = BinaryHeap::new();
+        let mut nearest = BinaryHeap::new();
+        for &point in entry_points {
+            let candidate = Candidate {
+                distance: self.distance(query, point),
+                point,
+            };
+            candidates.push(Reverse(candidate));
+            nearest.push(candidate);
+        }
+
+        while let Some(Reverse(candidate)) = candidates.pop() {
+            if nearest
+                .peek()
+                .is_some_and(|furthest: &Candidate| candidate.distance > furthest.distance)
+                && nearest.len() >= ef
+            {
+                break;
+            }
+            for &neighbor in &self.neighbors[candidate.point][layer] {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let distance = self.distance(query, neighbor);
+                if nearest.len() < ef || nearest.peek().is_some_and(|f| distance < f.distance) {
+                    let next = Candidate {
+                        distance,
+                        point: neighbor,
+                    };
+                    candidates.push(Reverse(next));
+                    nearest.push(next);
+                    if nearest.len() > ef {
+                        nearest.pop();
+                    }
+                }
+            }
+        }
+
+        nearest.into_sorted_vec()
+    }
+
+    // Walk down from the top layer to `layer`, following the closest point
+    fn descend(&self, query: &[f32], layer: usize) -> Option<usize> {
+        let mut entry = self.entry_point?;
+        for upper in (layer + 1..self.neighbors[entry].len()).rev() {
+            entry = self.search_layer(query, &[entry], 1, upper)[0].point;
+        }
+        Some(entry)
+    }
+
+    fn insert(&mut self, id: String, vector: Vec<f32>) {
+        let point = self.ids.len();
+        let level = Self::level(&id);
+        self.ids.push(id);
+        self.vectors.push(vector);
+        self.neighbors.push(vec![Vec::new(); level + 1]);
+
+        let Some(entry) = self.entry_point else {
+            self.entry_point = Some(point);
+            return;
+        };
+        let top = self.neighbors[entry].len() - 1;
+        let query = self.vectors[point].clone();
+
+        let mut entry_points = vec![self.descend(&query, level).unwrap_or(entry)];
+        for layer in (0..=level.min(top)).rev() {
+            let nearest = self.search_layer(&query, &entry_points, HNSW_EF_CONSTRUCTION, layer);
+            let limit = Self::max_neighbors(layer);
+            for candidate in nearest.iter().take(limit) {
+                self.neighbors[point][layer].push(candidate.point);
+                self.neighbors[candidate.point][layer].push(point);
+                // Only the closest links of an overfull point are kept
+                if self.neighbors[candidate.point][layer].len() > limit {
+                    let origin = self.vectors[candidate.point].clone();
+                    let mut links = std::mem::take(&mut self.neighbors[candidate.point][layer]);
+                    links.sort_by(|a, b| {
+                        self.distance(&origin, *a)
+                            .total_cmp(&self.distance(&origin, *b))
+                            .then(a.cmp(b))
+                    });
+                    links.truncate(limit);
+                    self.neighbors[candidate.point][layer] = links;
+                }
+            }
+            entry_points = nearest.iter().map(|candidate| candidate.point).collect();
+        }
+
+        if level > top {
+            self.entry_point = Some(point);
+        }
+    }
+
+    // Node IDs of the `k` embeddings most similar to the query, with their
+    // cosine similarity
+    pub fn search(&self, query: &[f32], k: usize) -> Vec<(&str, f32)> {
+        let query = normalized(query.to_vec());
+        if self.vectors.first().is_some_and(|v| v.len() != query.len()) {
+            return Vec::new();
+        }
+        let Some(entry) = self.descend(&query, 0) else {
+            return Vec::new();
+        };
+        self.search_layer(&query, &[entry], HNSW_EF_SEARCH.max(k), 0)
+            .into_iter()
+            .take(k)
+            .map(|candidate| (self.ids[candidate.point].as_str(), 1.0 - candidate.distance))
+            .collect()
+    }
+}
+
+impl CodeGraph {
+    // Embed every node with content that has no embedding yet and return
+    // how many were added
+    pub fn add_embeddings(&mut self, embedder: &dyn Embedder) -> io::Result<usize> {
+        let mut missing: Vec<&CodeNode> = self
+            .nodes
+            .values()
+            .filter(|node| !node.content.trim().is_empty())
+            .filter(|node| !self.embeddings.contains_key(&node.id))
+            .collect();
+        missing.sort_by(|a, b| a.id.cmp(&b.id));
+        info!("Embedding {} nodes", missing.len());
+
+        let ids: Vec<String> = missing.iter().map(|node| node.id.clone()).collect();
+        let texts: Vec<String> = missing.into_iter().map(embedding_text).collect();
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let vectors = embedder.embed_batch(&texts)?;
+        if vectors.len() != ids.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Embedder returned {} vectors for {} texts",
+                    vectors.len(),
+                    ids.len()
+                ),
+            ));
+        }
+        for (id, vector) in ids.into_iter().zip(vectors) {
+            self.embeddings.insert(id, vector);
+        }
+
+        Ok(texts.len())
+    }
+
+    pub fn embedding(&self, id: &str) -> Option<&[f32]> {
+        self.embeddings.get(id).map(Vec::as_slice)
+    }
+
+    pub fn set_embedding(&mut self, id: &str, vector: Vec<f32>) {
+        self.embeddings.insert(id.to_string(), vector);
+    }
+
+    // The `k` nodes whose embeddings are most similar to the query's, most
+    // similar first, with their cosine similarity. The index answers
+    // approximately; without one every embedding is compared.
+    pub fn semantic_search(
+        &self,
+        embedder: &dyn Embedder,
+        query: &str,
+        k: usize,
+        index: Option<&HnswIndex>,
+    ) -> io::Result<Vec<(&CodeNode, f32)>> {
+        let query = embedder.embed(query)?;
+
+        let mut results: Vec<(&CodeNode, f32)> = match index {
+            Some(index) => index
+                .search(&query, k)
+                .into_iter()
+                .filter_map(|(id, similarity)| Some((self.get_node(id)?, similarity)))
+                .collect(),
+            None => self
+                .embeddings
+                .iter()
+                .filter(|(_, vector)| vector.len() == query.len())
+                .filter_map(|(id, vector)| {
+                    Some((self.get_node(id)?, cosine_similarity(&query, vector)))
+                })
+                .collect(),
+        };
+        results.sort_by(|(a, a_score), (b, b_score)| {
+            b_score.total_cmp(a_score).then_with(|| a.id.cmp(&b.id))
+        });
+        results.truncate(k);
+
+        Ok(results)
+    }
+}
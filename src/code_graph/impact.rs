@@ -0,0 +1,63 @@
+use super::{CodeGraph, CodeNode, RelationshipType};
+use std::collections::{HashSet, VecDeque};
+
+// A node affected by a change, `depth` hops away from the nearest changed node
+#[derive(Debug, Clone)]
+pub struct ImpactedNode<'a> {
+    pub node: &'a CodeNode,
+    pub depth: usize,
+}
+
+impl CodeGraph {
+    // Everything that transitively depends on the given nodes: their callers,
+    // importers and containers, up to `max_depth` hops. The changed nodes
+    // themselves are not included. Ordered by depth, then file and line.
+    pub fn impacted_by(&self, node_ids: &[&str], max_depth: usize) -> Vec<ImpactedNode<'_>> {
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut queue = VecDeque::new();
+        for id in node_ids {
+            if let Some(node) = self.get_node(id)
+                && visited.insert(node.id.as_str())
+            {
+                queue.push_back((node.id.as_str(), 0));
+            }
+        }
+
+        let mut impacted = Vec::new();
+        while let Some((id, depth)) = queue.pop_front() {
+            if depth == max_depth {
+                continue;
+            }
+
+            for rel in self.incoming_relationships(id) {
+                if !matches!(
+                    rel.relationship_type,
+                    RelationshipType::Calls
+                        | RelationshipType::Imports
+                        | RelationshipType::Contains
+                ) {
+                    continue;
+                }
+
+                if let Some(node) = self.get_node(&rel.from_id)
+                    && visited.insert(node.id.as_str())
+                {
+                    impacted.push(ImpactedNode {
+                        node,
+                        depth: depth + 1,
+                    });
+                    queue.push_back((node.id.as_str(), depth + 1));
+                }
+            }
+        }
+
+        impacted.sort_by(|a, b| {
+            (a.depth, &a.node.file_path, a.node.line_range).cmp(&(
+                b.depth,
+                &b.node.file_path,
+                b.node.line_range,
+            ))
+        });
+        impacted
+    }
+}
@@ -0,0 +1,61 @@
+use super::CodeGraph;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// A codebase root the graph was indexed from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedRoot {
+    pub path: String,
+    // Commit the indexed files were read at, or HEAD of the working tree;
+    // None outside a git repository
+    #[serde(default)]
+    pub git_commit: Option<String>,
+}
+
+// Which snapshot of the code a graph represents and how it was indexed,
+// exported with the graph so downstream systems can tell dumps apart
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexProvenance {
+    pub tool: String,
+    pub tool_version: String,
+    // Seconds since the Unix epoch; `SOURCE_DATE_EPOCH` overrides the clock
+    // so that reproducible builds get identical exports
+    pub indexed_at: u64,
+    #[serde(default)]
+    pub roots: Vec<IndexedRoot>,
+    // Settings the graph was built with, by name
+    #[serde(default)]
+    pub config: BTreeMap<String, String>,
+}
+
+impl IndexProvenance {
+    pub fn new(roots: Vec<IndexedRoot>, config: BTreeMap<String, String>) -> Self {
+        let indexed_at = std::env::var("SOURCE_DATE_EPOCH")
+            .ok()
+            .and_then(|epoch| epoch.parse().ok())
+            .unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|elapsed| elapsed.as_secs())
+                    .unwrap_or_default()
+            });
+        IndexProvenance {
+            tool: env!("CARGO_PKG_NAME").to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            indexed_at,
+            roots,
+            config,
+        }
+    }
+}
+
+impl CodeGraph {
+    pub fn provenance(&self) -> Option<&IndexProvenance> {
+        self.provenance.as_ref()
+    }
+
+    pub fn set_provenance(&mut self, provenance: IndexProvenance) {
+        self.provenance = Some(provenance);
+    }
+}
@@ -0,0 +1,107 @@
+use super::{CodeGraph, CodeNode};
+use std::collections::HashMap;
+
+// Static interval tree over the line ranges of one file's nodes. Entries are
+// sorted by start line and form an implicit balanced tree (the middle entry
+// of a range is its root), each root storing the largest end line below it,
+// so overlap queries cost O(log n + k).
+#[derive(Debug, Clone, Default)]
+pub(super) struct LineIndex {
+    entries: Vec<(usize, usize, String)>,
+    subtree_max_end: Vec<usize>,
+}
+
+impl LineIndex {
+    fn build(mut entries: Vec<(usize, usize, String)>) -> Self {
+        entries.sort();
+        let mut index = LineIndex {
+            subtree_max_end: vec![0; entries.len()],
+            entries,
+        };
+        index.fill_max_end(0, index.entries.len());
+        index
+    }
+
+    fn fill_max_end(&mut self, lo: usize, hi: usize) -> usize {
+        if lo >= hi {
+            return 0;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let max_end = self.entries[mid]
+            .1
+            .max(self.fill_max_end(lo, mid))
+            .max(self.fill_max_end(mid + 1, hi));
+        self.subtree_max_end[mid] = max_end;
+        max_end
+    }
+
+    fn overlapping<'a>(&'a self, start: usize, end: usize, out: &mut Vec<&'a str>) {
+        self.search(0, self.entries.len(), start, end, out);
+    }
+
+    fn search<'a>(
+        &'a self,
+        lo: usize,
+        hi: usize,
+        start: usize,
+        end: usize,
+        out: &mut Vec<&'a str>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if self.subtree_max_end[mid] < start {
+            return;
+        }
+
+        self.search(lo, mid, start, end, out);
+
+        let (entry_start, entry_end, id) = &self.entries[mid];
+        if *entry_start > end {
+            return;
+        }
+        if *entry_end >= start {
+            out.push(id);
+        }
+
+        self.search(mid + 1, hi, start, end, out);
+    }
+}
+
+pub(super) fn build_line_indices(graph: &CodeGraph) -> HashMap<String, LineIndex> {
+    graph
+        .nodes_by_file
+        .iter()
+        .map(|(file, ids)| {
+            let entries = ids
+                .iter()
+                .filter_map(|id| graph.nodes.get(id))
+                .map(|node| (node.line_range.0, node.line_range.1, node.id.clone()))
+                .collect();
+            (file.clone(), LineIndex::build(entries))
+        })
+        .collect()
+}
+
+impl CodeGraph {
+    // Nodes of a file whose line range intersects `start_line..=end_line`,
+    // ordered by start line. Used to map changed hunks onto nodes.
+    pub fn find_nodes_overlapping(
+        &self,
+        file_path: &str,
+        start_line: usize,
+        end_line: usize,
+    ) -> Vec<&CodeNode> {
+        let indices = self.line_index.get_or_init(|| build_line_indices(self));
+        let Some(index) = indices.get(file_path) else {
+            return Vec::new();
+        };
+
+        let mut ids = Vec::new();
+        index.overlapping(start_line, end_line, &mut ids);
+        ids.into_iter()
+            .filter_map(|id| self.nodes.get(id))
+            .collect()
+    }
+}
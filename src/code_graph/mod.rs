@@ -1,36 +1,184 @@
+mod aggregate;
+pub mod algorithms;
+mod builder;
+pub mod chunks;
+mod compact;
+mod content;
+mod context;
+mod delta;
+pub mod embeddings;
+mod impact;
+mod index_provenance;
+mod intervals;
 mod node;
+mod outline;
+mod paths;
+pub mod query;
+mod refs;
 mod relationship;
+pub(crate) mod schema;
+mod shards;
+mod stats;
+mod subgraph;
+pub mod tokens;
+mod traverse;
+mod usage;
+mod validate;
+pub mod view;
 
-pub use node::{CodeNode, NodeType};
-pub use relationship::{Relationship, RelationshipType};
+pub use aggregate::Aggregation;
+pub use builder::CodeGraphBuilder;
+pub use chunks::{Chunk, ChunkOptions, chunk_graph};
+pub use compact::CompactionStats;
+pub use content::ContentLimit;
+pub use delta::GraphEvent;
+pub use embeddings::{Embedder, HashingEmbedder, HnswIndex};
+pub use impact::ImpactedNode;
+pub use index_provenance::{IndexProvenance, IndexedRoot};
+pub use node::{CodeNode, ComplexityMetrics, NodeType, Signature, Visibility};
+pub use outline::OutlineNode;
+pub use paths::RelationshipPath;
+pub use query::Query;
+pub use refs::{EdgeRef, NodeRef};
+pub use relationship::{Relationship, RelationshipType, provenance};
+pub use schema::SCHEMA_VERSION;
+pub use shards::{CrossShardEdge, GraphShards, SHARD_INDEX};
+pub use stats::{FileStats, GraphStats};
+pub use subgraph::SubgraphFilter;
+pub use tokens::{TokenEncoding, Tokenizer};
+pub use traverse::{Traversal, TraversalOptions, TraversedNode};
+pub use usage::SymbolUsage;
+pub use validate::ValidationReport;
+pub use view::{ViewConfig, ViewDefinition};
 
-use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::OnceLock;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+// Which edges to follow when walking the graph from a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Direction {
+    #[default]
+    Outgoing,
+    Incoming,
+    Both,
+}
+
+// Hash maps and sets are serialized in key order so that exports of the same
+// input are byte-identical
+fn sorted_map<S, K, V>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize + Ord,
+    V: Serialize,
+{
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
+fn sorted_index<S, K>(map: &HashMap<K, HashSet<String>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize + Ord,
+{
+    serializer.collect_map(
+        map.iter()
+            .map(|(key, ids)| (key, ids.iter().collect::<BTreeSet<_>>()))
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
+
+// Edge lists are filled by passes that may run in any order
+fn sorted_edges<S>(
+    map: &HashMap<String, Vec<Relationship>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_map(
+        map.iter()
+            .map(|(id, edges)| {
+                let mut edges: Vec<&Relationship> = edges.iter().collect();
+                edges.sort_by(|a, b| {
+                    (&a.relationship_type, &a.from_id, &a.to_id, &a.provenance).cmp(&(
+                        &b.relationship_type,
+                        &b.from_id,
+                        &b.to_id,
+                        &b.provenance,
+                    ))
+                });
+                (id, edges)
+            })
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct CodeGraph {
+    #[serde(default)]
+    schema_version: schema::SchemaVersion,
+    // How and from what the graph was indexed; first, so it leads exports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    provenance: Option<IndexProvenance>,
+    #[serde(serialize_with = "sorted_map")]
     nodes: HashMap<String, CodeNode>,
+    #[serde(serialize_with = "sorted_edges")]
     outgoing_edges: HashMap<String, Vec<Relationship>>,
+    #[serde(serialize_with = "sorted_edges")]
     incoming_edges: HashMap<String, Vec<Relationship>>,
 
+    #[serde(serialize_with = "sorted_index")]
     nodes_by_type: HashMap<NodeType, HashSet<String>>,
+    #[serde(serialize_with = "sorted_index")]
     nodes_by_file: HashMap<String, HashSet<String>>,
+    #[serde(serialize_with = "sorted_index")]
     nodes_by_name: HashMap<String, HashSet<String>>,
+    #[serde(default, serialize_with = "sorted_index")]
+    nodes_by_language: HashMap<String, HashSet<String>>,
+
+    // Embedding vectors by node ID, filled by `add_embeddings`
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted_map"
+    )]
+    embeddings: HashMap<String, Vec<f32>>,
+
+    // Built on first use and dropped whenever nodes may have changed
+    #[serde(skip)]
+    line_index: OnceLock<HashMap<String, intervals::LineIndex>>,
 }
 
 impl CodeGraph {
     pub fn new() -> Self {
         CodeGraph {
+            schema_version: schema::SchemaVersion,
+            provenance: None,
             nodes: HashMap::new(),
             outgoing_edges: HashMap::new(),
             incoming_edges: HashMap::new(),
             nodes_by_type: HashMap::new(),
             nodes_by_file: HashMap::new(),
             nodes_by_name: HashMap::new(),
+            nodes_by_language: HashMap::new(),
+            embeddings: HashMap::new(),
+            line_index: OnceLock::new(),
         }
     }
 
     pub fn add_node(&mut self, node: CodeNode) {
+        self.index_node(&node);
+        self.line_index.take();
+        // A replaced node's vector was embedded from its old content
+        self.embeddings.remove(&node.id);
+
+        self.outgoing_edges.entry(node.id.clone()).or_default();
+        self.incoming_edges.entry(node.id.clone()).or_default();
+
+        self.nodes.insert(node.id.clone(), node);
+    }
+
+    fn index_node(&mut self, node: &CodeNode) {
         self.nodes_by_type
             .entry(node.node_type.clone())
             .or_insert_with(HashSet::new)
@@ -46,14 +194,12 @@ impl CodeGraph {
             .or_insert_with(HashSet::new)
             .insert(node.id.clone());
 
-        self.outgoing_edges
-            .entry(node.id.clone())
-            .or_insert_with(Vec::new);
-        self.incoming_edges
-            .entry(node.id.clone())
-            .or_insert_with(Vec::new);
-
-        self.nodes.insert(node.id.clone(), node);
+        if let Some(language) = &node.language {
+            self.nodes_by_language
+                .entry(language.clone())
+                .or_default()
+                .insert(node.id.clone());
+        }
     }
 
     pub fn add_relationship(&mut self, relationship: Relationship) {
@@ -107,6 +253,13 @@ impl CodeGraph {
             .unwrap_or_else(Vec::new)
     }
 
+    pub fn find_nodes_by_language(&self, language: &str) -> Vec<&CodeNode> {
+        self.nodes_by_language
+            .get(language)
+            .map(|ids| ids.iter().filter_map(|id| self.nodes.get(id)).collect())
+            .unwrap_or_default()
+    }
+
     pub fn find_nodes_in_file(&self, file_path: &str) -> Vec<&CodeNode> {
         self.nodes_by_file
             .get(file_path)
@@ -114,38 +267,32 @@ impl CodeGraph {
             .unwrap_or_else(Vec::new)
     }
 
-    pub fn find_related_nodes(&self, node_id: &str, depth: usize) -> HashSet<&CodeNode> {
-        let mut result = HashSet::new();
-        let mut to_visit = vec![(node_id.to_string(), 0)];
-        let mut visited = HashSet::new();
-
-        while let Some((current_id, current_depth)) = to_visit.pop() {
-            if current_depth > depth || visited.contains(&current_id) {
-                continue;
-            }
-
-            visited.insert(current_id.clone());
-
-            if let Some(node) = self.nodes.get(&current_id) {
-                result.insert(node);
-
-                if current_depth < depth {
-                    if let Some(outgoing) = self.outgoing_edges.get(&current_id) {
-                        for rel in outgoing {
-                            to_visit.push((rel.to_id.clone(), current_depth + 1));
-                        }
-                    }
+    // Case-insensitive substring match over node names
+    pub fn search_nodes_by_name(&self, query: &str) -> Vec<&CodeNode> {
+        let query = query.to_lowercase();
+        self.nodes_by_name
+            .iter()
+            .filter(|(name, _)| name.to_lowercase().contains(&query))
+            .flat_map(|(_, ids)| ids.iter().filter_map(|id| self.nodes.get(id)))
+            .collect()
+    }
 
-                    if let Some(incoming) = self.incoming_edges.get(&current_id) {
-                        for rel in incoming {
-                            to_visit.push((rel.from_id.clone(), current_depth + 1));
-                        }
-                    }
-                }
-            }
-        }
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.nodes_by_file.keys().map(|path| path.as_str())
+    }
 
-        result
+    // Nodes within `depth` relationships of a node, in either direction
+    pub fn find_related_nodes(&self, node_id: &str, depth: usize) -> HashSet<&CodeNode> {
+        let options = TraversalOptions {
+            direction: Direction::Both,
+            max_depth: depth,
+            ..Default::default()
+        };
+        self.traverse(node_id, &options)
+            .nodes
+            .into_iter()
+            .map(|traversed| traversed.node)
+            .collect()
     }
 
     pub fn get_node(&self, id: &str) -> Option<&CodeNode> {
@@ -153,17 +300,68 @@ impl CodeGraph {
     }
 
     pub fn get_node_mut(&mut self, id: &str) -> Option<&mut CodeNode> {
+        self.line_index.take();
         self.nodes.get_mut(id)
     }
 
+    // Rename a node and keep the name index in sync
+    pub fn rename_node(&mut self, id: &str, name: String) -> bool {
+        let Some(node) = self.nodes.get_mut(id) else {
+            return false;
+        };
+
+        if let Some(ids) = self.nodes_by_name.get_mut(&node.name) {
+            ids.remove(id);
+            if ids.is_empty() {
+                self.nodes_by_name.remove(&node.name);
+            }
+        }
+        self.nodes_by_name
+            .entry(name.clone())
+            .or_default()
+            .insert(id.to_string());
+        node.name = name;
+
+        true
+    }
+
     pub fn all_nodes(&self) -> impl Iterator<Item = &CodeNode> {
         self.nodes.values()
     }
 
     pub fn all_nodes_mut(&mut self) -> impl Iterator<Item = &mut CodeNode> {
+        self.line_index.take();
         self.nodes.values_mut()
     }
 
+    // Every stored copy of every relationship: each is kept once among the
+    // outgoing and once among the incoming edges, and both copies must be
+    // changed alike
+    pub fn all_relationships_mut(&mut self) -> impl Iterator<Item = &mut Relationship> {
+        self.outgoing_edges
+            .values_mut()
+            .chain(self.incoming_edges.values_mut())
+            .flatten()
+    }
+
+    pub fn outgoing_relationships(&self, node_id: &str) -> &[Relationship] {
+        self.outgoing_edges
+            .get(node_id)
+            .map(|rels| rels.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn incoming_relationships(&self, node_id: &str) -> &[Relationship] {
+        self.incoming_edges
+            .get(node_id)
+            .map(|rels| rels.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn all_relationships(&self) -> impl Iterator<Item = &Relationship> {
+        self.outgoing_edges.values().flatten()
+    }
+
     pub fn node_count(&self) -> usize {
         self.nodes.len()
     }
@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::hash::{Hash, Hasher};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum NodeType {
     Function,
     Method,
@@ -10,9 +10,68 @@ pub enum NodeType {
     Interface,
     Module,
     TypeDefinition,
+    Variable,
+    Dependency,
+    // Prose documentation and the examples embedded in it
+    Documentation,
+    // Source files and the directories above them
+    File,
+    Directory,
+    // Placeholder for a call or import target not defined in the indexed code
+    ExternalSymbol,
+    // An HTTP route, by method and path, served by a handler function
+    Endpoint,
+    // A macro definition, such as Rust's `macro_rules!`
+    Macro,
+    // A field of a struct, a class property or an interface member
+    Field,
     Unknown,
 }
 
+impl NodeType {
+    pub const ALL: &[NodeType] = &[
+        NodeType::Function,
+        NodeType::Method,
+        NodeType::Class,
+        NodeType::Interface,
+        NodeType::Module,
+        NodeType::TypeDefinition,
+        NodeType::Variable,
+        NodeType::Dependency,
+        NodeType::Documentation,
+        NodeType::File,
+        NodeType::Directory,
+        NodeType::ExternalSymbol,
+        NodeType::Endpoint,
+        NodeType::Macro,
+        NodeType::Field,
+        NodeType::Unknown,
+    ];
+
+    // Case-insensitive, accepting `type_definition` as well as `TypeDefinition`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace('_', "").as_str() {
+            "function" => Some(NodeType::Function),
+            "method" => Some(NodeType::Method),
+            "class" => Some(NodeType::Class),
+            "interface" => Some(NodeType::Interface),
+            "module" => Some(NodeType::Module),
+            "typedefinition" => Some(NodeType::TypeDefinition),
+            "variable" => Some(NodeType::Variable),
+            "dependency" => Some(NodeType::Dependency),
+            "documentation" => Some(NodeType::Documentation),
+            "file" => Some(NodeType::File),
+            "directory" => Some(NodeType::Directory),
+            "externalsymbol" => Some(NodeType::ExternalSymbol),
+            "endpoint" => Some(NodeType::Endpoint),
+            "macro" => Some(NodeType::Macro),
+            "field" => Some(NodeType::Field),
+            "unknown" => Some(NodeType::Unknown),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub struct CodeNode {
     pub id: String,
@@ -22,7 +81,100 @@ pub struct CodeNode {
     pub line_range: (usize, usize),
     pub content: String,
     pub summary: Option<String>,
+    // Attributes without a field of their own, such as `test` or `decorators`
     pub metadata: HashMap<String, String>,
+    // Class, struct or service a method or field is declared in, and the ID
+    // of its node once resolved within the file
+    #[serde(default)]
+    pub parent_class: Option<String>,
+    #[serde(default)]
+    pub parent_class_id: Option<String>,
+    // `module` or `local` for variables
+    #[serde(default)]
+    pub scope: Option<String>,
+    // Type annotation of a variable
+    #[serde(default)]
+    pub declared_type: Option<String>,
+    #[serde(default)]
+    pub visibility: Option<Visibility>,
+    // Parameters of a function or method
+    #[serde(default)]
+    pub signature: Option<Signature>,
+    // Root the node was indexed from when several codebases share a graph
+    #[serde(default)]
+    pub repo: Option<String>,
+    // ID namespace of the graph, a repository name or commit, so nodes of
+    // graphs merged into one store can be told apart
+    #[serde(default)]
+    pub namespace: Option<String>,
+    // Language of the file the node comes from, `rust` or `python`
+    #[serde(default)]
+    pub language: Option<String>,
+    // Hash of the full source text, kept when content is truncated or
+    // left out of an export
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    // Size and complexity of functions and methods
+    #[serde(default)]
+    pub complexity: Option<ComplexityMetrics>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ComplexityMetrics {
+    // Independent paths through the body: one plus every branch and loop
+    pub cyclomatic_complexity: usize,
+    pub statements: usize,
+    // Deepest nesting of control flow blocks
+    pub nesting_depth: usize,
+    pub parameters: usize,
+}
+
+// Visibility inferred from modifiers, naming conventions and exports
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    Public,
+    // Visible within the crate, package or assembly
+    Internal,
+    Private,
+}
+
+impl Visibility {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "public" => Some(Visibility::Public),
+            "internal" => Some(Visibility::Internal),
+            "private" => Some(Visibility::Private),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Internal => "internal",
+            Visibility::Private => "private",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct Signature {
+    // Arguments a call may pass: at least `min_arity`, at most `arity`
+    // unless the function is variadic
+    pub arity: usize,
+    pub min_arity: usize,
+    #[serde(default)]
+    pub variadic: bool,
+    // Declared type of every parameter, `_` where none is given
+    #[serde(default)]
+    pub parameter_types: Vec<String>,
+}
+
+impl Signature {
+    pub fn accepts(&self, arguments: usize) -> bool {
+        self.min_arity <= arguments && (arguments <= self.arity || self.variadic)
+    }
 }
 
 impl Hash for CodeNode {
@@ -44,7 +196,23 @@ impl Serialize for CodeNode {
     {
         use serde::ser::SerializeStruct;
 
-        let mut state = serializer.serialize_struct("CodeNode", 7)?;
+        // Binary formats write the field count up front, so it leaves out
+        // the optional fields that are skipped
+        let optional = [
+            self.parent_class.is_some(),
+            self.parent_class_id.is_some(),
+            self.scope.is_some(),
+            self.declared_type.is_some(),
+            self.visibility.is_some(),
+            self.signature.is_some(),
+            self.repo.is_some(),
+            self.namespace.is_some(),
+            self.language.is_some(),
+            self.content_hash.is_some(),
+            self.complexity.is_some(),
+        ];
+        let len = 8 + optional.iter().filter(|present| **present).count();
+        let mut state = serializer.serialize_struct("CodeNode", len)?;
         state.serialize_field("id", &self.id)?;
         state.serialize_field("node_type", &self.node_type)?;
         state.serialize_field("name", &self.name)?;
@@ -52,7 +220,50 @@ impl Serialize for CodeNode {
         state.serialize_field("line_range", &self.line_range)?;
         state.serialize_field("content", &self.content)?;
         state.serialize_field("summary", &self.summary)?;
-        state.serialize_field("metadata", &self.metadata)?;
+        state.serialize_field(
+            "metadata",
+            &self.metadata.iter().collect::<BTreeMap<_, _>>(),
+        )?;
+        let fields = [
+            ("parent_class", &self.parent_class),
+            ("parent_class_id", &self.parent_class_id),
+            ("scope", &self.scope),
+            ("declared_type", &self.declared_type),
+        ];
+        for (name, value) in fields {
+            match value {
+                Some(value) => state.serialize_field(name, value)?,
+                None => state.skip_field(name)?,
+            }
+        }
+        match &self.visibility {
+            Some(visibility) => state.serialize_field("visibility", visibility)?,
+            None => state.skip_field("visibility")?,
+        }
+        match &self.signature {
+            Some(signature) => state.serialize_field("signature", signature)?,
+            None => state.skip_field("signature")?,
+        }
+        match &self.repo {
+            Some(repo) => state.serialize_field("repo", repo)?,
+            None => state.skip_field("repo")?,
+        }
+        match &self.namespace {
+            Some(namespace) => state.serialize_field("namespace", namespace)?,
+            None => state.skip_field("namespace")?,
+        }
+        match &self.language {
+            Some(language) => state.serialize_field("language", language)?,
+            None => state.skip_field("language")?,
+        }
+        match &self.content_hash {
+            Some(hash) => state.serialize_field("content_hash", hash)?,
+            None => state.skip_field("content_hash")?,
+        }
+        match &self.complexity {
+            Some(complexity) => state.serialize_field("complexity", complexity)?,
+            None => state.skip_field("complexity")?,
+        }
         state.end()
     }
 }
@@ -75,6 +286,17 @@ impl CodeNode {
             content,
             summary: None,
             metadata: HashMap::new(),
+            parent_class: None,
+            parent_class_id: None,
+            scope: None,
+            declared_type: None,
+            visibility: None,
+            signature: None,
+            repo: None,
+            namespace: None,
+            language: None,
+            content_hash: None,
+            complexity: None,
         }
     }
 
@@ -91,4 +313,51 @@ impl CodeNode {
         self.add_metadata(key, value);
         self
     }
+
+    pub fn with_parent_class(mut self, parent_class: String) -> Self {
+        self.parent_class = Some(parent_class);
+        self
+    }
+
+    pub fn with_scope(mut self, scope: &str) -> Self {
+        self.scope = Some(scope.to_string());
+        self
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.visibility == Some(Visibility::Public)
+    }
+
+    // The metadata map with the typed attributes added back as strings, for
+    // stores that keep node attributes as flat key-value pairs
+    pub fn flat_metadata(&self) -> BTreeMap<&str, String> {
+        let mut flat: BTreeMap<&str, String> = self
+            .metadata
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.clone()))
+            .collect();
+        let fields = [
+            ("parent_class", &self.parent_class),
+            ("parent_class_id", &self.parent_class_id),
+            ("scope", &self.scope),
+            ("type", &self.declared_type),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                flat.insert(key, value.clone());
+            }
+        }
+        if let Some(visibility) = self.visibility {
+            flat.insert("visibility", visibility.as_str().to_string());
+        }
+        if let Some(signature) = &self.signature {
+            flat.insert("arity", signature.arity.to_string());
+            flat.insert("min_arity", signature.min_arity.to_string());
+            if signature.variadic {
+                flat.insert("variadic", "true".to_string());
+            }
+            flat.insert("parameter_types", signature.parameter_types.join(", "));
+        }
+        flat
+    }
 }
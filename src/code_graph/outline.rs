@@ -0,0 +1,114 @@
+use super::{CodeGraph, CodeNode, NodeType, RelationshipType};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+// A symbol of a file with the symbols nested in it, ordered by line
+#[derive(Debug, Clone)]
+pub struct OutlineNode<'a> {
+    pub node: &'a CodeNode,
+    pub children: Vec<OutlineNode<'a>>,
+}
+
+impl<'a> OutlineNode<'a> {
+    // Nodes from this one down to the innermost child covering `line`, as
+    // shown in a breadcrumb; empty when this node does not cover it
+    pub fn path_to_line(&self, line: usize) -> Vec<&'a CodeNode> {
+        let (start, end) = self.node.line_range;
+        if line < start || line > end {
+            return Vec::new();
+        }
+        let mut path = vec![self.node];
+        if let Some(child) = self
+            .children
+            .iter()
+            .map(|child| child.path_to_line(line))
+            .find(|child_path| !child_path.is_empty())
+        {
+            path.extend(child);
+        }
+        path
+    }
+}
+
+// Outer nodes before the ones they enclose: by start line, then longest first
+fn outline_key(node: &CodeNode) -> (usize, Reverse<usize>, &str) {
+    (node.line_range.0, Reverse(node.line_range.1), &node.id)
+}
+
+impl CodeGraph {
+    // Symbols of a file as a tree ordered by line, such as classes over their
+    // methods and modules over their functions. A Contains edge from another
+    // node of the file picks the parent, so members declared outside their
+    // type (Go methods, for one) still nest under it; other nodes nest in
+    // the innermost node whose lines enclose theirs. File and Directory
+    // nodes are left out. Empty when the file has no nodes.
+    pub fn file_outline(&self, file_path: &str) -> Vec<OutlineNode<'_>> {
+        let mut nodes: Vec<&CodeNode> = self
+            .find_nodes_in_file(file_path)
+            .into_iter()
+            .filter(|node| !matches!(node.node_type, NodeType::File | NodeType::Directory))
+            .collect();
+        nodes.sort_by(|a, b| outline_key(a).cmp(&outline_key(b)));
+        let position: HashMap<&str, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.id.as_str(), index))
+            .collect();
+
+        // Parents always come earlier in the order, so the tree has no cycles
+        let encloses = |outer: &CodeNode, inner: &CodeNode| {
+            outer.line_range.0 <= inner.line_range.0 && inner.line_range.1 <= outer.line_range.1
+        };
+        let mut parents: Vec<Option<usize>> = vec![None; nodes.len()];
+        let mut open: Vec<usize> = Vec::new();
+        for (index, node) in nodes.iter().enumerate() {
+            while open.last().is_some_and(|&top| !encloses(nodes[top], node)) {
+                open.pop();
+            }
+
+            let containers: Vec<usize> = self
+                .incoming_relationships(&node.id)
+                .iter()
+                .filter(|rel| rel.relationship_type == RelationshipType::Contains)
+                .filter_map(|rel| position.get(rel.from_id.as_str()).copied())
+                .filter(|&parent| parent < index)
+                .collect();
+            // Methods are linked to every class of their name, so one that
+            // encloses them is preferred
+            let container = containers
+                .iter()
+                .copied()
+                .filter(|&parent| encloses(nodes[parent], node))
+                .max()
+                .or_else(|| containers.iter().copied().max());
+
+            parents[index] = container.or(open.last().copied());
+            open.push(index);
+        }
+
+        // Children have higher positions than their parents, so building
+        // from the last node completes every subtree before it is attached
+        let mut built: Vec<Option<OutlineNode>> = nodes
+            .iter()
+            .map(|node| {
+                Some(OutlineNode {
+                    node,
+                    children: Vec::new(),
+                })
+            })
+            .collect();
+        let mut roots = Vec::new();
+        for index in (0..nodes.len()).rev() {
+            let Some(mut item) = built[index].take() else {
+                continue;
+            };
+            item.children.reverse();
+            match parents[index].and_then(|parent| built[parent].as_mut()) {
+                Some(parent) => parent.children.push(item),
+                None => roots.push(item),
+            }
+        }
+        roots.reverse();
+        roots
+    }
+}
@@ -0,0 +1,134 @@
+use super::{CodeGraph, Relationship, RelationshipType};
+use log::debug;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// Upper bound on paths returned by `find_paths`, which grows exponentially
+// with depth on densely connected graphs
+const MAX_PATHS: usize = 1000;
+
+// A chain of relationships, each starting where the previous one ended
+pub type RelationshipPath<'a> = Vec<&'a Relationship>;
+
+impl CodeGraph {
+    // All simple paths of outgoing relationships from one node to another
+    // with at most `max_depth` hops, shortest first. An empty filter follows
+    // every relationship type.
+    pub fn find_paths(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        max_depth: usize,
+        rel_filter: &[RelationshipType],
+    ) -> Vec<RelationshipPath<'_>> {
+        let (Some(from), Some(_)) = (self.get_node(from_id), self.get_node(to_id)) else {
+            return Vec::new();
+        };
+
+        let mut search = PathSearch {
+            graph: self,
+            to_id,
+            rel_filter,
+            current: Vec::new(),
+            on_path: HashSet::from([from.id.as_str()]),
+            paths: Vec::new(),
+        };
+        search.visit(from_id, max_depth);
+        let mut paths = search.paths;
+
+        if paths.len() >= MAX_PATHS {
+            debug!(
+                "Stopped after {} paths from {} to {}",
+                MAX_PATHS, from_id, to_id
+            );
+        }
+
+        paths.sort_by_key(|path| path.len());
+        paths
+    }
+
+    // One shortest path of outgoing relationships, found breadth first
+    pub fn shortest_path(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        max_depth: usize,
+        rel_filter: &[RelationshipType],
+    ) -> Option<RelationshipPath<'_>> {
+        self.get_node(to_id)?;
+        self.get_node(from_id)?;
+
+        let mut reached_by: HashMap<&str, &Relationship> = HashMap::new();
+        let mut queue = VecDeque::from([(from_id, 0)]);
+        let mut visited = HashSet::from([from_id]);
+
+        while let Some((id, depth)) = queue.pop_front() {
+            if id == to_id {
+                let mut path = Vec::new();
+                let mut current = to_id;
+                while current != from_id {
+                    let rel = reached_by[current];
+                    path.push(rel);
+                    current = rel.from_id.as_str();
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            if depth == max_depth {
+                continue;
+            }
+
+            for rel in self.outgoing_relationships(id) {
+                if follows(rel_filter, &rel.relationship_type) && visited.insert(&rel.to_id) {
+                    reached_by.insert(&rel.to_id, rel);
+                    queue.push_back((&rel.to_id, depth + 1));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// Depth-first enumeration state for `find_paths`
+struct PathSearch<'a, 'q> {
+    graph: &'a CodeGraph,
+    to_id: &'q str,
+    rel_filter: &'q [RelationshipType],
+    current: Vec<&'a Relationship>,
+    on_path: HashSet<&'a str>,
+    paths: Vec<RelationshipPath<'a>>,
+}
+
+impl<'a> PathSearch<'a, '_> {
+    fn visit(&mut self, id: &str, remaining: usize) {
+        if remaining == 0 {
+            return;
+        }
+
+        for rel in self.graph.outgoing_relationships(id) {
+            if self.paths.len() >= MAX_PATHS {
+                return;
+            }
+            if !follows(self.rel_filter, &rel.relationship_type)
+                || self.on_path.contains(rel.to_id.as_str())
+            {
+                continue;
+            }
+
+            self.current.push(rel);
+            if rel.to_id == self.to_id {
+                self.paths.push(self.current.clone());
+            } else {
+                self.on_path.insert(&rel.to_id);
+                self.visit(&rel.to_id, remaining - 1);
+                self.on_path.remove(rel.to_id.as_str());
+            }
+            self.current.pop();
+        }
+    }
+}
+
+fn follows(rel_filter: &[RelationshipType], relationship_type: &RelationshipType) -> bool {
+    rel_filter.is_empty() || rel_filter.contains(relationship_type)
+}
@@ -0,0 +1,325 @@
+use super::{CodeGraph, CodeNode, Direction, NodeType, RelationshipType};
+use globset::{Glob, GlobMatcher};
+use log::debug;
+use regex::Regex;
+use std::collections::HashSet;
+use std::io;
+
+// A parsed graph query such as
+//   type:Method name:~"handle.*" file:"src/api/**" -> calls -> type:Function
+// Selectors are space separated `key:value` terms that must all match
+// (`type`, `name`, `name:~` for a regex, `file` as a glob, `repo`, `namespace`,
+// `language`, `owner` from CODEOWNERS metadata, `id`).
+// Steps are `-> rel ->` (outgoing), `<- rel <-` (incoming) or `<-> rel <->`
+// (both), where `rel` is a relationship type or `*` for any.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    pub start: NodeSelector,
+    pub steps: Vec<QueryStep>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryStep {
+    pub direction: Direction,
+    pub relationship_type: Option<RelationshipType>,
+    pub target: NodeSelector,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NodeSelector {
+    pub id: Option<String>,
+    pub node_types: Vec<NodeType>,
+    pub name: Option<NameMatch>,
+    pub file: Option<GlobMatcher>,
+    pub repo: Option<String>,
+    pub namespace: Option<String>,
+    pub language: Option<String>,
+    pub owner: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum NameMatch {
+    Exact(String),
+    Pattern(Regex),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Arrow(Direction),
+    Word(String),
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+impl Query {
+    pub fn parse(input: &str) -> io::Result<Self> {
+        let tokens = tokenize(input)?;
+        let mut tokens = tokens.into_iter().peekable();
+
+        let mut query = Query {
+            start: parse_selector(&mut tokens)?,
+            steps: Vec::new(),
+        };
+
+        while let Some(token) = tokens.next() {
+            let Token::Arrow(direction) = token else {
+                return Err(invalid(format!("Expected an arrow, found {:?}", token)));
+            };
+
+            let relationship_type = match tokens.next() {
+                Some(Token::Word(word)) => parse_relationship_type(&word)?,
+                _ => return Err(invalid("Expected a relationship type after arrow".into())),
+            };
+
+            match tokens.next() {
+                Some(Token::Arrow(closing)) if closing == direction => {}
+                _ => {
+                    return Err(invalid(format!(
+                        "Relationship step must be closed with the same arrow ({:?})",
+                        direction
+                    )));
+                }
+            }
+
+            query.steps.push(QueryStep {
+                direction,
+                relationship_type,
+                target: parse_selector(&mut tokens)?,
+            });
+        }
+
+        Ok(query)
+    }
+
+    pub fn step(mut self, step: QueryStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    // Nodes reached by the last step, ordered by file and position
+    pub fn execute<'a>(&self, graph: &'a CodeGraph) -> Vec<&'a CodeNode> {
+        let mut current: Vec<&CodeNode> = self
+            .start
+            .candidates(graph)
+            .into_iter()
+            .filter(|node| self.start.matches(node))
+            .collect();
+        debug!("Query start matched {} nodes", current.len());
+
+        for step in &self.steps {
+            let mut seen = HashSet::new();
+            let mut next = Vec::new();
+            for node in current {
+                for neighbor in step.reachable(graph, &node.id) {
+                    if seen.insert(neighbor.id.as_str()) {
+                        next.push(neighbor);
+                    }
+                }
+            }
+            debug!("Query step {:?} matched {} nodes", step, next.len());
+            current = next;
+        }
+
+        current.sort_by(|a, b| {
+            (&a.file_path, a.line_range, &a.name).cmp(&(&b.file_path, b.line_range, &b.name))
+        });
+        current
+    }
+}
+
+impl QueryStep {
+    fn neighbors<'a>(&self, graph: &'a CodeGraph, id: &str) -> Vec<&'a CodeNode> {
+        graph
+            .neighbors(id, self.direction, self.relationship_type.clone())
+            .map(|neighbor| neighbor.node)
+            .collect()
+    }
+
+    // Neighbours of a node this step leads to
+    pub fn reachable<'a>(&self, graph: &'a CodeGraph, id: &str) -> Vec<&'a CodeNode> {
+        self.neighbors(graph, id)
+            .into_iter()
+            .filter(|node| self.target.matches(node))
+            .collect()
+    }
+}
+
+impl NodeSelector {
+    pub fn matches(&self, node: &CodeNode) -> bool {
+        if self.id.as_ref().is_some_and(|id| id != &node.id) {
+            return false;
+        }
+
+        if !self.node_types.is_empty() && !self.node_types.contains(&node.node_type) {
+            return false;
+        }
+
+        let name_matches = match &self.name {
+            Some(NameMatch::Exact(name)) => &node.name == name,
+            Some(NameMatch::Pattern(regex)) => regex.is_match(&node.name),
+            None => true,
+        };
+
+        name_matches
+            && self
+                .file
+                .as_ref()
+                .is_none_or(|glob| glob.is_match(&node.file_path))
+            && self
+                .repo
+                .as_ref()
+                .is_none_or(|repo| node.repo.as_ref() == Some(repo))
+            && self
+                .namespace
+                .as_ref()
+                .is_none_or(|namespace| node.namespace.as_ref() == Some(namespace))
+            && self
+                .language
+                .as_ref()
+                .is_none_or(|language| node.language.as_ref() == Some(language))
+            && self.owner.as_ref().is_none_or(|owner| {
+                node.metadata
+                    .get("owners")
+                    .is_some_and(|owners| owners.split(',').any(|o| o == owner))
+            })
+    }
+
+    // Narrow the start set with the most selective index available
+    fn candidates<'a>(&self, graph: &'a CodeGraph) -> Vec<&'a CodeNode> {
+        if let Some(id) = &self.id {
+            return graph.get_node(id).into_iter().collect();
+        }
+
+        if let Some(NameMatch::Exact(name)) = &self.name {
+            return graph.find_nodes_by_name(name);
+        }
+
+        if !self.node_types.is_empty() {
+            return self
+                .node_types
+                .iter()
+                .flat_map(|node_type| graph.find_nodes_by_type(node_type))
+                .collect();
+        }
+
+        graph.all_nodes().collect()
+    }
+}
+
+fn tokenize(input: &str) -> io::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut word = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            chars.next();
+
+            if c == '"' {
+                // Quoted values may contain spaces and escaped quotes
+                let mut closed = false;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        '\\' if chars.peek() == Some(&'"') => word.push(chars.next().unwrap()),
+                        _ => word.push(c),
+                    }
+                }
+                if !closed {
+                    return Err(invalid(format!("Unterminated quote in query: {}", input)));
+                }
+            } else {
+                word.push(c);
+            }
+        }
+
+        tokens.push(match word.as_str() {
+            "->" => Token::Arrow(Direction::Outgoing),
+            "<-" => Token::Arrow(Direction::Incoming),
+            "<->" => Token::Arrow(Direction::Both),
+            _ => Token::Word(word),
+        });
+    }
+
+    Ok(tokens)
+}
+
+fn parse_selector(
+    tokens: &mut std::iter::Peekable<std::vec::IntoIter<Token>>,
+) -> io::Result<NodeSelector> {
+    let mut selector = NodeSelector::default();
+
+    while let Some(Token::Word(term)) = tokens.peek() {
+        let term = term.clone();
+        tokens.next();
+
+        if term == "*" {
+            continue;
+        }
+
+        let Some((key, value)) = term.split_once(':') else {
+            return Err(invalid(format!("Expected key:value, found '{}'", term)));
+        };
+
+        match key {
+            "id" => selector.id = Some(value.to_string()),
+            "type" => {
+                for name in value.split(',') {
+                    selector.node_types.push(parse_node_type(name)?);
+                }
+            }
+            "name" => {
+                selector.name = Some(match value.strip_prefix('~') {
+                    Some(pattern) => {
+                        NameMatch::Pattern(Regex::new(pattern).map_err(|e| invalid(e.to_string()))?)
+                    }
+                    None => NameMatch::Exact(value.to_string()),
+                });
+            }
+            "file" => {
+                let glob = Glob::new(value).map_err(|e| invalid(e.to_string()))?;
+                selector.file = Some(glob.compile_matcher());
+            }
+            "repo" => selector.repo = Some(value.to_string()),
+            "namespace" => selector.namespace = Some(value.to_string()),
+            "language" => selector.language = Some(value.to_lowercase()),
+            "owner" => selector.owner = Some(value.to_string()),
+            _ => return Err(invalid(format!("Unknown query key: {}", key))),
+        }
+    }
+
+    Ok(selector)
+}
+
+fn parse_node_type(name: &str) -> io::Result<NodeType> {
+    NodeType::parse(name).ok_or_else(|| invalid(format!("Unknown node type: {}", name)))
+}
+
+fn parse_relationship_type(name: &str) -> io::Result<Option<RelationshipType>> {
+    if name == "*" || name == "any" {
+        return Ok(None);
+    }
+
+    RelationshipType::parse(name)
+        .map(Some)
+        .ok_or_else(|| invalid(format!("Unknown relationship type: {}", name)))
+}
+
+impl CodeGraph {
+    pub fn query(&self, query: &str) -> io::Result<Vec<&CodeNode>> {
+        Ok(Query::parse(query)?.execute(self))
+    }
+}
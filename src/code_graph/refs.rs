@@ -0,0 +1,135 @@
+use super::{CodeGraph, CodeNode, Direction, Relationship, RelationshipType};
+use std::fmt;
+
+// A node borrowed from its graph, cheap to copy from hop to hop
+#[derive(Clone, Copy)]
+pub struct NodeRef<'a> {
+    pub graph: &'a CodeGraph,
+    pub node: &'a CodeNode,
+}
+
+// A relationship as seen from one of its nodes: `direction` is Outgoing when
+// that node is the source and Incoming when it is the target
+#[derive(Clone, Copy)]
+pub struct EdgeRef<'a> {
+    pub graph: &'a CodeGraph,
+    pub relationship: &'a Relationship,
+    pub direction: Direction,
+}
+
+impl fmt::Debug for NodeRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NodeRef").field(self.node).finish()
+    }
+}
+
+impl fmt::Debug for EdgeRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EdgeRef")
+            .field("relationship", self.relationship)
+            .field("direction", &self.direction)
+            .finish()
+    }
+}
+
+impl<'a> NodeRef<'a> {
+    pub fn id(&self) -> &'a str {
+        &self.node.id
+    }
+
+    pub fn edges(&self, direction: Direction) -> impl Iterator<Item = EdgeRef<'a>> + use<'a> {
+        self.graph.edges(&self.node.id, direction)
+    }
+
+    pub fn neighbors(
+        &self,
+        direction: Direction,
+        relationship_type: Option<RelationshipType>,
+    ) -> impl Iterator<Item = NodeRef<'a>> + use<'a> {
+        self.graph
+            .neighbors(&self.node.id, direction, relationship_type)
+    }
+}
+
+impl<'a> EdgeRef<'a> {
+    pub fn relationship_type(&self) -> &'a RelationshipType {
+        &self.relationship.relationship_type
+    }
+
+    pub fn source(&self) -> Option<NodeRef<'a>> {
+        self.graph.node_ref(&self.relationship.from_id)
+    }
+
+    pub fn target(&self) -> Option<NodeRef<'a>> {
+        self.graph.node_ref(&self.relationship.to_id)
+    }
+
+    // ID of the node at the far end from the one the edge was reached from
+    pub fn other_id(&self) -> &'a str {
+        match self.direction {
+            Direction::Incoming => &self.relationship.from_id,
+            _ => &self.relationship.to_id,
+        }
+    }
+
+    // The node at the far end, None when the edge dangles
+    pub fn other(&self) -> Option<NodeRef<'a>> {
+        self.graph.node_ref(self.other_id())
+    }
+}
+
+impl CodeGraph {
+    pub fn node_ref(&self, id: &str) -> Option<NodeRef<'_>> {
+        self.nodes.get(id).map(|node| NodeRef { graph: self, node })
+    }
+
+    // Relationships of a node in the given direction, outgoing ones first
+    // when both are wanted. Borrowed, so walking them copies no IDs.
+    pub fn edges<'a>(
+        &'a self,
+        id: &str,
+        direction: Direction,
+    ) -> impl Iterator<Item = EdgeRef<'a>> + use<'a> {
+        let outgoing = match direction {
+            Direction::Incoming => &[][..],
+            _ => self.outgoing_relationships(id),
+        };
+        let incoming = match direction {
+            Direction::Outgoing => &[][..],
+            _ => self.incoming_relationships(id),
+        };
+        let edge = |direction| {
+            move |relationship| EdgeRef {
+                graph: self,
+                relationship,
+                direction,
+            }
+        };
+        outgoing
+            .iter()
+            .map(edge(Direction::Outgoing))
+            .chain(incoming.iter().map(edge(Direction::Incoming)))
+    }
+
+    // All relationships of a node, outgoing then incoming
+    pub fn edges_of<'a>(&'a self, id: &str) -> impl Iterator<Item = EdgeRef<'a>> + use<'a> {
+        self.edges(id, Direction::Both)
+    }
+
+    // Nodes one hop away in the given direction, optionally only across
+    // relationships of one type. Edges to missing nodes are skipped.
+    pub fn neighbors<'a>(
+        &'a self,
+        id: &str,
+        direction: Direction,
+        relationship_type: Option<RelationshipType>,
+    ) -> impl Iterator<Item = NodeRef<'a>> + use<'a> {
+        self.edges(id, direction)
+            .filter(move |edge| {
+                relationship_type
+                    .as_ref()
+                    .is_none_or(|wanted| wanted == edge.relationship_type())
+            })
+            .filter_map(|edge| edge.other())
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum RelationshipType {
     Calls,
     Imports,
@@ -10,6 +10,77 @@ pub enum RelationshipType {
     Implements,
     Contains,
     DependsOn,
+    // From a test to the production code it exercises
+    Tests,
+    // From a symbol to the documentation that mentions it
+    DocumentedBy,
+    // From a function to an earlier function with a near-identical body
+    DuplicateOf,
+    // From a decorated declaration to the decorator or annotation definition
+    AnnotatedWith,
+}
+
+impl RelationshipType {
+    // Case-insensitive, accepting `depends_on` as well as `DependsOn`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace('_', "").as_str() {
+            "calls" => Some(RelationshipType::Calls),
+            "imports" => Some(RelationshipType::Imports),
+            "inherits" => Some(RelationshipType::Inherits),
+            "references" => Some(RelationshipType::References),
+            "implements" => Some(RelationshipType::Implements),
+            "contains" => Some(RelationshipType::Contains),
+            "dependson" => Some(RelationshipType::DependsOn),
+            "tests" => Some(RelationshipType::Tests),
+            "documentedby" => Some(RelationshipType::DocumentedBy),
+            "duplicateof" => Some(RelationshipType::DuplicateOf),
+            "annotatedwith" => Some(RelationshipType::AnnotatedWith),
+            _ => None,
+        }
+    }
+}
+
+// Names of the passes that create relationships, recorded as provenance
+pub mod provenance {
+    pub const CALL_QUERY: &str = "call-query";
+    pub const IMPORT_MATCH: &str = "import-match";
+    pub const HIERARCHY_CONTAINMENT: &str = "hierarchy-containment";
+    pub const METHOD_CLASS_METADATA: &str = "method-class-metadata";
+    pub const REFERENCE_QUERY: &str = "reference-query";
+    pub const PACKAGE_MANIFEST: &str = "package-manifest";
+    pub const TEST_CALLS: &str = "test-calls";
+    pub const GRAPHQL_RESOLVER: &str = "graphql-resolver";
+    pub const GRAPHQL_DOCUMENT: &str = "graphql-document";
+    pub const PROTO_SCHEMA: &str = "proto-schema";
+    pub const DOC_MENTIONS: &str = "doc-mentions";
+    pub const DUPLICATE_CODE: &str = "duplicate-code";
+    pub const DIRECTORY_HIERARCHY: &str = "directory-hierarchy";
+    pub const EXTERNAL_SYMBOL: &str = "external-symbol";
+    pub const INCLUDE_RESOLUTION: &str = "include-resolution";
+    pub const DECORATOR_MATCH: &str = "decorator-match";
+    pub const ROUTE_DETECTION: &str = "route-detection";
+    pub const INTERFACE_IMPLEMENTATION: &str = "interface-implementation";
+
+    pub const ALL: &[&str] = &[
+        CALL_QUERY,
+        IMPORT_MATCH,
+        HIERARCHY_CONTAINMENT,
+        METHOD_CLASS_METADATA,
+        REFERENCE_QUERY,
+        PACKAGE_MANIFEST,
+        TEST_CALLS,
+        GRAPHQL_RESOLVER,
+        GRAPHQL_DOCUMENT,
+        PROTO_SCHEMA,
+        DOC_MENTIONS,
+        DUPLICATE_CODE,
+        DIRECTORY_HIERARCHY,
+        EXTERNAL_SYMBOL,
+        INCLUDE_RESOLUTION,
+        DECORATOR_MATCH,
+        ROUTE_DETECTION,
+        INTERFACE_IMPLEMENTATION,
+    ];
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,7 +88,11 @@ pub struct Relationship {
     pub relationship_type: RelationshipType,
     pub from_id: String,
     pub to_id: String,
+    #[serde(serialize_with = "super::sorted_map")]
     pub metadata: HashMap<String, String>,
+    // Pass that produced this edge; absent for hand-built or older graphs
+    #[serde(default)]
+    pub provenance: Option<String>,
 }
 
 impl Relationship {
@@ -27,9 +102,15 @@ impl Relationship {
             from_id,
             to_id,
             metadata: HashMap::new(),
+            provenance: None,
         }
     }
 
+    pub fn with_provenance(mut self, provenance: &str) -> Self {
+        self.provenance = Some(provenance.to_string());
+        self
+    }
+
     pub fn add_metadata(&mut self, key: String, value: String) {
         self.metadata.insert(key, value);
     }
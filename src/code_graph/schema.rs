@@ -0,0 +1,170 @@
+use super::CodeGraph;
+use log::info;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Value, json};
+use std::io;
+use std::path::Path;
+
+// Version of the serialized graph structure, bumped whenever a change needs
+// older dumps to be migrated when they are loaded
+pub const SCHEMA_VERSION: u32 = 3;
+
+// Graphs written before the version was recorded
+const UNVERSIONED: u32 = 1;
+
+// Rewrites the self-describing form of a graph in place
+type Migration = fn(&mut Value);
+
+// Upgrades from the version they are listed with to the next one, applied
+// in order
+const MIGRATIONS: &[(u32, Migration)] = &[(1, node_languages), (2, typed_metadata)];
+
+// Always written as the current version: older graphs are migrated before
+// they are deserialized
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct SchemaVersion;
+
+impl Serialize for SchemaVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(SCHEMA_VERSION)
+    }
+}
+
+impl<'de> Deserialize<'de> for SchemaVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(|_| SchemaVersion)
+    }
+}
+
+fn unversioned() -> u32 {
+    UNVERSIONED
+}
+
+// Only the version of a serialized graph, read first to decide whether it
+// needs migrating
+#[derive(Debug, Deserialize)]
+pub(crate) struct SchemaProbe {
+    #[serde(default = "unversioned")]
+    pub schema_version: u32,
+}
+
+// Reject graphs written by a newer version of the crate, whose structure
+// cannot be known
+pub(crate) fn check_version(version: u32) -> io::Result<()> {
+    if version > SCHEMA_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Graph schema version {} is newer than the supported version {}",
+                version, SCHEMA_VERSION
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// Bring a graph of an older schema version up to the current structure
+pub(crate) fn upgrade(mut value: Value, version: u32) -> io::Result<CodeGraph> {
+    check_version(version)?;
+    for (from, migration) in MIGRATIONS {
+        if *from >= version {
+            info!(
+                "Migrating graph from schema version {} to {}",
+                from,
+                from + 1
+            );
+            migration(&mut value);
+        }
+    }
+
+    let mut graph: CodeGraph =
+        serde_json::from_value(value).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    graph.rebuild_indexes();
+    Ok(graph)
+}
+
+// Version 2 records the language as a node field. External symbols kept it
+// in metadata; other nodes get it from their file extension, as statistics
+// used to.
+fn node_languages(graph: &mut Value) {
+    let Some(nodes) = graph.get_mut("nodes").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for node in nodes.values_mut() {
+        if node
+            .get("language")
+            .is_some_and(|language| !language.is_null())
+        {
+            continue;
+        }
+        let language = node
+            .get_mut("metadata")
+            .and_then(Value::as_object_mut)
+            .and_then(|metadata| metadata.remove("language"))
+            .and_then(|language| language.as_str().map(str::to_string))
+            .or_else(|| {
+                let file_path = node.get("file_path")?.as_str()?;
+                crate::parsers::detect_language(Path::new(file_path))
+            });
+        if let (Some(language), Some(node)) = (language, node.as_object_mut()) {
+            node.insert("language".to_string(), Value::String(language));
+        }
+    }
+}
+
+// Version 3 moves common metadata into typed node fields
+fn typed_metadata(graph: &mut Value) {
+    let Some(nodes) = graph.get_mut("nodes").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for node in nodes.values_mut() {
+        let Some(node) = node.as_object_mut() else {
+            continue;
+        };
+        let Some(Value::Object(mut metadata)) = node.remove("metadata") else {
+            continue;
+        };
+        let fields = [
+            ("parent_class", "parent_class"),
+            ("parent_class_id", "parent_class_id"),
+            ("scope", "scope"),
+            ("type", "declared_type"),
+            ("visibility", "visibility"),
+        ];
+        for (key, field) in fields {
+            if let Some(value) = metadata.remove(key) {
+                node.insert(field.to_string(), value);
+            }
+        }
+
+        // Erlang functions keep an `arity` of their own without the bounds
+        if metadata.contains_key("min_arity") {
+            let count = |value: Option<Value>| value?.as_str()?.parse::<usize>().ok();
+            let arity = count(metadata.remove("arity")).unwrap_or_default();
+            let min_arity = count(metadata.remove("min_arity")).unwrap_or_default();
+            let variadic = metadata.remove("variadic").is_some();
+            let parameter_types: Vec<String> = metadata
+                .remove("parameter_types")
+                .as_ref()
+                .and_then(Value::as_str)
+                .map(|types| {
+                    types
+                        .split(", ")
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            node.insert(
+                "signature".to_string(),
+                json!({
+                    "arity": arity,
+                    "min_arity": min_arity,
+                    "variadic": variadic,
+                    "parameter_types": parameter_types,
+                }),
+            );
+        }
+        node.insert("metadata".to_string(), Value::Object(metadata));
+    }
+}
@@ -0,0 +1,159 @@
+use super::{CodeGraph, NodeType, Relationship};
+use log::debug;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+// Shard of nodes without a file, such as dependencies and external symbols
+pub const EXTERNAL_SHARD: &str = "_external";
+// Shard of files directly in the shared root directory
+pub const ROOT_SHARD: &str = "_root";
+// Name of the index written next to the shards of an export
+pub const SHARD_INDEX: &str = "_index";
+
+// An edge between nodes of two shards, kept out of both shard graphs
+#[derive(Debug, Clone, Serialize)]
+pub struct CrossShardEdge {
+    pub from_shard: String,
+    pub to_shard: String,
+    pub relationship: Relationship,
+}
+
+// A graph split by top-level directory
+#[derive(Debug, Clone, Default)]
+pub struct GraphShards {
+    // Directory the shard names are relative to
+    pub root: PathBuf,
+    pub shards: BTreeMap<String, CodeGraph>,
+    // Edges between shards, by the shard they leave
+    pub cross_edges: BTreeMap<String, Vec<CrossShardEdge>>,
+}
+
+impl CodeGraph {
+    // Split the graph into one graph per directory directly below the
+    // deepest directory shared by all files. Edges within a shard stay in
+    // its graph; the others are returned separately.
+    pub fn shard_by_directory(&self) -> GraphShards {
+        // Directory nodes sit at or above the root, so only files decide it
+        let mut root: Option<PathBuf> = None;
+        let files = self
+            .nodes
+            .values()
+            .filter(|node| node.node_type != NodeType::Directory && !node.file_path.is_empty())
+            .map(|node| node.file_path.as_str());
+        for file in files {
+            let path = Path::new(file);
+            let root = root.get_or_insert_with(|| path.parent().unwrap_or(path).to_path_buf());
+            while !path.starts_with(&*root) && root.pop() {}
+        }
+        let root = root.unwrap_or_default();
+
+        let mut members: BTreeMap<String, HashSet<&str>> = BTreeMap::new();
+        let mut shard_of: HashMap<&str, String> = HashMap::new();
+        for node in self.nodes.values() {
+            let shard = shard_name(
+                &root,
+                &node.file_path,
+                node.node_type == NodeType::Directory,
+            );
+            members
+                .entry(shard.clone())
+                .or_default()
+                .insert(node.id.as_str());
+            shard_of.insert(node.id.as_str(), shard);
+        }
+        debug!(
+            "Splitting graph into {} shards under {:?}",
+            members.len(),
+            root
+        );
+
+        let mut cross_edges: BTreeMap<String, Vec<CrossShardEdge>> = BTreeMap::new();
+        for rel in self.all_relationships() {
+            let (Some(from), Some(to)) = (
+                shard_of.get(rel.from_id.as_str()),
+                shard_of.get(rel.to_id.as_str()),
+            ) else {
+                continue;
+            };
+            if from != to {
+                cross_edges
+                    .entry(from.clone())
+                    .or_default()
+                    .push(CrossShardEdge {
+                        from_shard: from.clone(),
+                        to_shard: to.clone(),
+                        relationship: rel.clone(),
+                    });
+            }
+        }
+        for edges in cross_edges.values_mut() {
+            edges.sort_by(|a, b| {
+                (
+                    &a.relationship.from_id,
+                    &a.relationship.to_id,
+                    &a.relationship.relationship_type,
+                )
+                    .cmp(&(
+                        &b.relationship.from_id,
+                        &b.relationship.to_id,
+                        &b.relationship.relationship_type,
+                    ))
+            });
+        }
+
+        GraphShards {
+            shards: members
+                .into_iter()
+                .map(|(shard, ids)| (shard, self.induced_subgraph(&ids)))
+                .collect(),
+            cross_edges,
+            root,
+        }
+    }
+}
+
+// First path component below the root, made safe to use as a file name
+fn shard_name(root: &Path, file_path: &str, is_directory: bool) -> String {
+    if file_path.is_empty() {
+        return EXTERNAL_SHARD.to_string();
+    }
+    let path = Path::new(file_path);
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let mut components = relative
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)));
+    let first = components.next();
+    let nested = is_directory || components.next().is_some();
+    match first.filter(|_| nested) {
+        Some(first) => escape_shard_name(
+            first
+                .as_os_str()
+                .to_string_lossy()
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect(),
+        ),
+        // A file directly in the root, or the root directory itself
+        None => ROOT_SHARD.to_string(),
+    }
+}
+
+// Directory names that could be taken for the files of an export get one
+// more underscore: a leading one keeps `_root`, `_external` and `_index`
+// free, and a trailing one after `.cross` keeps `<shard>.cross.json` free
+fn escape_shard_name(mut name: String) -> String {
+    if name.starts_with('_') {
+        name.insert(0, '_');
+    }
+    if name.trim_end_matches('_').ends_with(".cross") {
+        name.push('_');
+    }
+    name
+}
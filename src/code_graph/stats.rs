@@ -0,0 +1,102 @@
+use super::CodeGraph;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Files listed in `GraphStats::largest_files`
+const LARGEST_FILES: usize = 10;
+
+// Size of one file in the graph
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FileStats {
+    pub file_path: String,
+    pub nodes: usize,
+    // Last line any node of the file reaches
+    pub lines: usize,
+}
+
+// Counts describing a graph, from `CodeGraph::stats`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GraphStats {
+    pub nodes: usize,
+    pub relationships: usize,
+    pub files: usize,
+    pub nodes_by_type: BTreeMap<String, usize>,
+    // Nodes without a language, such as directories, count as `other`
+    pub nodes_by_language: BTreeMap<String, usize>,
+    pub nodes_by_file: BTreeMap<String, usize>,
+    pub relationships_by_type: BTreeMap<String, usize>,
+    // Edges per node, counting both ends of every edge
+    pub average_degree: f64,
+    // Files with the most nodes, largest first
+    pub largest_files: Vec<FileStats>,
+    // IDs of nodes without any incoming or outgoing edge
+    pub orphaned_nodes: Vec<String>,
+}
+
+impl CodeGraph {
+    pub fn stats(&self) -> GraphStats {
+        let mut stats = GraphStats {
+            nodes: self.node_count(),
+            relationships: self.relationship_count(),
+            files: self.files().count(),
+            ..Default::default()
+        };
+
+        let mut lines: BTreeMap<&str, usize> = BTreeMap::new();
+        for node in self.all_nodes() {
+            *stats
+                .nodes_by_type
+                .entry(format!("{:?}", node.node_type))
+                .or_default() += 1;
+            let language = node.language.as_deref().unwrap_or("other");
+            *stats
+                .nodes_by_language
+                .entry(language.to_string())
+                .or_default() += 1;
+            if !node.file_path.is_empty() {
+                *stats
+                    .nodes_by_file
+                    .entry(node.file_path.clone())
+                    .or_default() += 1;
+                let last = lines.entry(node.file_path.as_str()).or_default();
+                *last = (*last).max(node.line_range.1);
+            }
+            if self.outgoing_relationships(&node.id).is_empty()
+                && self.incoming_relationships(&node.id).is_empty()
+            {
+                stats.orphaned_nodes.push(node.id.clone());
+            }
+        }
+        stats.orphaned_nodes.sort();
+
+        for rel in self.all_relationships() {
+            *stats
+                .relationships_by_type
+                .entry(format!("{:?}", rel.relationship_type))
+                .or_default() += 1;
+        }
+        if stats.nodes > 0 {
+            stats.average_degree = 2.0 * stats.relationships as f64 / stats.nodes as f64;
+        }
+
+        let mut files: Vec<FileStats> = stats
+            .nodes_by_file
+            .iter()
+            .map(|(file_path, nodes)| FileStats {
+                file_path: file_path.clone(),
+                nodes: *nodes,
+                lines: lines.get(file_path.as_str()).copied().unwrap_or_default(),
+            })
+            .collect();
+        files.sort_by(|a, b| {
+            b.nodes
+                .cmp(&a.nodes)
+                .then_with(|| b.lines.cmp(&a.lines))
+                .then_with(|| a.file_path.cmp(&b.file_path))
+        });
+        files.truncate(LARGEST_FILES);
+        stats.largest_files = files;
+
+        stats
+    }
+}
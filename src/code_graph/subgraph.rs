@@ -0,0 +1,90 @@
+use super::{CodeGraph, CodeNode, NodeType};
+use log::debug;
+use std::collections::HashSet;
+use std::path::{Component, Path};
+
+// Which nodes to keep when cutting a smaller graph out of a larger one
+#[derive(Debug, Clone)]
+pub enum SubgraphFilter {
+    // Nodes in files under a directory or matching a file path, compared by
+    // whole path components
+    PathPrefix(String),
+    NodeIds(HashSet<String>),
+    NodeTypes(Vec<NodeType>),
+    // A seed node and everything within `depth` edges of it, either direction
+    Neighborhood { seed: String, depth: usize },
+}
+
+impl SubgraphFilter {
+    fn select<'a>(&self, graph: &'a CodeGraph) -> HashSet<&'a str> {
+        match self {
+            SubgraphFilter::PathPrefix(prefix) => graph
+                .nodes_by_file
+                .iter()
+                .filter(|(file, _)| is_under(file, prefix))
+                .flat_map(|(_, ids)| ids.iter().map(String::as_str))
+                .collect(),
+            SubgraphFilter::NodeIds(ids) => ids
+                .iter()
+                .filter_map(|id| graph.nodes.get_key_value(id))
+                .map(|(id, _)| id.as_str())
+                .collect(),
+            SubgraphFilter::NodeTypes(node_types) => node_types
+                .iter()
+                .filter_map(|node_type| graph.nodes_by_type.get(node_type))
+                .flat_map(|ids| ids.iter().map(String::as_str))
+                .collect(),
+            SubgraphFilter::Neighborhood { seed, depth } => graph
+                .find_related_nodes(seed, *depth)
+                .into_iter()
+                .map(|node| node.id.as_str())
+                .collect(),
+        }
+    }
+}
+
+// Path prefix check that ignores `./` so relative roots compare equal
+fn is_under(file: &str, prefix: &str) -> bool {
+    components(file).starts_with(&components(prefix))
+}
+
+fn components(path: &str) -> Vec<Component<'_>> {
+    Path::new(path)
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+impl CodeGraph {
+    // A fully indexed copy of the nodes matched by `filter` and the edges
+    // running between them
+    pub fn subgraph(&self, filter: &SubgraphFilter) -> CodeGraph {
+        let included = filter.select(self);
+        debug!("Subgraph filter matched {} nodes", included.len());
+        self.induced_subgraph(&included)
+    }
+
+    pub(super) fn induced_subgraph(&self, included: &HashSet<&str>) -> CodeGraph {
+        // Insert in a stable order so exports of the same cut are identical
+        let mut nodes: Vec<&CodeNode> = included
+            .iter()
+            .filter_map(|id| self.nodes.get(*id))
+            .collect();
+        nodes.sort_by(|a, b| {
+            (&a.file_path, a.line_range, &a.id).cmp(&(&b.file_path, b.line_range, &b.id))
+        });
+
+        let mut subgraph = CodeGraph::new();
+        subgraph.provenance = self.provenance.clone();
+        for node in nodes {
+            subgraph.add_node(node.clone());
+        }
+        for rel in self.all_relationships() {
+            if included.contains(rel.from_id.as_str()) && included.contains(rel.to_id.as_str()) {
+                subgraph.add_relationship(rel.clone());
+            }
+        }
+
+        subgraph
+    }
+}
@@ -0,0 +1,138 @@
+use super::{CodeGraph, CodeNode};
+use log::debug;
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+
+// Counts tokens the way the embedding or language model downstream does
+pub trait Tokenizer: Send + Sync {
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+// About four bytes per token, which is close to BPE tokenizers on source code
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApproximateTokenizer;
+
+impl Tokenizer for ApproximateTokenizer {
+    fn count_tokens(&self, text: &str) -> usize {
+        text.len().div_ceil(4)
+    }
+}
+
+// Exact counts for an OpenAI BPE encoding
+#[cfg(feature = "tiktoken")]
+pub struct TiktokenTokenizer {
+    bpe: &'static tiktoken_rs::CoreBPE,
+}
+
+#[cfg(feature = "tiktoken")]
+impl TiktokenTokenizer {
+    pub fn cl100k() -> Self {
+        TiktokenTokenizer {
+            bpe: tiktoken_rs::cl100k_base_singleton(),
+        }
+    }
+
+    pub fn o200k() -> Self {
+        TiktokenTokenizer {
+            bpe: tiktoken_rs::o200k_base_singleton(),
+        }
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl Tokenizer for TiktokenTokenizer {
+    fn count_tokens(&self, text: &str) -> usize {
+        self.bpe.encode_ordinary(text).len()
+    }
+}
+
+// Tokenizers that can be picked by name; the BPE encodings need the
+// `tiktoken` feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenEncoding {
+    #[default]
+    Approximate,
+    Cl100k,
+    O200k,
+}
+
+impl TokenEncoding {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "approximate" => Some(TokenEncoding::Approximate),
+            "cl100k_base" | "cl100k" => Some(TokenEncoding::Cl100k),
+            "o200k_base" | "o200k" => Some(TokenEncoding::O200k),
+            _ => None,
+        }
+    }
+
+    pub fn tokenizer(&self) -> io::Result<Arc<dyn Tokenizer>> {
+        match self {
+            TokenEncoding::Approximate => Ok(Arc::new(ApproximateTokenizer)),
+            #[cfg(feature = "tiktoken")]
+            TokenEncoding::Cl100k => Ok(Arc::new(TiktokenTokenizer::cl100k())),
+            #[cfg(feature = "tiktoken")]
+            TokenEncoding::O200k => Ok(Arc::new(TiktokenTokenizer::o200k())),
+            #[cfg(not(feature = "tiktoken"))]
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("The {:?} encoding needs the tiktoken feature enabled", self),
+            )),
+        }
+    }
+}
+
+impl CodeGraph {
+    // Store the token count of every node's content as `token_count`
+    pub fn add_token_counts(&mut self, tokenizer: &dyn Tokenizer) {
+        let mut total = 0;
+        for node in self.all_nodes_mut() {
+            let count = tokenizer.count_tokens(&node.content);
+            total += count;
+            node.metadata
+                .insert("token_count".to_string(), count.to_string());
+        }
+        debug!("Counted {} tokens over {} nodes", total, self.node_count());
+    }
+
+    // Up to `k` of the highest scored nodes whose content fits in
+    // `max_tokens` together, with their token counts. Nodes are taken in
+    // score order and skipped when they no longer fit, so smaller ones
+    // further down can still fill the budget. Stored `token_count` metadata
+    // is used where present.
+    pub fn select_within_budget(
+        &self,
+        scores: &HashMap<String, f64>,
+        k: usize,
+        max_tokens: usize,
+        tokenizer: &dyn Tokenizer,
+    ) -> Vec<(&CodeNode, usize)> {
+        let mut ranked: Vec<(&CodeNode, f64)> = scores
+            .iter()
+            .filter_map(|(id, score)| Some((self.get_node(id)?, *score)))
+            .collect();
+        ranked.sort_by(|(a, a_score), (b, b_score)| {
+            b_score.total_cmp(a_score).then_with(|| a.id.cmp(&b.id))
+        });
+
+        let mut selected = Vec::new();
+        let mut remaining = max_tokens;
+        for (node, _) in ranked {
+            if selected.len() >= k {
+                break;
+            }
+            let tokens = node
+                .metadata
+                .get("token_count")
+                .and_then(|count| count.parse().ok())
+                .unwrap_or_else(|| tokenizer.count_tokens(&node.content));
+            if tokens <= remaining {
+                remaining -= tokens;
+                selected.push((node, tokens));
+            }
+        }
+
+        selected
+    }
+}
@@ -0,0 +1,117 @@
+use super::{CodeGraph, CodeNode, Direction, NodeType, Relationship, RelationshipType};
+use log::debug;
+use std::collections::{HashSet, VecDeque};
+
+// What `CodeGraph::traverse` follows and how far
+#[derive(Debug, Clone)]
+pub struct TraversalOptions {
+    pub direction: Direction,
+    // Relationship types to follow; empty for all
+    pub rel_types: Vec<RelationshipType>,
+    // Node types to visit; empty for all. Other nodes are neither returned
+    // nor walked through, but the start node always is.
+    pub node_types: Vec<NodeType>,
+    pub max_depth: usize,
+    // Nodes to return at most, the start node included
+    pub max_nodes: usize,
+}
+
+impl Default for TraversalOptions {
+    fn default() -> Self {
+        TraversalOptions {
+            direction: Direction::Outgoing,
+            rel_types: Vec::new(),
+            node_types: Vec::new(),
+            max_depth: 1,
+            max_nodes: usize::MAX,
+        }
+    }
+}
+
+impl TraversalOptions {
+    fn follows(&self, relationship_type: &RelationshipType) -> bool {
+        self.rel_types.is_empty() || self.rel_types.contains(relationship_type)
+    }
+
+    fn visits(&self, node: &CodeNode) -> bool {
+        self.node_types.is_empty() || self.node_types.contains(&node.node_type)
+    }
+}
+
+// A node reached by a traversal, `depth` hops from the start
+#[derive(Debug, Clone)]
+pub struct TraversedNode<'a> {
+    pub node: &'a CodeNode,
+    pub depth: usize,
+}
+
+// Nodes and relationships reached by a traversal, in breadth-first order
+#[derive(Debug, Clone, Default)]
+pub struct Traversal<'a> {
+    // The start node first
+    pub nodes: Vec<TraversedNode<'a>>,
+    // Every followed relationship between two returned nodes, once
+    pub edges: Vec<&'a Relationship>,
+}
+
+impl CodeGraph {
+    // Walk breadth-first from a node across the relationships and node types
+    // the options allow. Empty when the node does not exist.
+    pub fn traverse(&self, id: &str, options: &TraversalOptions) -> Traversal<'_> {
+        let mut traversal = Traversal::default();
+        let Some(start) = self.get_node(id) else {
+            return traversal;
+        };
+        if options.max_nodes == 0 {
+            return traversal;
+        }
+
+        let mut visited: HashSet<&str> = HashSet::from([start.id.as_str()]);
+        let mut followed: HashSet<(&str, &str, &RelationshipType)> = HashSet::new();
+        let mut queue = VecDeque::from([(start.id.as_str(), 0)]);
+        traversal.nodes.push(TraversedNode {
+            node: start,
+            depth: 0,
+        });
+
+        while let Some((id, depth)) = queue.pop_front() {
+            if depth == options.max_depth {
+                continue;
+            }
+
+            for edge in self.edges(id, options.direction) {
+                if !options.follows(edge.relationship_type()) {
+                    continue;
+                }
+                let Some(next) = edge.other().filter(|next| options.visits(next.node)) else {
+                    continue;
+                };
+
+                if !visited.contains(next.id()) {
+                    if traversal.nodes.len() == options.max_nodes {
+                        continue;
+                    }
+                    visited.insert(next.id());
+                    traversal.nodes.push(TraversedNode {
+                        node: next.node,
+                        depth: depth + 1,
+                    });
+                    queue.push_back((next.id(), depth + 1));
+                }
+
+                let rel = edge.relationship;
+                if followed.insert((&rel.from_id, &rel.to_id, &rel.relationship_type)) {
+                    traversal.edges.push(rel);
+                }
+            }
+        }
+
+        debug!(
+            "Traversal from {} reached {} nodes over {} relationships",
+            id,
+            traversal.nodes.len(),
+            traversal.edges.len()
+        );
+        traversal
+    }
+}
@@ -0,0 +1,102 @@
+use super::{CodeGraph, CodeNode, NodeType, RelationshipType};
+use std::path::Path;
+
+// Incoming usage counts of a symbol, split by whether the user lives in
+// the same package (directory) as the symbol
+#[derive(Debug, Clone)]
+pub struct SymbolUsage<'a> {
+    pub node: &'a CodeNode,
+    pub internal: usize,
+    pub external: usize,
+}
+
+impl SymbolUsage<'_> {
+    pub fn total(&self) -> usize {
+        self.internal + self.external
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.node.is_public()
+    }
+}
+
+// Edges that count as one symbol using another
+fn is_usage(relationship_type: &RelationshipType) -> bool {
+    matches!(
+        relationship_type,
+        RelationshipType::Calls
+            | RelationshipType::References
+            | RelationshipType::Imports
+            | RelationshipType::Inherits
+            | RelationshipType::Implements
+    )
+}
+
+fn is_symbol(node_type: &NodeType) -> bool {
+    !matches!(
+        node_type,
+        NodeType::Module
+            | NodeType::Dependency
+            | NodeType::Documentation
+            | NodeType::ExternalSymbol
+            | NodeType::Endpoint
+            | NodeType::Unknown
+    )
+}
+
+fn package_of(file_path: &str) -> &Path {
+    Path::new(file_path).parent().unwrap_or(Path::new(""))
+}
+
+impl CodeGraph {
+    // Usage counts for every symbol, most used first
+    pub fn symbol_usage(&self) -> Vec<SymbolUsage<'_>> {
+        let mut usage: Vec<SymbolUsage> = self
+            .all_nodes()
+            .filter(|node| is_symbol(&node.node_type))
+            .map(|node| {
+                let package = package_of(&node.file_path);
+                let mut internal = 0;
+                let mut external = 0;
+                for rel in self.incoming_relationships(&node.id) {
+                    if !is_usage(&rel.relationship_type) || rel.from_id == node.id {
+                        continue;
+                    }
+                    match self.get_node(&rel.from_id) {
+                        Some(user) if package_of(&user.file_path) == package => internal += 1,
+                        Some(_) => external += 1,
+                        None => {}
+                    }
+                }
+                SymbolUsage {
+                    node,
+                    internal,
+                    external,
+                }
+            })
+            .collect();
+
+        usage.sort_by(|a, b| {
+            b.total()
+                .cmp(&a.total())
+                .then_with(|| a.node.file_path.cmp(&b.node.file_path))
+                .then_with(|| a.node.line_range.cmp(&b.node.line_range))
+        });
+        usage
+    }
+
+    // Public symbols that nothing outside their own package uses; candidates
+    // for narrowing the API surface
+    pub fn unused_public_symbols(&self) -> Vec<SymbolUsage<'_>> {
+        let mut unused: Vec<SymbolUsage> = self
+            .symbol_usage()
+            .into_iter()
+            .filter(|usage| usage.is_public() && usage.external == 0)
+            .collect();
+
+        unused.sort_by(|a, b| {
+            (&a.node.file_path, a.node.line_range).cmp(&(&b.node.file_path, b.node.line_range))
+        });
+        unused
+    }
+}
@@ -0,0 +1,180 @@
+use super::{CodeGraph, Relationship, RelationshipType};
+use log::info;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+type EdgeKey = (String, String, RelationshipType);
+
+// Integrity problems found by `CodeGraph::validate`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    // Relationships whose source or target is not a node of the graph
+    pub dangling_relationships: Vec<Relationship>,
+    // Relationships listed as outgoing or incoming but not both
+    pub unmirrored_relationships: Vec<Relationship>,
+    // Extra copies of a relationship between the same nodes
+    pub duplicate_relationships: usize,
+    // Nodes absent from an index that should list them
+    pub unindexed_nodes: Vec<String>,
+    pub stale_index_entries: usize,
+    // ID of each node stored under a different key, by that key
+    pub mismatched_ids: BTreeMap<String, String>,
+    // IDs claimed by more than one node
+    pub duplicate_ids: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.problem_count() == 0
+    }
+
+    pub fn problem_count(&self) -> usize {
+        self.dangling_relationships.len()
+            + self.unmirrored_relationships.len()
+            + self.duplicate_relationships
+            + self.unindexed_nodes.len()
+            + self.stale_index_entries
+            + self.mismatched_ids.len()
+            + self.duplicate_ids.len()
+    }
+}
+
+fn edge_key(rel: &Relationship) -> EdgeKey {
+    (
+        rel.from_id.clone(),
+        rel.to_id.clone(),
+        rel.relationship_type.clone(),
+    )
+}
+
+impl CodeGraph {
+    // Check that every relationship joins two nodes and is listed both ways,
+    // that the indices agree with the nodes and that no ID is used twice.
+    // Graphs built by the indexer pass; hand-merged or incrementally
+    // updated ones may not.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        let mut claimed: HashMap<&str, usize> = HashMap::new();
+        for (key, node) in &self.nodes {
+            *claimed.entry(node.id.as_str()).or_default() += 1;
+            if *key != node.id {
+                report.mismatched_ids.insert(key.clone(), node.id.clone());
+            }
+        }
+        report.duplicate_ids = claimed
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(id, _)| id.to_string())
+            .collect();
+        report.duplicate_ids.sort();
+
+        let mut outgoing: HashMap<EdgeKey, &Relationship> = HashMap::new();
+        for rel in self.outgoing_edges.values().flatten() {
+            if outgoing.insert(edge_key(rel), rel).is_some() {
+                report.duplicate_relationships += 1;
+            }
+        }
+        let mut incoming: HashMap<EdgeKey, &Relationship> = HashMap::new();
+        for rel in self.incoming_edges.values().flatten() {
+            incoming.insert(edge_key(rel), rel);
+        }
+
+        let mut dangling = BTreeMap::new();
+        let mut unmirrored = BTreeMap::new();
+        for (key, rel) in outgoing.iter().chain(incoming.iter()) {
+            if !self.nodes.contains_key(&rel.from_id) || !self.nodes.contains_key(&rel.to_id) {
+                dangling.insert(key, *rel);
+            } else if !outgoing.contains_key(key) || !incoming.contains_key(key) {
+                unmirrored.insert(key, *rel);
+            }
+        }
+        report.dangling_relationships = dangling.into_values().cloned().collect();
+        report.unmirrored_relationships = unmirrored.into_values().cloned().collect();
+
+        let listed =
+            |ids: Option<&HashSet<String>>, id: &str| ids.is_some_and(|ids| ids.contains(id));
+        let mut unindexed = BTreeSet::new();
+        for (key, node) in &self.nodes {
+            let indexed = listed(self.nodes_by_type.get(&node.node_type), key)
+                && listed(self.nodes_by_file.get(&node.file_path), key)
+                && listed(self.nodes_by_name.get(&node.name), key)
+                && node
+                    .language
+                    .as_ref()
+                    .is_none_or(|language| listed(self.nodes_by_language.get(language), key));
+            if !indexed {
+                unindexed.insert(key.clone());
+            }
+        }
+        report.unindexed_nodes = unindexed.into_iter().collect();
+        report.stale_index_entries = self.stale_index_entry_count();
+
+        report
+    }
+
+    // Fix what `validate` reports and return that report. Misfiled nodes
+    // move to the key of their ID, or take their key as ID when another
+    // node has it; one-sided relationships are listed both ways; dangling
+    // and duplicate relationships are dropped and the indices rebuilt.
+    pub fn repair(&mut self) -> ValidationReport {
+        let report = self.validate();
+        if report.is_valid() {
+            return report;
+        }
+
+        for (key, id) in &report.mismatched_ids {
+            let Some(mut node) = self.nodes.remove(key) else {
+                continue;
+            };
+            let key = if self.nodes.contains_key(id) {
+                node.id = key.clone();
+                key.clone()
+            } else {
+                // The node's vector moves with it
+                match self.embeddings.remove(key) {
+                    Some(vector) => self.embeddings.insert(id.clone(), vector),
+                    None => self.embeddings.remove(id),
+                };
+                id.clone()
+            };
+            self.nodes.insert(key, node);
+        }
+
+        let outgoing: HashSet<EdgeKey> = self
+            .outgoing_edges
+            .values()
+            .flatten()
+            .map(edge_key)
+            .collect();
+        let incoming_only: Vec<Relationship> = self
+            .incoming_edges
+            .values()
+            .flatten()
+            .filter(|rel| !outgoing.contains(&edge_key(rel)))
+            .cloned()
+            .collect();
+        for rel in incoming_only {
+            self.outgoing_edges
+                .entry(rel.from_id.clone())
+                .or_default()
+                .push(rel);
+        }
+
+        // Compaction keeps only relationships between existing nodes and
+        // rebuilds the incoming edges and indices from what remains
+        self.compact();
+
+        info!(
+            "Repaired graph: {} dangling, {} unmirrored and {} duplicate relationships, {} unindexed nodes, {} stale index entries, {} mismatched and {} duplicate IDs",
+            report.dangling_relationships.len(),
+            report.unmirrored_relationships.len(),
+            report.duplicate_relationships,
+            report.unindexed_nodes.len(),
+            report.stale_index_entries,
+            report.mismatched_ids.len(),
+            report.duplicate_ids.len()
+        );
+        report
+    }
+}
@@ -0,0 +1,177 @@
+use super::query::{NameMatch, NodeSelector, QueryStep};
+use super::{CodeGraph, Direction, NodeType, Query, RelationshipType};
+use globset::Glob;
+use log::{debug, info};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// A saved query plus traversal spec that selects part of a graph
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ViewDefinition {
+    #[serde(default)]
+    pub description: Option<String>,
+    // Seed query in the syntax of `CodeGraph::query`; every node it returns
+    // is a seed, and an empty query returns every node
+    #[serde(default)]
+    pub query: Option<String>,
+    // Shorthands narrowing the nodes the query returns: every non-empty
+    // criterion must match
+    #[serde(default)]
+    pub node_types: Vec<NodeType>,
+    #[serde(default)]
+    pub name_pattern: Option<String>,
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+    // Traversal from the seeds: empty relationship types follow every edge
+    #[serde(default)]
+    pub relationship_types: Vec<RelationshipType>,
+    #[serde(default)]
+    pub direction: Direction,
+    #[serde(default)]
+    pub depth: usize,
+}
+
+// The `views` section of a config file, keyed by view name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ViewConfig {
+    #[serde(default)]
+    pub views: BTreeMap<String, ViewDefinition>,
+}
+
+impl ViewConfig {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ViewDefinition> {
+        self.views.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.views.keys().map(|name| name.as_str())
+    }
+
+    pub fn materialize(&self, name: &str, graph: &CodeGraph) -> io::Result<CodeGraph> {
+        match self.get(name) {
+            Some(view) => view.materialize(graph),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Unknown view: {}", name),
+            )),
+        }
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+impl ViewDefinition {
+    // The seed query with the shorthands added to the selector of the nodes
+    // it returns
+    pub fn seed_query(&self) -> io::Result<Query> {
+        let mut query = Query::parse(self.query.as_deref().unwrap_or_default())?;
+        let selector = match query.steps.last_mut() {
+            Some(step) => &mut step.target,
+            None => &mut query.start,
+        };
+
+        if !self.node_types.is_empty() {
+            if selector.node_types.is_empty() {
+                selector.node_types = self.node_types.clone();
+            } else {
+                selector
+                    .node_types
+                    .retain(|node_type| self.node_types.contains(node_type));
+                if selector.node_types.is_empty() {
+                    return Err(invalid(
+                        "The view's node types exclude every type of its query".into(),
+                    ));
+                }
+            }
+        }
+        if let Some(pattern) = &self.name_pattern {
+            if selector.name.is_some() {
+                return Err(invalid(
+                    "A view's query and name pattern cannot both match names".into(),
+                ));
+            }
+            let regex = Regex::new(pattern).map_err(|e| invalid(e.to_string()))?;
+            selector.name = Some(NameMatch::Pattern(regex));
+        }
+        if let Some(prefix) = &self.path_prefix {
+            if selector.file.is_some() {
+                return Err(invalid(
+                    "A view's query and path prefix cannot both match files".into(),
+                ));
+            }
+            let glob = Glob::new(&format!("{}*", globset::escape(prefix)))
+                .map_err(|e| invalid(e.to_string()))?;
+            selector.file = Some(glob.compile_matcher());
+        }
+
+        Ok(query)
+    }
+
+    // One query step per relationship type followed, to any node
+    fn traversal_steps(&self) -> Vec<QueryStep> {
+        let step = |relationship_type| QueryStep {
+            direction: self.direction,
+            relationship_type,
+            target: NodeSelector::default(),
+        };
+        if self.relationship_types.is_empty() {
+            return vec![step(None)];
+        }
+        self.relationship_types
+            .iter()
+            .map(|relationship_type| step(Some(relationship_type.clone())))
+            .collect()
+    }
+
+    pub fn materialize(&self, graph: &CodeGraph) -> io::Result<CodeGraph> {
+        let seeds: Vec<&str> = self
+            .seed_query()?
+            .execute(graph)
+            .into_iter()
+            .map(|node| node.id.as_str())
+            .collect();
+        debug!("View matched {} seed nodes", seeds.len());
+
+        let steps = self.traversal_steps();
+        let mut included: HashSet<&str> = seeds.iter().copied().collect();
+        let mut frontier = seeds;
+
+        for _ in 0..self.depth {
+            let mut next = Vec::new();
+            for id in frontier {
+                for step in &steps {
+                    for neighbor in step.reachable(graph, id) {
+                        if included.insert(neighbor.id.as_str()) {
+                            next.push(neighbor.id.as_str());
+                        }
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        let view_graph = graph.induced_subgraph(&included);
+
+        info!(
+            "Materialized view with {} nodes and {} relationships",
+            view_graph.node_count(),
+            view_graph.relationship_count()
+        );
+
+        Ok(view_graph)
+    }
+}
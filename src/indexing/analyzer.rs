@@ -1,13 +1,56 @@
-use crate::code_graph::{CodeGraph, NodeType, Relationship, RelationshipType};
+use crate::code_graph::{
+    CodeGraph, CodeNode, NodeType, Relationship, RelationshipType, provenance,
+};
+use crate::indexing::config::IndexConfig;
+use crate::indexing::external::{UnresolvedReference, add_external_symbols};
+use crate::indexing::extractor::{ResolverMapEntry, resolver_map_entries};
+use crate::indexing::imports::java::{JavaTypes, find_java_import_relationships};
+use crate::indexing::imports::python::{PythonModules, find_python_import_relationships};
+use crate::indexing::imports::rust::{RustModules, find_rust_import_relationships};
+use crate::indexing::imports::typescript::{ScriptModules, find_script_import_relationships};
+use crate::indexing::provider::{FileProvider, FsProvider};
+use crate::indexing::receivers::Receivers;
+use crate::indexing::summaries::{TemplateSummarizer, summarize_graph};
 use log::{debug, info, trace, warn};
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::Read;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
 pub fn identify_relationships(graph: &mut CodeGraph) {
+    identify_relationships_with_provider(
+        graph,
+        &FsProvider::new(Path::new("")),
+        &IndexConfig::new(),
+    );
+}
+
+pub fn identify_relationships_with_provider(
+    graph: &mut CodeGraph,
+    provider: &dyn FileProvider,
+    config: &IndexConfig,
+) {
     info!("Identifying precise relationships between code units...");
     let mut relationships_to_add = Vec::new();
+    let mut unresolved = Vec::new();
+    // Built on the first Java, Python, Rust or script file
+    let mut java_types: Option<JavaTypes> = None;
+    let mut python_modules: Option<PythonModules> = None;
+    let mut rust_modules: Option<RustModules> = None;
+    let mut script_modules: Option<ScriptModules> = None;
+    // Fields of JavaScript and TypeScript resolver maps, by file, for
+    // schemas with root types
+    let mut resolver_maps: Vec<(String, ResolverMapEntry)> = Vec::new();
+    let root_types: Vec<String> = if config.is_pass_enabled(provenance::GRAPHQL_RESOLVER) {
+        graph
+            .find_nodes_by_type(&NodeType::Method)
+            .into_iter()
+            .filter(|node| node.metadata.get("graphql_kind").map(String::as_str) == Some("field"))
+            .filter_map(|node| node.parent_class.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    } else {
+        Vec::new()
+    };
 
     // Group nodes by file for more efficient processing
     let mut nodes_by_file: HashMap<String, Vec<(String, String, NodeType)>> = HashMap::new();
@@ -45,7 +88,7 @@ pub fn identify_relationships(graph: &mut CodeGraph) {
         let file_path_obj = Path::new(file_path);
 
         // Skip file processing if it can't be read
-        let content = match read_file_content(file_path_obj) {
+        let content = match provider.read_file(file_path_obj) {
             Ok(content) => content,
             Err(e) => {
                 warn!("Failed to read file {}: {}", file_path, e);
@@ -55,35 +98,150 @@ pub fn identify_relationships(graph: &mut CodeGraph) {
 
         // Detect language and process accordingly
         if let Some(language) = crate::parsers::detect_language(file_path_obj) {
+            if language == "java" && java_types.is_none() {
+                java_types = Some(JavaTypes::new(provider, graph));
+            }
+
             // Find function call relationships
-            find_function_call_relationships(
-                &language,
-                file_path,
-                &content,
-                &nodes,
-                graph,
-                &mut relationships_to_add,
-            );
+            if config.is_pass_enabled(provenance::CALL_QUERY) {
+                find_function_call_relationships(
+                    &language,
+                    &content,
+                    nodes,
+                    graph,
+                    java_types.as_ref(),
+                    &mut relationships_to_add,
+                    &mut unresolved,
+                );
+            }
 
-            // Find import relationships
-            find_import_relationships(
-                &language,
-                file_path,
-                &content,
-                &nodes,
-                graph,
-                &mut relationships_to_add,
-            );
+            if !root_types.is_empty()
+                && matches!(language.as_str(), "javascript" | "typescript" | "tsx")
+            {
+                let type_names: Vec<&str> = root_types.iter().map(String::as_str).collect();
+                resolver_maps.extend(
+                    resolver_map_entries(&content, file_path_obj, &type_names)
+                        .into_iter()
+                        .map(|entry| (file_path.clone(), entry)),
+                );
+            }
+
+            // Find import relationships; Java, Python, Rust and JavaScript or
+            // TypeScript imports resolve through the package and module
+            // layout, falling back to names. C and C++ includes are paths,
+            // resolved to files by the include pass.
+            let resolved = config.is_pass_enabled(provenance::IMPORT_MATCH)
+                && match language.as_str() {
+                    "java" => java_types.as_ref().is_some_and(|types| {
+                        find_java_import_relationships(
+                            file_path,
+                            nodes,
+                            types,
+                            &mut relationships_to_add,
+                            &mut unresolved,
+                        )
+                    }),
+                    "python" => {
+                        let modules = python_modules
+                            .get_or_insert_with(|| PythonModules::new(provider, graph));
+                        find_python_import_relationships(
+                            file_path,
+                            &content,
+                            nodes,
+                            modules,
+                            &mut relationships_to_add,
+                            &mut unresolved,
+                        );
+                        true
+                    }
+                    "rust" => {
+                        let modules =
+                            rust_modules.get_or_insert_with(|| RustModules::new(provider, graph));
+                        find_rust_import_relationships(
+                            file_path,
+                            &content,
+                            nodes,
+                            modules,
+                            &mut relationships_to_add,
+                            &mut unresolved,
+                        )
+                    }
+                    "typescript" | "tsx" | "javascript" => {
+                        let modules = script_modules
+                            .get_or_insert_with(|| ScriptModules::new(provider, graph));
+                        find_script_import_relationships(
+                            &language,
+                            file_path,
+                            nodes,
+                            modules,
+                            &mut relationships_to_add,
+                            &mut unresolved,
+                        )
+                    }
+                    "c" | "cpp" => true,
+                    _ => false,
+                };
+            if !resolved && config.is_pass_enabled(provenance::IMPORT_MATCH) {
+                find_import_relationships(
+                    &language,
+                    file_path,
+                    &content,
+                    nodes,
+                    graph,
+                    &mut relationships_to_add,
+                    &mut unresolved,
+                );
+            }
 
             // Find hierarchical relationships
-            find_hierarchical_relationships(&nodes, graph, &mut relationships_to_add);
+            if config.is_pass_enabled(provenance::HIERARCHY_CONTAINMENT) {
+                find_hierarchical_relationships(nodes, graph, &mut relationships_to_add);
+            }
         } else {
             warn!("Could not determine language for file: {}", file_path);
         }
     }
 
-    // Add this new function call
-    find_method_class_relationships(graph, &mut relationships_to_add);
+    if config.is_pass_enabled(provenance::METHOD_CLASS_METADATA) {
+        find_method_class_relationships(graph, &mut relationships_to_add);
+    }
+
+    if config.is_pass_enabled(provenance::HIERARCHY_CONTAINMENT) {
+        find_field_relationships(graph, &mut relationships_to_add);
+    }
+
+    if config.is_pass_enabled(provenance::INTERFACE_IMPLEMENTATION) {
+        find_interface_method_relationships(graph, &mut relationships_to_add);
+    }
+
+    if config.is_pass_enabled(provenance::TEST_CALLS) {
+        find_test_relationships(graph, &mut relationships_to_add);
+    }
+
+    if config.is_pass_enabled(provenance::GRAPHQL_RESOLVER) {
+        find_graphql_resolver_relationships(graph, &resolver_maps, &mut relationships_to_add);
+    }
+
+    if config.is_pass_enabled(provenance::GRAPHQL_DOCUMENT) {
+        find_graphql_document_relationships(graph, &mut relationships_to_add);
+    }
+
+    if config.is_pass_enabled(provenance::PROTO_SCHEMA) {
+        find_proto_relationships(graph, &mut relationships_to_add);
+    }
+
+    if config.is_pass_enabled(provenance::DOC_MENTIONS) {
+        find_documentation_relationships(graph, &mut relationships_to_add);
+    }
+
+    // After the import passes, whose edges pick between same-named decorators
+    if config.is_pass_enabled(provenance::DECORATOR_MATCH) {
+        find_decorator_relationships(graph, &mut relationships_to_add);
+    }
+
+    if config.uses_external_symbols() && config.is_pass_enabled(provenance::EXTERNAL_SYMBOL) {
+        relationships_to_add.extend(add_external_symbols(provider, graph, unresolved, config));
+    }
 
     info!(
         "Adding {} precisely identified relationships",
@@ -91,37 +249,195 @@ pub fn identify_relationships(graph: &mut CodeGraph) {
     );
 
     // Add all unique relationships to the graph
-    let mut added_rels = HashSet::new();
-    for rel in relationships_to_add {
+    add_unique_relationships(graph, relationships_to_add);
+
+    info!("Relationship identification complete");
+}
+
+// (node id, file path, line range) of a variable or type that can be referenced
+type ReferenceTarget = (String, String, (usize, usize));
+
+// (node id, line range) of a function that can hold references
+type FunctionSpan<'a> = (&'a str, (usize, usize));
+
+// Opt-in pass linking functions to the module-level variables and types they use
+pub fn identify_references(graph: &mut CodeGraph) {
+    identify_references_with_provider(graph, &FsProvider::new(Path::new("")));
+}
+
+pub fn identify_references_with_provider(graph: &mut CodeGraph, provider: &dyn FileProvider) {
+    info!("Identifying references to variables and types...");
+    let mut relationships_to_add = Vec::new();
+
+    // Group reference targets by name: module-level variables and type declarations
+    let mut targets: HashMap<String, Vec<ReferenceTarget>> = HashMap::new();
+    let mut functions_by_file: HashMap<&str, Vec<FunctionSpan>> = HashMap::new();
+
+    for node in graph.all_nodes() {
+        if node.name.len() < 3 || node.metadata.contains_key("duplicate_of") {
+            continue;
+        }
+
+        match node.node_type {
+            NodeType::Variable
+            | NodeType::Class
+            | NodeType::Interface
+            | NodeType::TypeDefinition => {
+                targets.entry(node.name.clone()).or_default().push((
+                    node.id.clone(),
+                    node.file_path.clone(),
+                    node.line_range,
+                ));
+            }
+            NodeType::Function | NodeType::Method => {
+                functions_by_file
+                    .entry(node.file_path.as_str())
+                    .or_default()
+                    .push((node.id.as_str(), node.line_range));
+            }
+            _ => {}
+        }
+    }
+
+    if targets.is_empty() {
+        info!("No reference targets found");
+        return;
+    }
+
+    for (file_path, functions) in &functions_by_file {
+        let file_path_obj = Path::new(file_path);
+
+        let content = match provider.read_file(file_path_obj) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Failed to read file {}: {}", file_path, e);
+                continue;
+            }
+        };
+
+        let Some(extractor) = crate::parsers::detect_language(file_path_obj)
+            .and_then(|language| crate::indexing::extractor::get_extractor_for_language(&language))
+        else {
+            continue;
+        };
+
+        // One parse per file, grouping the referenced identifiers by name
+        let mut lines_by_name: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (name, line) in extractor.extract_references(&content) {
+            if targets.contains_key(&name) {
+                lines_by_name.entry(name).or_default().push(line);
+            }
+        }
+
+        for (name, lines) in &lines_by_name {
+            let target_nodes = &targets[name];
+            for &line in lines {
+                // Attribute the reference to the innermost enclosing function
+                let Some((func_id, _)) = functions
+                    .iter()
+                    .filter(|(_, range)| range.0 <= line && line <= range.1)
+                    .min_by_key(|(_, range)| range.1 - range.0)
+                else {
+                    continue;
+                };
+
+                for (target_id, target_file, target_range) in target_nodes {
+                    // Skip the declaration site itself
+                    if target_id == func_id
+                        || (target_file == file_path
+                            && target_range.0 <= line
+                            && line <= target_range.1)
+                    {
+                        continue;
+                    }
+
+                    trace!("Found reference: {} -> {}", func_id, name);
+                    relationships_to_add.push(
+                        Relationship::new(
+                            RelationshipType::References,
+                            func_id.to_string(),
+                            target_id.clone(),
+                        )
+                        .with_provenance(provenance::REFERENCE_QUERY)
+                        .with_metadata("line".to_string(), line.to_string()),
+                    );
+                }
+            }
+        }
+    }
+
+    info!(
+        "Adding {} reference relationships",
+        relationships_to_add.len()
+    );
+    add_unique_relationships(graph, relationships_to_add);
+
+    info!("Reference identification complete");
+}
+
+// Add relationships, keeping only the first for each (from, to, type) triple.
+// Repeated Calls edges are merged into it instead: `count` records how often
+// the caller calls the callee and `lines` every call site line.
+fn add_unique_relationships(graph: &mut CodeGraph, relationships: Vec<Relationship>) {
+    let mut unique: Vec<Relationship> = Vec::new();
+    let mut index_by_key = HashMap::new();
+    let mut call_lines: HashMap<usize, Vec<usize>> = HashMap::new();
+
+    for rel in relationships {
         let rel_key = (
             rel.from_id.clone(),
             rel.to_id.clone(),
             rel.relationship_type.clone(),
         );
-        if !added_rels.contains(&rel_key) {
-            graph.add_relationship(rel);
-            added_rels.insert(rel_key);
+        let index = match index_by_key.get(&rel_key) {
+            Some(&index) => index,
+            None => {
+                index_by_key.insert(rel_key, unique.len());
+                unique.push(rel.clone());
+                unique.len() - 1
+            }
+        };
+
+        if rel.relationship_type == RelationshipType::Calls {
+            let lines = call_lines.entry(index).or_default();
+            if let Some(line) = rel.metadata.get("line").and_then(|l| l.parse().ok()) {
+                lines.push(line);
+            }
+            let count = unique[index]
+                .metadata
+                .get("count")
+                .and_then(|c| c.parse::<usize>().ok())
+                .unwrap_or(0);
+            unique[index]
+                .metadata
+                .insert("count".to_string(), (count + 1).to_string());
         }
     }
 
-    info!("Relationship identification complete");
-}
+    for (index, mut lines) in call_lines {
+        lines.sort_unstable();
+        lines.dedup();
+        if !lines.is_empty() {
+            let lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+            unique[index]
+                .metadata
+                .insert("lines".to_string(), lines.join(","));
+        }
+    }
 
-// Helper function to read file content
-fn read_file_content(file_path: &Path) -> std::io::Result<String> {
-    let mut content = String::new();
-    let mut file = File::open(file_path)?;
-    file.read_to_string(&mut content)?;
-    Ok(content)
+    for rel in unique {
+        graph.add_relationship(rel);
+    }
 }
 
 fn find_function_call_relationships(
     language: &str,
-    _file_path: &str,
     content: &str,
     nodes: &[(String, String, NodeType)],
     graph: &CodeGraph,
+    java_types: Option<&JavaTypes>,
     relationships: &mut Vec<Relationship>,
+    unresolved: &mut Vec<UnresolvedReference>,
 ) {
     // Get function nodes in this file
     let functions_in_file: Vec<_> = nodes
@@ -133,16 +449,46 @@ fn find_function_call_relationships(
         return;
     }
 
-    // Create a map of function names to their IDs for quick lookup
+    // Generated gRPC clients in Java and TypeScript call `GetUser` as `getUser`
+    let rpc_aliases: Vec<(String, &str)> = graph
+        .all_nodes()
+        .filter(|node| is_rpc(node))
+        .map(|node| (lower_camel_case(&node.name), node.id.as_str()))
+        .collect();
+
+    // Create a map of function names to their IDs for quick lookup, and one
+    // of classes and structs, which calls such as `Service()` construct
     let mut function_map: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut class_map: HashMap<&str, Vec<&str>> = HashMap::new();
     for node in graph.all_nodes() {
-        if matches!(node.node_type, NodeType::Function | NodeType::Method) && node.name.len() >= 3 {
-            function_map
-                .entry(node.name.as_str())
+        // Calls resolve to the canonical copy of duplicated files only, and
+        // to definitions rather than C/C++ prototypes
+        if node.metadata.contains_key("duplicate_of") || node.metadata.contains_key("declaration") {
+            continue;
+        }
+
+        let map = match node.node_type {
+            NodeType::Function | NodeType::Method => &mut function_map,
+            NodeType::Class => &mut class_map,
+            _ => continue,
+        };
+        if node.name.len() >= 3 {
+            map.entry(node.name.as_str())
                 .or_insert_with(Vec::new)
                 .push(node.id.as_str());
         }
     }
+    for (alias, id) in &rpc_aliases {
+        let ids = function_map.entry(alias.as_str()).or_default();
+        if alias.len() >= 3 && !ids.contains(id) {
+            ids.push(id);
+        }
+    }
+
+    let receivers = graph
+        .get_node(&functions_in_file[0].0)
+        .and_then(|node| Receivers::new(graph, &node.file_path));
+    let lines: Vec<&str> = content.lines().collect();
 
     // Use language-specific extractor to find function calls
     if let Some(extractor) = crate::indexing::extractor::get_extractor_for_language(language) {
@@ -150,36 +496,614 @@ fn find_function_call_relationships(
             if let Some(func_node) = graph.get_node(func_id) {
                 // Get function's line range
                 let func_range = func_node.line_range;
+                let locals = match &receivers {
+                    Some(receivers) => receivers.local_types(
+                        &lines
+                            .get(func_range.0.saturating_sub(1)..func_range.1.min(lines.len()))
+                            .unwrap_or_default()
+                            .join("\n"),
+                    ),
+                    None => HashMap::new(),
+                };
 
                 // Find all function calls within this function
                 let function_calls =
                     extractor.extract_function_calls(content, func_range, func_name.as_str());
 
                 // Map function calls to relationships
-                for called_func_name in function_calls {
-                    if called_func_name.len() < 3 {
+                for call_site in function_calls {
+                    if call_site.name.len() < 3 {
                         continue;
                     }
 
-                    if let Some(target_ids) = function_map.get(called_func_name.as_str()) {
-                        for target_id in target_ids {
-                            // Skip self-calls
-                            if func_id == *target_id {
-                                continue;
-                            }
-
-                            trace!("Found function call: {} -> {}", func_name, called_func_name);
-                            relationships.push(Relationship::new(
-                                RelationshipType::Calls,
-                                func_id.clone(),
-                                (*target_id).to_string(),
-                            ));
+                    let call = |target_id: &str| {
+                        Relationship::new(
+                            RelationshipType::Calls,
+                            func_id.clone(),
+                            target_id.to_string(),
+                        )
+                        .with_provenance(provenance::CALL_QUERY)
+                        .with_metadata("line".to_string(), call_site.line.to_string())
+                        .with_metadata("column".to_string(), call_site.column.to_string())
+                        .with_metadata("call_expression".to_string(), call_site.expression.clone())
+                        .with_metadata("conditional".to_string(), call_site.conditional.to_string())
+                    };
+
+                    // Java calls on a known type or on the caller's own
+                    // class only reach that class's methods, as do calls on
+                    // `self`, a typed field or a typed local variable
+                    let scoped = java_types
+                        .and_then(|types| {
+                            types.call_targets(
+                                &func_node.file_path,
+                                func_id,
+                                &call_site.name,
+                                &call_site.expression,
+                            )
+                        })
+                        .or_else(|| {
+                            receivers.as_ref()?.call_targets(
+                                func_node,
+                                &locals,
+                                &call_site.name,
+                                &call_site.expression,
+                            )
+                        });
+                    let Some(target_ids) = scoped
+                        .as_ref()
+                        .or_else(|| function_map.get(call_site.name.as_str()))
+                        .or_else(|| class_map.get(call_site.name.as_str()))
+                    else {
+                        unresolved.push(UnresolvedReference {
+                            language: language.to_string(),
+                            name: call_site.name.clone(),
+                            relationship: call(""),
+                        });
+                        continue;
+                    };
+                    let target_ids: Vec<&str> = target_ids
+                        .iter()
+                        .copied()
+                        .filter(|target_id| func_id != target_id)
+                        .collect();
+                    let target_ids = match_arity(graph, target_ids, call_site.arguments);
+                    for target_id in &target_ids {
+                        trace!("Found function call: {} -> {}", func_name, call_site.name);
+                        let mut relationship = call(target_id);
+                        // Overloads or same-named functions the call could
+                        // equally be to
+                        if target_ids.len() > 1 {
+                            relationship = relationship
+                                .with_metadata("ambiguous".to_string(), "true".to_string())
+                                .with_metadata(
+                                    "candidates".to_string(),
+                                    target_ids.len().to_string(),
+                                );
                         }
+                        relationships.push(relationship);
                     }
                 }
             }
         }
     }
+
+    if language == "rust" {
+        find_macro_call_relationships(content, &functions_in_file, graph, relationships);
+    }
+}
+
+// Whether a function with a known signature can take that many arguments
+fn accepts_arguments(node: &CodeNode, arguments: usize) -> bool {
+    node.signature
+        .as_ref()
+        .is_none_or(|signature| signature.accepts(arguments))
+}
+
+// The targets of a call whose signatures take the number of arguments it
+// passes, or all of them when none does
+fn match_arity<'a>(
+    graph: &CodeGraph,
+    target_ids: Vec<&'a str>,
+    arguments: Option<usize>,
+) -> Vec<&'a str> {
+    let Some(arguments) = arguments.filter(|_| target_ids.len() > 1) else {
+        return target_ids;
+    };
+    let matching: Vec<&str> = target_ids
+        .iter()
+        .copied()
+        .filter(|id| {
+            graph
+                .get_node(id)
+                .is_none_or(|node| accepts_arguments(node, arguments))
+        })
+        .collect();
+    if matching.is_empty() {
+        target_ids
+    } else {
+        matching
+    }
+}
+
+// Link Rust functions to the `macro_rules!` macros of the codebase they
+// invoke. Invocations of other macros, `println!` or `vec!`, are not
+// reported as unresolved.
+fn find_macro_call_relationships(
+    content: &str,
+    functions: &[&(String, String, NodeType)],
+    graph: &CodeGraph,
+    relationships: &mut Vec<Relationship>,
+) {
+    let mut macros: HashMap<&str, Vec<&str>> = HashMap::new();
+    for node in graph.find_nodes_by_type(&NodeType::Macro) {
+        if node.file_path.ends_with(".rs") {
+            macros
+                .entry(node.name.as_str())
+                .or_default()
+                .push(node.id.as_str());
+        }
+    }
+    if macros.is_empty() {
+        return;
+    }
+
+    for call_site in crate::indexing::extractor::rust_macro_invocations(content) {
+        let Some(target_ids) = macros.get(call_site.name.as_str()) else {
+            continue;
+        };
+        // The innermost function the invocation is in
+        let caller = functions
+            .iter()
+            .filter_map(|(id, _, _)| graph.get_node(id))
+            .filter(|node| {
+                node.line_range.0 <= call_site.line && call_site.line <= node.line_range.1
+            })
+            .min_by_key(|node| node.line_range.1 - node.line_range.0);
+        let Some(caller) = caller else {
+            continue;
+        };
+        for target_id in target_ids {
+            trace!(
+                "Found macro invocation: {} -> {}!",
+                caller.name, call_site.name
+            );
+            relationships.push(
+                Relationship::new(
+                    RelationshipType::Calls,
+                    caller.id.clone(),
+                    target_id.to_string(),
+                )
+                .with_provenance(provenance::CALL_QUERY)
+                .with_metadata("line".to_string(), call_site.line.to_string())
+                .with_metadata("column".to_string(), call_site.column.to_string())
+                .with_metadata("call_expression".to_string(), call_site.expression.clone())
+                .with_metadata("conditional".to_string(), call_site.conditional.to_string()),
+            );
+        }
+    }
+}
+
+// Link tests to the production functions and methods they call directly
+fn find_test_relationships(graph: &CodeGraph, relationships: &mut Vec<Relationship>) {
+    let is_test = |id: &str| {
+        graph
+            .get_node(id)
+            .is_some_and(|node| node.metadata.contains_key("test"))
+    };
+
+    let tests: Vec<Relationship> = relationships
+        .iter()
+        .filter(|rel| rel.relationship_type == RelationshipType::Calls)
+        .filter(|rel| is_test(&rel.from_id) && !is_test(&rel.to_id))
+        .map(|rel| {
+            Relationship::new(
+                RelationshipType::Tests,
+                rel.from_id.clone(),
+                rel.to_id.clone(),
+            )
+            .with_provenance(provenance::TEST_CALLS)
+        })
+        .collect();
+
+    debug!("Found {} test relationships", tests.len());
+    relationships.extend(tests);
+}
+
+// Link resolvers to the root schema fields they implement by type and field
+// name: the fields of JavaScript and TypeScript resolver maps, and
+// graphene's `resolve_user` methods on a `Query` class. Functions that only
+// share a field's name are not linked.
+fn find_graphql_resolver_relationships(
+    graph: &CodeGraph,
+    resolver_maps: &[(String, ResolverMapEntry)],
+    relationships: &mut Vec<Relationship>,
+) {
+    let mut fields: HashMap<(&str, &str), Vec<&str>> = HashMap::new();
+    for node in graph.find_nodes_by_type(&NodeType::Method) {
+        if node.metadata.get("graphql_kind").map(String::as_str) == Some("field")
+            && let Some(type_name) = &node.parent_class
+        {
+            fields
+                .entry((type_name.as_str(), node.name.as_str()))
+                .or_default()
+                .push(node.id.as_str());
+        }
+    }
+    if fields.is_empty() {
+        return;
+    }
+
+    // Import targets by importing node, including the edges found in this run
+    let mut imported: HashMap<&str, Vec<String>> = HashMap::new();
+    for rel in relationships.iter() {
+        if rel.relationship_type == RelationshipType::Imports
+            && let Some(from) = graph.get_node(&rel.from_id)
+        {
+            imported
+                .entry(from.id.as_str())
+                .or_default()
+                .push(rel.to_id.clone());
+        }
+    }
+
+    let is_function =
+        |node: &CodeNode| matches!(node.node_type, NodeType::Function | NodeType::Method);
+    let mut links: Vec<(&str, &[&str])> = Vec::new();
+    for (file_path, entry) in resolver_maps {
+        let Some(field_ids) = fields.get(&(entry.type_name.as_str(), entry.field.as_str())) else {
+            continue;
+        };
+        let in_file = graph.find_nodes_in_file(file_path);
+        let resolvers: Vec<&CodeNode> = match &entry.function {
+            // Inline resolvers start where the map field does
+            None => in_file
+                .into_iter()
+                .filter(|node| is_function(node) && node.line_range.0 == entry.line)
+                .collect(),
+            // A named function: defined in the file, else imported into it
+            Some(name) => {
+                let local: Vec<&CodeNode> = in_file
+                    .iter()
+                    .copied()
+                    .filter(|node| is_function(node) && &node.name == name)
+                    .collect();
+                if local.is_empty() {
+                    let mut targets: Vec<&CodeNode> = in_file
+                        .iter()
+                        .flat_map(|node| {
+                            graph
+                                .outgoing_relationships(&node.id)
+                                .iter()
+                                .filter(|rel| rel.relationship_type == RelationshipType::Imports)
+                                .map(|rel| &rel.to_id)
+                                .chain(imported.get(node.id.as_str()).into_iter().flatten())
+                        })
+                        .filter_map(|id| graph.get_node(id))
+                        .filter(|node| is_function(node) && &node.name == name)
+                        .collect();
+                    targets.sort_by(|a, b| a.id.cmp(&b.id));
+                    targets.dedup_by(|a, b| a.id == b.id);
+                    targets
+                } else {
+                    local
+                }
+            }
+        };
+        for resolver in resolvers {
+            links.push((resolver.id.as_str(), field_ids));
+        }
+    }
+
+    for method in graph.find_nodes_by_type(&NodeType::Method) {
+        let (Some(type_name), Some(field)) =
+            (&method.parent_class, method.name.strip_prefix("resolve_"))
+        else {
+            continue;
+        };
+        if crate::parsers::detect_language(Path::new(&method.file_path)).as_deref()
+            != Some("python")
+        {
+            continue;
+        }
+        if let Some(field_ids) = fields.get(&(type_name.as_str(), field)) {
+            links.push((method.id.as_str(), field_ids));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for (resolver_id, field_ids) in links {
+        for field_id in field_ids {
+            if !seen.insert((resolver_id, *field_id)) {
+                continue;
+            }
+            trace!("Found resolver {} for field {}", resolver_id, field_id);
+            relationships.push(
+                Relationship::new(
+                    RelationshipType::Implements,
+                    resolver_id.to_string(),
+                    field_id.to_string(),
+                )
+                .with_provenance(provenance::GRAPHQL_RESOLVER),
+            );
+        }
+    }
+}
+
+// Link GraphQL operations and fragments to the fragments they spread, the
+// type a fragment applies to and the root fields an operation selects
+fn find_graphql_document_relationships(graph: &CodeGraph, relationships: &mut Vec<Relationship>) {
+    let documents = graph.all_nodes().filter(|node| {
+        matches!(
+            node.metadata.get("graphql_kind").map(String::as_str),
+            Some("fragment" | "query" | "mutation" | "subscription")
+        )
+    });
+
+    for document in documents {
+        let graphql_nodes = |name: &str, kinds: &[&str]| {
+            graph
+                .find_nodes_by_name(name)
+                .into_iter()
+                .filter(|node| {
+                    node.metadata
+                        .get("graphql_kind")
+                        .is_some_and(|kind| kinds.contains(&kind.as_str()))
+                })
+                .map(|node| node.id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut targets = Vec::new();
+        if let Some(fragments) = document.metadata.get("fragments") {
+            for fragment in fragments.split(',') {
+                targets.extend(graphql_nodes(fragment, &["fragment"]));
+            }
+        }
+        if let Some(type_condition) = document.metadata.get("type_condition") {
+            targets.extend(graphql_nodes(
+                type_condition,
+                &["type", "interface", "union"],
+            ));
+        }
+        if let (Some(root_fields), Some(kind)) = (
+            document.metadata.get("root_fields"),
+            document.metadata.get("graphql_kind"),
+        ) {
+            // `query` selects fields of the `Query` type, and so on
+            let root_type = format!("{}{}", kind[..1].to_uppercase(), &kind[1..]);
+            for field in root_fields.split(',').filter(|field| !field.is_empty()) {
+                targets.extend(
+                    graph
+                        .find_nodes_by_name(field)
+                        .into_iter()
+                        .filter(|node| node.parent_class.as_ref() == Some(&root_type))
+                        .filter(|node| node.metadata.contains_key("graphql_kind"))
+                        .map(|node| node.id.clone()),
+                );
+            }
+        }
+
+        for target in targets {
+            relationships.push(
+                Relationship::new(RelationshipType::References, document.id.clone(), target)
+                    .with_provenance(provenance::GRAPHQL_DOCUMENT),
+            );
+        }
+    }
+}
+
+// Link symbols to the documents and examples that mention them by name, and
+// documents to the examples they hold
+fn find_documentation_relationships(graph: &CodeGraph, relationships: &mut Vec<Relationship>) {
+    let documents = graph.find_nodes_by_type(&NodeType::Documentation);
+
+    for document in &documents {
+        if document.metadata.get("doc_kind").map(String::as_str) == Some("file") {
+            for example in graph.find_nodes_in_file(&document.file_path) {
+                if example.node_type == NodeType::Documentation && example.id != document.id {
+                    relationships.push(
+                        Relationship::new(
+                            RelationshipType::Contains,
+                            document.id.clone(),
+                            example.id.clone(),
+                        )
+                        .with_provenance(provenance::DOC_MENTIONS),
+                    );
+                }
+            }
+        }
+
+        let Some(mentions) = document.metadata.get("mentions") else {
+            continue;
+        };
+        for mention in mentions.split(',') {
+            let symbols = graph
+                .find_nodes_by_name(mention)
+                .into_iter()
+                .filter(|node| {
+                    matches!(
+                        node.node_type,
+                        NodeType::Function
+                            | NodeType::Method
+                            | NodeType::Class
+                            | NodeType::Interface
+                            | NodeType::TypeDefinition
+                    )
+                });
+            for symbol in symbols {
+                trace!("Found mention of {} in {}", symbol.name, document.file_path);
+                relationships.push(
+                    Relationship::new(
+                        RelationshipType::DocumentedBy,
+                        symbol.id.clone(),
+                        document.id.clone(),
+                    )
+                    .with_provenance(provenance::DOC_MENTIONS),
+                );
+            }
+        }
+    }
+}
+
+// Link decorated declarations to the in-repo definitions of their decorators
+// and annotations: `@login_required` to the `login_required` function, or
+// `@Audited` to the `@interface Audited`. A definition in the same file wins,
+// then one the file imports, then any of the same language.
+fn find_decorator_relationships(graph: &CodeGraph, relationships: &mut Vec<Relationship>) {
+    let mut imported: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for rel in relationships.iter() {
+        if rel.relationship_type == RelationshipType::Imports
+            && let Some(source) = graph.get_node(&rel.from_id)
+        {
+            imported
+                .entry(source.file_path.as_str())
+                .or_default()
+                .insert(rel.to_id.as_str());
+        }
+    }
+
+    let mut annotations = Vec::new();
+    for node in graph.all_nodes() {
+        let Some(decorators) = node.metadata.get("decorators") else {
+            continue;
+        };
+        let language = crate::parsers::detect_language(Path::new(&node.file_path));
+        for decorator in decorators.split(',') {
+            // `functools.wraps`, `org.junit.Test` and `tracing::instrument`
+            // are defined as `wraps`, `Test` and `instrument`
+            let name = decorator.rsplit(['.', ':']).next().unwrap_or(decorator);
+            let candidates: Vec<&CodeNode> = graph
+                .find_nodes_by_name(name)
+                .into_iter()
+                .filter(|candidate| {
+                    candidate.id != node.id
+                        && matches!(
+                            candidate.node_type,
+                            NodeType::Function | NodeType::Class | NodeType::Interface
+                        )
+                        && crate::parsers::detect_language(Path::new(&candidate.file_path))
+                            == language
+                        // Rust attributes are defined by attribute macros only
+                        && (language.as_deref() != Some("rust")
+                            || candidate.metadata.get("decorators").is_some_and(|names| {
+                                names.split(',').any(|name| name == "proc_macro_attribute")
+                            }))
+                })
+                .collect();
+
+            let file_imports = imported.get(node.file_path.as_str());
+            let same_file: Vec<&CodeNode> = candidates
+                .iter()
+                .copied()
+                .filter(|candidate| candidate.file_path == node.file_path)
+                .collect();
+            let imports: Vec<&CodeNode> = candidates
+                .iter()
+                .copied()
+                .filter(|candidate| {
+                    file_imports.is_some_and(|ids| ids.contains(candidate.id.as_str()))
+                })
+                .collect();
+            let targets = if !same_file.is_empty() {
+                same_file
+            } else if !imports.is_empty() {
+                imports
+            } else {
+                candidates
+            };
+
+            for target in targets {
+                trace!("Found decorator {} on {}", target.name, node.name);
+                annotations.push(
+                    Relationship::new(
+                        RelationshipType::AnnotatedWith,
+                        node.id.clone(),
+                        target.id.clone(),
+                    )
+                    .with_provenance(provenance::DECORATOR_MATCH)
+                    .with_metadata("decorator".to_string(), decorator.to_string()),
+                );
+            }
+        }
+    }
+
+    debug!("Found {} decorator relationships", annotations.len());
+    relationships.extend(annotations);
+}
+
+fn is_rpc(node: &CodeNode) -> bool {
+    node.metadata.get("proto_kind").map(String::as_str) == Some("rpc")
+}
+
+fn lower_camel_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// Link RPCs to their request and response messages, and server-side
+// methods to the RPCs they implement: a method named after the RPC in a
+// class named after its service, such as `UserServiceServicer.GetUser`
+fn find_proto_relationships(graph: &CodeGraph, relationships: &mut Vec<Relationship>) {
+    let rpcs: Vec<&CodeNode> = graph
+        .find_nodes_by_type(&NodeType::Method)
+        .into_iter()
+        .filter(|node| is_rpc(node))
+        .collect();
+
+    for rpc in rpcs {
+        for key in ["request_type", "response_type"] {
+            let Some(message) = rpc.metadata.get(key) else {
+                continue;
+            };
+            // Qualified names such as `google.protobuf.Empty` match by their last part
+            let message = message.rsplit('.').next().unwrap_or(message);
+            for target in graph.find_nodes_by_name(message) {
+                if target.metadata.get("proto_kind").map(String::as_str) == Some("message") {
+                    relationships.push(
+                        Relationship::new(
+                            RelationshipType::References,
+                            rpc.id.clone(),
+                            target.id.clone(),
+                        )
+                        .with_provenance(provenance::PROTO_SCHEMA)
+                        .with_metadata("role".to_string(), key.replace("_type", "")),
+                    );
+                }
+            }
+        }
+
+        let Some(service) = rpc.parent_class.as_ref().map(|s| s.to_lowercase()) else {
+            continue;
+        };
+        let implementations = graph
+            .find_nodes_by_name(&rpc.name)
+            .into_iter()
+            .chain(graph.find_nodes_by_name(&lower_camel_case(&rpc.name)))
+            .filter(|node| node.node_type == NodeType::Method && !is_rpc(node))
+            .filter(|node| {
+                node.parent_class
+                    .as_ref()
+                    .is_some_and(|class| class.to_lowercase().contains(&service))
+            });
+
+        for implementation in implementations {
+            trace!(
+                "Found implementation {} of rpc {}",
+                implementation.name, rpc.name
+            );
+            relationships.push(
+                Relationship::new(
+                    RelationshipType::Implements,
+                    implementation.id.clone(),
+                    rpc.id.clone(),
+                )
+                .with_provenance(provenance::PROTO_SCHEMA),
+            );
+        }
+    }
 }
 
 fn find_import_relationships(
@@ -189,6 +1113,7 @@ fn find_import_relationships(
     nodes: &[(String, String, NodeType)],
     graph: &CodeGraph,
     relationships: &mut Vec<Relationship>,
+    unresolved: &mut Vec<UnresolvedReference>,
 ) {
     if let Some(extractor) = crate::indexing::extractor::get_extractor_for_language(language) {
         // Extract all imported modules from this file
@@ -203,39 +1128,50 @@ fn find_import_relationships(
 
         // For each imported module, find matching nodes in the graph
         for module_name in imported_modules {
-            // Find potential target modules/classes
-            for node in graph.all_nodes() {
-                if !matches!(
-                    node.node_type,
-                    NodeType::Module | NodeType::Class | NodeType::Interface
-                ) {
-                    continue;
-                }
-
-                // Skip nodes in the same file
-                if current_file_nodes.contains(&node.id) {
-                    continue;
-                }
-
+            // Find potential target modules/classes; the first by file and
+            // position wins so the choice does not depend on map order
+            let target = graph
+                .all_nodes()
+                .filter(|node| {
+                    matches!(
+                        node.node_type,
+                        NodeType::Module | NodeType::Class | NodeType::Interface
+                    )
+                })
+                // Skip nodes in the same file and non-canonical duplicates
+                .filter(|node| {
+                    !current_file_nodes.contains(&node.id)
+                        && !node.metadata.contains_key("duplicate_of")
+                })
                 // Check if this node matches the import
-                if node.name == module_name
-                    || Path::new(&node.file_path)
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .map(|s| s == module_name)
-                        .unwrap_or(false)
-                {
-                    // Add import relationship from each node in current file
-                    for (source_id, _, _) in nodes {
+                .filter(|node| {
+                    node.name == module_name
+                        || Path::new(&node.file_path)
+                            .file_stem()
+                            .and_then(|s| s.to_str())
+                            .map(|s| s == module_name)
+                            .unwrap_or(false)
+                })
+                .min_by(|a, b| (&a.file_path, a.line_range).cmp(&(&b.file_path, b.line_range)));
+
+            // Add import relationship from each node in current file
+            for (source_id, _, _) in nodes {
+                let relationship = Relationship::new(
+                    RelationshipType::Imports,
+                    source_id.clone(),
+                    target.map(|node| node.id.clone()).unwrap_or_default(),
+                )
+                .with_provenance(provenance::IMPORT_MATCH);
+                match target {
+                    Some(node) => {
                         trace!("Found import from {} to {}", file_path, node.name);
-                        relationships.push(Relationship::new(
-                            RelationshipType::Imports,
-                            source_id.clone(),
-                            node.id.clone(),
-                        ));
+                        relationships.push(relationship);
                     }
-
-                    break;
+                    None => unresolved.push(UnresolvedReference {
+                        language: language.to_string(),
+                        name: module_name.clone(),
+                        relationship,
+                    }),
                 }
             }
         }
@@ -245,33 +1181,200 @@ fn find_import_relationships(
 fn find_method_class_relationships(graph: &CodeGraph, relationships: &mut Vec<Relationship>) {
     // Find methods with parent_class metadata
     for node in graph.all_nodes() {
-        if node.node_type == NodeType::Method {
-            if let Some(parent_class) = node.metadata.get("parent_class") {
-                // Find all classes with this name
-                let potential_classes = graph.find_nodes_by_name(parent_class);
-
-                for class_node in potential_classes {
-                    if class_node.node_type == NodeType::Class
-                        || class_node.node_type == NodeType::Interface
-                    {
-                        trace!(
-                            "Found method-class relationship: {} belongs to {}",
-                            node.name, class_node.name
-                        );
+        if node.node_type == NodeType::Method
+            && let Some(parent_class) = &node.parent_class
+        {
+            // Find all classes with this name
+            let potential_classes = graph.find_nodes_by_name(parent_class);
+
+            for class_node in potential_classes {
+                if class_node.node_type == NodeType::Class
+                    || class_node.node_type == NodeType::Interface
+                {
+                    trace!(
+                        "Found method-class relationship: {} belongs to {}",
+                        node.name, class_node.name
+                    );
 
-                        // Add relationship from class to method (containment)
-                        relationships.push(Relationship::new(
+                    // Add relationship from class to method (containment)
+                    relationships.push(
+                        Relationship::new(
                             RelationshipType::Contains,
                             class_node.id.clone(),
                             node.id.clone(),
-                        ));
-                    }
+                        )
+                        .with_provenance(provenance::METHOD_CLASS_METADATA),
+                    );
                 }
             }
         }
     }
 }
 
+// Link fields to the innermost class, interface or type of their file that
+// declares them. Unlike the other containment, this covers short names
+// such as `id`.
+fn find_field_relationships(graph: &CodeGraph, relationships: &mut Vec<Relationship>) {
+    let mut owners: HashMap<&str, Vec<&CodeNode>> = HashMap::new();
+    let mut fields = Vec::new();
+    for node in graph.all_nodes() {
+        match node.node_type {
+            NodeType::Class | NodeType::Interface | NodeType::TypeDefinition => owners
+                .entry(node.file_path.as_str())
+                .or_default()
+                .push(node),
+            NodeType::Field => fields.push(node),
+            _ => {}
+        }
+    }
+
+    for field in fields {
+        let owner = owners
+            .get(field.file_path.as_str())
+            .into_iter()
+            .flatten()
+            .filter(|owner| {
+                owner.line_range.0 <= field.line_range.0 && field.line_range.1 <= owner.line_range.1
+            })
+            .min_by_key(|owner| owner.line_range.1 - owner.line_range.0);
+        if let Some(owner) = owner {
+            trace!("Found field {} of {}", field.name, owner.name);
+            relationships.push(
+                Relationship::new(
+                    RelationshipType::Contains,
+                    owner.id.clone(),
+                    field.id.clone(),
+                )
+                .with_provenance(provenance::HIERARCHY_CONTAINMENT),
+            );
+        }
+    }
+}
+
+// TypeScript in `.ts` and `.tsx` files implements the same interfaces
+pub(crate) fn language_family(file_path: &str) -> Option<String> {
+    crate::parsers::detect_language(Path::new(file_path)).map(|language| match language.as_str() {
+        "tsx" => "typescript".to_string(),
+        _ => language,
+    })
+}
+
+// Link methods to the interface and trait methods they implement: methods
+// of classes whose `implements` clause names the interface, methods of Rust
+// `impl Trait for Type` blocks, and methods of Go types whose method set
+// covers the interface's
+fn find_interface_method_relationships(graph: &CodeGraph, relationships: &mut Vec<Relationship>) {
+    let mut owners: HashMap<&str, Vec<&CodeNode>> = HashMap::new();
+    for node in graph.all_nodes() {
+        if matches!(node.node_type, NodeType::Class | NodeType::Interface) {
+            owners
+                .entry(node.file_path.as_str())
+                .or_default()
+                .push(node);
+        }
+    }
+    // Innermost class or interface around a method
+    let owner_of = |method: &CodeNode| {
+        owners
+            .get(method.file_path.as_str())
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|owner| {
+                owner.line_range.0 <= method.line_range.0
+                    && method.line_range.1 <= owner.line_range.1
+            })
+            .min_by_key(|owner| owner.line_range.1 - owner.line_range.0)
+    };
+
+    // Methods of every interface by language and interface name, then by
+    // method name
+    type InterfaceMethods<'a> = HashMap<(String, &'a str), HashMap<&'a str, Vec<&'a str>>>;
+    let mut declared: InterfaceMethods = HashMap::new();
+    let mut methods = Vec::new();
+    for node in graph.find_nodes_by_type(&NodeType::Method) {
+        let Some(language) = language_family(&node.file_path) else {
+            continue;
+        };
+        match owner_of(node) {
+            Some(owner) if owner.node_type == NodeType::Interface => declared
+                .entry((language, owner.name.as_str()))
+                .or_default()
+                .entry(node.name.as_str())
+                .or_default()
+                .push(node.id.as_str()),
+            // Go methods are declared apart from their type
+            owner => methods.push((node, language, owner)),
+        }
+    }
+    if declared.is_empty() {
+        return;
+    }
+
+    // Go types by their method names
+    let mut go_types: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for (method, language, _) in &methods {
+        if language == "go"
+            && let Some(receiver) = &method.parent_class
+        {
+            go_types
+                .entry(receiver.as_str())
+                .or_default()
+                .insert(method.name.as_str());
+        }
+    }
+
+    let mut implementations = Vec::new();
+    for (method, language, owner) in &methods {
+        let interfaces: Vec<&str> = if let Some(implemented) = method.metadata.get("implements") {
+            vec![implemented.as_str()]
+        } else if language == "go" {
+            let Some(method_names) = method
+                .parent_class
+                .as_ref()
+                .and_then(|receiver| go_types.get(receiver.as_str()))
+            else {
+                continue;
+            };
+            declared
+                .iter()
+                .filter(|((other, _), members)| {
+                    other == "go" && members.keys().all(|name| method_names.contains(name))
+                })
+                .map(|((_, interface), _)| *interface)
+                .collect()
+        } else {
+            owner
+                .and_then(|owner| owner.metadata.get("implements"))
+                .map(|names| names.split(',').collect())
+                .unwrap_or_default()
+        };
+
+        for interface in interfaces {
+            let targets = declared
+                .get(&(language.clone(), interface))
+                .and_then(|members| members.get(method.name.as_str()));
+            for target in targets.into_iter().flatten() {
+                trace!("Found implementation of {}::{}", interface, method.name);
+                implementations.push(
+                    Relationship::new(
+                        RelationshipType::Implements,
+                        method.id.clone(),
+                        target.to_string(),
+                    )
+                    .with_provenance(provenance::INTERFACE_IMPLEMENTATION),
+                );
+            }
+        }
+    }
+
+    debug!(
+        "Found {} implementations of interface methods",
+        implementations.len()
+    );
+    relationships.extend(implementations);
+}
+
 fn find_hierarchical_relationships(
     nodes: &[(String, String, NodeType)],
     graph: &CodeGraph,
@@ -310,69 +1413,92 @@ fn find_hierarchical_relationships(
                     "Found class containment: {} contains {}",
                     outer.name, inner.name
                 );
-                relationships.push(Relationship::new(
-                    RelationshipType::Contains,
-                    outer.id.clone(),
-                    inner.id.clone(),
-                ));
+                relationships.push(
+                    Relationship::new(
+                        RelationshipType::Contains,
+                        outer.id.clone(),
+                        inner.id.clone(),
+                    )
+                    .with_provenance(provenance::HIERARCHY_CONTAINMENT),
+                );
             }
         }
     }
 }
 
+// Template summaries for every node, containers described by their members
 pub fn generate_summaries(graph: &mut CodeGraph) {
-    info!("Generating summaries for {} nodes", graph.node_count());
-
-    let mut summary_counts = HashMap::new();
-
-    for node in graph.all_nodes_mut() {
-        let node_type = &node.node_type;
-
-        let summary = match node_type {
-            NodeType::Function => format!("Function that handles {}", node.name),
-            NodeType::Method => format!("Method that implements {}", node.name),
-            NodeType::Class => format!("Class that represents {}", node.name),
-            NodeType::Interface => format!("Interface for {}", node.name),
-            NodeType::Module => format!("Module containing {}", node.name),
-            NodeType::TypeDefinition => format!("Type definition for {}", node.name),
-            _ => format!("Code unit: {}", node.name),
-        };
-
-        node.summary = Some(summary);
-
-        *summary_counts.entry(node_type.clone()).or_insert(0) += 1;
-    }
-
-    for (node_type, count) in summary_counts {
-        debug!("Generated {:?} summaries for {} nodes", node_type, count);
-    }
-
-    info!("Summary generation complete");
+    summarize_graph(graph, &TemplateSummarizer);
 }
 
+// File, class and name shared by the overloads of a function
+type OverloadKey<'a> = (&'a str, Option<&'a str>, &'a str);
+
 pub fn enhance_method_names(graph: &mut CodeGraph) {
     info!("Enhancing method names with parent class information...");
     let mut methods_to_update = Vec::new();
 
     // First collect all methods that need updating
     for node in graph.all_nodes() {
-        if node.node_type == NodeType::Method {
-            if let Some(parent_class) = node.metadata.get("parent_class") {
-                methods_to_update
-                    .push((node.id.clone(), format!("{}::{}", parent_class, node.name)));
+        if node.node_type == NodeType::Method
+            && let Some(parent_class) = &node.parent_class
+        {
+            let prefix = format!("{}::", parent_class);
+            // Nodes reused from a previous graph are already enhanced
+            if !node.name.starts_with(&prefix) {
+                methods_to_update.push((node.id.clone(), format!("{}{}", prefix, node.name)));
+            }
+        }
+    }
+
+    // Overloads, functions of a file and class sharing a name, are told
+    // apart by their parameter types
+    let mut by_name: HashMap<OverloadKey, Vec<&CodeNode>> = HashMap::new();
+    for node in graph.all_nodes() {
+        if matches!(node.node_type, NodeType::Function | NodeType::Method)
+            && node.signature.is_some()
+        {
+            let name = node.name.split('(').next().unwrap_or_default();
+            let name = name.rsplit("::").next().unwrap_or(name);
+            let parent_class = node.parent_class.as_deref();
+            by_name
+                .entry((node.file_path.as_str(), parent_class, name))
+                .or_default()
+                .push(node);
+        }
+    }
+    for overloads in by_name.values().filter(|overloads| overloads.len() > 1) {
+        let signatures: HashSet<&Vec<String>> = overloads
+            .iter()
+            .filter_map(|node| node.signature.as_ref())
+            .map(|signature| &signature.parameter_types)
+            .collect();
+        if signatures.len() < 2 {
+            continue;
+        }
+        for node in overloads {
+            let parameter_types = node
+                .signature
+                .as_ref()
+                .map(|signature| signature.parameter_types.join(", "))
+                .unwrap_or_default();
+            let suffix = format!("({})", parameter_types);
+            if node.name.ends_with(&suffix) {
+                continue;
             }
+            let name = methods_to_update
+                .iter()
+                .position(|(id, _)| *id == node.id)
+                .map(|index| methods_to_update.remove(index).1)
+                .unwrap_or_else(|| node.name.clone());
+            methods_to_update.push((node.id.clone(), format!("{}{}", name, suffix)));
         }
     }
 
     // Now update the methods with enhanced names
     for (id, enhanced_name) in methods_to_update {
-        if let Some(node) = graph.get_node_mut(&id) {
-            debug!(
-                "Updating method name from '{}' to '{}'",
-                node.name, enhanced_name
-            );
-            node.name = enhanced_name;
-        }
+        debug!("Updating method name of {} to '{}'", id, enhanced_name);
+        graph.rename_node(&id, enhanced_name);
     }
 
     info!("Method names enhancement complete");
@@ -0,0 +1,64 @@
+use crate::code_graph::{CodeNode, SCHEMA_VERSION};
+use crate::indexing::config::IndexConfig;
+use crate::utils::hash::content_hash;
+use log::{debug, trace, warn};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Extracted nodes of every file, stored on disk so that unchanged files are
+// not parsed again on the next run. Entries are keyed by the file's path and
+// content hash, along with everything else that changes what extraction
+// produces, so stale entries are never read; they are simply left behind.
+pub struct IndexCache {
+    dir: PathBuf,
+    salt: String,
+}
+
+impl IndexCache {
+    pub fn open(dir: &Path, config: &IndexConfig) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        debug!("Using index cache at {:?}", dir);
+
+        Ok(IndexCache {
+            dir: dir.to_path_buf(),
+            salt: format!(
+                "{}:{}:{:?}:{:?}",
+                env!("CARGO_PKG_VERSION"),
+                SCHEMA_VERSION,
+                config.generated_files,
+                config.max_file_size
+            ),
+        })
+    }
+
+    fn entry_path(&self, path: &Path, hash: &str) -> PathBuf {
+        let key = content_hash(format!("{}:{}:{}", self.salt, path.display(), hash).as_bytes());
+        self.dir.join(format!("{}.json", key))
+    }
+
+    pub fn load(&self, path: &Path, hash: &str) -> Option<Vec<CodeNode>> {
+        let json = fs::read_to_string(self.entry_path(path, hash)).ok()?;
+        match serde_json::from_str(&json) {
+            Ok(nodes) => Some(nodes),
+            Err(e) => {
+                warn!("Ignoring unreadable cache entry for {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    pub fn store(&self, path: &Path, hash: &str, nodes: &[CodeNode]) -> io::Result<()> {
+        let entry = self.entry_path(path, hash);
+        let json = serde_json::to_string(nodes).map_err(io::Error::other)?;
+
+        // Written under a temporary name first so a concurrent or interrupted
+        // run never reads half an entry
+        let partial = entry.with_extension("json.partial");
+        fs::write(&partial, json)?;
+        fs::rename(&partial, &entry)?;
+        trace!("Cached {} nodes for {:?}", nodes.len(), path);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,573 @@
+use crate::code_graph::{CodeNode, ContentLimit, TokenEncoding};
+use crate::indexing::extractor::common::stable_node_id;
+use crate::utils::hash::content_hash;
+use crate::utils::io::Compression;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// How files with identical content at different paths are indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicatePolicy {
+    // Index every copy independently
+    #[default]
+    Keep,
+    // Index only the canonical copy and record the other paths as aliases
+    Alias,
+    // Index every copy, tagging non-canonical nodes with `duplicate_of`
+    Tag,
+}
+
+// How generated and minified files are indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GeneratedPolicy {
+    #[default]
+    Skip,
+    // Index them, marking their nodes with `generated: true`
+    Tag,
+    // Index them like any other file
+    Keep,
+}
+
+impl DuplicatePolicy {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "keep" => Some(DuplicatePolicy::Keep),
+            "alias" => Some(DuplicatePolicy::Alias),
+            "tag" => Some(DuplicatePolicy::Tag),
+            _ => None,
+        }
+    }
+}
+
+impl GeneratedPolicy {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "skip" => Some(GeneratedPolicy::Skip),
+            "tag" => Some(GeneratedPolicy::Tag),
+            "keep" => Some(GeneratedPolicy::Keep),
+            _ => None,
+        }
+    }
+}
+
+// How node IDs are derived. All schemes are deterministic; nodes that
+// would collide get a `#n` suffix in file order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdScheme {
+    // Name-based UUID over file, node type, name and line range
+    #[default]
+    Uuid,
+    // Hash of node type, name and content; stable when code moves
+    ContentHash,
+    // Readable `file::name` identifier
+    QualifiedName,
+}
+
+impl IdScheme {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "uuid" => Some(IdScheme::Uuid),
+            "content-hash" => Some(IdScheme::ContentHash),
+            "qualified-name" => Some(IdScheme::QualifiedName),
+            _ => None,
+        }
+    }
+}
+
+// How much source text container nodes (classes, interfaces, modules) keep.
+// Their members are nodes of their own, so the full body repeats them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContainerContent {
+    #[default]
+    Full,
+    // Declaration up to the opening of the body
+    Header,
+    FirstLines(usize),
+}
+
+impl ContainerContent {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "full" => Some(ContainerContent::Full),
+            "header" => Some(ContainerContent::Header),
+            _ => value
+                .strip_prefix("lines:")
+                .and_then(|n| n.parse().ok())
+                .map(ContainerContent::FirstLines),
+        }
+    }
+}
+
+// How node summaries are written
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SummaryBackend {
+    // From templates over node types, names and members
+    #[default]
+    Template,
+    // By a chat completions endpoint, with the `llm` feature
+    Llm {
+        url: String,
+        model: String,
+    },
+}
+
+impl SummaryBackend {
+    // `template` or `llm:<model>@<url>`
+    pub fn parse(value: &str) -> Option<Self> {
+        if value == "template" {
+            return Some(SummaryBackend::Template);
+        }
+        let (model, url) = value.strip_prefix("llm:")?.split_once('@')?;
+        (!model.is_empty() && !url.is_empty()).then(|| SummaryBackend::Llm {
+            url: url.to_string(),
+            model: model.to_string(),
+        })
+    }
+}
+
+// Kinds of nodes a run can leave out of the graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeCategory {
+    // Every variable and constant
+    Variables,
+    // Variables declared inside a function body
+    LocalVariables,
+    // Anonymous functions passed as values: lambdas, arrow functions and
+    // closures that are not bound to a name
+    Closures,
+    // Struct fields and class properties
+    Fields,
+}
+
+impl NodeCategory {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "variables" => Some(NodeCategory::Variables),
+            "local-variables" => Some(NodeCategory::LocalVariables),
+            "closures" => Some(NodeCategory::Closures),
+            "fields" => Some(NodeCategory::Fields),
+            _ => None,
+        }
+    }
+}
+
+const DEFAULT_MAX_FILE_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct IndexConfig {
+    pub num_threads: usize,
+    pub duplicate_files: DuplicatePolicy,
+    // Emit References edges to variables and types (increases graph size)
+    pub references: bool,
+    // Relationship passes (by provenance name) that should not run
+    pub disabled_passes: HashSet<String>,
+    pub id_scheme: IdScheme,
+    // Prepended to every node ID (e.g. a repository or snapshot name) so
+    // IDs from several graphs can share one store
+    pub id_namespace: Option<String>,
+    pub container_content: ContainerContent,
+    // Node content kept in the exported graph
+    pub export_content: ContentLimit,
+    // Mask credentials found in node content before the graph is exported
+    pub redact_secrets: bool,
+    pub export_compression: Compression,
+    // Write one graph per top-level directory into the output directory
+    pub shard_output: bool,
+    // Store fan-in, fan-out and PageRank scores as node metadata
+    pub metrics: bool,
+    // Only files in these languages are parsed; None parses every language
+    pub languages: Option<HashSet<String>>,
+    pub excluded_languages: HashSet<String>,
+    // Nodes left out of the graph in every language
+    pub excluded_nodes: HashSet<NodeCategory>,
+    // Nodes left out of the graph for one language only
+    pub excluded_language_nodes: HashMap<String, HashSet<NodeCategory>>,
+    // Files larger than this many bytes are not parsed
+    pub max_file_size: Option<usize>,
+    pub generated_files: GeneratedPolicy,
+    // Walk into symlinked directories and index symlinked files
+    pub follow_symlinks: bool,
+    // Index files that resolve to a location outside the indexed root
+    pub allow_outside_root: bool,
+    // Directory of the on-disk cache of extracted nodes; None disables it
+    pub cache_dir: Option<PathBuf>,
+    // Index only files tracked by git
+    pub git_tracked_only: bool,
+    // Index the tree of this git revision instead of the working tree
+    pub git_revision: Option<String>,
+    // Annotate nodes with their last commit and the number of commits that
+    // changed them within this many days; None skips the history pass
+    pub git_history_days: Option<u32>,
+    // Add File and Directory nodes above the symbols, with relationships
+    // rolled up to them
+    pub directory_hierarchy: bool,
+    // Store the token count of every node's content in this encoding;
+    // None skips counting
+    pub token_encoding: Option<TokenEncoding>,
+    // Point unresolved calls and imports at ExternalSymbol placeholder nodes
+    // instead of dropping them
+    pub external_symbols: bool,
+    // Annotate File and Module nodes with their CODEOWNERS owners and
+    // license
+    pub file_ownership: bool,
+    // Index only the files CODEOWNERS assigns to this owner (e.g.
+    // `@org/team`); references into other files become ExternalSymbol
+    // placeholders
+    pub owned_by: Option<String>,
+    // Searched for `#include` targets after the including file's directory;
+    // relative directories match at any depth of the codebase
+    pub include_dirs: Vec<PathBuf>,
+    pub summaries: SummaryBackend,
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        IndexConfig {
+            num_threads: num_cpus::get(),
+            duplicate_files: DuplicatePolicy::default(),
+            references: false,
+            disabled_passes: HashSet::new(),
+            id_scheme: IdScheme::default(),
+            id_namespace: None,
+            container_content: ContainerContent::default(),
+            export_content: ContentLimit::default(),
+            redact_secrets: false,
+            export_compression: Compression::default(),
+            shard_output: false,
+            metrics: false,
+            languages: None,
+            excluded_languages: HashSet::new(),
+            // Locals are opt-in
+            excluded_nodes: HashSet::from([NodeCategory::LocalVariables]),
+            excluded_language_nodes: HashMap::new(),
+            max_file_size: Some(DEFAULT_MAX_FILE_SIZE),
+            generated_files: GeneratedPolicy::default(),
+            follow_symlinks: false,
+            allow_outside_root: false,
+            cache_dir: None,
+            git_tracked_only: false,
+            git_revision: None,
+            git_history_days: None,
+            directory_hierarchy: false,
+            token_encoding: None,
+            external_symbols: false,
+            file_ownership: false,
+            owned_by: None,
+            include_dirs: Vec::new(),
+            summaries: SummaryBackend::default(),
+        }
+    }
+}
+
+impl IndexConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads;
+        self
+    }
+
+    pub fn duplicate_files(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_files = policy;
+        self
+    }
+
+    pub fn references(mut self, enabled: bool) -> Self {
+        self.references = enabled;
+        self
+    }
+
+    pub fn disable_pass(mut self, provenance: &str) -> Self {
+        self.disabled_passes.insert(provenance.to_string());
+        self
+    }
+
+    pub fn id_scheme(mut self, scheme: IdScheme) -> Self {
+        self.id_scheme = scheme;
+        self
+    }
+
+    pub fn id_namespace(mut self, namespace: &str) -> Self {
+        self.id_namespace = Some(namespace.to_string());
+        self
+    }
+
+    pub fn container_content(mut self, content: ContainerContent) -> Self {
+        self.container_content = content;
+        self
+    }
+
+    pub fn export_content(mut self, limit: ContentLimit) -> Self {
+        self.export_content = limit;
+        self
+    }
+
+    pub fn redact_secrets(mut self, enabled: bool) -> Self {
+        self.redact_secrets = enabled;
+        self
+    }
+
+    pub fn export_compression(mut self, compression: Compression) -> Self {
+        self.export_compression = compression;
+        self
+    }
+
+    pub fn shard_output(mut self, enabled: bool) -> Self {
+        self.shard_output = enabled;
+        self
+    }
+
+    pub fn metrics(mut self, enabled: bool) -> Self {
+        self.metrics = enabled;
+        self
+    }
+
+    pub fn languages(mut self, languages: &[&str]) -> Self {
+        self.languages = Some(languages.iter().map(|l| l.to_string()).collect());
+        self
+    }
+
+    pub fn exclude_language(mut self, language: &str) -> Self {
+        self.excluded_languages.insert(language.to_string());
+        self
+    }
+
+    pub fn exclude_nodes(mut self, category: NodeCategory) -> Self {
+        self.excluded_nodes.insert(category);
+        self
+    }
+
+    pub fn include_nodes(mut self, category: NodeCategory) -> Self {
+        self.excluded_nodes.remove(&category);
+        for categories in self.excluded_language_nodes.values_mut() {
+            categories.remove(&category);
+        }
+        self
+    }
+
+    pub fn exclude_language_nodes(mut self, language: &str, category: NodeCategory) -> Self {
+        self.excluded_language_nodes
+            .entry(language.to_string())
+            .or_default()
+            .insert(category);
+        self
+    }
+
+    pub fn max_file_size(mut self, bytes: Option<usize>) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    pub fn generated_files(mut self, policy: GeneratedPolicy) -> Self {
+        self.generated_files = policy;
+        self
+    }
+
+    pub fn follow_symlinks(mut self, enabled: bool) -> Self {
+        self.follow_symlinks = enabled;
+        self
+    }
+
+    pub fn allow_outside_root(mut self, enabled: bool) -> Self {
+        self.allow_outside_root = enabled;
+        self
+    }
+
+    pub fn cache_dir(mut self, dir: &Path) -> Self {
+        self.cache_dir = Some(dir.to_path_buf());
+        self
+    }
+
+    pub fn git_tracked_only(mut self, enabled: bool) -> Self {
+        self.git_tracked_only = enabled;
+        self
+    }
+
+    pub fn git_revision(mut self, revision: &str) -> Self {
+        self.git_revision = Some(revision.to_string());
+        self
+    }
+
+    pub fn git_history(mut self, days: Option<u32>) -> Self {
+        self.git_history_days = days;
+        self
+    }
+
+    pub fn directory_hierarchy(mut self, enabled: bool) -> Self {
+        self.directory_hierarchy = enabled;
+        self
+    }
+
+    pub fn token_counts(mut self, encoding: Option<TokenEncoding>) -> Self {
+        self.token_encoding = encoding;
+        self
+    }
+
+    pub fn external_symbols(mut self, enabled: bool) -> Self {
+        self.external_symbols = enabled;
+        self
+    }
+
+    pub fn file_ownership(mut self, enabled: bool) -> Self {
+        self.file_ownership = enabled;
+        self
+    }
+
+    pub fn owned_by(mut self, owner: &str) -> Self {
+        self.owned_by = Some(owner.to_string());
+        self
+    }
+
+    // Placeholders are needed whenever part of the codebase is left out on
+    // purpose, or references across the boundary would be lost
+    pub fn uses_external_symbols(&self) -> bool {
+        self.external_symbols || self.owned_by.is_some()
+    }
+
+    pub fn include_dir(mut self, dir: &Path) -> Self {
+        self.include_dirs.push(dir.to_path_buf());
+        self
+    }
+
+    pub fn summaries(mut self, backend: SummaryBackend) -> Self {
+        self.summaries = backend;
+        self
+    }
+
+    pub fn uses_git(&self) -> bool {
+        self.git_tracked_only || self.git_revision.is_some()
+    }
+
+    pub fn node_id(&self, node: &CodeNode) -> String {
+        let id = match self.id_scheme {
+            IdScheme::Uuid => stable_node_id(
+                &node.node_type,
+                &node.name,
+                &node.file_path,
+                node.line_range,
+            ),
+            IdScheme::ContentHash => content_hash(
+                format!("{:?}:{}:{}", node.node_type, node.name, node.content).as_bytes(),
+            ),
+            IdScheme::QualifiedName => format!("{}::{}", node.file_path, node.name),
+        };
+        self.namespaced_id(id)
+    }
+
+    // Prefix an ID with the namespace, for nodes whose ID does not follow
+    // the configured scheme
+    pub fn namespaced_id(&self, id: String) -> String {
+        match &self.id_namespace {
+            Some(namespace) => format!("{}:{}", namespace, id),
+            None => id,
+        }
+    }
+
+    // Settings that shape the graph, by name, as recorded in the provenance
+    // of exports. Thread count and cache location do not change the output
+    // and are left out.
+    pub fn settings(&self) -> BTreeMap<String, String> {
+        fn sorted<T: ToString>(values: impl IntoIterator<Item = T>) -> String {
+            let mut values: Vec<String> = values.into_iter().map(|v| v.to_string()).collect();
+            values.sort();
+            values.join(",")
+        }
+        let categories = |categories: &HashSet<NodeCategory>| {
+            sorted(categories.iter().map(|category| format!("{:?}", category)))
+        };
+
+        let mut settings = BTreeMap::from([
+            ("id_scheme", format!("{:?}", self.id_scheme)),
+            ("duplicate_files", format!("{:?}", self.duplicate_files)),
+            ("generated_files", format!("{:?}", self.generated_files)),
+            ("container_content", format!("{:?}", self.container_content)),
+            ("export_content", format!("{:?}", self.export_content)),
+            ("references", self.references.to_string()),
+            ("metrics", self.metrics.to_string()),
+            ("directory_hierarchy", self.directory_hierarchy.to_string()),
+            ("external_symbols", self.external_symbols.to_string()),
+            ("file_ownership", self.file_ownership.to_string()),
+            ("redact_secrets", self.redact_secrets.to_string()),
+            // The endpoint may carry credentials, so only the model is kept
+            (
+                "summaries",
+                match &self.summaries {
+                    SummaryBackend::Template => "template".to_string(),
+                    SummaryBackend::Llm { model, .. } => format!("llm:{}", model),
+                },
+            ),
+            ("git_tracked_only", self.git_tracked_only.to_string()),
+            ("follow_symlinks", self.follow_symlinks.to_string()),
+            ("allow_outside_root", self.allow_outside_root.to_string()),
+            ("excluded_nodes", categories(&self.excluded_nodes)),
+        ]);
+        let optional = [
+            ("id_namespace", self.id_namespace.clone()),
+            ("git_revision", self.git_revision.clone()),
+            ("owned_by", self.owned_by.clone()),
+            (
+                "git_history_days",
+                self.git_history_days.map(|d| d.to_string()),
+            ),
+            ("max_file_size", self.max_file_size.map(|s| s.to_string())),
+            (
+                "token_encoding",
+                self.token_encoding.map(|e| format!("{:?}", e)),
+            ),
+            ("languages", self.languages.as_ref().map(sorted)),
+        ];
+        for (name, value) in optional {
+            if let Some(value) = value {
+                settings.insert(name, value);
+            }
+        }
+        let sets = [
+            ("excluded_languages", sorted(&self.excluded_languages)),
+            ("disabled_passes", sorted(&self.disabled_passes)),
+            (
+                "excluded_language_nodes",
+                sorted(
+                    self.excluded_language_nodes
+                        .iter()
+                        .map(|(language, nodes)| format!("{}:{}", language, categories(nodes))),
+                ),
+            ),
+            (
+                "include_dirs",
+                sorted(self.include_dirs.iter().map(|dir| dir.display())),
+            ),
+        ];
+        for (name, value) in sets {
+            if !value.is_empty() {
+                settings.insert(name, value);
+            }
+        }
+
+        settings
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    pub fn is_pass_enabled(&self, provenance: &str) -> bool {
+        !self.disabled_passes.contains(provenance)
+    }
+
+    pub fn is_node_category_enabled(&self, language: &str, category: NodeCategory) -> bool {
+        !self.excluded_nodes.contains(&category)
+            && self
+                .excluded_language_nodes
+                .get(language)
+                .is_none_or(|categories| !categories.contains(&category))
+    }
+
+    pub fn is_language_enabled(&self, language: &str) -> bool {
+        !self.excluded_languages.contains(language)
+            && self
+                .languages
+                .as_ref()
+                .is_none_or(|languages| languages.contains(language))
+    }
+}
@@ -0,0 +1,523 @@
+use crate::code_graph::{
+    CodeGraph, CodeNode, NodeType, Relationship, RelationshipType, provenance,
+};
+use crate::indexing::config::IndexConfig;
+use crate::indexing::extractor::common::create_node;
+use crate::indexing::extractor::decorator_name;
+use crate::indexing::hierarchy::add_unique_node;
+use crate::indexing::provider::FileProvider;
+use log::{debug, info, trace, warn};
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+const HTTP_METHODS: &[&str] = &["get", "post", "put", "delete", "patch", "head", "options"];
+
+// A route a framework serves, before it is linked to its handler
+struct Route {
+    method: String,
+    path: String,
+    framework: &'static str,
+    line: usize,
+    expression: String,
+}
+
+// Patterns of route declarations that are not decorators
+struct RoutePatterns {
+    // First string literal of an argument list, `"/items"` in `("/items", ...)`
+    first_string: Regex,
+    // `methods=["GET", "POST"]` of Flask routes
+    flask_methods: Regex,
+    // `method = RequestMethod.POST` of Spring request mappings
+    spring_methods: Regex,
+    // Express `app.get('/users/:id', handler)`
+    express_route: Regex,
+    // axum and actix `.route("/users/{id}", get(show).post(update))`, with
+    // parentheses nested two deep in the method router
+    rust_route: Regex,
+    axum_handler: Regex,
+    actix_handler: Regex,
+    // actix and Rocket `#[get("/users/{id}")]` on a handler
+    rust_attribute: Regex,
+}
+
+impl RoutePatterns {
+    fn new() -> Option<Self> {
+        Some(RoutePatterns {
+            first_string: Regex::new(r#"["'`]([^"'`]*)["'`]"#).ok()?,
+            flask_methods: Regex::new(r"methods\s*=\s*[\[(]([^\])]*)[\])]").ok()?,
+            spring_methods: Regex::new(r"RequestMethod\.([A-Z]+)").ok()?,
+            express_route: Regex::new(
+                r#"\b[\w$]+\.(get|post|put|delete|patch|head|options|all)\(\s*["'`](/[^"'`]*)["'`]\s*,([^;]*)"#,
+            )
+            .ok()?,
+            rust_route: Regex::new(
+                r#"\.route\(\s*"([^"]*)"\s*,\s*((?:[^()]|\((?:[^()]|\([^()]*\))*\))*)\)"#,
+            )
+            .ok()?,
+            axum_handler: Regex::new(
+                r"\b(get|post|put|delete|patch|head|options|any)\(\s*([\w:]+)\s*\)",
+            )
+            .ok()?,
+            actix_handler: Regex::new(
+                r"web::(get|post|put|delete|patch|head)\(\)\s*\.to\(\s*([\w:]+)\s*\)",
+            )
+            .ok()?,
+            rust_attribute: Regex::new(
+                r#"^#\[(?:[\w:]+::)?(get|post|put|delete|patch|head|options)\(\s*"([^"]*)""#,
+            )
+            .ok()?,
+        })
+    }
+
+    fn first_string(&self, arguments: &str) -> Option<String> {
+        self.first_string
+            .captures(arguments)
+            .map(|captures| captures[1].to_string())
+    }
+}
+
+// How a route names the function that serves it
+enum Handler<'a> {
+    // A function node
+    Node(&'a str),
+    // A function referred to by name, `users::show` or `UserController.show`
+    Named(String),
+    // An inline function starting on the route's line
+    Inline,
+}
+
+fn arguments(expression: &str) -> &str {
+    expression
+        .split_once('(')
+        .map(|(_, rest)| rest.trim_end().trim_end_matches(')'))
+        .unwrap_or_default()
+}
+
+fn decorator_calls(node: &CodeNode) -> Vec<String> {
+    node.metadata
+        .get("decorator_calls")
+        .and_then(|calls| serde_json::from_str(calls).ok())
+        .unwrap_or_default()
+}
+
+// A controller prefix and a route path joined into one path with a single
+// leading slash
+fn join_paths(prefix: &str, path: &str) -> String {
+    let joined: Vec<&str> = prefix
+        .split('/')
+        .chain(path.split('/'))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    format!("/{}", joined.join("/"))
+}
+
+// FastAPI `@app.get("/items/{id}")` and `@router.api_route(...)`, Flask
+// `@app.route("/items", methods=["GET", "POST"])` and `@bp.get("/items")`
+fn python_routes(
+    patterns: &RoutePatterns,
+    expression: &str,
+    line: usize,
+    fastapi: bool,
+) -> Vec<Route> {
+    let name = decorator_name(expression);
+    let Some((_, attribute)) = name.rsplit_once('.') else {
+        return Vec::new();
+    };
+    let arguments = arguments(expression);
+    let Some(path) = patterns
+        .first_string(arguments)
+        .filter(|path| path.starts_with('/'))
+    else {
+        return Vec::new();
+    };
+    let methods: Vec<String> = match attribute {
+        "route" | "api_route" => match patterns.flask_methods.captures(arguments) {
+            Some(captures) => captures[1]
+                .split(',')
+                .map(|method| method.trim().trim_matches(['"', '\'']).to_uppercase())
+                .filter(|method| !method.is_empty())
+                .collect(),
+            None => vec!["GET".to_string()],
+        },
+        _ if HTTP_METHODS.contains(&attribute) => vec![attribute.to_uppercase()],
+        _ => return Vec::new(),
+    };
+    let framework = if fastapi { "fastapi" } else { "flask" };
+    methods
+        .into_iter()
+        .map(|method| Route {
+            method,
+            path: path.clone(),
+            framework,
+            line,
+            expression: expression.to_string(),
+        })
+        .collect()
+}
+
+// NestJS `@Get(':id')` under `@Controller('users')`
+fn nest_routes(
+    patterns: &RoutePatterns,
+    expression: &str,
+    prefix: Option<&str>,
+    line: usize,
+) -> Vec<Route> {
+    let name = decorator_name(expression);
+    let method = match name {
+        "Get" | "Post" | "Put" | "Delete" | "Patch" | "Head" | "Options" => name.to_uppercase(),
+        "All" => "ANY".to_string(),
+        _ => return Vec::new(),
+    };
+    let path = patterns
+        .first_string(arguments(expression))
+        .unwrap_or_default();
+    vec![Route {
+        method,
+        path: join_paths(prefix.unwrap_or_default(), &path),
+        framework: "nestjs",
+        line,
+        expression: expression.to_string(),
+    }]
+}
+
+// Spring `@GetMapping("/{id}")` and `@RequestMapping(value = "/", method =
+// RequestMethod.POST)` under a class-level `@RequestMapping("/users")`
+fn spring_routes(
+    patterns: &RoutePatterns,
+    expression: &str,
+    prefix: Option<&str>,
+    line: usize,
+) -> Vec<Route> {
+    let name = decorator_name(expression);
+    let arguments = arguments(expression);
+    let methods: Vec<String> = match name.strip_suffix("Mapping") {
+        Some("Request") => {
+            let methods: Vec<String> = patterns
+                .spring_methods
+                .captures_iter(arguments)
+                .map(|captures| captures[1].to_string())
+                .collect();
+            if methods.is_empty() {
+                vec!["ANY".to_string()]
+            } else {
+                methods
+            }
+        }
+        Some(method) if HTTP_METHODS.contains(&method.to_lowercase().as_str()) => {
+            vec![method.to_uppercase()]
+        }
+        _ => return Vec::new(),
+    };
+    let path = patterns.first_string(arguments).unwrap_or_default();
+    let path = join_paths(prefix.unwrap_or_default(), &path);
+    methods
+        .into_iter()
+        .map(|method| Route {
+            method,
+            path: path.clone(),
+            framework: "spring",
+            line,
+            expression: expression.to_string(),
+        })
+        .collect()
+}
+
+// Path prefix a class-level decorator gives the routes of its methods
+fn controller_prefix(patterns: &RoutePatterns, class: &CodeNode) -> Option<String> {
+    decorator_calls(class)
+        .into_iter()
+        .find_map(|expression| match decorator_name(&expression) {
+            "Controller" | "RequestMapping" => patterns.first_string(arguments(&expression)),
+            _ => None,
+        })
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+// Express routes registered on an app or router
+fn express_routes(patterns: &RoutePatterns, content: &str) -> Vec<(Route, Handler<'static>)> {
+    let mut routes = Vec::new();
+    for captures in patterns.express_route.captures_iter(content) {
+        let whole = captures.get(0).unwrap();
+        // The handler is the last argument; middlewares come before it
+        let rest = &captures[3];
+        let last = rest
+            .rsplit(',')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .trim_end_matches(')')
+            .trim();
+        let handler = if !last.is_empty()
+            && last
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.'))
+        {
+            Handler::Named(last.to_string())
+        } else {
+            Handler::Inline
+        };
+        let method = match &captures[1] {
+            "all" => "ANY".to_string(),
+            method => method.to_uppercase(),
+        };
+        let line = line_of(content, whole.start());
+        routes.push((
+            Route {
+                method,
+                path: captures[2].to_string(),
+                framework: "express",
+                line,
+                expression: content[whole.start()..]
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string(),
+            },
+            handler,
+        ));
+    }
+    routes
+}
+
+// axum `.route(path, get(handler))` and actix `.route(path,
+// web::get().to(handler))` builders
+fn rust_builder_routes(patterns: &RoutePatterns, content: &str) -> Vec<(Route, Handler<'static>)> {
+    let mut routes = Vec::new();
+    for captures in patterns.rust_route.captures_iter(content) {
+        let whole = captures.get(0).unwrap();
+        let router = &captures[2];
+        let (handlers, framework) = if router.contains("web::") {
+            (&patterns.actix_handler, "actix")
+        } else {
+            (&patterns.axum_handler, "axum")
+        };
+        let line = line_of(content, whole.start());
+        let expression = whole
+            .as_str()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        for handler in handlers.captures_iter(router) {
+            let method = match &handler[1] {
+                "any" => "ANY".to_string(),
+                method => method.to_uppercase(),
+            };
+            routes.push((
+                Route {
+                    method,
+                    path: captures[1].to_string(),
+                    framework,
+                    line,
+                    expression: expression.clone(),
+                },
+                Handler::Named(handler[2].to_string()),
+            ));
+        }
+    }
+    routes
+}
+
+// actix and Rocket route attributes on the lines above a function
+fn rust_attribute_routes<'a>(
+    patterns: &RoutePatterns,
+    content: &str,
+    functions: &[&'a CodeNode],
+) -> Vec<(Route, Handler<'a>)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let framework = if content.contains("actix_web") {
+        "actix"
+    } else {
+        "rocket"
+    };
+    let mut routes = Vec::new();
+    for function in functions {
+        let mut index = function.line_range.0.saturating_sub(1);
+        while index > 0 {
+            index -= 1;
+            let line = lines.get(index).map(|line| line.trim()).unwrap_or_default();
+            if !line.starts_with("#[") && !line.starts_with("//") {
+                break;
+            }
+            if let Some(captures) = patterns.rust_attribute.captures(line) {
+                routes.push((
+                    Route {
+                        method: captures[1].to_uppercase(),
+                        path: captures[2].to_string(),
+                        framework,
+                        line: index + 1,
+                        expression: line.to_string(),
+                    },
+                    Handler::Node(function.id.as_str()),
+                ));
+            }
+        }
+    }
+    routes
+}
+
+fn is_function(node: &CodeNode) -> bool {
+    matches!(node.node_type, NodeType::Function | NodeType::Method)
+}
+
+// Add an Endpoint node per HTTP route declared with FastAPI, Flask, NestJS
+// or Spring decorators, Express app and router calls, axum and actix route
+// builders or actix and Rocket attributes, with the HTTP method, path and
+// framework as metadata, and an Implements edge from the handler serving it
+pub fn add_endpoints(provider: &dyn FileProvider, graph: &mut CodeGraph, config: &IndexConfig) {
+    let Some(patterns) = RoutePatterns::new() else {
+        return;
+    };
+    let mut by_file: BTreeMap<&str, Vec<&CodeNode>> = BTreeMap::new();
+    for node in graph.all_nodes() {
+        if !node.file_path.is_empty() {
+            by_file
+                .entry(node.file_path.as_str())
+                .or_default()
+                .push(node);
+        }
+    }
+
+    let mut routes: Vec<(String, Route, Option<String>)> = Vec::new();
+    for (file, nodes) in &by_file {
+        let language = crate::parsers::detect_language(Path::new(file)).unwrap_or_default();
+        let language = language.as_str();
+        let decorated = nodes
+            .iter()
+            .any(|node| is_function(node) && node.metadata.contains_key("decorator_calls"));
+        let scanned = matches!(language, "javascript" | "typescript" | "tsx" | "rust");
+        if !decorated && !scanned {
+            continue;
+        }
+        let content = match provider.read_file(Path::new(file)) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Failed to read {:?}: {}", file, e);
+                continue;
+            }
+        };
+
+        let mut found: Vec<(Route, Handler)> = Vec::new();
+        for node in nodes.iter().filter(|node| is_function(node)) {
+            let calls = decorator_calls(node);
+            if calls.is_empty() {
+                continue;
+            }
+            let class = nodes
+                .iter()
+                .filter(|class| {
+                    class.node_type == NodeType::Class
+                        && class.line_range.0 <= node.line_range.0
+                        && node.line_range.1 <= class.line_range.1
+                })
+                .min_by_key(|class| class.line_range.1 - class.line_range.0);
+            let prefix = class.and_then(|class| controller_prefix(&patterns, class));
+            for expression in calls {
+                let line = node.line_range.0;
+                let node_routes = match language {
+                    "python" => {
+                        python_routes(&patterns, &expression, line, content.contains("fastapi"))
+                    }
+                    "typescript" | "tsx" | "javascript" => {
+                        nest_routes(&patterns, &expression, prefix.as_deref(), line)
+                    }
+                    "java" => spring_routes(&patterns, &expression, prefix.as_deref(), line),
+                    _ => Vec::new(),
+                };
+                found.extend(
+                    node_routes
+                        .into_iter()
+                        .map(|route| (route, Handler::Node(node.id.as_str()))),
+                );
+            }
+        }
+        match language {
+            "javascript" | "typescript" | "tsx" => {
+                found.extend(express_routes(&patterns, &content))
+            }
+            "rust" => {
+                let functions: Vec<&CodeNode> = nodes
+                    .iter()
+                    .copied()
+                    .filter(|node| is_function(node))
+                    .collect();
+                found.extend(rust_builder_routes(&patterns, &content));
+                found.extend(rust_attribute_routes(&patterns, &content, &functions));
+            }
+            _ => {}
+        }
+
+        for (route, handler) in found {
+            let handler_id = match handler {
+                Handler::Node(id) => Some(id.to_string()),
+                Handler::Named(name) => find_handler(graph, file, language, &name),
+                Handler::Inline => nodes
+                    .iter()
+                    .find(|node| is_function(node) && node.line_range.0 == route.line)
+                    .map(|node| node.id.clone()),
+            };
+            routes.push((file.to_string(), route, handler_id));
+        }
+    }
+    if routes.is_empty() {
+        return;
+    }
+    info!("Adding {} HTTP endpoints...", routes.len());
+
+    let mut seen = HashSet::new();
+    for (file, route, handler_id) in routes {
+        let name = format!("{} {}", route.method, route.path);
+        if !seen.insert((file.clone(), name.clone(), handler_id.clone())) {
+            continue;
+        }
+        let mut node = create_node(
+            NodeType::Endpoint,
+            name,
+            &file,
+            (route.line, route.line),
+            route.expression,
+        );
+        node.repo = provider.repo_for(Path::new(&file));
+        node.language = crate::parsers::detect_language(Path::new(&file));
+        node.add_metadata("http_method".to_string(), route.method);
+        node.add_metadata("path".to_string(), route.path);
+        node.add_metadata("framework".to_string(), route.framework.to_string());
+        let endpoint_id = add_unique_node(graph, node, config);
+
+        match handler_id {
+            Some(handler_id) => {
+                trace!("{} handles {}", handler_id, endpoint_id);
+                graph.add_relationship(
+                    Relationship::new(RelationshipType::Implements, handler_id, endpoint_id)
+                        .with_provenance(provenance::ROUTE_DETECTION),
+                );
+            }
+            None => debug!("No handler found for endpoint {}", endpoint_id),
+        }
+    }
+}
+
+// The function a route names: one of the same file, else the only one of
+// the language with that name
+fn find_handler(graph: &CodeGraph, file: &str, language: &str, name: &str) -> Option<String> {
+    let simple = name.rsplit(['.', ':']).next().unwrap_or(name);
+    let candidates: Vec<&CodeNode> = graph
+        .all_nodes()
+        .filter(|node| is_function(node) && node.name == simple)
+        .filter(|node| {
+            crate::parsers::detect_language(Path::new(&node.file_path))
+                .is_some_and(|other| other == language)
+        })
+        .collect();
+    if let Some(node) = candidates.iter().find(|node| node.file_path == file) {
+        return Some(node.id.clone());
+    }
+    match candidates.as_slice() {
+        [node] => Some(node.id.clone()),
+        _ => None,
+    }
+}
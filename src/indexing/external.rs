@@ -0,0 +1,551 @@
+use crate::code_graph::{
+    CodeGraph, CodeNode, NodeType, Relationship, RelationshipType, provenance,
+};
+use crate::indexing::IndexConfig;
+use crate::indexing::extractor::common::stable_node_id;
+use crate::indexing::ownership::{CrossingReference, OwnershipBoundary};
+use crate::indexing::provider::FileProvider;
+use log::{debug, info};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+
+// A call or import whose target is not defined in the indexed code. The
+// relationship is complete except for its target.
+pub struct UnresolvedReference {
+    pub language: String,
+    pub name: String,
+    pub relationship: Relationship,
+}
+
+// Receivers that refer to the calling object, so the callee should have been
+// defined in the repository
+const SELF_RECEIVERS: &[&str] = &["self", "this", "Self", "cls", "super"];
+
+// Rust paths rooted in the current crate
+const RUST_CRATE_ROOTS: &[&str] = &["crate", "self", "super"];
+
+const PYTHON_STDLIB: &[&str] = &[
+    "abc",
+    "argparse",
+    "asyncio",
+    "base64",
+    "collections",
+    "contextlib",
+    "copy",
+    "csv",
+    "dataclasses",
+    "datetime",
+    "enum",
+    "functools",
+    "glob",
+    "hashlib",
+    "heapq",
+    "io",
+    "itertools",
+    "json",
+    "logging",
+    "math",
+    "multiprocessing",
+    "os",
+    "pathlib",
+    "pickle",
+    "random",
+    "re",
+    "shutil",
+    "socket",
+    "sqlite3",
+    "string",
+    "subprocess",
+    "sys",
+    "tempfile",
+    "threading",
+    "time",
+    "typing",
+    "unittest",
+    "urllib",
+    "uuid",
+    "warnings",
+];
+
+const PYTHON_BUILTINS: &[&str] = &[
+    "abs",
+    "all",
+    "any",
+    "bool",
+    "dict",
+    "enumerate",
+    "filter",
+    "float",
+    "format",
+    "getattr",
+    "hasattr",
+    "int",
+    "isinstance",
+    "issubclass",
+    "iter",
+    "len",
+    "list",
+    "map",
+    "max",
+    "min",
+    "next",
+    "open",
+    "print",
+    "range",
+    "repr",
+    "reversed",
+    "round",
+    "set",
+    "setattr",
+    "sorted",
+    "str",
+    "sum",
+    "super",
+    "tuple",
+    "type",
+    "zip",
+    "append",
+    "extend",
+    "items",
+    "keys",
+    "values",
+    "join",
+    "split",
+    "strip",
+    "replace",
+    "startswith",
+    "endswith",
+];
+
+const RUST_STDLIB: &[&str] = &[
+    "std", "core", "alloc", "Vec", "String", "Box", "Rc", "Arc", "Option", "Result", "HashMap",
+    "HashSet", "BTreeMap", "BTreeSet", "Path", "PathBuf",
+];
+
+const RUST_BUILTINS: &[&str] = &[
+    "Some",
+    "Ok",
+    "Err",
+    "new",
+    "clone",
+    "unwrap",
+    "expect",
+    "iter",
+    "into_iter",
+    "map",
+    "collect",
+    "push",
+    "insert",
+    "get",
+    "contains",
+    "len",
+    "is_empty",
+    "to_string",
+    "as_str",
+    "format",
+    "println",
+    "eprintln",
+    "vec",
+    "write",
+    "writeln",
+    "panic",
+    "assert",
+    "assert_eq",
+    "default",
+    "from",
+    "into",
+];
+
+const GO_STDLIB: &[&str] = &[
+    "bufio", "bytes", "context", "errors", "fmt", "http", "io", "ioutil", "json", "log", "math",
+    "os", "path", "filepath", "reflect", "regexp", "sort", "strconv", "strings", "sync", "time",
+];
+
+const GO_BUILTINS: &[&str] = &[
+    "append", "cap", "close", "copy", "delete", "len", "make", "new", "panic", "recover",
+];
+
+const JS_STDLIB: &[&str] = &[
+    "console", "Math", "JSON", "Object", "Array", "Promise", "Date", "Number", "String", "Symbol",
+    "Reflect", "fs", "path", "http", "crypto", "util", "events", "process",
+];
+
+const JS_BUILTINS: &[&str] = &[
+    "require",
+    "setTimeout",
+    "setInterval",
+    "clearTimeout",
+    "parseInt",
+    "parseFloat",
+    "fetch",
+    "push",
+    "map",
+    "filter",
+    "reduce",
+    "forEach",
+    "then",
+    "catch",
+    "join",
+    "split",
+    "slice",
+];
+
+const JAVA_STDLIB: &[&str] = &[
+    "java",
+    "javax",
+    "System",
+    "String",
+    "Math",
+    "Integer",
+    "Long",
+    "Objects",
+    "Arrays",
+    "Collections",
+    "List",
+    "Map",
+    "Set",
+    "Optional",
+    "Stream",
+];
+
+const JAVA_BUILTINS: &[&str] = &[
+    "println", "equals", "hashCode", "toString", "add", "put", "get", "size", "isEmpty",
+];
+
+// C and C++ standard headers, matched against the whole included path
+const C_STDLIB_HEADERS: &[&str] = &[
+    "assert.h",
+    "ctype.h",
+    "errno.h",
+    "float.h",
+    "limits.h",
+    "math.h",
+    "signal.h",
+    "stdarg.h",
+    "stdbool.h",
+    "stddef.h",
+    "stdint.h",
+    "stdio.h",
+    "stdlib.h",
+    "string.h",
+    "time.h",
+    "algorithm",
+    "array",
+    "cstdint",
+    "cstdio",
+    "cstdlib",
+    "cstring",
+    "functional",
+    "iostream",
+    "map",
+    "memory",
+    "optional",
+    "set",
+    "sstream",
+    "string",
+    "unordered_map",
+    "unordered_set",
+    "utility",
+    "vector",
+];
+
+fn known_names(language: &str) -> (&'static [&'static str], &'static [&'static str]) {
+    match language {
+        "python" => (PYTHON_STDLIB, PYTHON_BUILTINS),
+        "rust" => (RUST_STDLIB, RUST_BUILTINS),
+        "go" => (GO_STDLIB, GO_BUILTINS),
+        "javascript" | "typescript" | "tsx" => (JS_STDLIB, JS_BUILTINS),
+        "java" => (JAVA_STDLIB, JAVA_BUILTINS),
+        _ => (&[], &[]),
+    }
+}
+
+// Segments of the callee in a call expression: `os.path.join(p)` gives
+// `["os", "path", "join"]`
+fn callee_path(expression: &str) -> Vec<&str> {
+    let callee = expression.split('(').next().unwrap_or_default();
+    callee
+        .trim_end_matches('!')
+        .split(['.', ':', '>'])
+        .map(|segment| segment.trim_matches(|c: char| !(c.is_alphanumeric() || c == '_')))
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+// Where an unresolved target most likely lives: `internal` when it should
+// have been found in the repository, `stdlib` for the language's standard
+// library and builtins, `unknown` otherwise. Third-party packages are
+// recognised later from the manifests.
+fn classify(reference: &UnresolvedReference) -> (&'static str, Option<String>) {
+    let (stdlib, builtins) = known_names(&reference.language);
+    let name = reference.name.as_str();
+
+    // Includes name a header path; a quoted one is meant to be found next
+    // to the including file or in the project's include directories
+    if reference.relationship.provenance.as_deref() == Some(provenance::INCLUDE_RESOLUTION) {
+        let system = reference
+            .relationship
+            .metadata
+            .get("system")
+            .is_some_and(|system| system == "true");
+        let origin = if C_STDLIB_HEADERS.contains(&name) {
+            "stdlib"
+        } else if !system {
+            "internal"
+        } else {
+            "unknown"
+        };
+        return (origin, None);
+    }
+
+    if reference.relationship.relationship_type == RelationshipType::Imports {
+        // Python relative imports start with dots; Java packages are
+        // recognised by their first segment
+        let root = name.split('.').next().unwrap_or(name);
+        let origin = if name.starts_with('.')
+            || (reference.language == "rust" && RUST_CRATE_ROOTS.contains(&name))
+        {
+            "internal"
+        } else if stdlib.contains(&name) || stdlib.contains(&root) {
+            "stdlib"
+        } else {
+            "unknown"
+        };
+        return (origin, None);
+    }
+
+    let expression = reference
+        .relationship
+        .metadata
+        .get("call_expression")
+        .map(String::as_str)
+        .unwrap_or_default();
+    let path = callee_path(expression);
+    let qualifier = (path.len() > 1).then(|| path[0].to_string());
+
+    let origin = match qualifier.as_deref() {
+        Some(root) if path.len() == 2 && SELF_RECEIVERS.contains(&root) => "internal",
+        Some(root) if stdlib.contains(&root) => "stdlib",
+        _ if builtins.contains(&name) => "stdlib",
+        _ => "unknown",
+    };
+    (origin, qualifier)
+}
+
+// Severity order of origins, used to break ties between equally common ones
+fn origin_rank(origin: &str) -> usize {
+    match origin {
+        "internal" => 3,
+        "unknown" => 2,
+        "third_party" => 1,
+        _ => 0,
+    }
+}
+
+fn external_symbol_node(
+    language: &str,
+    name: &str,
+    target_file: &str,
+    origin: &str,
+    config: &IndexConfig,
+) -> CodeNode {
+    let id = config.namespaced_id(stable_node_id(
+        &NodeType::ExternalSymbol,
+        &format!("{}:{}", language, name),
+        target_file,
+        (0, 0),
+    ));
+    let mut node = CodeNode::new(
+        id,
+        NodeType::ExternalSymbol,
+        name.to_string(),
+        String::new(),
+        (0, 0),
+        String::new(),
+    )
+    .with_metadata("external".to_string(), "true".to_string())
+    .with_metadata("origin".to_string(), origin.to_string());
+    node.language = Some(language.to_string());
+    node
+}
+
+// Create one ExternalSymbol node per language and name for the unresolved
+// references, flagged with `external: true` and the most common origin among
+// their references, and return the relationships pointing at them. When the
+// provider leaves other owners' files out, references into those files get
+// `internal` symbols per target file, with its path and owners.
+pub fn add_external_symbols(
+    provider: &dyn FileProvider,
+    graph: &mut CodeGraph,
+    references: Vec<UnresolvedReference>,
+    config: &IndexConfig,
+) -> Vec<Relationship> {
+    let mut relationships = Vec::new();
+    let symbols_before = graph.find_nodes_by_type(&NodeType::ExternalSymbol).len();
+    let boundary = config
+        .owned_by
+        .as_ref()
+        .and_then(|_| OwnershipBoundary::new(provider));
+    let references = match &boundary {
+        Some(boundary) => {
+            let (crossing, references) = boundary.split(provider, graph, references);
+            relationships.extend(add_crossing_symbols(graph, boundary, crossing, config));
+            references
+        }
+        None => references,
+    };
+
+    let mut by_symbol: BTreeMap<(String, String), Vec<UnresolvedReference>> = BTreeMap::new();
+    for reference in references {
+        by_symbol
+            .entry((reference.language.clone(), reference.name.clone()))
+            .or_default()
+            .push(reference);
+    }
+
+    for ((language, name), references) in &by_symbol {
+        let mut origins: HashMap<&'static str, usize> = HashMap::new();
+        let mut qualifiers = BTreeSet::new();
+        for reference in references {
+            let (origin, qualifier) = classify(reference);
+            *origins.entry(origin).or_insert(0) += 1;
+            qualifiers.extend(qualifier);
+        }
+        let origin = origins
+            .into_iter()
+            .max_by_key(|(origin, count)| (*count, origin_rank(origin)))
+            .map(|(origin, _)| origin)
+            .unwrap_or("unknown");
+
+        let mut node = external_symbol_node(language, name, "", origin, config);
+        let id = node.id.clone();
+        if !qualifiers.is_empty() {
+            let qualifiers: Vec<String> = qualifiers.into_iter().collect();
+            node.add_metadata("qualifiers".to_string(), qualifiers.join(","));
+        }
+        graph.add_node(node);
+
+        for reference in references {
+            let mut relationship = reference.relationship.clone();
+            relationship.to_id = id.clone();
+            relationships.push(relationship);
+        }
+    }
+
+    info!(
+        "Recorded {} unresolved references to {} external symbols",
+        relationships.len(),
+        graph.find_nodes_by_type(&NodeType::ExternalSymbol).len() - symbols_before
+    );
+    relationships
+}
+
+// Symbols for references into files of other owners, one per target file
+// and name
+fn add_crossing_symbols(
+    graph: &mut CodeGraph,
+    boundary: &OwnershipBoundary,
+    crossing: Vec<CrossingReference>,
+    config: &IndexConfig,
+) -> Vec<Relationship> {
+    let mut by_symbol: BTreeMap<(String, String, PathBuf), Vec<Relationship>> = BTreeMap::new();
+    for CrossingReference {
+        reference,
+        target_file,
+    } in crossing
+    {
+        by_symbol
+            .entry((reference.language, reference.name, target_file))
+            .or_default()
+            .push(reference.relationship);
+    }
+
+    let mut relationships = Vec::new();
+    for ((language, name, target_file), references) in by_symbol {
+        let path = target_file.to_string_lossy();
+        let mut node = external_symbol_node(&language, &name, &path, "internal", config)
+            .with_metadata("target_file".to_string(), path.to_string());
+        if let Some(owners) = boundary.owners_of(&target_file) {
+            node.add_metadata("owners".to_string(), owners);
+        }
+        let id = node.id.clone();
+        graph.add_node(node);
+
+        relationships.extend(references.into_iter().map(|mut relationship| {
+            relationship.to_id = id.clone();
+            relationship
+        }));
+    }
+    relationships
+}
+
+// `serde-json`, `serde_json` and `github.com/x/serde_json` all name the same
+// package in code
+fn package_key(name: &str) -> String {
+    name.rsplit('/')
+        .next()
+        .unwrap_or(name)
+        .to_ascii_lowercase()
+        .replace('-', "_")
+}
+
+// Mark external symbols that belong to a declared dependency as `third_party`
+// and link them to its Dependency node
+pub fn link_external_dependencies(graph: &mut CodeGraph) {
+    let mut dependencies: HashMap<String, &str> = HashMap::new();
+    // Maven and Gradle coordinates: Java packages start with the group id
+    let mut groups: Vec<(&str, &str)> = Vec::new();
+    for node in graph.all_nodes() {
+        if node.node_type == NodeType::Dependency {
+            if let Some((group, _)) = node.name.split_once(':') {
+                groups.push((group, &node.id));
+            }
+            let id = dependencies
+                .entry(package_key(&node.name))
+                .or_insert(&node.id);
+            if node.id.as_str() < *id {
+                *id = &node.id;
+            }
+        }
+    }
+
+    let mut links = Vec::new();
+    for node in graph.all_nodes() {
+        if node.node_type != NodeType::ExternalSymbol
+            || node.metadata.get("origin").is_some_and(|o| o == "internal")
+        {
+            continue;
+        }
+        let qualifiers = node.metadata.get("qualifiers").map(String::as_str);
+        let dependency = std::iter::once(node.name.as_str())
+            .chain(qualifiers.unwrap_or_default().split(','))
+            .find_map(|name| dependencies.get(&package_key(name)).copied())
+            .or_else(|| {
+                groups
+                    .iter()
+                    .filter(|(group, _)| {
+                        node.name == *group
+                            || node
+                                .name
+                                .strip_prefix(group)
+                                .is_some_and(|rest| rest.starts_with('.'))
+                    })
+                    .max_by_key(|(group, id)| (group.len(), std::cmp::Reverse(*id)))
+                    .map(|(_, id)| *id)
+            });
+        if let Some(dependency) = dependency {
+            links.push((node.id.clone(), dependency.to_string()));
+        }
+    }
+
+    debug!("Linking {} external symbols to dependencies", links.len());
+    for (id, dependency) in links {
+        if let Some(node) = graph.get_node_mut(&id) {
+            node.add_metadata("origin".to_string(), "third_party".to_string());
+        }
+        graph.add_relationship(
+            Relationship::new(RelationshipType::DependsOn, id, dependency)
+                .with_provenance(provenance::EXTERNAL_SYMBOL),
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use crate::code_graph::{CodeNode, NodeType};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
+use crate::parsers::treesitter::queries::bash as queries;
+use log::warn;
+use std::path::Path;
+use tree_sitter::{Query, QueryCursor, StreamingIterator};
+
+// Commands that pull another script into the current shell
+const SOURCE_COMMANDS: &[&str] = &["source", "."];
+
+pub struct BashExtractor;
+
+impl BashExtractor {
+    pub fn new() -> Self {
+        BashExtractor
+    }
+}
+
+impl LanguageExtractor for BashExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[NodeType::Function, NodeType::Module, NodeType::Variable],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+            ],
+        )
+    }
+
+    fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, file_path) {
+            // The script itself is a module that other scripts can source
+            let script_name = file_path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("");
+            if !script_name.is_empty() {
+                code_units.push(common::create_node(
+                    NodeType::Module,
+                    script_name.to_string(),
+                    file_path.to_str().unwrap_or(""),
+                    (1, content.lines().count().max(1)),
+                    content.to_string(),
+                ));
+            }
+
+            // Extract functions
+            let function_nodes =
+                common::execute_query(queries::FUNCTION_QUERY, &tree, content.as_bytes(), "node");
+
+            for node in function_nodes {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    let start_line = node.start_position().row + 1;
+                    let end_line = node.end_position().row + 1;
+
+                    code_units.push(common::create_node(
+                        NodeType::Function,
+                        common::get_node_text(name_node, content),
+                        file_path.to_str().unwrap_or(""),
+                        (start_line, end_line),
+                        common::get_node_text(node, content),
+                    ));
+                }
+            }
+
+            // Extract script-level variables and exports
+            code_units.extend(common::extract_global_variables(
+                queries::GLOBAL_VARIABLE_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
+        } else {
+            warn!("Failed to parse shell script: {:?}", file_path);
+        }
+
+        code_units
+    }
+
+    fn extract_function_calls(
+        &self,
+        content: &str,
+        func_range: (usize, usize),
+        _func_name: &str,
+    ) -> Vec<CallSite> {
+        let mut calls = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.sh")) {
+            let call_nodes =
+                common::execute_query(queries::CALL_QUERY, &tree, content.as_bytes(), "func_name");
+
+            for node in call_nodes {
+                let call_line = node.start_position().row + 1;
+
+                // Check if call is within function range
+                if call_line >= func_range.0 && call_line <= func_range.1 {
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
+                    }
+                }
+            }
+        }
+
+        calls
+    }
+
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.sh"))
+    }
+
+    fn extract_imported_modules(&self, content: &str) -> Vec<String> {
+        let mut modules = Vec::new();
+
+        let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.sh")) else {
+            return modules;
+        };
+        let Ok(query) = Query::new(&tree.language(), queries::IMPORT_QUERY) else {
+            warn!("Failed to create query: {}", queries::IMPORT_QUERY);
+            return modules;
+        };
+        let (Some(command_idx), Some(path_idx)) = (
+            query.capture_index_for_name("command"),
+            query.capture_index_for_name("import_path"),
+        ) else {
+            return modules;
+        };
+
+        let mut query_cursor = QueryCursor::new();
+        let mut matches = query_cursor.matches(&query, tree.root_node(), content.as_bytes());
+
+        while let Some(match_result) = matches.next() {
+            let capture = |index| {
+                match_result
+                    .captures
+                    .iter()
+                    .find(|c| c.index == index)
+                    .map(|c| common::get_node_text(c.node, content))
+            };
+            let (Some(command), Some(import_path)) = (capture(command_idx), capture(path_idx))
+            else {
+                continue;
+            };
+            if !SOURCE_COMMANDS.contains(&command.as_str()) {
+                continue;
+            }
+
+            // Sourced scripts are matched by file name, like other module imports
+            let import_path = import_path.trim_matches(|c| c == '"' || c == '\'');
+            if let Some(stem) = Path::new(import_path).file_stem().and_then(|s| s.to_str()) {
+                modules.push(stem.to_string());
+            }
+        }
+
+        modules
+    }
+}
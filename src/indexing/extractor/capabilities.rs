@@ -0,0 +1,99 @@
+use crate::code_graph::{NodeType, RelationshipType};
+use crate::indexing::extractor::get_extractor_for_language;
+use serde::Serialize;
+
+// What a language extractor can find beyond plain declarations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    Calls,
+    Imports,
+    References,
+    GlobalVariables,
+    Visibility,
+    Inheritance,
+    // Methods linked to the interface or trait methods they implement
+    Implementations,
+    Fields,
+    Tests,
+    Decorators,
+}
+
+// Coverage of one language, so callers can tell "nothing found" apart
+// from "not supported"
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LanguageCapabilities {
+    pub language: String,
+    pub extensions: Vec<&'static str>,
+    pub node_types: Vec<NodeType>,
+    pub relationship_types: Vec<RelationshipType>,
+    pub features: Vec<Feature>,
+}
+
+impl LanguageCapabilities {
+    pub fn new(node_types: &[NodeType], features: &[Feature]) -> Self {
+        let mut relationship_types = Vec::new();
+        for feature in features {
+            match feature {
+                Feature::Calls => relationship_types.push(RelationshipType::Calls),
+                Feature::Imports => relationship_types.push(RelationshipType::Imports),
+                Feature::References => relationship_types.push(RelationshipType::References),
+                Feature::Inheritance => relationship_types.push(RelationshipType::Inherits),
+                Feature::Implementations => relationship_types.push(RelationshipType::Implements),
+                Feature::Tests => relationship_types.push(RelationshipType::Tests),
+                Feature::Decorators => relationship_types.push(RelationshipType::AnnotatedWith),
+                _ => {}
+            }
+        }
+        if node_types.contains(&NodeType::Method) || node_types.contains(&NodeType::Field) {
+            relationship_types.push(RelationshipType::Contains);
+        }
+
+        LanguageCapabilities {
+            node_types: node_types.to_vec(),
+            relationship_types,
+            features: features.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.features.contains(&feature)
+    }
+
+    pub fn is_supported(&self) -> bool {
+        !self.node_types.is_empty()
+    }
+}
+
+// Capabilities of a language by name; unsupported languages get an empty entry
+pub fn capabilities_for_language(language: &str) -> LanguageCapabilities {
+    let mut capabilities = get_extractor_for_language(language)
+        .map(|extractor| extractor.capabilities())
+        .unwrap_or_default();
+
+    capabilities.language = language.to_string();
+    capabilities.extensions = crate::parsers::common::get_supported_extensions()
+        .into_iter()
+        .filter(|ext| {
+            crate::parsers::treesitter::languages::detect_language_from_extension(ext).as_deref()
+                == Some(language)
+        })
+        .collect();
+    capabilities
+}
+
+// Every language with at least one recognized file extension
+pub fn language_capabilities() -> Vec<LanguageCapabilities> {
+    let mut languages: Vec<String> = crate::parsers::common::get_supported_extensions()
+        .into_iter()
+        .filter_map(crate::parsers::treesitter::languages::detect_language_from_extension)
+        .collect();
+    languages.sort();
+    languages.dedup();
+
+    languages
+        .iter()
+        .map(|language| capabilities_for_language(language))
+        .collect()
+}
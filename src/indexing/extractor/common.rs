@@ -1,20 +1,169 @@
-use crate::code_graph::{CodeNode, NodeType};
+use crate::code_graph::{CodeNode, NodeType, Visibility};
+use crate::indexing::extractor::CallSite;
 use log::warn;
+use std::collections::HashSet;
 use std::path::Path;
 use tree_sitter::{Node, Query, QueryCursor, StreamingIterator, Tree};
 use uuid::Uuid;
 
 // Helper functions shared by multiple language extractors
 
+// Maximum number of characters of a call expression kept as edge metadata
+pub const MAX_CALL_EXPRESSION_LEN: usize = 200;
+
+// Node kinds that wrap a call, across all supported grammars
+const CALL_KINDS: &[&str] = &[
+    "call_expression",
+    "call",
+    "method_invocation",
+    "method_reference",
+    "method_call",
+    "command",
+    "application_expression",
+    "macrocall_expression",
+    "macro_invocation",
+];
+
+// Node kinds whose body is only executed under some condition
+const CONDITIONAL_KINDS: &[&str] = &[
+    "if_expression",
+    "if_statement",
+    "if",
+    "else_clause",
+    "elif_clause",
+    "unless",
+    "match_expression",
+    "match_statement",
+    "switch_statement",
+    "switch_expression",
+    "expression_switch_statement",
+    "type_switch_statement",
+    "case",
+    "case_item",
+    "case_expr",
+    "if_expr",
+    "when",
+    "conditional_expression",
+    "ternary_expression",
+    "for_expression",
+    "for_statement",
+    "for_in_statement",
+    "enhanced_for_statement",
+    "for_range_loop",
+    "for",
+    "while_expression",
+    "while_statement",
+    "while",
+    "until",
+    "do_statement",
+    "loop_expression",
+    "catch_clause",
+    "except_clause",
+];
+
+// Node kinds that start a new function body, where the conditional search stops
+pub(crate) const FUNCTION_KINDS: &[&str] = &[
+    "function_item",
+    "function_definition",
+    "function_declaration",
+    "method_declaration",
+    "method_definition",
+    "constructor_declaration",
+    "function",
+    "arrow_function",
+    "closure_expression",
+    "lambda",
+    "method",
+    "singleton_method",
+    "fun_expression",
+    "function_expression",
+    "macro_definition",
+    "anonymous_fun",
+];
+
+// Text of a node. Spans are byte offsets and may not fall on character
+// boundaries when the tree was parsed from different text, so the bytes are
+// converted lossily instead of slicing the string.
 pub fn get_node_text(node: Node, source: &str) -> String {
-    let start_byte = node.start_byte();
-    let end_byte = node.end_byte();
+    source
+        .as_bytes()
+        .get(node.start_byte()..node.end_byte())
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        .unwrap_or_default()
+}
 
-    if start_byte >= source.len() || end_byte > source.len() {
-        return String::new();
+// Build a call site from the captured callee name node
+pub fn create_call_site(name_node: Node, source: &str) -> CallSite {
+    let call_node = find_enclosing_call(name_node).unwrap_or(name_node);
+    let expression = get_node_text(call_node, source)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(MAX_CALL_EXPRESSION_LEN)
+        .collect();
+
+    CallSite {
+        name: get_node_text(name_node, source),
+        line: name_node.start_position().row + 1,
+        column: name_node.start_position().column + 1,
+        expression,
+        conditional: is_conditional(call_node),
+        arguments: find_enclosing_call(name_node).and_then(argument_count),
     }
+}
 
-    source[start_byte..end_byte].to_string()
+// Number of arguments a call passes, unknown when it spreads a collection
+// into them
+fn argument_count(call_node: Node) -> Option<usize> {
+    let arguments = call_node.child_by_field_name("arguments")?;
+    if !arguments.kind().contains("argument") {
+        return None;
+    }
+    let mut cursor = arguments.walk();
+    let mut count = 0;
+    for argument in arguments.named_children(&mut cursor) {
+        match argument.kind() {
+            kind if kind.contains("comment") => {}
+            "list_splat"
+            | "dictionary_splat"
+            | "spread_element"
+            | "splat_argument"
+            | "hash_splat_argument"
+            | "variadic_argument" => return None,
+            _ => count += 1,
+        }
+    }
+    Some(count)
+}
+
+fn find_enclosing_call(node: Node) -> Option<Node> {
+    let mut parent_iter = node.parent();
+
+    while let Some(parent) = parent_iter {
+        if CALL_KINDS.contains(&parent.kind()) {
+            return Some(parent);
+        }
+        parent_iter = parent.parent();
+    }
+
+    None
+}
+
+fn is_conditional(node: Node) -> bool {
+    let mut parent_iter = node.parent();
+
+    while let Some(parent) = parent_iter {
+        if FUNCTION_KINDS.contains(&parent.kind()) {
+            return false;
+        }
+        if CONDITIONAL_KINDS.contains(&parent.kind()) {
+            return true;
+        }
+        parent_iter = parent.parent();
+    }
+
+    false
 }
 
 pub fn extract_module_name_from_path(path: &str) -> String {
@@ -34,6 +183,28 @@ pub fn extract_module_name_from_path(path: &str) -> String {
     module_name
 }
 
+// Namespace for name-based (v5) node IDs
+const NODE_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6f1c_2a4e_8d3b_4f5a_9c7e_1b2d_3e4f_5a6b);
+
+// Derive a node ID from where the node is, so re-indexing the same code
+// yields the same IDs regardless of thread scheduling
+pub fn stable_node_id(
+    node_type: &NodeType,
+    name: &str,
+    file_path: &str,
+    line_range: (usize, usize),
+) -> String {
+    let key = format!(
+        "{}:{:?}:{}:{}-{}",
+        file_path, node_type, name, line_range.0, line_range.1
+    );
+    Uuid::new_v5(&NODE_ID_NAMESPACE, key.as_bytes()).to_string()
+}
+
+pub fn disambiguate_node_id(id: &str, occurrence: usize) -> String {
+    format!("{}#{}", id, occurrence)
+}
+
 pub fn create_node(
     node_type: NodeType,
     name: String,
@@ -42,7 +213,7 @@ pub fn create_node(
     content: String,
 ) -> CodeNode {
     CodeNode::new(
-        Uuid::new_v4().to_string(),
+        stable_node_id(&node_type, &name, file_path, line_range),
         node_type,
         name,
         file_path.to_string(),
@@ -51,11 +222,478 @@ pub fn create_node(
     )
 }
 
+// Text of the 1-based, inclusive line range, for extractors without a syntax tree
+pub fn line_range_text(content: &str, start_line: usize, end_line: usize) -> String {
+    content
+        .lines()
+        .skip(start_line.saturating_sub(1))
+        .take((end_line + 1).saturating_sub(start_line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 pub fn parse_with_tree_sitter(content: &str, file_path: &Path) -> Option<(Tree, String)> {
     let mut parser = crate::parsers::treesitter::TreeSitterParser::new();
     parser.parse_file(file_path, content)
 }
 
+// Function values the extractors already index as Function nodes
+const FUNCTION_VALUE_KINDS: &[&str] = &["arrow_function", "function_expression", "function"];
+
+// `const f = () => {}` is the function `f`, not a variable holding one
+fn binds_function(declaration: Node) -> bool {
+    declaration
+        .child_by_field_name("value")
+        .is_some_and(|value| FUNCTION_VALUE_KINDS.contains(&value.kind()))
+}
+
+// Extract module-level variables from a query capturing `@node` and `@name`,
+// ignoring matches nested inside a function body
+pub fn extract_global_variables(
+    query_str: &str,
+    tree: &Tree,
+    source: &str,
+    file_path: &Path,
+) -> Vec<CodeNode> {
+    let mut variables = Vec::new();
+
+    let query = match Query::new(&tree.language(), query_str) {
+        Ok(query) => query,
+        Err(_) => {
+            warn!("Failed to create query: {}", query_str);
+            return variables;
+        }
+    };
+    let (Some(node_idx), Some(name_idx)) = (
+        query.capture_index_for_name("node"),
+        query.capture_index_for_name("name"),
+    ) else {
+        return variables;
+    };
+
+    let mut query_cursor = QueryCursor::new();
+    let mut matches = query_cursor.matches(&query, tree.root_node(), source.as_bytes());
+
+    while let Some(match_result) = matches.next() {
+        let node = match_result
+            .captures
+            .iter()
+            .find(|c| c.index == node_idx)
+            .map(|c| c.node);
+        let name = match_result
+            .captures
+            .iter()
+            .find(|c| c.index == name_idx)
+            .map(|c| get_node_text(c.node, source));
+
+        if let (Some(node), Some(name)) = (node, name) {
+            if is_inside_function(node) || name.is_empty() || binds_function(node) {
+                continue;
+            }
+
+            let code_node = create_node(
+                NodeType::Variable,
+                name,
+                file_path.to_str().unwrap_or(""),
+                (node.start_position().row + 1, node.end_position().row + 1),
+                get_node_text(node, source),
+            )
+            .with_scope("module");
+
+            variables.push(code_node);
+        }
+    }
+
+    variables
+}
+
+// Text and line of every node a reference query captures as `@reference`
+pub fn extract_references(
+    query_str: &str,
+    content: &str,
+    file_path: &Path,
+) -> Vec<(String, usize)> {
+    let Some((tree, _)) = parse_with_tree_sitter(content, file_path) else {
+        return Vec::new();
+    };
+    execute_query(query_str, &tree, content.as_bytes(), "reference")
+        .into_iter()
+        .map(|node| (get_node_text(node, content), node.start_position().row + 1))
+        .collect()
+}
+
+// Declarations of local bindings, with the field naming what they bind
+const LOCAL_DECLARATIONS: &[(&str, &str)] = &[
+    ("let_declaration", "pattern"),
+    ("variable_declarator", "name"),
+    ("short_var_declaration", "left"),
+    ("var_spec", "name"),
+    ("declaration", "declarator"),
+    ("assignment", "left"),
+];
+
+// Patterns and declarators that bind every identifier inside them
+const BINDING_PATTERN_KINDS: &[&str] = &[
+    "tuple_pattern",
+    "mut_pattern",
+    "pattern_list",
+    "tuple",
+    "list_pattern",
+    "expression_list",
+    "pointer_declarator",
+    "reference_declarator",
+    "parenthesized_declarator",
+    "array_pattern",
+    "object_pattern",
+    "shorthand_property_identifier_pattern",
+    "left_assignment_list",
+];
+
+fn binding_names<'a>(node: Node<'a>, names: &mut Vec<Node<'a>>) {
+    match node.kind() {
+        "identifier" | "shorthand_property_identifier_pattern" => names.push(node),
+        "array_declarator" | "init_declarator" => {
+            if let Some(declarator) = node.child_by_field_name("declarator") {
+                binding_names(declarator, names);
+            }
+        }
+        kind if BINDING_PATTERN_KINDS.contains(&kind) => {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                binding_names(child, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_local_declarations<'a>(node: Node<'a>, found: &mut Vec<(Node<'a>, Node<'a>)>) {
+    if let Some((_, field)) = LOCAL_DECLARATIONS
+        .iter()
+        .find(|(kind, _)| *kind == node.kind())
+        && is_inside_function(node)
+    {
+        let mut names = Vec::new();
+        let mut cursor = node.walk();
+        for target in node.children_by_field_name(field, &mut cursor) {
+            binding_names(target, &mut names);
+        }
+        found.extend(names.into_iter().map(|name| (node, name)));
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_local_declarations(child, found);
+    }
+}
+
+fn enclosing_function(node: Node) -> Option<Node> {
+    let mut parent_iter = node.parent();
+    while let Some(parent) = parent_iter {
+        if FUNCTION_KINDS.contains(&parent.kind()) {
+            return Some(parent);
+        }
+        parent_iter = parent.parent();
+    }
+    None
+}
+
+// Variables bound inside function bodies, each marked with `scope: local`
+// and its declared type when it has one. A name reassigned in the same
+// function is one variable, declared where it is first bound.
+pub fn extract_local_variables(tree: &Tree, source: &str, file_path: &Path) -> Vec<CodeNode> {
+    let mut declarations = Vec::new();
+    collect_local_declarations(tree.root_node(), &mut declarations);
+
+    let mut seen = HashSet::new();
+    let mut variables = Vec::new();
+    for (declaration, name_node) in declarations {
+        let name = get_node_text(name_node, source);
+        let function = enclosing_function(declaration).map(|function| function.start_byte());
+        if name.is_empty() || binds_function(declaration) || !seen.insert((function, name.clone()))
+        {
+            continue;
+        }
+        // Declarators keep the type on the declaration around them
+        let variable_type = declaration
+            .child_by_field_name("type")
+            .or_else(|| declaration.parent()?.child_by_field_name("type"))
+            .map(|node| {
+                get_node_text(node, source)
+                    .trim_start_matches(':')
+                    .trim()
+                    .to_string()
+            });
+
+        let mut variable = create_node(
+            NodeType::Variable,
+            name,
+            file_path.to_str().unwrap_or(""),
+            (
+                declaration.start_position().row + 1,
+                declaration.end_position().row + 1,
+            ),
+            get_node_text(declaration, source),
+        )
+        .with_scope("local");
+        variable.declared_type = variable_type.filter(|t| !t.is_empty());
+        variables.push(variable);
+    }
+    variables
+}
+
+// Name of a type without its path or type arguments: `Display` for
+// `std::fmt::Display` and `Comparable` for `java.lang.Comparable<T>`
+pub fn simple_type_name(text: &str) -> String {
+    let base = text.split('<').next().unwrap_or(text).trim();
+    base.rsplit(['.', ':'])
+        .next()
+        .unwrap_or(base)
+        .trim_start_matches(['&', '*'])
+        .trim()
+        .to_string()
+}
+
+// Declarations fields belong to
+const FIELD_OWNER_KINDS: &[&str] = &[
+    "class_definition",
+    "class_declaration",
+    "abstract_class_declaration",
+    "interface_declaration",
+    "enum_declaration",
+    "record_declaration",
+    "struct_item",
+    "type_spec",
+];
+
+// Extract the fields of classes, structs and interfaces from a query
+// capturing `@node`, `@name` and optionally `@type`, keeping the first
+// declaration of each name in its class
+pub fn extract_fields(
+    query_str: &str,
+    tree: &Tree,
+    source: &str,
+    file_path: &Path,
+) -> Vec<CodeNode> {
+    let mut fields = Vec::new();
+
+    let query = match Query::new(&tree.language(), query_str) {
+        Ok(query) => query,
+        Err(_) => {
+            warn!("Failed to create query: {}", query_str);
+            return fields;
+        }
+    };
+    let (Some(node_idx), Some(name_idx)) = (
+        query.capture_index_for_name("node"),
+        query.capture_index_for_name("name"),
+    ) else {
+        return fields;
+    };
+    let type_idx = query.capture_index_for_name("type");
+
+    let mut seen = std::collections::HashSet::new();
+    let mut query_cursor = QueryCursor::new();
+    let mut matches = query_cursor.matches(&query, tree.root_node(), source.as_bytes());
+    while let Some(match_result) = matches.next() {
+        let capture = |index: u32| {
+            match_result
+                .captures
+                .iter()
+                .find(|c| c.index == index)
+                .map(|c| c.node)
+        };
+        let (Some(node), Some(name)) = (capture(node_idx), capture(name_idx)) else {
+            continue;
+        };
+        let name = get_node_text(name, source);
+        // Computed and quoted property names are not fields of a fixed name
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '#'))
+        {
+            continue;
+        }
+
+        let mut owner = node.parent();
+        while let Some(parent) = owner.filter(|parent| !FIELD_OWNER_KINDS.contains(&parent.kind()))
+        {
+            owner = parent.parent();
+        }
+        if !seen.insert((owner.map(|owner| owner.id()), name.clone())) {
+            continue;
+        }
+
+        let mut code_node = create_node(
+            NodeType::Field,
+            name,
+            file_path.to_str().unwrap_or(""),
+            (node.start_position().row + 1, node.end_position().row + 1),
+            get_node_text(node, source),
+        );
+        if let Some(field_type) = type_idx.and_then(capture) {
+            code_node.add_metadata(
+                "field_type".to_string(),
+                get_node_text(field_type, source)
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+        fields.push(code_node);
+    }
+
+    fields
+}
+
+// Functions that register a test case in JavaScript test runners
+const TEST_BLOCK_CALLEES: &[&str] = &["it", "test"];
+
+// Turn `it("does something", () => ...)` calls into test Function nodes named
+// after their description, so the calls inside them can be attributed
+pub fn extract_test_blocks(
+    query_str: &str,
+    tree: &Tree,
+    source: &str,
+    file_path: &Path,
+) -> Vec<CodeNode> {
+    let mut tests = Vec::new();
+
+    let query = match Query::new(&tree.language(), query_str) {
+        Ok(query) => query,
+        Err(_) => {
+            warn!("Failed to create query: {}", query_str);
+            return tests;
+        }
+    };
+    let (Some(node_idx), Some(callee_idx), Some(name_idx)) = (
+        query.capture_index_for_name("node"),
+        query.capture_index_for_name("callee"),
+        query.capture_index_for_name("name"),
+    ) else {
+        return tests;
+    };
+
+    let mut query_cursor = QueryCursor::new();
+    let mut matches = query_cursor.matches(&query, tree.root_node(), source.as_bytes());
+
+    while let Some(match_result) = matches.next() {
+        let capture = |index| {
+            match_result
+                .captures
+                .iter()
+                .find(|c| c.index == index)
+                .map(|c| c.node)
+        };
+        let (Some(node), Some(callee), Some(name)) =
+            (capture(node_idx), capture(callee_idx), capture(name_idx))
+        else {
+            continue;
+        };
+
+        if !TEST_BLOCK_CALLEES.contains(&get_node_text(callee, source).as_str()) {
+            continue;
+        }
+
+        let name = get_node_text(name, source);
+        let name = name.trim_matches(|c| c == '"' || c == '\'' || c == '`');
+        if name.is_empty() {
+            continue;
+        }
+
+        tests.push(
+            create_node(
+                NodeType::Function,
+                name.to_string(),
+                file_path.to_str().unwrap_or(""),
+                (node.start_position().row + 1, node.end_position().row + 1),
+                get_node_text(node, source),
+            )
+            .with_metadata("test".to_string(), "true".to_string())
+            .with_metadata("test_framework".to_string(), "jest".to_string()),
+        );
+    }
+
+    tests
+}
+
+// Test framework of a function or method, recognized by attributes,
+// annotations or naming conventions
+pub fn detect_test_framework(
+    language: &str,
+    node: &CodeNode,
+    source: &str,
+) -> Option<&'static str> {
+    if !matches!(node.node_type, NodeType::Function | NodeType::Method) {
+        return None;
+    }
+
+    let bare_name = node.name.rsplit([':', '.']).next().unwrap_or(&node.name);
+    let file_name = Path::new(&node.file_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+
+    // Attributes and annotations directly above the declaration
+    let lines: Vec<&str> = source.lines().collect();
+    let start = node.line_range.0.saturating_sub(1).min(lines.len());
+    let preceding: Vec<&str> = lines[..start]
+        .iter()
+        .rev()
+        .map(|line| line.trim())
+        .take_while(|line| {
+            line.starts_with("#[") || line.starts_with('@') || line.starts_with("//")
+        })
+        .collect();
+    let has_marker = |marker: &str| {
+        preceding.iter().any(|line| line.contains(marker)) || node.content.contains(marker)
+    };
+
+    match language {
+        "rust"
+            if preceding
+                .iter()
+                .any(|line| line.starts_with("#[") && line.contains("test")) =>
+        {
+            Some("rust")
+        }
+        "python"
+            if bare_name.starts_with("test")
+                && (file_name.starts_with("test_")
+                    || file_name.ends_with("_test.py")
+                    || node
+                        .parent_class
+                        .as_ref()
+                        .is_some_and(|class| class.starts_with("Test"))) =>
+        {
+            Some("pytest")
+        }
+        "java" if has_marker("@Test") || has_marker("@ParameterizedTest") => Some("junit"),
+        "go" if file_name.ends_with("_test.go")
+            && ["Test", "Benchmark", "Example", "Fuzz"]
+                .iter()
+                .any(|prefix| bare_name.starts_with(prefix)) =>
+        {
+            Some("go")
+        }
+        "ruby" if bare_name.starts_with("test_") => Some("minitest"),
+        _ => None,
+    }
+}
+
+fn is_inside_function(node: Node) -> bool {
+    let mut parent_iter = node.parent();
+
+    while let Some(parent) = parent_iter {
+        if FUNCTION_KINDS.contains(&parent.kind()) {
+            return true;
+        }
+        parent_iter = parent.parent();
+    }
+
+    false
+}
+
 pub fn execute_query<'a>(
     query_str: &str,
     tree: &'a Tree,
@@ -84,3 +722,108 @@ pub fn execute_query<'a>(
 
     result
 }
+
+// Best-effort visibility of a declaration: public, internal or private.
+// Looks at the modifiers in front of the declared name and at naming
+// conventions; returns None for languages without a usable signal.
+pub fn infer_visibility(language: &str, node: &CodeNode, source: &str) -> Option<Visibility> {
+    let bare_name = node.name.rsplit([':', '.']).next().unwrap_or(&node.name);
+    let first_line = source
+        .lines()
+        .nth(node.line_range.0.saturating_sub(1))
+        .unwrap_or("");
+    // Modifiers such as `export` may sit on the line before the node starts,
+    // but only back to the end of the previous statement or block
+    let content_first_line = node.content.lines().next().unwrap_or("");
+    let line_prefix = first_line
+        .find(content_first_line)
+        .map_or("", |start| &first_line[..start]);
+    let line_prefix = line_prefix
+        .rsplit(['{', '}', ';', ','])
+        .next()
+        .unwrap_or("");
+    // The name itself, not `a` inside `private` for a field named `a`
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let header_end = node
+        .content
+        .match_indices(bare_name)
+        .find(|(start, _)| {
+            let before = node.content[..*start].chars().next_back();
+            let after = node.content[start + bare_name.len()..].chars().next();
+            !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+        })
+        .map_or(0, |(start, _)| start);
+    let header = format!("{} {}", line_prefix, &node.content[..header_end]);
+    let has_word = |word: &str| {
+        header
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|w| w == word)
+    };
+
+    let visibility = match language {
+        "rust" => {
+            if node.metadata.contains_key("macro_export") {
+                Visibility::Public
+            } else if header.contains("pub(") {
+                Visibility::Internal
+            } else if has_word("pub") {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            }
+        }
+        "go" => {
+            if bare_name.starts_with(|c: char| c.is_uppercase()) {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            }
+        }
+        "python" => {
+            let dunder = bare_name.starts_with("__") && bare_name.ends_with("__");
+            if bare_name.starts_with('_') && !dunder {
+                Visibility::Private
+            } else {
+                Visibility::Public
+            }
+        }
+        "java" => {
+            if has_word("public") {
+                Visibility::Public
+            } else if has_word("private") {
+                Visibility::Private
+            } else {
+                Visibility::Internal
+            }
+        }
+        // Groovy declarations are public unless marked otherwise
+        "groovy" => {
+            if has_word("private") || has_word("protected") {
+                Visibility::Private
+            } else {
+                Visibility::Public
+            }
+        }
+        "javascript" | "typescript" | "tsx" => {
+            if has_word("private") || has_word("protected") || bare_name.starts_with('#') {
+                Visibility::Private
+            } else if matches!(node.node_type, NodeType::Method | NodeType::Field)
+                || has_word("export")
+            {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            }
+        }
+        "cpp" | "c" => {
+            if has_word("static") && node.node_type != NodeType::Method {
+                Visibility::Private
+            } else {
+                Visibility::Public
+            }
+        }
+        _ => return None,
+    };
+
+    Some(visibility)
+}
@@ -0,0 +1,240 @@
+use super::common::FUNCTION_KINDS;
+use crate::code_graph::{CodeNode, ComplexityMetrics, NodeType};
+use tree_sitter::{Node, Tree};
+
+// Node kinds that add a path through a function, across all supported grammars
+const DECISION_KINDS: &[&str] = &[
+    "if_expression",
+    "if_statement",
+    "if",
+    "elif_clause",
+    "elsif",
+    "unless",
+    "match_arm",
+    "switch_case",
+    "case_statement",
+    "switch_block_statement_group",
+    "switch_rule",
+    "expression_case",
+    "type_case",
+    "communication_case",
+    "case_clause",
+    "case_item",
+    "when",
+    "for_expression",
+    "for_statement",
+    "for_in_statement",
+    "enhanced_for_statement",
+    "for_range_loop",
+    "c_style_for_statement",
+    "for",
+    "while_expression",
+    "while_statement",
+    "while",
+    "until",
+    "do_statement",
+    "catch_clause",
+    "except_clause",
+    "rescue",
+    "conditional_expression",
+    "ternary_expression",
+    "conditional",
+    "for_in_clause",
+    "if_clause",
+];
+
+// Node kinds that open a nested block of control flow
+const NESTING_KINDS: &[&str] = &[
+    "if_expression",
+    "if_statement",
+    "if",
+    "unless",
+    "match_expression",
+    "match_statement",
+    "switch_statement",
+    "switch_expression",
+    "expression_switch_statement",
+    "type_switch_statement",
+    "case",
+    "case_statement",
+    "for_expression",
+    "for_statement",
+    "for_in_statement",
+    "enhanced_for_statement",
+    "for_range_loop",
+    "c_style_for_statement",
+    "for",
+    "while_expression",
+    "while_statement",
+    "while",
+    "until",
+    "do_statement",
+    "loop_expression",
+    "try_statement",
+    "try_expression",
+    "begin",
+];
+
+// Node kinds whose named children are statements
+const BLOCK_KINDS: &[&str] = &[
+    "block",
+    "statement_block",
+    "compound_statement",
+    "body_statement",
+    "statement_list",
+    "do_block",
+    "constructor_body",
+];
+
+// Operators that short-circuit and so add a path of their own
+const BOOLEAN_OPERATORS: &[&str] = &["&&", "||", "and", "or"];
+
+// Attach complexity metrics to every function and method whose syntax node
+// can be found in the tree the file was parsed into
+pub fn annotate_complexity(tree: &Tree, units: &mut [CodeNode]) {
+    let mut functions = Vec::new();
+    collect_functions(tree.root_node(), &mut functions);
+
+    for unit in units.iter_mut() {
+        if let Some(function) = function_of(&functions, unit) {
+            unit.complexity = Some(complexity_metrics(function));
+        }
+    }
+}
+
+// Syntax node of a function or method unit: the outermost function ending
+// on the unit's last line, so a unit whose range includes decorators or
+// comments still finds its body
+pub(super) fn function_of<'a>(functions: &[Node<'a>], unit: &CodeNode) -> Option<Node<'a>> {
+    if !matches!(unit.node_type, NodeType::Function | NodeType::Method) {
+        return None;
+    }
+    let (start, end) = unit.line_range;
+    functions
+        .iter()
+        .filter(|node| {
+            let node_start = node.start_position().row + 1;
+            node.end_position().row + 1 == end && node_start >= start && node_start <= end
+        })
+        .min_by_key(|node| node.start_byte())
+        .copied()
+}
+
+pub(super) fn collect_functions<'a>(node: Node<'a>, functions: &mut Vec<Node<'a>>) {
+    if FUNCTION_KINDS.contains(&node.kind()) {
+        functions.push(node);
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_functions(child, functions);
+    }
+}
+
+// Metrics of one function; nested functions and closures are measured on
+// their own and do not count towards the enclosing function
+pub fn complexity_metrics(function: Node) -> ComplexityMetrics {
+    let mut metrics = ComplexityMetrics {
+        cyclomatic_complexity: 1,
+        parameters: parameter_list(function).map_or(0, count_parameters),
+        ..Default::default()
+    };
+
+    let mut cursor = function.walk();
+    for child in function.named_children(&mut cursor) {
+        measure(child, 0, &mut metrics);
+    }
+
+    metrics
+}
+
+fn measure(node: Node, depth: usize, metrics: &mut ComplexityMetrics) {
+    let kind = node.kind();
+    if FUNCTION_KINDS.contains(&kind) {
+        return;
+    }
+
+    if DECISION_KINDS.contains(&kind) || is_short_circuit(node) {
+        metrics.cyclomatic_complexity += 1;
+    }
+    if BLOCK_KINDS.contains(&kind) {
+        let mut cursor = node.walk();
+        metrics.statements += node
+            .named_children(&mut cursor)
+            .filter(|child| !is_comment(*child) && !BLOCK_KINDS.contains(&child.kind()))
+            .count();
+    }
+
+    // An `else if` continues the chain it belongs to rather than nesting
+    let depth = if NESTING_KINDS.contains(&kind) && !is_else_if(node) {
+        depth + 1
+    } else {
+        depth
+    };
+    metrics.nesting_depth = metrics.nesting_depth.max(depth);
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        measure(child, depth, metrics);
+    }
+}
+
+// The parameter list of a function, searched outside of its body so that
+// declarators (C and C++) and wrapped definitions are looked through
+pub(super) fn parameter_list(node: Node) -> Option<Node> {
+    if let Some(parameters) = node.child_by_field_name("parameters") {
+        return Some(parameters);
+    }
+    let mut cursor = node.walk();
+    for (index, child) in node.children(&mut cursor).enumerate() {
+        let field = node.field_name_for_child(index as u32);
+        if !child.is_named() || matches!(field, Some("body") | Some("receiver")) {
+            continue;
+        }
+        if matches!(
+            child.kind(),
+            "parameters" | "formal_parameters" | "parameter_list" | "method_parameters"
+        ) {
+            return Some(child);
+        }
+        if let Some(parameters) = parameter_list(child) {
+            return Some(parameters);
+        }
+    }
+    None
+}
+
+fn count_parameters(parameters: Node) -> usize {
+    let mut cursor = parameters.walk();
+    parameters
+        .named_children(&mut cursor)
+        .filter(|child| !is_comment(*child))
+        .count()
+}
+
+fn is_comment(node: Node) -> bool {
+    node.kind().contains("comment")
+}
+
+fn is_short_circuit(node: Node) -> bool {
+    if !matches!(
+        node.kind(),
+        "binary_expression" | "boolean_operator" | "binary" | "binary_operator"
+    ) {
+        return false;
+    }
+    node.child_by_field_name("operator")
+        .is_some_and(|operator| BOOLEAN_OPERATORS.contains(&operator.kind()))
+}
+
+fn is_else_if(node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    if parent.kind() == "else_clause" {
+        return true;
+    }
+    let mut cursor = parent.walk();
+    parent
+        .children_by_field_name("alternative", &mut cursor)
+        .any(|alternative| alternative.id() == node.id())
+}
@@ -1,11 +1,54 @@
 use crate::code_graph::{CodeNode, NodeType};
-use crate::indexing::extractor::{LanguageExtractor, common};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
 use crate::parsers::treesitter::queries::cpp as queries;
 use log::warn;
-use std::collections::HashMap;
 use std::path::Path;
 use tree_sitter::Node;
 
+// An `#include` directive: `"util/math.h"` is quoted, `<stdio.h>` is a
+// system include
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CInclude {
+    pub path: String,
+    pub system: bool,
+    pub line: usize,
+}
+
+pub fn c_includes(content: &str) -> Vec<CInclude> {
+    let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.cpp")) else {
+        return Vec::new();
+    };
+
+    common::execute_query(
+        queries::IMPORT_QUERY,
+        &tree,
+        content.as_bytes(),
+        "import_path",
+    )
+    .into_iter()
+    .map(|node| {
+        let text = common::get_node_text(node, content);
+        CInclude {
+            path: text.trim_matches(['"', '<', '>']).to_string(),
+            system: node.kind() == "system_lib_string",
+            line: node.start_position().row + 1,
+        }
+    })
+    .collect()
+}
+
+// Name a prototype declares, under any pointer declarators
+fn prototype_name(declaration: Node, source: &str) -> Option<String> {
+    let mut declarator = declaration.child_by_field_name("declarator")?;
+    while declarator.kind() != "function_declarator" {
+        declarator = declarator.child_by_field_name("declarator")?;
+    }
+    let name = declarator.child_by_field_name("declarator")?;
+    Some(common::get_node_text(name, source))
+}
+
 pub struct CppExtractor;
 
 impl CppExtractor {
@@ -96,6 +139,24 @@ impl CppExtractor {
 }
 
 impl LanguageExtractor for CppExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Function,
+                NodeType::Method,
+                NodeType::Class,
+                NodeType::Variable,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+                Feature::Visibility,
+            ],
+        )
+    }
+
     fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
         let mut code_units = Vec::new();
 
@@ -117,14 +178,6 @@ impl LanguageExtractor for CppExtractor {
                         NodeType::Function
                     };
 
-                    let mut metadata = HashMap::new();
-
-                    if is_method {
-                        if let Some(parent_class) = self.find_parent_class(node, content) {
-                            metadata.insert("parent_class".to_string(), parent_class);
-                        }
-                    }
-
                     let mut code_node = common::create_node(
                         node_type,
                         name,
@@ -133,14 +186,37 @@ impl LanguageExtractor for CppExtractor {
                         node_content,
                     );
 
-                    for (key, value) in metadata {
-                        code_node.add_metadata(key, value);
+                    if is_method {
+                        code_node.parent_class = self.find_parent_class(node, content);
                     }
 
                     code_units.push(code_node);
                 }
             }
 
+            // Extract function prototypes, marked as declarations so calls
+            // resolve to the definitions
+            let prototype_nodes =
+                common::execute_query(queries::PROTOTYPE_QUERY, &tree, content.as_bytes(), "node");
+
+            for node in prototype_nodes {
+                if let Some(name) = prototype_name(node, content) {
+                    let start_line = node.start_position().row + 1;
+                    let end_line = node.end_position().row + 1;
+
+                    let code_node = common::create_node(
+                        NodeType::Function,
+                        name,
+                        file_path.to_str().unwrap_or(""),
+                        (start_line, end_line),
+                        common::get_node_text(node, content),
+                    )
+                    .with_metadata("declaration".to_string(), "true".to_string());
+
+                    code_units.push(code_node);
+                }
+            }
+
             // Extract classes
             let class_nodes =
                 common::execute_query(queries::CLASS_QUERY, &tree, content.as_bytes(), "node");
@@ -162,6 +238,14 @@ impl LanguageExtractor for CppExtractor {
                     code_units.push(code_node);
                 }
             }
+
+            // Extract module-level constants and globals
+            code_units.extend(common::extract_global_variables(
+                queries::GLOBAL_VARIABLE_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
         } else {
             warn!("Failed to parse C/C++ file: {:?}", file_path);
         }
@@ -174,7 +258,7 @@ impl LanguageExtractor for CppExtractor {
         content: &str,
         func_range: (usize, usize),
         _func_name: &str,
-    ) -> Vec<String> {
+    ) -> Vec<CallSite> {
         let mut calls = Vec::new();
 
         if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.cpp")) {
@@ -186,9 +270,9 @@ impl LanguageExtractor for CppExtractor {
 
                 // Check if call is within function range
                 if call_line >= func_range.0 && call_line <= func_range.1 {
-                    let call_name = common::get_node_text(node, content);
-                    if !call_name.is_empty() {
-                        calls.push(call_name);
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
                     }
                 }
             }
@@ -197,36 +281,8 @@ impl LanguageExtractor for CppExtractor {
         calls
     }
 
-    fn extract_variable_references(
-        &self,
-        content: &str,
-        func_range: (usize, usize),
-        var_name: &str,
-    ) -> Vec<(usize, usize)> {
-        let mut references = Vec::new();
-
-        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.cpp")) {
-            let reference_nodes = common::execute_query(
-                queries::REFERENCE_QUERY,
-                &tree,
-                content.as_bytes(),
-                "reference",
-            );
-
-            for node in reference_nodes {
-                let ref_line = node.start_position().row + 1;
-
-                // Check if reference is within function range
-                if ref_line >= func_range.0 && ref_line <= func_range.1 {
-                    let ref_name = common::get_node_text(node, content);
-                    if ref_name == var_name {
-                        references.push((ref_line, node.end_position().row + 1));
-                    }
-                }
-            }
-        }
-
-        references
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.cpp"))
     }
 
     fn extract_imported_modules(&self, content: &str) -> Vec<String> {
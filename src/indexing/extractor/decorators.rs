@@ -0,0 +1,152 @@
+use crate::code_graph::{CodeNode, NodeType};
+use crate::indexing::extractor::common;
+use tree_sitter::{Node, Tree};
+
+// Declarations that decorators and annotations can sit on
+const DECORATED_KINDS: &[&str] = &[
+    // Python
+    "function_definition",
+    "class_definition",
+    // TypeScript
+    "function_declaration",
+    "class_declaration",
+    "abstract_class_declaration",
+    "method_definition",
+    // Java
+    "method_declaration",
+    "constructor_declaration",
+    "interface_declaration",
+    "annotation_type_declaration",
+    // Rust
+    "function_item",
+    "struct_item",
+    "enum_item",
+];
+
+const ANNOTATION_KINDS: &[&str] = &["decorator", "annotation", "marker_annotation"];
+
+// Whether a sibling before a declaration annotates it: the decorators of
+// TypeScript class members, and Rust attributes with any doc comments
+// between them
+fn annotates(sibling: Node, declaration: Node) -> bool {
+    match sibling.kind() {
+        "decorator" => declaration
+            .parent()
+            .is_some_and(|parent| parent.kind() == "class_body"),
+        "attribute_item" | "line_comment" | "block_comment" => {
+            declaration.kind().ends_with("_item")
+        }
+        _ => false,
+    }
+}
+
+fn collect_declarations<'a>(node: Node<'a>, declarations: &mut Vec<Node<'a>>) {
+    if DECORATED_KINDS.contains(&node.kind()) {
+        declarations.push(node);
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_declarations(child, declarations);
+    }
+}
+
+// Decorator and annotation nodes of a declaration: the `decorator` children
+// of Python's `decorated_definition` and of TypeScript declarations (and of
+// an `export` around them), the ones before a class member, the annotations
+// among Java modifiers and the attributes before a Rust item
+fn annotations(declaration: Node) -> Vec<Node> {
+    let mut holders = vec![declaration];
+    if let Some(parent) = declaration.parent()
+        && matches!(parent.kind(), "decorated_definition" | "export_statement")
+    {
+        holders.insert(0, parent);
+    }
+    let mut cursor = declaration.walk();
+    holders.extend(
+        declaration
+            .named_children(&mut cursor)
+            .filter(|child| child.kind() == "modifiers"),
+    );
+
+    let mut found = Vec::new();
+    let mut sibling = declaration.prev_named_sibling();
+    while let Some(node) = sibling.filter(|node| annotates(*node, declaration)) {
+        if !node.kind().ends_with("comment") {
+            found.insert(0, node);
+        }
+        sibling = node.prev_named_sibling();
+    }
+    for holder in holders {
+        let mut cursor = holder.walk();
+        found.extend(
+            holder
+                .named_children(&mut cursor)
+                .filter(|child| ANNOTATION_KINDS.contains(&child.kind())),
+        );
+    }
+    found
+}
+
+// Name of a decorator expression without `@` or arguments:
+// `@app.get("/items")` is `app.get` and `#[tokio::main]` is `tokio::main`
+pub fn decorator_name(expression: &str) -> &str {
+    expression
+        .trim_start_matches('@')
+        .trim_start_matches("#[")
+        .split(['(', '=', ']'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+}
+
+// Record the decorators of Python and TypeScript declarations, the
+// annotations of Java ones and the attributes of Rust items as metadata: `decorators` holds their names,
+// comma-separated, and `decorator_calls` a JSON array of the full
+// expressions, arguments included
+pub fn annotate_decorators(tree: &Tree, source: &str, units: &mut [CodeNode]) {
+    let mut declarations = Vec::new();
+    collect_declarations(tree.root_node(), &mut declarations);
+
+    for unit in units.iter_mut() {
+        if !matches!(
+            unit.node_type,
+            NodeType::Function | NodeType::Method | NodeType::Class | NodeType::Interface
+        ) {
+            continue;
+        }
+        let (start, end) = unit.line_range;
+        // A unit's range may or may not include the decorators above it
+        let declaration = declarations
+            .iter()
+            .filter(|node| {
+                let node_start = node.start_position().row + 1;
+                node.end_position().row + 1 == end && node_start >= start && node_start <= end
+            })
+            .min_by_key(|node| node.start_byte());
+        let Some(declaration) = declaration else {
+            continue;
+        };
+
+        let expressions: Vec<String> = annotations(*declaration)
+            .into_iter()
+            .map(|node| {
+                let text = common::get_node_text(node, source);
+                let text = match text.strip_prefix("#[") {
+                    Some(attribute) => attribute.strip_suffix(']').unwrap_or(attribute),
+                    None => text.trim_start_matches('@'),
+                };
+                text.split_whitespace().collect::<Vec<_>>().join(" ")
+            })
+            .collect();
+        if expressions.is_empty() {
+            continue;
+        }
+
+        let names: Vec<&str> = expressions.iter().map(|e| decorator_name(e)).collect();
+        unit.add_metadata("decorators".to_string(), names.join(","));
+        unit.add_metadata(
+            "decorator_calls".to_string(),
+            serde_json::to_string(&expressions).unwrap_or_default(),
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use crate::code_graph::{CodeNode, NodeType, Visibility};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
+use crate::parsers::treesitter::queries::erlang as queries;
+use log::warn;
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::{Node, Tree};
+
+// Functions are named `module:function/arity`, the way Erlang itself refers
+// to them, so calls resolve to the right module and arity by name alone
+
+fn mfa(module: Option<&str>, function: &str, arity: usize) -> String {
+    match module {
+        Some(module) => format!("{}:{}/{}", module, function, arity),
+        None => format!("{}/{}", function, arity),
+    }
+}
+
+pub struct ErlangExtractor;
+
+impl ErlangExtractor {
+    pub fn new() -> Self {
+        ErlangExtractor
+    }
+
+    // Name from the `-module(name).` attribute
+    fn module_name(&self, tree: &Tree, content: &str) -> Option<String> {
+        common::execute_query(queries::MODULE_QUERY, tree, content.as_bytes(), "name")
+            .first()
+            .map(|node| common::get_node_text(*node, content))
+    }
+
+    fn argument_count(&self, node: Node) -> usize {
+        node.child_by_field_name("args").map_or(0, |args| {
+            let mut cursor = args.walk();
+            args.children_by_field_name("args", &mut cursor).count()
+        })
+    }
+
+    // Name and arity of an `fa` such as `deposit/2`
+    fn function_arity(&self, node: Node, content: &str) -> Option<(String, usize)> {
+        let function = common::get_node_text(node.child_by_field_name("fun")?, content);
+        let arity = node.child_by_field_name("arity")?;
+        // The arity node includes the slash in `/2`
+        let value = arity.child_by_field_name("value").unwrap_or(arity);
+        Some((
+            function,
+            common::get_node_text(value, content).parse().ok()?,
+        ))
+    }
+
+    // Functions brought into scope with `-import(lists, [foldl/3]).`
+    fn imported_functions(&self, tree: &Tree, content: &str) -> HashMap<(String, usize), String> {
+        let mut imported = HashMap::new();
+
+        for node in common::execute_query(
+            queries::IMPORTED_FUNCTION_QUERY,
+            tree,
+            content.as_bytes(),
+            "node",
+        ) {
+            let Some(module) = node.child_by_field_name("module") else {
+                continue;
+            };
+            let module = common::get_node_text(module, content);
+            let mut cursor = node.walk();
+            for fa in node.children_by_field_name("funs", &mut cursor) {
+                if let Some(function_arity) = self.function_arity(fa, content) {
+                    imported.insert(function_arity, module.clone());
+                }
+            }
+        }
+
+        imported
+    }
+
+    // `module:function/arity` of the function a call site refers to, or None
+    // when the module or function is only known at runtime
+    fn call_target(
+        &self,
+        name_node: Node,
+        content: &str,
+        module: Option<&str>,
+        imported: &HashMap<(String, usize), String>,
+    ) -> Option<String> {
+        let parent = name_node.parent()?;
+        let function = common::get_node_text(name_node, content);
+        let local_module = |arity: usize| {
+            imported
+                .get(&(function.clone(), arity))
+                .map(String::as_str)
+                .or(module)
+        };
+
+        match parent.kind() {
+            "call" => {
+                let arity = self.argument_count(parent);
+                let remote = parent
+                    .parent()
+                    .filter(|remote| remote.kind() == "remote")
+                    .and_then(|remote| remote.child_by_field_name("module"))
+                    .and_then(|remote_module| remote_module.child_by_field_name("module"));
+                match remote {
+                    Some(remote) if remote.kind() == "atom" => Some(mfa(
+                        Some(&common::get_node_text(remote, content)),
+                        &function,
+                        arity,
+                    )),
+                    Some(_) => None,
+                    None => Some(mfa(local_module(arity), &function, arity)),
+                }
+            }
+            "internal_fun" => {
+                let (_, arity) = self.function_arity(parent, content)?;
+                Some(mfa(local_module(arity), &function, arity))
+            }
+            "external_fun" => {
+                let (_, arity) = self.function_arity(parent, content)?;
+                let remote = parent
+                    .child_by_field_name("module")?
+                    .child_by_field_name("name")
+                    .filter(|name| name.kind() == "atom")?;
+                Some(mfa(
+                    Some(&common::get_node_text(remote, content)),
+                    &function,
+                    arity,
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl LanguageExtractor for ErlangExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Function,
+                NodeType::Module,
+                NodeType::TypeDefinition,
+            ],
+            &[Feature::Calls, Feature::Imports, Feature::Visibility],
+        )
+    }
+
+    fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+        let file_path_str = file_path.to_str().unwrap_or("");
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, file_path) {
+            let module = self.module_name(&tree, content);
+
+            // Extract the module, covering the whole file
+            if let Some(module) = &module {
+                let mut module_node = common::create_node(
+                    NodeType::Module,
+                    module.clone(),
+                    file_path_str,
+                    (1, content.lines().count().max(1)),
+                    content.to_string(),
+                );
+                let behaviours: Vec<String> = tree
+                    .root_node()
+                    .children(&mut tree.walk())
+                    .filter(|node| node.kind() == "behaviour_attribute")
+                    .filter_map(|node| node.child_by_field_name("name"))
+                    .map(|name| common::get_node_text(name, content))
+                    .collect();
+                if !behaviours.is_empty() {
+                    module_node.add_metadata("behaviours".to_string(), behaviours.join(","));
+                }
+                code_units.push(module_node);
+            }
+
+            let exports: Vec<(String, usize)> =
+                common::execute_query(queries::EXPORT_QUERY, &tree, content.as_bytes(), "export")
+                    .into_iter()
+                    .filter_map(|fa| self.function_arity(fa, content))
+                    .collect();
+
+            // Extract functions; all clauses of a function form one declaration
+            let function_nodes =
+                common::execute_query(queries::FUNCTION_QUERY, &tree, content.as_bytes(), "node");
+            let mut previous: Option<(String, usize)> = None;
+
+            for node in function_nodes {
+                let Some(clause) = node.child_by_field_name("clause") else {
+                    continue;
+                };
+                let Some(name_node) = clause.child_by_field_name("name") else {
+                    continue;
+                };
+                let function = common::get_node_text(name_node, content);
+                let arity = self.argument_count(clause);
+                let exported = exports.contains(&(function.clone(), arity));
+
+                // Each clause is parsed as its own declaration
+                let end_line = node.end_position().row + 1;
+                let key = Some((function.clone(), arity));
+                if previous == key
+                    && let Some(last) = code_units.last_mut()
+                {
+                    last.line_range.1 = end_line;
+                    last.content = common::line_range_text(content, last.line_range.0, end_line);
+                    last.id = common::stable_node_id(
+                        &last.node_type,
+                        &last.name,
+                        &last.file_path,
+                        last.line_range,
+                    );
+                    continue;
+                }
+                previous = key;
+
+                let mut code_node = common::create_node(
+                    NodeType::Function,
+                    mfa(module.as_deref(), &function, arity),
+                    file_path_str,
+                    (node.start_position().row + 1, end_line),
+                    common::get_node_text(node, content),
+                )
+                .with_metadata("function".to_string(), function)
+                .with_metadata("arity".to_string(), arity.to_string())
+                .with_metadata("exported".to_string(), exported.to_string());
+                code_node.visibility = Some(if exported {
+                    Visibility::Public
+                } else {
+                    Visibility::Private
+                });
+                if let Some(module) = &module {
+                    code_node.add_metadata("erlang_module".to_string(), module.clone());
+                }
+
+                code_units.push(code_node);
+            }
+
+            // Extract records and types
+            let type_nodes =
+                common::execute_query(queries::TYPE_QUERY, &tree, content.as_bytes(), "node");
+
+            for node in type_nodes {
+                let name_node = match node.kind() {
+                    "record_decl" => node.child_by_field_name("name"),
+                    _ => node
+                        .child_by_field_name("name")
+                        .and_then(|type_name| type_name.child_by_field_name("name")),
+                };
+                let Some(name_node) = name_node else {
+                    continue;
+                };
+                let kind = match node.kind() {
+                    "record_decl" => "record",
+                    "opaque" => "opaque",
+                    _ => "type",
+                };
+
+                code_units.push(
+                    common::create_node(
+                        NodeType::TypeDefinition,
+                        common::get_node_text(name_node, content),
+                        file_path_str,
+                        (node.start_position().row + 1, node.end_position().row + 1),
+                        common::get_node_text(node, content),
+                    )
+                    .with_metadata("erlang_kind".to_string(), kind.to_string()),
+                );
+            }
+        } else {
+            warn!("Failed to parse Erlang file: {:?}", file_path);
+        }
+
+        code_units
+    }
+
+    fn extract_function_calls(
+        &self,
+        content: &str,
+        func_range: (usize, usize),
+        _func_name: &str,
+    ) -> Vec<CallSite> {
+        let mut calls = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.erl")) {
+            let module = self.module_name(&tree, content);
+            let imported = self.imported_functions(&tree, content);
+            let call_nodes =
+                common::execute_query(queries::CALL_QUERY, &tree, content.as_bytes(), "func_name");
+
+            for node in call_nodes {
+                let call_line = node.start_position().row + 1;
+
+                // Check if call is within function range
+                if call_line >= func_range.0 && call_line <= func_range.1 {
+                    let Some(target) =
+                        self.call_target(node, content, module.as_deref(), &imported)
+                    else {
+                        continue;
+                    };
+                    let mut call_site = common::create_call_site(node, content);
+                    call_site.name = target;
+                    calls.push(call_site);
+                }
+            }
+        }
+
+        calls
+    }
+
+    fn extract_references(&self, _content: &str) -> Vec<(String, usize)> {
+        // Erlang variables are local to a clause
+        Vec::new()
+    }
+
+    fn extract_imported_modules(&self, content: &str) -> Vec<String> {
+        let mut modules: Vec<String> = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.erl")) {
+            let module_nodes =
+                common::execute_query(queries::IMPORT_QUERY, &tree, content.as_bytes(), "module");
+            let include_nodes =
+                common::execute_query(queries::IMPORT_QUERY, &tree, content.as_bytes(), "include");
+
+            // Included headers are matched by file name, like other imports
+            let included = include_nodes.into_iter().filter_map(|node| {
+                let path = common::get_node_text(node, content);
+                Path::new(path.trim_matches('"'))
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .map(str::to_string)
+            });
+
+            for module in module_nodes
+                .into_iter()
+                .map(|node| common::get_node_text(node, content))
+                .chain(included)
+            {
+                if !modules.contains(&module) {
+                    modules.push(module);
+                }
+            }
+        }
+
+        modules
+    }
+}
@@ -1,8 +1,9 @@
 use crate::code_graph::{CodeNode, NodeType};
-use crate::indexing::extractor::{LanguageExtractor, common};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
 use crate::parsers::treesitter::queries::go as queries;
 use log::warn;
-use std::collections::HashMap;
 use std::path::Path;
 use tree_sitter::Node;
 
@@ -16,11 +17,12 @@ impl GoExtractor {
     fn find_node_name(&self, node: Node, source: &str, node_type: &NodeType) -> Option<String> {
         match node_type {
             NodeType::Function | NodeType::Method => {
+                // Method names are field identifiers, after the receiver
                 for i in 0..node.named_child_count() {
-                    if let Some(child) = node.named_child(i) {
-                        if child.kind() == "identifier" {
-                            return Some(common::get_node_text(child, source));
-                        }
+                    if let Some(child) = node.named_child(i)
+                        && matches!(child.kind(), "identifier" | "field_identifier")
+                    {
+                        return Some(common::get_node_text(child, source));
                     }
                 }
             }
@@ -94,6 +96,29 @@ impl GoExtractor {
 }
 
 impl LanguageExtractor for GoExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Function,
+                NodeType::Method,
+                NodeType::Class,
+                NodeType::Interface,
+                NodeType::Variable,
+                NodeType::Field,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+                Feature::Visibility,
+                Feature::Fields,
+                Feature::Implementations,
+                Feature::Tests,
+            ],
+        )
+    }
+
     fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
         let mut code_units = Vec::new();
 
@@ -132,12 +157,6 @@ impl LanguageExtractor for GoExtractor {
                     let end_line = node.end_position().row + 1;
                     let node_content = common::get_node_text(node, content);
 
-                    let mut metadata = HashMap::new();
-
-                    if let Some(receiver_type) = self.find_receiver_type(node, content) {
-                        metadata.insert("parent_class".to_string(), receiver_type);
-                    }
-
                     let mut code_node = common::create_node(
                         NodeType::Method,
                         name,
@@ -145,15 +164,56 @@ impl LanguageExtractor for GoExtractor {
                         (start_line, end_line),
                         node_content,
                     );
-
-                    for (key, value) in metadata {
-                        code_node.add_metadata(key, value);
-                    }
+                    code_node.parent_class = self.find_receiver_type(node, content);
 
                     code_units.push(code_node);
                 }
             }
 
+            // Extract interfaces and the methods they declare
+            let interface_nodes =
+                common::execute_query(queries::INTERFACE_QUERY, &tree, content.as_bytes(), "node");
+
+            for node in interface_nodes {
+                if let Some(name) = self.find_node_name(node, content, &NodeType::Interface) {
+                    code_units.push(common::create_node(
+                        NodeType::Interface,
+                        name,
+                        file_path.to_str().unwrap_or(""),
+                        (node.start_position().row + 1, node.end_position().row + 1),
+                        common::get_node_text(node, content),
+                    ));
+                }
+            }
+
+            let method_elems = common::execute_query(
+                queries::INTERFACE_METHOD_QUERY,
+                &tree,
+                content.as_bytes(),
+                "node",
+            );
+
+            for node in method_elems {
+                let Some(name) = node.child_by_field_name("name") else {
+                    continue;
+                };
+                let interface = node
+                    .parent()
+                    .and_then(|interface_type| interface_type.parent())
+                    .and_then(|type_spec| type_spec.child_by_field_name("name"));
+                let mut code_node = common::create_node(
+                    NodeType::Method,
+                    common::get_node_text(name, content),
+                    file_path.to_str().unwrap_or(""),
+                    (node.start_position().row + 1, node.end_position().row + 1),
+                    common::get_node_text(node, content),
+                );
+                code_node.parent_class =
+                    interface.map(|interface| common::get_node_text(interface, content));
+                code_node.add_metadata("declaration".to_string(), "true".to_string());
+                code_units.push(code_node);
+            }
+
             // Extract structs as "classes"
             let struct_nodes =
                 common::execute_query(queries::CLASS_QUERY, &tree, content.as_bytes(), "node");
@@ -175,6 +235,22 @@ impl LanguageExtractor for GoExtractor {
                     code_units.push(code_node);
                 }
             }
+
+            // Extract fields and properties
+            code_units.extend(common::extract_fields(
+                queries::FIELD_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
+
+            // Extract module-level constants and globals
+            code_units.extend(common::extract_global_variables(
+                queries::GLOBAL_VARIABLE_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
         } else {
             warn!("Failed to parse Go file: {:?}", file_path);
         }
@@ -187,7 +263,7 @@ impl LanguageExtractor for GoExtractor {
         content: &str,
         func_range: (usize, usize),
         _func_name: &str,
-    ) -> Vec<String> {
+    ) -> Vec<CallSite> {
         let mut calls = Vec::new();
 
         if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.go")) {
@@ -199,9 +275,9 @@ impl LanguageExtractor for GoExtractor {
 
                 // Check if call is within function range
                 if call_line >= func_range.0 && call_line <= func_range.1 {
-                    let call_name = common::get_node_text(node, content);
-                    if !call_name.is_empty() {
-                        calls.push(call_name);
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
                     }
                 }
             }
@@ -210,36 +286,8 @@ impl LanguageExtractor for GoExtractor {
         calls
     }
 
-    fn extract_variable_references(
-        &self,
-        content: &str,
-        func_range: (usize, usize),
-        var_name: &str,
-    ) -> Vec<(usize, usize)> {
-        let mut references = Vec::new();
-
-        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.go")) {
-            let reference_nodes = common::execute_query(
-                queries::REFERENCE_QUERY,
-                &tree,
-                content.as_bytes(),
-                "reference",
-            );
-
-            for node in reference_nodes {
-                let ref_line = node.start_position().row + 1;
-
-                // Check if reference is within function range
-                if ref_line >= func_range.0 && ref_line <= func_range.1 {
-                    let ref_name = common::get_node_text(node, content);
-                    if ref_name == var_name {
-                        references.push((ref_line, node.end_position().row + 1));
-                    }
-                }
-            }
-        }
-
-        references
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.go"))
     }
 
     fn extract_imported_modules(&self, content: &str) -> Vec<String> {
@@ -0,0 +1,371 @@
+use crate::code_graph::{CodeNode, NodeType};
+use crate::indexing::extractor::{CallSite, LanguageCapabilities, LanguageExtractor, common};
+use std::path::Path;
+
+// There is no tree-sitter grammar for GraphQL among our dependencies, so
+// schema and operation documents are read with a small tokenizer instead
+
+// Root types whose fields are the entry points of the API
+const ROOT_TYPES: &[&str] = &["Query", "Mutation", "Subscription"];
+
+const DEFINITION_KEYWORDS: &[&str] = &[
+    "schema",
+    "scalar",
+    "type",
+    "interface",
+    "union",
+    "enum",
+    "input",
+    "directive",
+    "extend",
+    "fragment",
+    "query",
+    "mutation",
+    "subscription",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Punct(char),
+    Spread,
+    Str,
+}
+
+// A token and the 1-based line it starts on
+type Spanned = (Token, usize);
+
+fn tokenize(content: &str) -> Vec<Spanned> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            '#' => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '"' => {
+                let start_line = line;
+                let mut rest = chars.clone();
+                if rest.next() == Some('"') && rest.next() == Some('"') {
+                    // Block strings run to the next three quotes
+                    chars.nth(1);
+                    let mut quotes = 0;
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            line += 1;
+                        }
+                        quotes = if c == '"' { quotes + 1 } else { 0 };
+                        if quotes == 3 {
+                            break;
+                        }
+                    }
+                } else {
+                    let mut escaped = false;
+                    for c in chars.by_ref() {
+                        match c {
+                            '\n' => {
+                                line += 1;
+                                break;
+                            }
+                            '"' if !escaped => break,
+                            _ => {}
+                        }
+                        escaped = c == '\\' && !escaped;
+                    }
+                }
+                tokens.push((Token::Str, start_line));
+            }
+            '.' if chars.peek() == Some(&'.') => {
+                chars.nth(1);
+                tokens.push((Token::Spread, line));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut name = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' {
+                        name.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push((Token::Name(name), line));
+            }
+            c if "{}()[]:=@|&!$".contains(c) => tokens.push((Token::Punct(c), line)),
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+fn name_at(tokens: &[Spanned], index: usize) -> Option<&str> {
+    match tokens.get(index) {
+        Some((Token::Name(name), _)) => Some(name),
+        _ => None,
+    }
+}
+
+fn is_punct(tokens: &[Spanned], index: usize, punct: char) -> bool {
+    matches!(tokens.get(index), Some((Token::Punct(c), _)) if *c == punct)
+}
+
+// Index of the brace closing the one opened at `open`
+fn matching_brace(tokens: &[Spanned], open: usize) -> usize {
+    let mut depth = 0;
+    for (index, (token, _)) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Token::Punct('{') => depth += 1,
+            Token::Punct('}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return index;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len() - 1
+}
+
+// Index of the last token of the definition starting at `start`: the end of
+// its body when it has one, else the token before the next definition
+fn definition_end(tokens: &[Spanned], start: usize) -> usize {
+    let mut parens = 0;
+    for index in start + 1..tokens.len() {
+        match &tokens[index].0 {
+            Token::Punct('(') => parens += 1,
+            Token::Punct(')') => parens -= 1,
+            Token::Punct('{') if parens == 0 => return matching_brace(tokens, index),
+            Token::Str if parens == 0 => return index - 1,
+            Token::Name(name)
+                if parens == 0
+                    && DEFINITION_KEYWORDS.contains(&name.as_str())
+                    && !matches!(tokens[index - 1].0, Token::Punct(_)) =>
+            {
+                return index - 1;
+            }
+            _ => {}
+        }
+    }
+    tokens.len() - 1
+}
+
+// Field names, fragment spreads and the selected root fields of a body
+struct Selections {
+    fields: Vec<(String, usize, usize)>,
+    spreads: Vec<String>,
+}
+
+// Walk the top level of a `{ ... }` body starting at `open`. Fields of a
+// type are names followed by arguments or a type; fields of a selection set
+// are any other top-level names.
+fn selections(tokens: &[Spanned], open: usize, close: usize, schema: bool) -> Selections {
+    let mut result = Selections {
+        fields: Vec::new(),
+        spreads: Vec::new(),
+    };
+    let mut depth = 0;
+    let mut parens = 0;
+
+    for index in open..=close {
+        match &tokens[index].0 {
+            Token::Punct('{') => depth += 1,
+            Token::Punct('}') => depth -= 1,
+            Token::Punct('(') => parens += 1,
+            Token::Punct(')') => parens -= 1,
+            Token::Spread => {
+                if let Some(name) = name_at(tokens, index + 1).filter(|name| *name != "on") {
+                    result.spreads.push(name.to_string());
+                }
+            }
+            Token::Name(name) if depth == 1 && parens == 0 => {
+                let previous = &tokens[index - 1].0;
+                if matches!(previous, Token::Spread | Token::Punct('@')) {
+                    continue;
+                }
+                let is_field = if schema {
+                    is_punct(tokens, index + 1, '(') || is_punct(tokens, index + 1, ':')
+                } else {
+                    // `alias: field` selects `field`
+                    !is_punct(tokens, index + 1, ':') && name != "on"
+                };
+                if is_field {
+                    let line = tokens[index].1;
+                    result.fields.push((name.clone(), line, line));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // A schema field runs until the next one starts
+    if schema {
+        let close_line = tokens[close].1;
+        for i in 0..result.fields.len() {
+            let start_line = result.fields[i].1;
+            let next_line = match result.fields.get(i + 1) {
+                Some((_, next_line, _)) => *next_line,
+                None => close_line,
+            };
+            result.fields[i].2 = (next_line.saturating_sub(1)).max(start_line);
+        }
+    }
+
+    result
+}
+
+pub struct GraphQLExtractor;
+
+impl GraphQLExtractor {
+    pub fn new() -> Self {
+        GraphQLExtractor
+    }
+}
+
+impl LanguageExtractor for GraphQLExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Class,
+                NodeType::Interface,
+                NodeType::TypeDefinition,
+                NodeType::Method,
+                NodeType::Function,
+            ],
+            &[],
+        )
+    }
+
+    fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+        let file_path_str = file_path.to_str().unwrap_or("");
+        let tokens = tokenize(content);
+
+        let node = |node_type, name: &str, start: usize, end: usize, kind: &str| {
+            common::create_node(
+                node_type,
+                name.to_string(),
+                file_path_str,
+                (tokens[start].1, tokens[end].1),
+                common::line_range_text(content, tokens[start].1, tokens[end].1),
+            )
+            .with_metadata("graphql_kind".to_string(), kind.to_string())
+        };
+
+        let mut index = 0;
+        while index < tokens.len() {
+            let Some(keyword) = name_at(&tokens, index) else {
+                // Descriptions and anonymous `{ ... }` operations
+                index = match tokens[index].0 {
+                    Token::Punct('{') => matching_brace(&tokens, index) + 1,
+                    _ => index + 1,
+                };
+                continue;
+            };
+            if keyword == "extend" {
+                index += 1;
+                continue;
+            }
+
+            let end = definition_end(&tokens, index);
+            let body = (index..=end).find(|&i| is_punct(&tokens, i, '{'));
+
+            match (keyword, name_at(&tokens, index + 1)) {
+                ("type" | "interface" | "input" | "enum" | "union" | "scalar", Some(name)) => {
+                    let node_type = match keyword {
+                        "type" => NodeType::Class,
+                        "interface" => NodeType::Interface,
+                        _ => NodeType::TypeDefinition,
+                    };
+                    code_units.push(node(node_type, name, index, end, keyword));
+
+                    // Root fields are what resolvers implement
+                    if keyword == "type"
+                        && ROOT_TYPES.contains(&name)
+                        && let Some(open) = body
+                    {
+                        for (field, start_line, end_line) in
+                            selections(&tokens, open, end, true).fields
+                        {
+                            code_units.push(
+                                common::create_node(
+                                    NodeType::Method,
+                                    field,
+                                    file_path_str,
+                                    (start_line, end_line),
+                                    common::line_range_text(content, start_line, end_line),
+                                )
+                                .with_metadata("graphql_kind".to_string(), "field".to_string())
+                                .with_parent_class(name.to_string()),
+                            );
+                        }
+                    }
+                }
+                ("fragment", Some(name)) => {
+                    let mut fragment = node(NodeType::TypeDefinition, name, index, end, keyword);
+                    if name_at(&tokens, index + 2) == Some("on")
+                        && let Some(type_condition) = name_at(&tokens, index + 3)
+                    {
+                        fragment
+                            .add_metadata("type_condition".to_string(), type_condition.to_string());
+                    }
+                    if let Some(open) = body {
+                        let spreads = selections(&tokens, open, end, false).spreads;
+                        if !spreads.is_empty() {
+                            fragment.add_metadata("fragments".to_string(), spreads.join(","));
+                        }
+                    }
+                    code_units.push(fragment);
+                }
+                ("query" | "mutation" | "subscription", Some(name)) => {
+                    let mut operation = node(NodeType::Function, name, index, end, keyword);
+                    if let Some(open) = body {
+                        let selections = selections(&tokens, open, end, false);
+                        let root_fields: Vec<String> = selections
+                            .fields
+                            .into_iter()
+                            .map(|(field, _, _)| field)
+                            .collect();
+                        operation.add_metadata("root_fields".to_string(), root_fields.join(","));
+                        if !selections.spreads.is_empty() {
+                            operation.add_metadata(
+                                "fragments".to_string(),
+                                selections.spreads.join(","),
+                            );
+                        }
+                    }
+                    code_units.push(operation);
+                }
+                _ => {}
+            }
+
+            index = end + 1;
+        }
+
+        code_units
+    }
+
+    fn extract_function_calls(
+        &self,
+        _content: &str,
+        _func_range: (usize, usize),
+        _func_name: &str,
+    ) -> Vec<CallSite> {
+        // Operations are linked to schema fields from metadata instead
+        Vec::new()
+    }
+
+    fn extract_references(&self, _content: &str) -> Vec<(String, usize)> {
+        Vec::new()
+    }
+
+    fn extract_imported_modules(&self, _content: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,299 @@
+use crate::code_graph::{CodeNode, NodeType};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
+use crate::parsers::treesitter::queries::groovy as queries;
+use log::warn;
+use regex::Regex;
+use std::path::Path;
+use tree_sitter::Node;
+
+pub struct GroovyExtractor;
+
+impl GroovyExtractor {
+    pub fn new() -> Self {
+        GroovyExtractor
+    }
+
+    fn find_parent_class(&self, node: Node, source: &str) -> Option<String> {
+        let mut parent_iter = node.parent();
+
+        while let Some(parent) = parent_iter {
+            if (parent.kind() == "class_declaration" || parent.kind() == "interface_declaration")
+                && let Some(name) = parent.child_by_field_name("name")
+            {
+                return Some(common::get_node_text(name, source));
+            }
+            parent_iter = parent.parent();
+        }
+
+        None
+    }
+
+    // Line of the brace closing the block opened at `open`, skipping quoted text
+    fn closing_line(&self, content: &str, open: usize) -> usize {
+        let mut depth = 0;
+        let mut quote: Option<char> = None;
+        let mut line = content[..open].matches('\n').count() + 1;
+        let mut previous = ' ';
+
+        for c in content[open..].chars() {
+            match (quote, c) {
+                (_, '\n') => line += 1,
+                (Some(q), _) if c == q && previous != '\\' => quote = None,
+                (Some(_), _) => {}
+                (None, '\'' | '"') => quote = Some(c),
+                (None, '{') => depth += 1,
+                (None, '}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return line;
+                    }
+                }
+                _ => {}
+            }
+            previous = c;
+        }
+
+        line
+    }
+
+    // Tasks declared in a Gradle build script with `task name { ... }`,
+    // `task name(type: Copy)` or `tasks.register('name') { ... }`. The
+    // grammar does not understand the task DSL, so they are found in the text.
+    fn extract_gradle_tasks(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
+        let mut tasks = Vec::new();
+        let (Ok(task_regex), Ok(type_regex), Ok(depends_regex)) = (
+            Regex::new(
+                r#"(?m)^[ \t]*(?:task\b\s*\(?\s*['"]?(\w+)['"]?|tasks\.(?:register|create)\s*\(\s*['"](\w+)['"])"#,
+            ),
+            Regex::new(r"(?:\btype\s*:|,)\s*([A-Z][\w.]*)"),
+            Regex::new(r#"\bdependsOn\b\s*[:(=]?\s*\[?([\w'", \t]+)"#),
+        ) else {
+            return tasks;
+        };
+
+        for capture in task_regex.captures_iter(content) {
+            let (Some(whole), Some(name)) = (capture.get(0), capture.get(1).or(capture.get(2)))
+            else {
+                continue;
+            };
+            let start = whole.start() + whole.as_str().len() - whole.as_str().trim_start().len();
+            let start_line = content[..start].matches('\n').count() + 1;
+
+            // The declaration ends at its closing brace, or at the end of the
+            // line when the task has no configuration block
+            let mut depth = 0;
+            let mut header_end = content.len();
+            let mut end_line = start_line;
+            for (offset, c) in content[whole.end()..].char_indices() {
+                let position = whole.end() + offset;
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    '{' if depth <= 0 => {
+                        header_end = position;
+                        end_line = self.closing_line(content, position);
+                        break;
+                    }
+                    '\n' if depth <= 0 => {
+                        header_end = position;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+
+            let header = &content[whole.end()..header_end];
+            let text = common::line_range_text(content, start_line, end_line);
+            let depends_on: Vec<String> = depends_regex
+                .captures_iter(&text)
+                .flat_map(|c| {
+                    c[1].split(|ch: char| !ch.is_alphanumeric() && ch != '_')
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+
+            let mut code_node = common::create_node(
+                NodeType::Function,
+                name.as_str().to_string(),
+                file_path.to_str().unwrap_or(""),
+                (start_line, end_line),
+                text,
+            )
+            .with_metadata("gradle_kind".to_string(), "task".to_string());
+            if let Some(task_type) = type_regex.captures(header) {
+                code_node.add_metadata("task_type".to_string(), task_type[1].to_string());
+            }
+            if !depends_on.is_empty() {
+                code_node.add_metadata("depends_on".to_string(), depends_on.join(","));
+            }
+
+            tasks.push(code_node);
+        }
+
+        tasks
+    }
+}
+
+impl LanguageExtractor for GroovyExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Method,
+                NodeType::Function,
+                NodeType::Class,
+                NodeType::Interface,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::Visibility,
+            ],
+        )
+    }
+
+    fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, file_path) {
+            // Extract methods and constructors
+            let method_nodes =
+                common::execute_query(queries::METHOD_QUERY, &tree, content.as_bytes(), "node");
+
+            for node in method_nodes {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    let mut code_node = common::create_node(
+                        NodeType::Method,
+                        common::get_node_text(name_node, content),
+                        file_path.to_str().unwrap_or(""),
+                        (node.start_position().row + 1, node.end_position().row + 1),
+                        common::get_node_text(node, content),
+                    );
+                    code_node.parent_class = self.find_parent_class(node, content);
+                    code_units.push(code_node);
+                }
+            }
+
+            // Extract script-level functions
+            let function_nodes =
+                common::execute_query(queries::FUNCTION_QUERY, &tree, content.as_bytes(), "node");
+
+            for node in function_nodes {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    code_units.push(common::create_node(
+                        NodeType::Function,
+                        common::get_node_text(name_node, content),
+                        file_path.to_str().unwrap_or(""),
+                        (node.start_position().row + 1, node.end_position().row + 1),
+                        common::get_node_text(node, content),
+                    ));
+                }
+            }
+
+            // Extract classes and interfaces
+            let class_nodes =
+                common::execute_query(queries::CLASS_QUERY, &tree, content.as_bytes(), "node");
+
+            for node in class_nodes {
+                let Some(name_node) = node.child_by_field_name("name") else {
+                    continue;
+                };
+                let node_type = if node.kind() == "interface_declaration" {
+                    NodeType::Interface
+                } else {
+                    NodeType::Class
+                };
+
+                let mut code_node = common::create_node(
+                    node_type,
+                    common::get_node_text(name_node, content),
+                    file_path.to_str().unwrap_or(""),
+                    (node.start_position().row + 1, node.end_position().row + 1),
+                    common::get_node_text(node, content),
+                );
+                if let Some(superclass) = node
+                    .child_by_field_name("superclass")
+                    .and_then(|superclass| superclass.named_child(0))
+                {
+                    code_node.add_metadata(
+                        "superclass".to_string(),
+                        common::get_node_text(superclass, content),
+                    );
+                }
+                code_units.push(code_node);
+            }
+        } else {
+            warn!("Failed to parse Groovy file: {:?}", file_path);
+        }
+
+        if file_path.extension().and_then(|e| e.to_str()) == Some("gradle") {
+            code_units.extend(self.extract_gradle_tasks(content, file_path));
+        }
+
+        code_units
+    }
+
+    fn extract_function_calls(
+        &self,
+        content: &str,
+        func_range: (usize, usize),
+        _func_name: &str,
+    ) -> Vec<CallSite> {
+        let mut calls = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.groovy")) {
+            let call_nodes =
+                common::execute_query(queries::CALL_QUERY, &tree, content.as_bytes(), "func_name");
+
+            for node in call_nodes {
+                let call_line = node.start_position().row + 1;
+
+                // Check if call is within function range
+                if call_line >= func_range.0 && call_line <= func_range.1 {
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
+                    }
+                }
+            }
+        }
+
+        calls
+    }
+
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.groovy"))
+    }
+
+    fn extract_imported_modules(&self, content: &str) -> Vec<String> {
+        let mut modules: Vec<String> = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.groovy")) {
+            let import_nodes = common::execute_query(
+                queries::IMPORT_QUERY,
+                &tree,
+                content.as_bytes(),
+                "import_path",
+            );
+
+            for node in import_nodes {
+                // `import a.b.*` names the package, `import a.b.C` the class
+                let import_text = common::get_node_text(node, content);
+                if let Some(module) = import_text
+                    .rsplit('.')
+                    .find(|part| !part.is_empty() && *part != "*")
+                    .map(str::to_string)
+                    && !modules.contains(&module)
+                {
+                    modules.push(module);
+                }
+            }
+        }
+
+        modules
+    }
+}
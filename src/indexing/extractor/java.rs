@@ -1,11 +1,77 @@
 use crate::code_graph::{CodeNode, NodeType};
-use crate::indexing::extractor::{LanguageExtractor, common};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
 use crate::parsers::treesitter::queries::java as queries;
 use log::warn;
-use std::collections::HashMap;
 use std::path::Path;
 use tree_sitter::Node;
 
+// An import declaration: `import static a.b.C.d;` is path `a.b.C.d`, static
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JavaImport {
+    pub path: String,
+    // `import a.b.*`
+    pub wildcard: bool,
+    pub is_static: bool,
+    pub line: usize,
+}
+
+// The package declaration and imports at the top of a Java file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JavaSource {
+    pub package: Option<String>,
+    pub imports: Vec<JavaImport>,
+}
+
+pub fn java_source(content: &str) -> JavaSource {
+    let mut source = JavaSource::default();
+    let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.java")) else {
+        return source;
+    };
+
+    let statements = common::execute_query(
+        queries::SOURCE_HEADER_QUERY,
+        &tree,
+        content.as_bytes(),
+        "statement",
+    );
+    for statement in statements {
+        // Annotations on package declarations are not part of the name
+        let text: String = common::get_node_text(statement, content)
+            .split_whitespace()
+            .filter(|word| !word.starts_with('@'))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let text = text.trim_end_matches(';').trim();
+
+        if statement.kind() == "package_declaration" {
+            let name = text.trim_start_matches("package").trim();
+            source.package = Some(name.replace(' ', ""));
+            continue;
+        }
+
+        let rest = text.trim_start_matches("import").trim_start();
+        let (is_static, rest) = match rest.strip_prefix("static ") {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let path = rest.replace(' ', "");
+        let (path, wildcard) = match path.strip_suffix(".*") {
+            Some(path) => (path.to_string(), true),
+            None => (path, false),
+        };
+        source.imports.push(JavaImport {
+            path,
+            wildcard,
+            is_static,
+            line: statement.start_position().row + 1,
+        });
+    }
+
+    source
+}
+
 pub struct JavaExtractor;
 
 impl JavaExtractor {
@@ -69,12 +135,55 @@ impl JavaExtractor {
         None
     }
 
+    // Simple names of the interfaces in a class's `implements` clause
+    fn implemented_interfaces(&self, node: Node, source: &str) -> Vec<String> {
+        let Some(interfaces) = node.child_by_field_name("interfaces") else {
+            return Vec::new();
+        };
+        let mut cursor = interfaces.walk();
+        let Some(list) = interfaces
+            .named_children(&mut cursor)
+            .find(|child| child.kind() == "type_list")
+        else {
+            return Vec::new();
+        };
+        let mut cursor = list.walk();
+        list.named_children(&mut cursor)
+            .map(|interface| common::simple_type_name(&common::get_node_text(interface, source)))
+            .collect()
+    }
+
+    // Annotation types (`@interface`) are interfaces too
     fn is_interface(&self, node: Node) -> bool {
-        node.kind() == "interface_declaration"
+        matches!(
+            node.kind(),
+            "interface_declaration" | "annotation_type_declaration"
+        )
     }
 }
 
 impl LanguageExtractor for JavaExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Method,
+                NodeType::Class,
+                NodeType::Interface,
+                NodeType::Field,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::Visibility,
+                Feature::Fields,
+                Feature::Implementations,
+                Feature::Tests,
+                Feature::Decorators,
+            ],
+        )
+    }
+
     fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
         let mut code_units = Vec::new();
 
@@ -89,12 +198,6 @@ impl LanguageExtractor for JavaExtractor {
                     let end_line = node.end_position().row + 1;
                     let node_content = common::get_node_text(node, content);
 
-                    let mut metadata = HashMap::new();
-
-                    if let Some(parent_class) = self.find_parent_class(node, content) {
-                        metadata.insert("parent_class".to_string(), parent_class);
-                    }
-
                     let mut code_node = common::create_node(
                         NodeType::Method,
                         name,
@@ -102,9 +205,10 @@ impl LanguageExtractor for JavaExtractor {
                         (start_line, end_line),
                         node_content,
                     );
-
-                    for (key, value) in metadata {
-                        code_node.add_metadata(key, value);
+                    code_node.parent_class = self.find_parent_class(node, content);
+                    // Interface and abstract methods
+                    if node.child_by_field_name("body").is_none() {
+                        code_node.add_metadata("declaration".to_string(), "true".to_string());
                     }
 
                     code_units.push(code_node);
@@ -127,7 +231,7 @@ impl LanguageExtractor for JavaExtractor {
                         NodeType::Class
                     };
 
-                    let code_node = common::create_node(
+                    let mut code_node = common::create_node(
                         node_type,
                         name,
                         file_path.to_str().unwrap_or(""),
@@ -135,9 +239,22 @@ impl LanguageExtractor for JavaExtractor {
                         node_content,
                     );
 
+                    let interfaces = self.implemented_interfaces(node, content);
+                    if !interfaces.is_empty() {
+                        code_node.add_metadata("implements".to_string(), interfaces.join(","));
+                    }
+
                     code_units.push(code_node);
                 }
             }
+
+            // Extract fields and constants
+            code_units.extend(common::extract_fields(
+                queries::FIELD_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
         } else {
             warn!("Failed to parse Java file: {:?}", file_path);
         }
@@ -150,7 +267,7 @@ impl LanguageExtractor for JavaExtractor {
         content: &str,
         func_range: (usize, usize),
         _func_name: &str,
-    ) -> Vec<String> {
+    ) -> Vec<CallSite> {
         let mut calls = Vec::new();
 
         if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.java")) {
@@ -162,9 +279,9 @@ impl LanguageExtractor for JavaExtractor {
 
                 // Check if call is within function range
                 if call_line >= func_range.0 && call_line <= func_range.1 {
-                    let call_name = common::get_node_text(node, content);
-                    if !call_name.is_empty() {
-                        calls.push(call_name);
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
                     }
                 }
             }
@@ -173,36 +290,8 @@ impl LanguageExtractor for JavaExtractor {
         calls
     }
 
-    fn extract_variable_references(
-        &self,
-        content: &str,
-        func_range: (usize, usize),
-        var_name: &str,
-    ) -> Vec<(usize, usize)> {
-        let mut references = Vec::new();
-
-        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.java")) {
-            let reference_nodes = common::execute_query(
-                queries::REFERENCE_QUERY,
-                &tree,
-                content.as_bytes(),
-                "reference",
-            );
-
-            for node in reference_nodes {
-                let ref_line = node.start_position().row + 1;
-
-                // Check if reference is within function range
-                if ref_line >= func_range.0 && ref_line <= func_range.1 {
-                    let ref_name = common::get_node_text(node, content);
-                    if ref_name == var_name {
-                        references.push((ref_line, node.end_position().row + 1));
-                    }
-                }
-            }
-        }
-
-        references
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.java"))
     }
 
     fn extract_imported_modules(&self, content: &str) -> Vec<String> {
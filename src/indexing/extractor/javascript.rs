@@ -1,8 +1,9 @@
 use crate::code_graph::{CodeNode, NodeType};
-use crate::indexing::extractor::{LanguageExtractor, common};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
 use crate::parsers::treesitter::queries::javascript as queries;
 use log::warn;
-use std::collections::HashMap;
 use std::path::Path;
 use tree_sitter::Node;
 
@@ -131,6 +132,28 @@ impl JavaScriptExtractor {
 }
 
 impl LanguageExtractor for JavaScriptExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Function,
+                NodeType::Method,
+                NodeType::Class,
+                NodeType::Variable,
+                NodeType::Field,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+                Feature::Visibility,
+                Feature::Fields,
+                Feature::Tests,
+                Feature::Decorators,
+            ],
+        )
+    }
+
     fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
         let mut code_units = Vec::new();
 
@@ -167,12 +190,6 @@ impl LanguageExtractor for JavaScriptExtractor {
                     let end_line = node.end_position().row + 1;
                     let node_content = common::get_node_text(node, content);
 
-                    let mut metadata = HashMap::new();
-
-                    if let Some(parent_class) = self.find_parent_class(node, content) {
-                        metadata.insert("parent_class".to_string(), parent_class);
-                    }
-
                     let mut code_node = common::create_node(
                         NodeType::Method,
                         name,
@@ -180,10 +197,7 @@ impl LanguageExtractor for JavaScriptExtractor {
                         (start_line, end_line),
                         node_content,
                     );
-
-                    for (key, value) in metadata {
-                        code_node.add_metadata(key, value);
-                    }
+                    code_node.parent_class = self.find_parent_class(node, content);
 
                     code_units.push(code_node);
                 }
@@ -210,6 +224,30 @@ impl LanguageExtractor for JavaScriptExtractor {
                     code_units.push(code_node);
                 }
             }
+
+            // Extract fields and properties
+            code_units.extend(common::extract_fields(
+                queries::FIELD_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
+
+            // Extract module-level constants and globals
+            code_units.extend(common::extract_global_variables(
+                queries::GLOBAL_VARIABLE_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
+
+            // Test blocks have no declaration of their own
+            code_units.extend(common::extract_test_blocks(
+                queries::TEST_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
         } else {
             warn!("Failed to parse JavaScript file: {:?}", file_path);
         }
@@ -222,7 +260,7 @@ impl LanguageExtractor for JavaScriptExtractor {
         content: &str,
         func_range: (usize, usize),
         _func_name: &str,
-    ) -> Vec<String> {
+    ) -> Vec<CallSite> {
         let mut calls = Vec::new();
 
         if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.js")) {
@@ -234,9 +272,9 @@ impl LanguageExtractor for JavaScriptExtractor {
 
                 // Check if call is within function range
                 if call_line >= func_range.0 && call_line <= func_range.1 {
-                    let call_name = common::get_node_text(node, content);
-                    if !call_name.is_empty() {
-                        calls.push(call_name);
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
                     }
                 }
             }
@@ -245,35 +283,8 @@ impl LanguageExtractor for JavaScriptExtractor {
         calls
     }
 
-    fn extract_variable_references(
-        &self,
-        content: &str,
-        func_range: (usize, usize),
-        var_name: &str,
-    ) -> Vec<(usize, usize)> {
-        let mut references = Vec::new();
-
-        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.js")) {
-            let reference_nodes = common::execute_query(
-                queries::REFERENCE_QUERY,
-                &tree,
-                content.as_bytes(),
-                "reference",
-            );
-
-            for node in reference_nodes {
-                let ref_line = node.start_position().row + 1;
-
-                // Check if reference is within function range
-                if ref_line >= func_range.0 && ref_line <= func_range.1 {
-                    let ref_name = common::get_node_text(node, content);
-                    if ref_name == var_name {
-                        references.push((ref_line, node.end_position().row + 1));
-                    }
-                }
-            }
-        }
-        references
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.js"))
     }
 
     fn extract_imported_modules(&self, content: &str) -> Vec<String> {
@@ -0,0 +1,398 @@
+use crate::code_graph::{CodeNode, NodeType};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
+use crate::parsers::treesitter::queries::julia as queries;
+use log::warn;
+use std::path::Path;
+use tree_sitter::{Node, Query, QueryCursor, StreamingIterator, Tree};
+
+pub struct JuliaExtractor;
+
+impl JuliaExtractor {
+    pub fn new() -> Self {
+        JuliaExtractor
+    }
+
+    fn named_children<'a>(&self, node: Node<'a>) -> Vec<Node<'a>> {
+        (0..node.named_child_count())
+            .filter_map(|i| node.named_child(i))
+            .collect()
+    }
+
+    // Rightmost name of `Base.show` or `A.B.C`
+    fn last_identifier(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "identifier" => Some(common::get_node_text(node, source)),
+            "operator" => Some(common::get_node_text(node, source)),
+            _ => self
+                .named_children(node)
+                .into_iter()
+                .rev()
+                .find_map(|child| self.last_identifier(child, source)),
+        }
+    }
+
+    // The call inside a signature: `f(x)`, `f(x)::T` or `f(x) where T`
+    fn signature_call<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        if node.kind() == "call_expression" {
+            return Some(node);
+        }
+        self.named_children(node)
+            .into_iter()
+            .find_map(|child| self.signature_call(child))
+    }
+
+    // Name of the function a signature defines, and the module it extends
+    // for definitions such as `function Base.show(io, x)`
+    fn signature_name(&self, signature: Node, source: &str) -> Option<(String, Option<String>)> {
+        let call = self.signature_call(signature)?;
+        let callee = call.named_child(0)?;
+        match callee.kind() {
+            "identifier" | "operator" => Some((common::get_node_text(callee, source), None)),
+            "field_expression" => {
+                let name = self.last_identifier(callee, source)?;
+                let owner = callee
+                    .child_by_field_name("value")
+                    .map(|value| common::get_node_text(value, source));
+                Some((name, owner))
+            }
+            _ => None,
+        }
+    }
+
+    // Name and supertype of `struct Point{T} <: Shape`
+    fn type_head(&self, head: Node, source: &str) -> Option<(String, Option<String>)> {
+        let name_of = |node: Node| match node.kind() {
+            "parametrized_type_expression" => node
+                .named_child(0)
+                .map(|name| common::get_node_text(name, source)),
+            "identifier" => Some(common::get_node_text(node, source)),
+            _ => None,
+        };
+
+        let inner = head.named_child(0)?;
+        if inner.kind() == "binary_expression" {
+            let children = self.named_children(inner);
+            let name = name_of(*children.first()?)?;
+            let supertype = children.last().and_then(|node| name_of(*node));
+            return Some((name, supertype));
+        }
+        Some((name_of(inner)?, None))
+    }
+
+    fn find_parent_module(&self, node: Node, source: &str) -> Option<String> {
+        let mut parent_iter = node.parent();
+
+        while let Some(parent) = parent_iter {
+            if parent.kind() == "module_definition"
+                && let Some(name) = parent.child_by_field_name("name")
+            {
+                return Some(common::get_node_text(name, source));
+            }
+            parent_iter = parent.parent();
+        }
+
+        None
+    }
+
+    // A call that is the head of a definition rather than a call site
+    fn is_definition_head(&self, node: Node) -> bool {
+        let mut current = node;
+        let mut parent_iter = node.parent();
+
+        while let Some(parent) = parent_iter {
+            match parent.kind() {
+                "signature" => return true,
+                "typed_expression" | "where_expression" | "call_expression" => {}
+                "assignment" => return parent.named_child(0) == Some(current),
+                _ => return false,
+            }
+            // Only the callee of a call can be the defined name
+            if parent.kind() == "call_expression" && parent.named_child(0) != Some(current) {
+                return false;
+            }
+            current = parent;
+            parent_iter = parent.parent();
+        }
+
+        false
+    }
+
+    fn extract_functions(
+        &self,
+        query_str: &str,
+        tree: &Tree,
+        content: &str,
+        file_path: &Path,
+        code_units: &mut Vec<CodeNode>,
+    ) {
+        let Ok(query) = Query::new(&tree.language(), query_str) else {
+            warn!("Failed to create query: {}", query_str);
+            return;
+        };
+        let (Some(node_idx), Some(signature_idx)) = (
+            query.capture_index_for_name("node"),
+            query.capture_index_for_name("signature"),
+        ) else {
+            return;
+        };
+
+        let mut query_cursor = QueryCursor::new();
+        let mut matches = query_cursor.matches(&query, tree.root_node(), content.as_bytes());
+
+        while let Some(match_result) = matches.next() {
+            let capture = |index| {
+                match_result
+                    .captures
+                    .iter()
+                    .find(|c| c.index == index)
+                    .map(|c| c.node)
+            };
+            let (Some(node), Some(signature)) = (capture(node_idx), capture(signature_idx)) else {
+                continue;
+            };
+            let Some((name, owner)) = self.signature_name(signature, content) else {
+                continue;
+            };
+
+            let mut code_node = common::create_node(
+                NodeType::Function,
+                name,
+                file_path.to_str().unwrap_or(""),
+                (node.start_position().row + 1, node.end_position().row + 1),
+                common::get_node_text(node, content),
+            );
+            if node.kind() == "macro_definition" {
+                code_node.add_metadata("macro".to_string(), "true".to_string());
+            }
+            // `function Base.show(...)` adds a method to another module's function
+            if let Some(owner) = owner {
+                code_node.add_metadata("extends_module".to_string(), owner);
+            }
+            if let Some(parent_module) = self.find_parent_module(node, content) {
+                code_node.add_metadata("parent_module".to_string(), parent_module);
+            }
+
+            code_units.push(code_node);
+        }
+    }
+
+    fn extract_types(
+        &self,
+        query_str: &str,
+        node_type: NodeType,
+        tree: &Tree,
+        content: &str,
+        file_path: &Path,
+        code_units: &mut Vec<CodeNode>,
+    ) {
+        let type_nodes = common::execute_query(query_str, tree, content.as_bytes(), "node");
+
+        for node in type_nodes {
+            let Some(head) = self
+                .named_children(node)
+                .into_iter()
+                .find(|child| child.kind() == "type_head")
+            else {
+                continue;
+            };
+            let Some((name, supertype)) = self.type_head(head, content) else {
+                continue;
+            };
+
+            let mut code_node = common::create_node(
+                node_type.clone(),
+                name,
+                file_path.to_str().unwrap_or(""),
+                (node.start_position().row + 1, node.end_position().row + 1),
+                common::get_node_text(node, content),
+            );
+            if let Some(supertype) = supertype {
+                code_node.add_metadata("superclass".to_string(), supertype);
+            }
+            if common::get_node_text(node, content).starts_with("mutable") {
+                code_node.add_metadata("mutable".to_string(), "true".to_string());
+            }
+            if let Some(parent_module) = self.find_parent_module(node, content) {
+                code_node.add_metadata("parent_module".to_string(), parent_module);
+            }
+
+            code_units.push(code_node);
+        }
+    }
+
+    // Module loaded by one item of a `using` or `import` statement
+    fn imported_module(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "identifier" | "scoped_identifier" | "import_path" => {
+                self.last_identifier(node, source)
+            }
+            // `import Base: show` and `import A as B` load `Base` and `A`
+            "selected_import" | "import_alias" => node
+                .named_child(0)
+                .and_then(|child| self.imported_module(child, source)),
+            _ => None,
+        }
+    }
+}
+
+impl LanguageExtractor for JuliaExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Function,
+                NodeType::Class,
+                NodeType::Interface,
+                NodeType::Module,
+                NodeType::Variable,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+            ],
+        )
+    }
+
+    fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, file_path) {
+            // Extract functions, short-form functions and macros
+            for query_str in [
+                queries::FUNCTION_QUERY,
+                queries::SHORT_FUNCTION_QUERY,
+                queries::MACRO_QUERY,
+            ] {
+                self.extract_functions(query_str, &tree, content, file_path, &mut code_units);
+            }
+
+            // Extract structs and abstract types
+            self.extract_types(
+                queries::STRUCT_QUERY,
+                NodeType::Class,
+                &tree,
+                content,
+                file_path,
+                &mut code_units,
+            );
+            self.extract_types(
+                queries::ABSTRACT_TYPE_QUERY,
+                NodeType::Interface,
+                &tree,
+                content,
+                file_path,
+                &mut code_units,
+            );
+
+            // Extract modules
+            let module_nodes =
+                common::execute_query(queries::MODULE_QUERY, &tree, content.as_bytes(), "node");
+
+            for node in module_nodes {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    let mut code_node = common::create_node(
+                        NodeType::Module,
+                        common::get_node_text(name_node, content),
+                        file_path.to_str().unwrap_or(""),
+                        (node.start_position().row + 1, node.end_position().row + 1),
+                        common::get_node_text(node, content),
+                    );
+                    if let Some(parent_module) = self.find_parent_module(node, content) {
+                        code_node.add_metadata("parent_module".to_string(), parent_module);
+                    }
+                    code_units.push(code_node);
+                }
+            }
+
+            // Extract module-level constants and globals
+            code_units.extend(common::extract_global_variables(
+                queries::GLOBAL_VARIABLE_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
+        } else {
+            warn!("Failed to parse Julia file: {:?}", file_path);
+        }
+
+        code_units
+    }
+
+    fn extract_function_calls(
+        &self,
+        content: &str,
+        func_range: (usize, usize),
+        _func_name: &str,
+    ) -> Vec<CallSite> {
+        let mut calls = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.jl")) {
+            let call_nodes =
+                common::execute_query(queries::CALL_QUERY, &tree, content.as_bytes(), "func_name");
+
+            for node in call_nodes {
+                let call_line = node.start_position().row + 1;
+
+                // Check if call is within function range
+                if call_line >= func_range.0
+                    && call_line <= func_range.1
+                    && !self.is_definition_head(node)
+                {
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
+                    }
+                }
+            }
+        }
+
+        calls
+    }
+
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.jl"))
+    }
+
+    fn extract_imported_modules(&self, content: &str) -> Vec<String> {
+        let mut modules: Vec<String> = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.jl")) {
+            let statements =
+                common::execute_query(queries::IMPORT_QUERY, &tree, content.as_bytes(), "import");
+
+            for statement in statements {
+                for item in self.named_children(statement) {
+                    if let Some(module) = self.imported_module(item, content)
+                        && !modules.contains(&module)
+                    {
+                        modules.push(module);
+                    }
+                }
+            }
+
+            // `include("geometry.jl")` pulls in another file
+            let included = common::execute_query(
+                queries::IMPORT_QUERY,
+                &tree,
+                content.as_bytes(),
+                "import_path",
+            );
+
+            for path_node in included {
+                let path = common::get_node_text(path_node, content);
+                if let Some(stem) = Path::new(path.trim_matches('"'))
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    && !modules.iter().any(|module| module == stem)
+                {
+                    modules.push(stem.to_string());
+                }
+            }
+        }
+
+        modules
+    }
+}
@@ -0,0 +1,155 @@
+use crate::code_graph::{CodeNode, NodeType};
+use crate::indexing::extractor::{CallSite, LanguageCapabilities, LanguageExtractor, common};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+// Markdown documents become one Documentation node for the file and one per
+// fenced code block. Identifiers in inline code and code blocks are kept as
+// `mentions` metadata and resolved to symbols once the graph is built.
+
+// Shorter identifiers are too ambiguous to link
+const MIN_MENTION_LEN: usize = 3;
+
+fn identifiers(text: &str, mentions: &mut BTreeSet<String>) {
+    for word in text.split(|c: char| !c.is_alphanumeric() && c != '_') {
+        if word.len() >= MIN_MENTION_LEN
+            && word.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        {
+            mentions.insert(word.to_string());
+        }
+    }
+}
+
+// Contents of `inline code` spans on a line of prose
+fn inline_code(line: &str) -> impl Iterator<Item = &str> {
+    line.split('`').skip(1).step_by(2)
+}
+
+// The fence that opens a code block: its marker and the info string
+fn fence(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    ["```", "~~~"].into_iter().find_map(|marker| {
+        trimmed
+            .strip_prefix(marker)
+            .map(|info| (marker, info.trim_start_matches(['`', '~']).trim()))
+    })
+}
+
+fn heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    (text.len() < line.len() && text.starts_with(' '))
+        .then(|| text.trim().trim_end_matches('#').trim())
+}
+
+pub struct MarkdownExtractor;
+
+impl MarkdownExtractor {
+    pub fn new() -> Self {
+        MarkdownExtractor
+    }
+}
+
+impl LanguageExtractor for MarkdownExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(&[NodeType::Documentation], &[])
+    }
+
+    fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+        let file_path_str = file_path.to_str().unwrap_or("");
+        let document_name = file_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        let mut prose_mentions = BTreeSet::new();
+        let mut current_heading: Option<&str> = None;
+        // (fence marker, info string, first line) of the open code block
+        let mut open_block: Option<(&str, &str, usize)> = None;
+
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+
+            if let Some((marker, language, start_line)) = open_block {
+                if !line.trim_start().starts_with(marker) {
+                    continue;
+                }
+                open_block = None;
+
+                let body = common::line_range_text(content, start_line + 1, line_number - 1);
+                let mut mentions = BTreeSet::new();
+                identifiers(&body, &mut mentions);
+
+                let name = current_heading.unwrap_or(&document_name).to_string();
+                let mut example = common::create_node(
+                    NodeType::Documentation,
+                    name,
+                    file_path_str,
+                    (start_line, line_number),
+                    body,
+                )
+                .with_metadata("doc_kind".to_string(), "example".to_string());
+                if !language.is_empty() {
+                    example.add_metadata("example_language".to_string(), language.to_string());
+                }
+                if !mentions.is_empty() {
+                    example.add_metadata(
+                        "mentions".to_string(),
+                        mentions.into_iter().collect::<Vec<_>>().join(","),
+                    );
+                }
+                code_units.push(example);
+                continue;
+            }
+
+            if let Some((marker, info)) = fence(line) {
+                let language = info.split_whitespace().next().unwrap_or("");
+                open_block = Some((marker, language, line_number));
+            } else if let Some(text) = heading(line) {
+                current_heading = Some(text);
+                inline_code(text).for_each(|code| identifiers(code, &mut prose_mentions));
+            } else {
+                inline_code(line).for_each(|code| identifiers(code, &mut prose_mentions));
+            }
+        }
+
+        let mut document = common::create_node(
+            NodeType::Documentation,
+            document_name,
+            file_path_str,
+            (1, content.lines().count().max(1)),
+            content.to_string(),
+        )
+        .with_metadata("doc_kind".to_string(), "file".to_string());
+        if !prose_mentions.is_empty() {
+            document.add_metadata(
+                "mentions".to_string(),
+                prose_mentions.into_iter().collect::<Vec<_>>().join(","),
+            );
+        }
+        code_units.insert(0, document);
+
+        code_units
+    }
+
+    fn extract_function_calls(
+        &self,
+        _content: &str,
+        _func_range: (usize, usize),
+        _func_name: &str,
+    ) -> Vec<CallSite> {
+        Vec::new()
+    }
+
+    fn extract_references(&self, _content: &str) -> Vec<(String, usize)> {
+        Vec::new()
+    }
+
+    fn extract_imported_modules(&self, _content: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
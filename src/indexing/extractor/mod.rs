@@ -1,34 +1,70 @@
-mod common;
+mod bash;
+mod capabilities;
+pub(crate) mod common;
+mod complexity;
 mod cpp;
+mod decorators;
+mod erlang;
 mod go;
+mod graphql;
+mod groovy;
 mod java;
 mod javascript;
+mod julia;
+mod markdown;
+mod ocaml;
+mod perl;
+mod proto;
 mod python;
+mod r;
 mod ruby;
 mod rust;
+mod signature;
 mod typescript;
 
-use crate::code_graph::CodeNode;
+use crate::code_graph::{CodeNode, NodeType};
+pub use capabilities::{
+    Feature, LanguageCapabilities, capabilities_for_language, language_capabilities,
+};
+pub use cpp::{CInclude, c_includes};
+pub use decorators::decorator_name;
+pub use java::{JavaImport, JavaSource, java_source};
 use log::{debug, error, trace, warn};
+pub use python::{PythonImport, python_imports};
+pub use rust::{
+    RustModule, RustUse, rust_macro_invocations, rust_module_declarations, rust_use_paths,
+};
 use std::fs::File;
 use std::io::{self, Read};
 use std::path::Path;
+pub use typescript::{
+    EsImport, EsModule, ResolverMapEntry, es_module_statements, resolver_map_entries,
+};
+
+// A single call expression found inside a function body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSite {
+    pub name: String,
+    pub line: usize,
+    pub column: usize,
+    pub expression: String,
+    pub conditional: bool,
+    // Number of arguments passed, when known
+    pub arguments: Option<usize>,
+}
 
 // Trait defining common functionality for language-specific extractors
 pub trait LanguageExtractor {
+    fn capabilities(&self) -> LanguageCapabilities;
     fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode>;
     fn extract_function_calls(
         &self,
         content: &str,
         func_range: (usize, usize),
         func_name: &str,
-    ) -> Vec<String>;
-    fn extract_variable_references(
-        &self,
-        content: &str,
-        func_range: (usize, usize),
-        var_name: &str,
-    ) -> Vec<(usize, usize)>;
+    ) -> Vec<CallSite>;
+    // Every identifier the file references, with its line, from one parse
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)>;
     fn extract_imported_modules(&self, content: &str) -> Vec<String>;
 }
 
@@ -43,6 +79,16 @@ pub fn get_extractor_for_language(language: &str) -> Option<Box<dyn LanguageExtr
         "cpp" | "c" => Some(Box::new(cpp::CppExtractor::new())),
         "go" => Some(Box::new(go::GoExtractor::new())),
         "ruby" => Some(Box::new(ruby::RubyExtractor::new())),
+        "bash" => Some(Box::new(bash::BashExtractor::new())),
+        "ocaml" => Some(Box::new(ocaml::OCamlExtractor::new())),
+        "r" => Some(Box::new(r::RExtractor::new())),
+        "perl" => Some(Box::new(perl::PerlExtractor::new())),
+        "julia" => Some(Box::new(julia::JuliaExtractor::new())),
+        "erlang" => Some(Box::new(erlang::ErlangExtractor::new())),
+        "groovy" => Some(Box::new(groovy::GroovyExtractor::new())),
+        "graphql" => Some(Box::new(graphql::GraphQLExtractor::new())),
+        "proto" => Some(Box::new(proto::ProtoExtractor::new())),
+        "markdown" => Some(Box::new(markdown::MarkdownExtractor::new())),
         _ => None,
     }
 }
@@ -65,19 +111,91 @@ pub fn extract_code_units(file_path: &Path) -> io::Result<Vec<CodeNode>> {
         return Err(e);
     }
 
+    Ok(extract_code_units_from_content(file_path, &content))
+}
+
+// Extract code units from already loaded file content
+pub fn extract_code_units_from_content(file_path: &Path, content: &str) -> Vec<CodeNode> {
     // Detect language from file extension
-    if let Some(language) = crate::parsers::detect_language(file_path) {
-        if let Some(extractor) = get_extractor_for_language(&language) {
-            let code_units = extractor.extract_code_units(&content, file_path);
-            debug!(
-                "Extracted {} code units from {:?}",
-                code_units.len(),
-                file_path
-            );
-            return Ok(code_units);
+    let language = crate::parsers::detect_language(file_path);
+    let extractor = language.as_deref().and_then(get_extractor_for_language);
+
+    if let (Some(language), Some(extractor)) = (language, extractor) {
+        let mut code_units = extractor.extract_code_units(content, file_path);
+        for unit in code_units.iter_mut() {
+            if let Some(visibility) = common::infer_visibility(&language, unit, content) {
+                unit.visibility = Some(visibility);
+            }
+            if let Some(framework) = common::detect_test_framework(&language, unit, content) {
+                unit.add_metadata("test".to_string(), "true".to_string());
+                unit.add_metadata("test_framework".to_string(), framework.to_string());
+            }
         }
+        let has_declarations = code_units.iter().any(|unit| {
+            matches!(
+                unit.node_type,
+                NodeType::Function | NodeType::Method | NodeType::Class | NodeType::Interface
+            )
+        });
+        if has_declarations
+            && let Some((tree, _)) = common::parse_with_tree_sitter(content, file_path)
+        {
+            complexity::annotate_complexity(&tree, &mut code_units);
+            signature::annotate_signatures(&tree, content, &mut code_units);
+            decorators::annotate_decorators(&tree, content, &mut code_units);
+            annotate_closures(&tree, &mut code_units);
+            code_units.extend(common::extract_local_variables(&tree, content, file_path));
+        }
+        debug!(
+            "Extracted {} code units from {:?}",
+            code_units.len(),
+            file_path
+        );
+        return code_units;
     }
 
     warn!("Unsupported language for file: {:?}", file_path);
-    Ok(Vec::new())
+    Vec::new()
+}
+
+// Anonymous function kinds, across all supported grammars
+const ANONYMOUS_FUNCTION_KINDS: &[&str] = &[
+    "arrow_function",
+    "function_expression",
+    "function",
+    "lambda",
+    "closure_expression",
+    "fun_expression",
+    "anonymous_fun",
+];
+
+// Parents that bind an anonymous function to a name, `const f = () => 1`
+const BINDING_KINDS: &[&str] = &[
+    "variable_declarator",
+    "assignment",
+    "assignment_expression",
+    "pair",
+    "public_field_definition",
+    "field_definition",
+    "let_declaration",
+];
+
+// Mark anonymous functions that are not bound to a name, the callbacks and
+// lambdas passed as values, with `closure`
+fn annotate_closures(tree: &tree_sitter::Tree, units: &mut [CodeNode]) {
+    let mut functions = Vec::new();
+    complexity::collect_functions(tree.root_node(), &mut functions);
+
+    for unit in units.iter_mut() {
+        let Some(function) = complexity::function_of(&functions, unit) else {
+            continue;
+        };
+        if ANONYMOUS_FUNCTION_KINDS.contains(&function.kind())
+            && function
+                .parent()
+                .is_none_or(|parent| !BINDING_KINDS.contains(&parent.kind()))
+        {
+            unit.add_metadata("closure".to_string(), "true".to_string());
+        }
+    }
 }
@@ -0,0 +1,350 @@
+use crate::code_graph::{CodeNode, NodeType};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
+use crate::parsers::treesitter::queries::ocaml as queries;
+use log::warn;
+use std::path::Path;
+use tree_sitter::{Node, Query, QueryCursor, StreamingIterator};
+
+pub struct OCamlExtractor;
+
+impl OCamlExtractor {
+    pub fn new() -> Self {
+        OCamlExtractor
+    }
+
+    fn named_child_of_kind<'a>(&self, node: Node<'a>, kind: &str) -> Option<Node<'a>> {
+        (0..node.named_child_count())
+            .filter_map(|i| node.named_child(i))
+            .find(|child| child.kind() == kind)
+    }
+
+    // Name of the innermost `module M = ...` the node is defined in
+    fn find_parent_module(&self, node: Node, source: &str) -> Option<String> {
+        let mut parent_iter = node.parent();
+
+        while let Some(parent) = parent_iter {
+            if parent.kind() == "module_binding"
+                && let Some(name) = self.named_child_of_kind(parent, "module_name")
+            {
+                return Some(common::get_node_text(name, source));
+            }
+            parent_iter = parent.parent();
+        }
+
+        None
+    }
+
+    // `let ... in` bindings are local to an expression, not declarations
+    fn is_local_binding(&self, node: Node) -> bool {
+        let mut parent_iter = node.parent();
+
+        while let Some(parent) = parent_iter {
+            match parent.kind() {
+                "let_expression" | "class_definition" | "object_expression" => return true,
+                "structure" | "compilation_unit" => return false,
+                _ => {}
+            }
+            parent_iter = parent.parent();
+        }
+
+        false
+    }
+
+    // `let f x = ...`, `let f = fun x -> ...` and `let f = function ...`
+    fn is_function_binding(&self, node: Node) -> bool {
+        let has_parameters = (0..node.named_child_count())
+            .filter_map(|i| node.named_child(i))
+            .any(|child| matches!(child.kind(), "parameter" | "abstract_type"));
+
+        has_parameters
+            || node
+                .child_by_field_name("body")
+                .is_some_and(|body| matches!(body.kind(), "fun_expression" | "function_expression"))
+    }
+
+    fn extract_let_bindings(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        file_path: &Path,
+    ) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+
+        let Ok(query) = Query::new(&tree.language(), queries::LET_BINDING_QUERY) else {
+            warn!("Failed to create query: {}", queries::LET_BINDING_QUERY);
+            return code_units;
+        };
+        let (Some(node_idx), Some(name_idx)) = (
+            query.capture_index_for_name("node"),
+            query.capture_index_for_name("name"),
+        ) else {
+            return code_units;
+        };
+
+        let mut query_cursor = QueryCursor::new();
+        let mut matches = query_cursor.matches(&query, tree.root_node(), content.as_bytes());
+
+        while let Some(match_result) = matches.next() {
+            let capture = |index| {
+                match_result
+                    .captures
+                    .iter()
+                    .find(|c| c.index == index)
+                    .map(|c| c.node)
+            };
+            let (Some(node), Some(name_node)) = (capture(node_idx), capture(name_idx)) else {
+                continue;
+            };
+            if self.is_local_binding(node) {
+                continue;
+            }
+
+            let name = common::get_node_text(name_node, content);
+            // `let _ = ...` and `let () = ...` run code without declaring anything
+            if name.is_empty() || name.starts_with('_') {
+                continue;
+            }
+
+            let is_function = self.is_function_binding(node);
+            let node_type = if is_function {
+                NodeType::Function
+            } else {
+                NodeType::Variable
+            };
+            let mut code_node = common::create_node(
+                node_type,
+                name,
+                file_path.to_str().unwrap_or(""),
+                (node.start_position().row + 1, node.end_position().row + 1),
+                common::get_node_text(node, content),
+            );
+            if !is_function {
+                code_node.scope = Some("module".to_string());
+            }
+            if let Some(parent_module) = self.find_parent_module(node, content) {
+                code_node.add_metadata("parent_module".to_string(), parent_module);
+            }
+
+            code_units.push(code_node);
+        }
+
+        code_units
+    }
+
+    fn extract_modules(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        file_path: &Path,
+    ) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+        let module_nodes =
+            common::execute_query(queries::MODULE_QUERY, tree, content.as_bytes(), "node");
+
+        for node in module_nodes {
+            let Some(name_node) = self.named_child_of_kind(node, "module_name") else {
+                continue;
+            };
+
+            let mut code_node = common::create_node(
+                NodeType::Module,
+                common::get_node_text(name_node, content),
+                file_path.to_str().unwrap_or(""),
+                (node.start_position().row + 1, node.end_position().row + 1),
+                common::get_node_text(node, content),
+            );
+
+            // `module F (X : S) = ...` and `module F = functor (X : S) -> ...`
+            let body = node.child_by_field_name("body");
+            let parameter_holder = match body {
+                Some(body) if body.kind() == "functor" => body,
+                _ => node,
+            };
+            let parameters: Vec<String> = (0..parameter_holder.named_child_count())
+                .filter_map(|i| parameter_holder.named_child(i))
+                .filter(|child| child.kind() == "module_parameter")
+                .filter_map(|parameter| self.named_child_of_kind(parameter, "module_name"))
+                .map(|name| common::get_node_text(name, content))
+                .collect();
+
+            if parameters.is_empty() {
+                code_node.add_metadata("ocaml_kind".to_string(), "module".to_string());
+            } else {
+                code_node.add_metadata("ocaml_kind".to_string(), "functor".to_string());
+                code_node.add_metadata("functor_parameters".to_string(), parameters.join(","));
+            }
+
+            // `module M = Make (X)` instantiates a functor
+            if let Some(functor) = body
+                .filter(|body| body.kind() == "module_application")
+                .and_then(|body| body.child_by_field_name("functor"))
+            {
+                code_node.add_metadata(
+                    "functor_application".to_string(),
+                    common::get_node_text(functor, content),
+                );
+            }
+            if let Some(parent_module) = self.find_parent_module(node, content) {
+                code_node.add_metadata("parent_module".to_string(), parent_module);
+            }
+
+            code_units.push(code_node);
+        }
+
+        code_units
+    }
+}
+
+impl LanguageExtractor for OCamlExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Function,
+                NodeType::Module,
+                NodeType::Interface,
+                NodeType::TypeDefinition,
+                NodeType::Variable,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+            ],
+        )
+    }
+
+    fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, file_path) {
+            // Every .ml file is a module named after it: `list.ml` is `List`
+            let file_stem = file_path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("");
+            let mut chars = file_stem.chars();
+            if let Some(first) = chars.next() {
+                code_units.push(
+                    common::create_node(
+                        NodeType::Module,
+                        first.to_uppercase().chain(chars).collect(),
+                        file_path.to_str().unwrap_or(""),
+                        (1, content.lines().count().max(1)),
+                        content.to_string(),
+                    )
+                    .with_metadata("ocaml_kind".to_string(), "compilation_unit".to_string()),
+                );
+            }
+
+            // Extract let-bound functions and values
+            code_units.extend(self.extract_let_bindings(&tree, content, file_path));
+
+            // Extract modules and functors
+            code_units.extend(self.extract_modules(&tree, content, file_path));
+
+            // Extract module types
+            let module_type_nodes = common::execute_query(
+                queries::MODULE_TYPE_QUERY,
+                &tree,
+                content.as_bytes(),
+                "node",
+            );
+
+            for node in module_type_nodes {
+                if let Some(name_node) = self.named_child_of_kind(node, "module_type_name") {
+                    code_units.push(
+                        common::create_node(
+                            NodeType::Interface,
+                            common::get_node_text(name_node, content),
+                            file_path.to_str().unwrap_or(""),
+                            (node.start_position().row + 1, node.end_position().row + 1),
+                            common::get_node_text(node, content),
+                        )
+                        .with_metadata("ocaml_kind".to_string(), "module_type".to_string()),
+                    );
+                }
+            }
+
+            // Extract type definitions
+            let type_nodes =
+                common::execute_query(queries::TYPE_QUERY, &tree, content.as_bytes(), "node");
+
+            for node in type_nodes {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    code_units.push(common::create_node(
+                        NodeType::TypeDefinition,
+                        common::get_node_text(name_node, content),
+                        file_path.to_str().unwrap_or(""),
+                        (node.start_position().row + 1, node.end_position().row + 1),
+                        common::get_node_text(node, content),
+                    ));
+                }
+            }
+        } else {
+            warn!("Failed to parse OCaml file: {:?}", file_path);
+        }
+
+        code_units
+    }
+
+    fn extract_function_calls(
+        &self,
+        content: &str,
+        func_range: (usize, usize),
+        _func_name: &str,
+    ) -> Vec<CallSite> {
+        let mut calls = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.ml")) {
+            let call_nodes =
+                common::execute_query(queries::CALL_QUERY, &tree, content.as_bytes(), "func_name");
+
+            for node in call_nodes {
+                let call_line = node.start_position().row + 1;
+
+                // Check if call is within function range
+                if call_line >= func_range.0 && call_line <= func_range.1 {
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
+                    }
+                }
+            }
+        }
+
+        calls
+    }
+
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.ml"))
+    }
+
+    fn extract_imported_modules(&self, content: &str) -> Vec<String> {
+        let mut modules: Vec<String> = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.ml")) {
+            let path_nodes = common::execute_query(
+                queries::IMPORT_QUERY,
+                &tree,
+                content.as_bytes(),
+                "import_path",
+            );
+
+            // `Core.List.map` depends on the top-level module `Core`
+            for node in path_nodes {
+                let path = common::get_node_text(node, content);
+                let Some(root) = path.split('.').next().map(str::trim) else {
+                    continue;
+                };
+                if !root.is_empty() && !modules.iter().any(|module| module == root) {
+                    modules.push(root.to_string());
+                }
+            }
+        }
+
+        modules
+    }
+}
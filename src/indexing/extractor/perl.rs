@@ -0,0 +1,546 @@
+use crate::code_graph::{CodeNode, NodeType};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
+use std::path::Path;
+
+// There is no tree-sitter grammar for Perl among our dependencies, so Perl
+// is read with a small scanner over the source with comments, POD and
+// string contents blanked out.
+
+// `use` targets that change how the compiler behaves rather than load code
+const PRAGMAS: &[&str] = &[
+    "strict",
+    "warnings",
+    "utf8",
+    "lib",
+    "constant",
+    "vars",
+    "feature",
+    "integer",
+    "overload",
+    "bytes",
+    "open",
+    "version",
+    "diagnostics",
+    "subs",
+    "fields",
+    "mro",
+    "if",
+    "experimental",
+];
+
+// `use parent` and `use base` load the superclasses named after them
+const INHERITANCE_PRAGMAS: &[&str] = &["parent", "base"];
+
+// Words followed by a parenthesis that are not calls
+const KEYWORDS: &[&str] = &[
+    "if", "elsif", "unless", "while", "until", "for", "foreach", "my", "our", "local", "return",
+    "and", "or", "not", "sub", "qw", "q", "qq", "package", "use", "require", "no", "else", "do",
+    "eval",
+];
+
+// Keywords whose block, or whose statement when used as a suffix, may not run
+const CONDITIONAL_KEYWORDS: &[&str] = &[
+    "if", "elsif", "else", "unless", "while", "until", "for", "foreach",
+];
+
+// Replace comments, POD and the contents of string literals with spaces,
+// keeping every character on its original line and column
+fn blank(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut in_pod = false;
+    let mut quote: Option<char> = None;
+    let blank_line = |line: &str| -> String {
+        line.chars()
+            .map(|c| if c == '\n' { '\n' } else { ' ' })
+            .collect()
+    };
+
+    for line in content.split_inclusive('\n') {
+        if quote.is_none() {
+            if in_pod {
+                in_pod = !line.starts_with("=cut");
+                out.push_str(&blank_line(line));
+                continue;
+            }
+            if line.starts_with('=') && line[1..].starts_with(|c: char| c.is_alphabetic()) {
+                in_pod = true;
+                out.push_str(&blank_line(line));
+                continue;
+            }
+            if line.starts_with("__END__") || line.starts_with("__DATA__") {
+                break;
+            }
+        }
+
+        let mut previous = ' ';
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            if let Some(q) = quote {
+                if c == q && previous != '\\' {
+                    quote = None;
+                    out.push(c);
+                } else {
+                    out.push(if c == '\n' { '\n' } else { ' ' });
+                }
+                // An escaped backslash does not escape what follows it
+                previous = if previous == '\\' && c == '\\' {
+                    ' '
+                } else {
+                    c
+                };
+                continue;
+            }
+            match c {
+                // `$#array` is the last index of an array, not a comment
+                '#' if previous != '$' => {
+                    out.push(' ');
+                    for c in chars.by_ref() {
+                        out.push(if c == '\n' { '\n' } else { ' ' });
+                    }
+                }
+                '"' | '\'' | '`' => {
+                    quote = Some(c);
+                    out.push(c);
+                }
+                _ => out.push(c),
+            }
+            previous = c;
+        }
+    }
+
+    out
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    // Identifiers, including package-qualified names such as `Foo::bar`
+    Word(String),
+    Punct(char),
+}
+
+// A token and the 1-based line and column it starts on
+type Spanned = (Token, usize, usize);
+
+fn tokenize(content: &str) -> Vec<Spanned> {
+    let mut tokens = Vec::new();
+
+    for (line_index, line) in blank(content).lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c.is_alphabetic() || c == '_' {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric()
+                        || chars[i] == '_'
+                        || (chars[i] == ':' && chars.get(i + 1) == Some(&':')))
+                {
+                    i += if chars[i] == ':' { 2 } else { 1 };
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push((Token::Word(word), line_index + 1, start + 1));
+                continue;
+            }
+            if c.is_ascii_digit() {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                continue;
+            }
+            if "{}();,-><&$@%=".contains(c) {
+                tokens.push((Token::Punct(c), line_index + 1, i + 1));
+            }
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+fn word_at(tokens: &[Spanned], index: usize) -> Option<&str> {
+    match tokens.get(index) {
+        Some((Token::Word(word), _, _)) => Some(word),
+        _ => None,
+    }
+}
+
+fn is_punct(tokens: &[Spanned], index: usize, punct: char) -> bool {
+    matches!(tokens.get(index), Some((Token::Punct(c), _, _)) if *c == punct)
+}
+
+fn is_sigil(tokens: &[Spanned], index: usize) -> bool {
+    is_punct(tokens, index, '$') || is_punct(tokens, index, '@') || is_punct(tokens, index, '%')
+}
+
+// First index at or after `start` holding `{` or `;` outside parentheses
+fn block_or_statement_end(tokens: &[Spanned], start: usize) -> Option<usize> {
+    let mut parens = 0;
+    for (index, (token, _, _)) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Punct('(') => parens += 1,
+            Token::Punct(')') => parens -= 1,
+            Token::Punct('{') | Token::Punct(';') if parens <= 0 => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+// Whether the statement leading up to the block opened at `open` starts
+// with a conditional keyword, like `if (...) {` or `} else {`
+fn opens_conditional_block(tokens: &[Spanned], open: usize) -> bool {
+    tokens[..open]
+        .iter()
+        .rev()
+        .take_while(|(token, _, _)| {
+            !matches!(token, Token::Punct(';') | Token::Punct('{') | Token::Punct('}'))
+        })
+        .any(|(token, _, _)| {
+            matches!(token, Token::Word(word) if CONDITIONAL_KEYWORDS.contains(&word.as_str()))
+        })
+}
+
+// Whether the token at `index` sits in a conditional block of its function,
+// or in a statement with a suffix condition such as `foo() if $ready;`
+fn is_conditional(tokens: &[Spanned], index: usize) -> bool {
+    let mut depth = 0;
+    for open in (0..index).rev() {
+        match &tokens[open].0 {
+            Token::Punct('}') => depth += 1,
+            Token::Punct('{') if depth > 0 => depth -= 1,
+            Token::Punct('{') => {
+                if open >= 2 && word_at(tokens, open - 2) == Some("sub") {
+                    break;
+                }
+                if opens_conditional_block(tokens, open) {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut depth = 0;
+    for (token, _, _) in &tokens[index..] {
+        match token {
+            Token::Punct('(') | Token::Punct('{') => depth += 1,
+            Token::Punct(')') => depth -= 1,
+            Token::Punct('}') if depth == 0 => break,
+            Token::Punct('}') => depth -= 1,
+            Token::Punct(';') if depth <= 0 => break,
+            Token::Word(word) if depth <= 0 && CONDITIONAL_KEYWORDS.contains(&word.as_str()) => {
+                return true;
+            }
+            _ => {}
+        }
+    }
+
+    false
+}
+
+// `Foo::Bar` for `require "Foo/Bar.pm"`
+fn module_from_path(path: &str) -> String {
+    path.trim_end_matches(".pm")
+        .trim_end_matches(".pl")
+        .replace('/', "::")
+}
+
+pub struct PerlExtractor;
+
+impl PerlExtractor {
+    pub fn new() -> Self {
+        PerlExtractor
+    }
+}
+
+impl LanguageExtractor for PerlExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[NodeType::Function, NodeType::Module, NodeType::Variable],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+            ],
+        )
+    }
+
+    fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+        let file_path_str = file_path.to_str().unwrap_or("");
+        let tokens = tokenize(content);
+        // Anything after `__END__` is data, not code
+        let last_line = tokens.last().map_or(1, |(_, line, _)| *line);
+
+        let new_node = |node_type, name: &str, start_line: usize| {
+            common::create_node(
+                node_type,
+                name.to_string(),
+                file_path_str,
+                (start_line, start_line),
+                String::new(),
+            )
+        };
+        let finish = |mut node: CodeNode, end_line: usize| {
+            node.line_range.1 = end_line.max(node.line_range.0);
+            node.content = common::line_range_text(content, node.line_range.0, node.line_range.1);
+            node.id = common::stable_node_id(
+                &node.node_type,
+                &node.name,
+                &node.file_path,
+                node.line_range,
+            );
+            node
+        };
+
+        // `package Foo;` runs until the next package statement, while
+        // `package Foo { ... }` and sub bodies are open blocks
+        let mut file_package: Option<CodeNode> = None;
+        let mut blocks: Vec<Option<CodeNode>> = Vec::new();
+        let mut index = 0;
+
+        while index < tokens.len() {
+            let line = tokens[index].1;
+            let in_sub = blocks
+                .iter()
+                .flatten()
+                .any(|node| node.node_type == NodeType::Function);
+
+            match &tokens[index].0 {
+                Token::Word(keyword) if keyword == "package" => {
+                    let Some(name) = word_at(&tokens, index + 1) else {
+                        index += 1;
+                        continue;
+                    };
+                    let Some(end) = block_or_statement_end(&tokens, index + 2) else {
+                        break;
+                    };
+                    let package = new_node(NodeType::Module, name, line)
+                        .with_metadata("perl_kind".to_string(), "package".to_string());
+                    if is_punct(&tokens, end, '{') {
+                        blocks.push(Some(package));
+                    } else {
+                        if let Some(previous) = file_package.take() {
+                            code_units.push(finish(previous, line - 1));
+                        }
+                        if name != "main" {
+                            file_package = Some(package);
+                        }
+                    }
+                    index = end + 1;
+                    continue;
+                }
+                Token::Word(keyword) if keyword == "sub" => {
+                    let Some(name) = word_at(&tokens, index + 1) else {
+                        // Anonymous subs are plain blocks
+                        index += 1;
+                        continue;
+                    };
+                    let Some(end) = block_or_statement_end(&tokens, index + 2) else {
+                        break;
+                    };
+                    // `sub name;` only declares the sub
+                    if is_punct(&tokens, end, '{') {
+                        let mut function = new_node(NodeType::Function, name, line);
+                        let package = blocks
+                            .iter()
+                            .rev()
+                            .flatten()
+                            .find(|node| node.node_type == NodeType::Module)
+                            .or(file_package.as_ref());
+                        if let Some(package) = package {
+                            function
+                                .add_metadata("parent_module".to_string(), package.name.clone());
+                        }
+                        blocks.push(Some(function));
+                    }
+                    index = end + 1;
+                    continue;
+                }
+                // `our $VERSION = ...` and file-level `my ($a, $b) = ...`
+                Token::Word(keyword) if (keyword == "our" || keyword == "my") && !in_sub => {
+                    let Some(end) = block_or_statement_end(&tokens, index + 1) else {
+                        break;
+                    };
+                    let mut declared = index + 1;
+                    while declared + 1 < end {
+                        if is_punct(&tokens, declared, '=') {
+                            break;
+                        }
+                        if is_sigil(&tokens, declared)
+                            && let Some(name) = word_at(&tokens, declared + 1)
+                        {
+                            let end_line = tokens[end].1;
+                            code_units.push(
+                                finish(new_node(NodeType::Variable, name, line), end_line)
+                                    .with_scope("module"),
+                            );
+                        }
+                        declared += 1;
+                    }
+                    index = end;
+                    continue;
+                }
+                Token::Punct('{') => blocks.push(None),
+                Token::Punct('}') => {
+                    if let Some(Some(node)) = blocks.pop() {
+                        code_units.push(finish(node, line));
+                    }
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+
+        for node in blocks.into_iter().flatten().chain(file_package) {
+            code_units.push(finish(node, last_line));
+        }
+
+        code_units.sort_by_key(|node| node.line_range);
+        code_units
+    }
+
+    fn extract_function_calls(
+        &self,
+        content: &str,
+        func_range: (usize, usize),
+        _func_name: &str,
+    ) -> Vec<CallSite> {
+        let mut calls = Vec::new();
+        let tokens = tokenize(content);
+        let lines: Vec<&str> = content.lines().collect();
+
+        for (index, (token, line, column)) in tokens.iter().enumerate() {
+            let Token::Word(word) = token else {
+                continue;
+            };
+            if *line < func_range.0 || *line > func_range.1 {
+                continue;
+            }
+
+            let previous = index.checked_sub(1);
+            let after_arrow = index >= 2
+                && is_punct(&tokens, index - 1, '>')
+                && is_punct(&tokens, index - 2, '-');
+            let after_ampersand = previous.is_some_and(|p| {
+                is_punct(&tokens, p, '&') && !(p > 0 && is_punct(&tokens, p - 1, '&'))
+            });
+            let is_call = after_arrow
+                || after_ampersand
+                || (is_punct(&tokens, index + 1, '(')
+                    && !KEYWORDS.contains(&word.as_str())
+                    && !previous.is_some_and(|p| is_sigil(&tokens, p)));
+            if !is_call {
+                continue;
+            }
+
+            // `Foo::Bar::baz()` calls `baz`
+            let name = word.rsplit("::").next().unwrap_or(word);
+            let expression = lines
+                .get(line - 1)
+                .map(|text| text.chars().skip(column - 1).collect::<String>())
+                .unwrap_or_default()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .chars()
+                .take(common::MAX_CALL_EXPRESSION_LEN)
+                .collect();
+
+            calls.push(CallSite {
+                name: name.to_string(),
+                line: *line,
+                column: *column,
+                expression,
+                conditional: is_conditional(&tokens, index),
+                arguments: None,
+            });
+        }
+
+        calls
+    }
+
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        let tokens = tokenize(content);
+
+        // `$name`, `@name`, `%name` and element accesses like `$name[0]`
+        tokens
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index > 0 && is_sigil(&tokens, index - 1))
+            .filter_map(|(_, (token, line, _))| match token {
+                Token::Word(word) => Some((word.clone(), *line)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn extract_imported_modules(&self, content: &str) -> Vec<String> {
+        let tokens = tokenize(content);
+        let lines: Vec<&str> = content.lines().collect();
+        let mut modules: Vec<String> = Vec::new();
+        let mut add = |module: String| {
+            if !module.is_empty() && !modules.contains(&module) {
+                modules.push(module);
+            }
+        };
+
+        for index in 0..tokens.len() {
+            let keyword = word_at(&tokens, index);
+            if keyword != Some("use") && keyword != Some("require") {
+                continue;
+            }
+            // Only statements, not `$obj->require` or hash keys
+            if index > 0 && !matches!(tokens[index - 1].0, Token::Punct(';' | '{' | '}')) {
+                continue;
+            }
+            let Some(end) = block_or_statement_end(&tokens, index + 1) else {
+                continue;
+            };
+            let (start_line, start_column) = (tokens[index].1, tokens[index].2);
+            let end_line = tokens[end].1;
+
+            // String contents are blanked in tokens, so read the statement
+            // back from the source
+            let statement = (start_line..=end_line)
+                .filter_map(|line| lines.get(line - 1))
+                .enumerate()
+                .map(|(offset, text)| {
+                    if offset == 0 {
+                        text.chars().skip(start_column - 1).collect::<String>()
+                    } else {
+                        text.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            let statement = statement.split(';').next().unwrap_or("");
+            let mut words = statement
+                .split(|c: char| c.is_whitespace() || "(),'\"".contains(c))
+                .filter(|word| !word.is_empty())
+                .skip(1);
+
+            let Some(target) = words.next() else {
+                continue;
+            };
+            if INHERITANCE_PRAGMAS.contains(&target) {
+                // `use parent -norequire, 'Base';` and `use base qw(Base Other);`
+                words
+                    .filter(|word| !word.starts_with('-') && *word != "qw")
+                    .for_each(|word| add(word.to_string()));
+            } else if target.contains('/') || target.ends_with(".pm") || target.ends_with(".pl") {
+                add(module_from_path(target));
+            } else if !PRAGMAS.contains(&target)
+                && target.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            {
+                add(target.to_string());
+            }
+        }
+
+        modules
+    }
+}
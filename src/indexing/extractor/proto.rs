@@ -0,0 +1,295 @@
+use crate::code_graph::{CodeNode, NodeType};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
+use std::path::Path;
+
+// Protocol buffer definitions are read with a small tokenizer, as there is
+// no tree-sitter grammar for them among our dependencies
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    // Identifiers, including dotted type names such as `google.protobuf.Empty`
+    Name(String),
+    Punct(char),
+    Str(String),
+}
+
+// A token and the 1-based line it starts on
+type Spanned = (Token, usize);
+
+fn tokenize(content: &str) -> Vec<Spanned> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '"' | '\'' => {
+                let mut value = String::new();
+                let mut escaped = false;
+                for next in chars.by_ref() {
+                    if next == c && !escaped {
+                        break;
+                    }
+                    escaped = next == '\\' && !escaped;
+                    value.push(next);
+                }
+                tokens.push((Token::Str(value), line));
+            }
+            c if c.is_alphabetic() || c == '_' || c == '.' => {
+                let mut name = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == '.' {
+                        name.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push((Token::Name(name), line));
+            }
+            c if "{}()[];=<>,".contains(c) => tokens.push((Token::Punct(c), line)),
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+fn name_at(tokens: &[Spanned], index: usize) -> Option<&str> {
+    match tokens.get(index) {
+        Some((Token::Name(name), _)) => Some(name),
+        _ => None,
+    }
+}
+
+fn is_punct(tokens: &[Spanned], index: usize, punct: char) -> bool {
+    matches!(tokens.get(index), Some((Token::Punct(c), _)) if *c == punct)
+}
+
+// Request or response of an RPC: `(stream Request)` gives ("Request", true)
+// and the index of the closing parenthesis
+fn rpc_type(tokens: &[Spanned], open: usize) -> Option<(&str, bool, usize)> {
+    if !is_punct(tokens, open, '(') {
+        return None;
+    }
+    let streaming =
+        name_at(tokens, open + 1) == Some("stream") && name_at(tokens, open + 2).is_some();
+    let type_index = if streaming { open + 2 } else { open + 1 };
+    let close = type_index + 1;
+    is_punct(tokens, close, ')').then_some((name_at(tokens, type_index)?, streaming, close))
+}
+
+struct RpcSignature<'a> {
+    name: &'a str,
+    request: &'a str,
+    client_streaming: bool,
+    response: &'a str,
+    server_streaming: bool,
+    // Index of the `)` closing the response type
+    end: usize,
+}
+
+// `rpc Name(Request) returns (stream Response)` starting at the `rpc` keyword
+fn parse_rpc(tokens: &[Spanned], index: usize) -> Option<RpcSignature<'_>> {
+    let name = name_at(tokens, index + 1)?;
+    let (request, client_streaming, close) = rpc_type(tokens, index + 2)?;
+    if name_at(tokens, close + 1) != Some("returns") {
+        return None;
+    }
+    let (response, server_streaming, end) = rpc_type(tokens, close + 2)?;
+
+    Some(RpcSignature {
+        name,
+        request,
+        client_streaming,
+        response,
+        server_streaming,
+        end,
+    })
+}
+
+pub struct ProtoExtractor;
+
+impl ProtoExtractor {
+    pub fn new() -> Self {
+        ProtoExtractor
+    }
+}
+
+impl LanguageExtractor for ProtoExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Class,
+                NodeType::Interface,
+                NodeType::Method,
+                NodeType::TypeDefinition,
+            ],
+            &[Feature::Imports],
+        )
+    }
+
+    fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+        let file_path_str = file_path.to_str().unwrap_or("");
+        let tokens = tokenize(content);
+
+        let package = tokens
+            .iter()
+            .position(|(token, _)| *token == Token::Name("package".to_string()))
+            .and_then(|index| name_at(&tokens, index + 1));
+
+        let new_node = |node_type, name: &str, start_line: usize, kind: &str| {
+            let mut node = common::create_node(
+                node_type,
+                name.to_string(),
+                file_path_str,
+                (start_line, start_line),
+                String::new(),
+            )
+            .with_metadata("proto_kind".to_string(), kind.to_string());
+            if let Some(package) = package {
+                node.add_metadata("proto_package".to_string(), package.to_string());
+            }
+            node
+        };
+        let finish = |mut node: CodeNode, end_line: usize| {
+            node.line_range.1 = end_line;
+            node.content = common::line_range_text(content, node.line_range.0, end_line);
+            node.id = common::stable_node_id(
+                &node.node_type,
+                &node.name,
+                &node.file_path,
+                node.line_range,
+            );
+            node
+        };
+
+        // Open `{ ... }` bodies and the declaration each belongs to, if any
+        let mut blocks: Vec<Option<CodeNode>> = Vec::new();
+        let mut index = 0;
+
+        while index < tokens.len() {
+            let line = tokens[index].1;
+            match &tokens[index].0 {
+                Token::Name(keyword)
+                    if matches!(keyword.as_str(), "message" | "enum" | "service")
+                        && is_punct(&tokens, index + 2, '{') =>
+                {
+                    let Some(name) = name_at(&tokens, index + 1) else {
+                        index += 1;
+                        continue;
+                    };
+                    let node_type = match keyword.as_str() {
+                        "message" => NodeType::Class,
+                        "service" => NodeType::Interface,
+                        _ => NodeType::TypeDefinition,
+                    };
+                    blocks.push(Some(new_node(node_type, name, line, keyword)));
+                    index += 3;
+                    continue;
+                }
+                Token::Name(keyword) if keyword == "rpc" => {
+                    let Some(signature) = parse_rpc(&tokens, index) else {
+                        index += 1;
+                        continue;
+                    };
+                    let close = signature.end;
+
+                    let mut rpc = new_node(NodeType::Method, signature.name, line, "rpc")
+                        .with_metadata("request_type".to_string(), signature.request.to_string())
+                        .with_metadata("response_type".to_string(), signature.response.to_string())
+                        .with_metadata(
+                            "client_streaming".to_string(),
+                            signature.client_streaming.to_string(),
+                        )
+                        .with_metadata(
+                            "server_streaming".to_string(),
+                            signature.server_streaming.to_string(),
+                        );
+                    let service = blocks.iter().rev().flatten().find(|node| {
+                        node.metadata.get("proto_kind").map(String::as_str) == Some("service")
+                    });
+                    rpc.parent_class = service.map(|service| service.name.clone());
+
+                    // `rpc A(B) returns (C);` or with an options body `{ ... }`
+                    if is_punct(&tokens, close + 1, '{') {
+                        blocks.push(Some(rpc));
+                        index = close + 2;
+                    } else {
+                        code_units.push(finish(rpc, tokens[close].1));
+                        index = close + 1;
+                    }
+                    continue;
+                }
+                Token::Punct('{') => blocks.push(None),
+                Token::Punct('}') => {
+                    if let Some(Some(node)) = blocks.pop() {
+                        code_units.push(finish(node, line));
+                    }
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+
+        code_units.sort_by_key(|node| node.line_range);
+        code_units
+    }
+
+    fn extract_function_calls(
+        &self,
+        _content: &str,
+        _func_range: (usize, usize),
+        _func_name: &str,
+    ) -> Vec<CallSite> {
+        Vec::new()
+    }
+
+    fn extract_references(&self, _content: &str) -> Vec<(String, usize)> {
+        Vec::new()
+    }
+
+    fn extract_imported_modules(&self, content: &str) -> Vec<String> {
+        let tokens = tokenize(content);
+        let mut modules = Vec::new();
+
+        for index in 0..tokens.len() {
+            if name_at(&tokens, index) != Some("import") {
+                continue;
+            }
+            // `import public "a.proto";` and `import weak "a.proto";`
+            let path = match (&tokens.get(index + 1), &tokens.get(index + 2)) {
+                (Some((Token::Str(path), _)), _) => path,
+                (Some((Token::Name(_), _)), Some((Token::Str(path), _))) => path,
+                _ => continue,
+            };
+            if let Some(stem) = Path::new(path).file_stem().and_then(|s| s.to_str()) {
+                modules.push(stem.to_string());
+            }
+        }
+
+        modules
+    }
+}
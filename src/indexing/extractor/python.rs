@@ -1,11 +1,96 @@
 use crate::code_graph::{CodeNode, NodeType};
-use crate::indexing::extractor::{LanguageExtractor, common};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
 use crate::parsers::treesitter::queries::python as queries;
 use log::warn;
-use std::collections::HashMap;
 use std::path::Path;
 use tree_sitter::Node;
 
+// One imported module and the names taken from it: `from ..a.b import c, d`
+// is module `a.b` at level 2 with names `c` and `d`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PythonImport {
+    pub module: String,
+    // Leading dots of a relative import; 0 for absolute imports
+    pub level: usize,
+    // Names after `import` in `from` imports; `*` for wildcard imports
+    pub names: Vec<String>,
+    pub line: usize,
+}
+
+// Dotted name of an import path, without any `as` alias
+fn import_name(node: Node, source: &str) -> String {
+    let name = match node.kind() {
+        "aliased_import" => node.child_by_field_name("name").unwrap_or(node),
+        _ => node,
+    };
+    common::get_node_text(name, source)
+}
+
+// Every import statement of a Python file, including those inside functions
+pub fn python_imports(content: &str) -> Vec<PythonImport> {
+    let mut imports = Vec::new();
+    let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.py")) else {
+        return imports;
+    };
+
+    let statements = common::execute_query(
+        queries::IMPORT_STATEMENT_QUERY,
+        &tree,
+        content.as_bytes(),
+        "import",
+    );
+    for statement in statements {
+        let line = statement.start_position().row + 1;
+        let mut cursor = statement.walk();
+
+        if statement.kind() == "import_statement" {
+            for name in statement.children_by_field_name("name", &mut cursor) {
+                imports.push(PythonImport {
+                    module: import_name(name, content),
+                    level: 0,
+                    names: Vec::new(),
+                    line,
+                });
+            }
+            continue;
+        }
+
+        let Some(module) = statement.child_by_field_name("module_name") else {
+            continue;
+        };
+        let (module, level) = if module.kind() == "relative_import" {
+            let text = common::get_node_text(module, content);
+            let dotted = text.trim_start_matches('.');
+            (dotted.to_string(), text.len() - dotted.len())
+        } else {
+            (common::get_node_text(module, content), 0)
+        };
+
+        let mut names: Vec<String> = statement
+            .children_by_field_name("name", &mut cursor)
+            .map(|name| import_name(name, content))
+            .collect();
+        let mut cursor = statement.walk();
+        if statement
+            .named_children(&mut cursor)
+            .any(|child| child.kind() == "wildcard_import")
+        {
+            names.push("*".to_string());
+        }
+
+        imports.push(PythonImport {
+            module,
+            level,
+            names,
+            line,
+        });
+    }
+
+    imports
+}
+
 pub struct PythonExtractor;
 
 impl PythonExtractor {
@@ -90,6 +175,28 @@ impl PythonExtractor {
 }
 
 impl LanguageExtractor for PythonExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Function,
+                NodeType::Method,
+                NodeType::Class,
+                NodeType::Variable,
+                NodeType::Field,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+                Feature::Visibility,
+                Feature::Fields,
+                Feature::Tests,
+                Feature::Decorators,
+            ],
+        )
+    }
+
     fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
         let mut code_units = Vec::new();
 
@@ -111,14 +218,6 @@ impl LanguageExtractor for PythonExtractor {
                         NodeType::Function
                     };
 
-                    let mut metadata = HashMap::new();
-
-                    if is_method {
-                        if let Some(parent_class) = self.find_parent_class(node, content) {
-                            metadata.insert("parent_class".to_string(), parent_class);
-                        }
-                    }
-
                     let mut code_node = common::create_node(
                         node_type,
                         name,
@@ -127,8 +226,8 @@ impl LanguageExtractor for PythonExtractor {
                         node_content,
                     );
 
-                    for (key, value) in metadata {
-                        code_node.add_metadata(key, value);
+                    if is_method {
+                        code_node.parent_class = self.find_parent_class(node, content);
                     }
 
                     code_units.push(code_node);
@@ -156,6 +255,22 @@ impl LanguageExtractor for PythonExtractor {
                     code_units.push(code_node);
                 }
             }
+
+            // Extract fields and properties
+            code_units.extend(common::extract_fields(
+                queries::FIELD_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
+
+            // Extract module-level constants and globals
+            code_units.extend(common::extract_global_variables(
+                queries::GLOBAL_VARIABLE_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
         } else {
             warn!("Failed to parse Python file: {:?}", file_path);
         }
@@ -168,7 +283,7 @@ impl LanguageExtractor for PythonExtractor {
         content: &str,
         func_range: (usize, usize),
         _func_name: &str,
-    ) -> Vec<String> {
+    ) -> Vec<CallSite> {
         let mut calls = Vec::new();
 
         if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.py")) {
@@ -180,9 +295,9 @@ impl LanguageExtractor for PythonExtractor {
 
                 // Check if call is within function range
                 if call_line >= func_range.0 && call_line <= func_range.1 {
-                    let call_name = common::get_node_text(node, content);
-                    if !call_name.is_empty() {
-                        calls.push(call_name);
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
                     }
                 }
             }
@@ -191,36 +306,8 @@ impl LanguageExtractor for PythonExtractor {
         calls
     }
 
-    fn extract_variable_references(
-        &self,
-        content: &str,
-        func_range: (usize, usize),
-        var_name: &str,
-    ) -> Vec<(usize, usize)> {
-        let mut references = Vec::new();
-
-        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.py")) {
-            let reference_nodes = common::execute_query(
-                queries::REFERENCE_QUERY,
-                &tree,
-                content.as_bytes(),
-                "reference",
-            );
-
-            for node in reference_nodes {
-                let ref_line = node.start_position().row + 1;
-
-                // Check if reference is within function range
-                if ref_line >= func_range.0 && ref_line <= func_range.1 {
-                    let ref_name = common::get_node_text(node, content);
-                    if ref_name == var_name {
-                        references.push((ref_line, node.end_position().row + 1));
-                    }
-                }
-            }
-        }
-
-        references
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.py"))
     }
 
     fn extract_imported_modules(&self, content: &str) -> Vec<String> {
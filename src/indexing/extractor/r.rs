@@ -0,0 +1,421 @@
+use crate::code_graph::{CodeNode, NodeType};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
+use crate::parsers::treesitter::queries::r as queries;
+use log::warn;
+use std::path::Path;
+use tree_sitter::{Node, Query, QueryCursor, StreamingIterator};
+
+// Calls that attach a package or load another script
+const IMPORT_FUNCTIONS: &[&str] = &[
+    "library",
+    "require",
+    "requireNamespace",
+    "loadNamespace",
+    "source",
+];
+
+// Arguments of R6Class() and setRefClass() holding lists of methods
+const METHOD_LISTS: &[&str] = &["public", "private", "active", "methods"];
+
+pub struct RExtractor;
+
+impl RExtractor {
+    pub fn new() -> Self {
+        RExtractor
+    }
+
+    // Text of a string literal without its quotes
+    fn string_value(&self, node: Node, source: &str) -> Option<String> {
+        if node.kind() != "string" {
+            return None;
+        }
+        Some(
+            node.child_by_field_name("content")
+                .map(|content| common::get_node_text(content, source))
+                .unwrap_or_default(),
+        )
+    }
+
+    // `name = value` pairs of a call; unnamed arguments have no name
+    fn arguments<'a>(&self, call: Node<'a>, source: &str) -> Vec<(Option<String>, Node<'a>)> {
+        let Some(arguments) = call.child_by_field_name("arguments") else {
+            return Vec::new();
+        };
+
+        (0..arguments.named_child_count())
+            .filter_map(|i| arguments.named_child(i))
+            .filter(|argument| argument.kind() == "argument")
+            .filter_map(|argument| {
+                let value = argument.child_by_field_name("value")?;
+                let name = argument.child_by_field_name("name").map(|name| {
+                    self.string_value(name, source)
+                        .unwrap_or_else(|| common::get_node_text(name, source))
+                });
+                Some((name, value))
+            })
+            .collect()
+    }
+
+    // A class name given as a string, or a class or package symbol
+    fn name_value(&self, node: Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "string" => self.string_value(node, source),
+            "identifier" => Some(common::get_node_text(node, source)),
+            // `signature("Circle")` in setMethod()
+            "call" => self
+                .arguments(node, source)
+                .into_iter()
+                .find_map(|(_, value)| self.string_value(value, source)),
+            _ => None,
+        }
+    }
+
+    // `Person <- R6Class(...)` names the class after the variable it is assigned to
+    fn assigned_name(&self, node: Node, source: &str) -> Option<String> {
+        let parent = node.parent().filter(|p| p.kind() == "binary_operator")?;
+        let lhs = parent.child_by_field_name("lhs")?;
+        (lhs.kind() == "identifier").then(|| common::get_node_text(lhs, source))
+    }
+
+    fn extract_class(
+        &self,
+        node: Node,
+        constructor: &str,
+        content: &str,
+        file_path: &Path,
+        code_units: &mut Vec<CodeNode>,
+    ) {
+        let arguments = self.arguments(node, content);
+        let first_unnamed = arguments.iter().find(|(name, _)| name.is_none());
+        let first_name = first_unnamed.and_then(|(_, value)| self.name_value(*value, content));
+        let named = |key: &str| {
+            arguments
+                .iter()
+                .find(|(name, _)| name.as_deref() == Some(key))
+                .map(|(_, value)| *value)
+        };
+        let line_range = (node.start_position().row + 1, node.end_position().row + 1);
+        let file_path_str = file_path.to_str().unwrap_or("");
+
+        match constructor {
+            "R6Class" | "setClass" | "setRefClass" => {
+                let Some(class_name) = first_name
+                    .or_else(|| self.assigned_name(node, content))
+                    .filter(|name| !name.is_empty())
+                else {
+                    return;
+                };
+                let class_system = match constructor {
+                    "R6Class" => "R6",
+                    "setClass" => "S4",
+                    _ => "RC",
+                };
+
+                let mut class_node = common::create_node(
+                    NodeType::Class,
+                    class_name.clone(),
+                    file_path_str,
+                    line_range,
+                    common::get_node_text(node, content),
+                )
+                .with_metadata("class_system".to_string(), class_system.to_string());
+                if let Some(superclass) = named("inherit")
+                    .or_else(|| named("contains"))
+                    .and_then(|value| self.name_value(value, content))
+                {
+                    class_node.add_metadata("superclass".to_string(), superclass);
+                }
+                code_units.push(class_node);
+
+                // `public = list(greet = function() ...)`
+                for list_name in METHOD_LISTS {
+                    let Some(list) = named(list_name).filter(|value| value.kind() == "call") else {
+                        continue;
+                    };
+                    for (method_name, value) in self.arguments(list, content) {
+                        let Some(method_name) = method_name else {
+                            continue;
+                        };
+                        if value.kind() != "function_definition" {
+                            continue;
+                        }
+                        let method = value.parent().unwrap_or(value);
+                        code_units.push(
+                            common::create_node(
+                                NodeType::Method,
+                                method_name,
+                                file_path_str,
+                                (
+                                    method.start_position().row + 1,
+                                    method.end_position().row + 1,
+                                ),
+                                common::get_node_text(method, content),
+                            )
+                            .with_parent_class(class_name.clone()),
+                        );
+                    }
+                }
+            }
+            // `setMethod("area", "Circle", function(shape) ...)`
+            "setMethod" => {
+                let (Some(method_name), Some(class_name)) = (
+                    first_name,
+                    named("signature")
+                        .or_else(|| {
+                            arguments
+                                .iter()
+                                .filter(|(name, _)| name.is_none())
+                                .nth(1)
+                                .map(|(_, value)| *value)
+                        })
+                        .and_then(|value| self.name_value(value, content)),
+                ) else {
+                    return;
+                };
+                code_units.push(
+                    common::create_node(
+                        NodeType::Method,
+                        method_name,
+                        file_path_str,
+                        line_range,
+                        common::get_node_text(node, content),
+                    )
+                    .with_parent_class(class_name)
+                    .with_metadata("class_system".to_string(), "S4".to_string()),
+                );
+            }
+            // `setGeneric("area", function(shape) standardGeneric("area"))`
+            _ => {
+                let Some(generic_name) = first_name else {
+                    return;
+                };
+                code_units.push(
+                    common::create_node(
+                        NodeType::Function,
+                        generic_name,
+                        file_path_str,
+                        line_range,
+                        common::get_node_text(node, content),
+                    )
+                    .with_metadata("generic".to_string(), "true".to_string()),
+                );
+            }
+        }
+    }
+}
+
+impl LanguageExtractor for RExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Function,
+                NodeType::Method,
+                NodeType::Class,
+                NodeType::Module,
+                NodeType::Variable,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+            ],
+        )
+    }
+
+    fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
+        let mut code_units = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, file_path) {
+            // The script itself is a module that other scripts can source()
+            let script_name = file_path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("");
+            if !script_name.is_empty() {
+                code_units.push(common::create_node(
+                    NodeType::Module,
+                    script_name.to_string(),
+                    file_path.to_str().unwrap_or(""),
+                    (1, content.lines().count().max(1)),
+                    content.to_string(),
+                ));
+            }
+
+            // Extract functions assigned with `<-`, `<<-` or `=`
+            let function_nodes =
+                common::execute_query(queries::FUNCTION_QUERY, &tree, content.as_bytes(), "node");
+            let mut function_ranges = Vec::new();
+
+            for node in function_nodes {
+                let Some(name_node) = node.child_by_field_name("lhs") else {
+                    continue;
+                };
+                let name = self
+                    .string_value(name_node, content)
+                    .unwrap_or_else(|| common::get_node_text(name_node, content));
+                let line_range = (node.start_position().row + 1, node.end_position().row + 1);
+                function_ranges.push(line_range);
+
+                code_units.push(common::create_node(
+                    NodeType::Function,
+                    name,
+                    file_path.to_str().unwrap_or(""),
+                    line_range,
+                    common::get_node_text(node, content),
+                ));
+            }
+
+            // Extract R6, S4 and reference classes, S4 generics and methods
+            match Query::new(&tree.language(), queries::CLASS_QUERY) {
+                Ok(query) => {
+                    let node_idx = query.capture_index_for_name("node");
+                    let constructor_idx = query.capture_index_for_name("constructor");
+                    let mut query_cursor = QueryCursor::new();
+                    let mut matches =
+                        query_cursor.matches(&query, tree.root_node(), content.as_bytes());
+
+                    while let Some(match_result) = matches.next() {
+                        let capture = |index| {
+                            match_result
+                                .captures
+                                .iter()
+                                .find(|c| Some(c.index) == index)
+                                .map(|c| c.node)
+                        };
+                        if let (Some(node), Some(constructor)) =
+                            (capture(node_idx), capture(constructor_idx))
+                        {
+                            let constructor = common::get_node_text(constructor, content);
+                            self.extract_class(
+                                node,
+                                &constructor,
+                                content,
+                                file_path,
+                                &mut code_units,
+                            );
+                        }
+                    }
+                }
+                Err(_) => warn!("Failed to create query: {}", queries::CLASS_QUERY),
+            }
+
+            // Extract script-level variables, leaving out function and
+            // class assignments
+            let class_ranges: Vec<_> = code_units
+                .iter()
+                .filter(|unit| unit.node_type == NodeType::Class)
+                .map(|unit| unit.line_range)
+                .collect();
+            code_units.extend(
+                common::extract_global_variables(
+                    queries::GLOBAL_VARIABLE_QUERY,
+                    &tree,
+                    content,
+                    file_path,
+                )
+                .into_iter()
+                .filter(|variable| {
+                    !function_ranges.contains(&variable.line_range)
+                        && !class_ranges.iter().any(|range| {
+                            range.0 >= variable.line_range.0 && range.1 <= variable.line_range.1
+                        })
+                }),
+            );
+        } else {
+            warn!("Failed to parse R file: {:?}", file_path);
+        }
+
+        code_units
+    }
+
+    fn extract_function_calls(
+        &self,
+        content: &str,
+        func_range: (usize, usize),
+        _func_name: &str,
+    ) -> Vec<CallSite> {
+        let mut calls = Vec::new();
+
+        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.R")) {
+            let call_nodes =
+                common::execute_query(queries::CALL_QUERY, &tree, content.as_bytes(), "func_name");
+
+            for node in call_nodes {
+                let call_line = node.start_position().row + 1;
+
+                // Check if call is within function range
+                if call_line >= func_range.0 && call_line <= func_range.1 {
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
+                    }
+                }
+            }
+        }
+
+        calls
+    }
+
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.R"))
+    }
+
+    fn extract_imported_modules(&self, content: &str) -> Vec<String> {
+        let mut modules: Vec<String> = Vec::new();
+
+        let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.R")) else {
+            return modules;
+        };
+        let Ok(query) = Query::new(&tree.language(), queries::IMPORT_QUERY) else {
+            warn!("Failed to create query: {}", queries::IMPORT_QUERY);
+            return modules;
+        };
+        let function_idx = query.capture_index_for_name("function");
+        let path_idx = query.capture_index_for_name("import_path");
+
+        let mut query_cursor = QueryCursor::new();
+        let mut matches = query_cursor.matches(&query, tree.root_node(), content.as_bytes());
+
+        while let Some(match_result) = matches.next() {
+            let capture = |index| {
+                match_result
+                    .captures
+                    .iter()
+                    .find(|c| Some(c.index) == index)
+                    .map(|c| c.node)
+            };
+            let Some(path_node) = capture(path_idx) else {
+                continue;
+            };
+            let function = capture(function_idx).map(|node| common::get_node_text(node, content));
+            if function
+                .as_deref()
+                .is_some_and(|function| !IMPORT_FUNCTIONS.contains(&function))
+            {
+                continue;
+            }
+
+            let Some(path) = self.name_value(path_node, content) else {
+                continue;
+            };
+            // Sourced scripts are matched by file name, packages by name
+            let module = if function.as_deref() == Some("source") {
+                Path::new(&path)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("")
+                    .to_string()
+            } else {
+                path
+            };
+            if !module.is_empty() && !modules.contains(&module) {
+                modules.push(module);
+            }
+        }
+
+        modules
+    }
+}
@@ -1,8 +1,9 @@
 use crate::code_graph::{CodeNode, NodeType};
-use crate::indexing::extractor::{LanguageExtractor, common};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
 use crate::parsers::treesitter::queries::ruby as queries;
 use log::warn;
-use std::collections::HashMap;
 use std::path::Path;
 use tree_sitter::Node;
 
@@ -72,6 +73,24 @@ impl RubyExtractor {
 }
 
 impl LanguageExtractor for RubyExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Method,
+                NodeType::Class,
+                NodeType::Module,
+                NodeType::Variable,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+                Feature::Tests,
+            ],
+        )
+    }
+
     fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
         let mut code_units = Vec::new();
 
@@ -86,12 +105,6 @@ impl LanguageExtractor for RubyExtractor {
                     let end_line = node.end_position().row + 1;
                     let node_content = common::get_node_text(node, content);
 
-                    let mut metadata = HashMap::new();
-
-                    if let Some(parent_class) = self.find_parent_class(node, content) {
-                        metadata.insert("parent_class".to_string(), parent_class);
-                    }
-
                     let mut code_node = common::create_node(
                         NodeType::Method,
                         name,
@@ -99,10 +112,7 @@ impl LanguageExtractor for RubyExtractor {
                         (start_line, end_line),
                         node_content,
                     );
-
-                    for (key, value) in metadata {
-                        code_node.add_metadata(key, value);
-                    }
+                    code_node.parent_class = self.find_parent_class(node, content);
 
                     code_units.push(code_node);
                 }
@@ -135,6 +145,14 @@ impl LanguageExtractor for RubyExtractor {
                     code_units.push(code_node);
                 }
             }
+
+            // Extract module-level constants and globals
+            code_units.extend(common::extract_global_variables(
+                queries::GLOBAL_VARIABLE_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
         } else {
             warn!("Failed to parse Ruby file: {:?}", file_path);
         }
@@ -147,7 +165,7 @@ impl LanguageExtractor for RubyExtractor {
         content: &str,
         func_range: (usize, usize),
         _func_name: &str,
-    ) -> Vec<String> {
+    ) -> Vec<CallSite> {
         let mut calls = Vec::new();
 
         if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.rb")) {
@@ -159,9 +177,9 @@ impl LanguageExtractor for RubyExtractor {
 
                 // Check if call is within function range
                 if call_line >= func_range.0 && call_line <= func_range.1 {
-                    let call_name = common::get_node_text(node, content);
-                    if !call_name.is_empty() {
-                        calls.push(call_name);
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
                     }
                 }
             }
@@ -170,36 +188,8 @@ impl LanguageExtractor for RubyExtractor {
         calls
     }
 
-    fn extract_variable_references(
-        &self,
-        content: &str,
-        func_range: (usize, usize),
-        var_name: &str,
-    ) -> Vec<(usize, usize)> {
-        let mut references = Vec::new();
-
-        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.rb")) {
-            let reference_nodes = common::execute_query(
-                queries::REFERENCE_QUERY,
-                &tree,
-                content.as_bytes(),
-                "reference",
-            );
-
-            for node in reference_nodes {
-                let ref_line = node.start_position().row + 1;
-
-                // Check if reference is within function range
-                if ref_line >= func_range.0 && ref_line <= func_range.1 {
-                    let ref_name = common::get_node_text(node, content);
-                    if ref_name == var_name {
-                        references.push((ref_line, node.end_position().row + 1));
-                    }
-                }
-            }
-        }
-
-        references
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.rb"))
     }
 
     fn extract_imported_modules(&self, content: &str) -> Vec<String> {
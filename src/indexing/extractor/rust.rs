@@ -1,11 +1,250 @@
 use crate::code_graph::{CodeNode, NodeType};
-use crate::indexing::extractor::{LanguageExtractor, common};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
 use crate::parsers::treesitter::queries::rust as queries;
 use log::warn;
 use std::collections::HashMap;
 use std::path::Path;
 use tree_sitter::{Node, StreamingIterator};
 
+// One path brought into scope by a `use` declaration: `use a::{b::C, d::*}`
+// yields `a::b::C` and the glob `a::d`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustUse {
+    pub path: Vec<String>,
+    pub glob: bool,
+    pub line: usize,
+}
+
+// A `mod` declaration, with the inline modules it is nested in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustModule {
+    pub name: String,
+    pub parents: Vec<String>,
+    // Value of a `#[path = "..."]` attribute
+    pub path: Option<String>,
+    // Lines of the body of an inline `mod name { ... }`
+    pub inline: Option<(usize, usize)>,
+}
+
+fn path_segments(node: Node, source: &str) -> Vec<String> {
+    common::get_node_text(node, source)
+        .split("::")
+        .map(|segment| segment.trim().to_string())
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+fn expand_use_tree(
+    node: Node,
+    source: &str,
+    prefix: &[String],
+    line: usize,
+    uses: &mut Vec<RustUse>,
+) {
+    let joined = |node: Option<Node>| {
+        let mut path = prefix.to_vec();
+        if let Some(node) = node {
+            path.extend(path_segments(node, source));
+        }
+        path
+    };
+
+    match node.kind() {
+        "use_as_clause" => {
+            if let Some(path) = node.child_by_field_name("path") {
+                expand_use_tree(path, source, prefix, line, uses);
+            }
+        }
+        "use_list" => {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                expand_use_tree(child, source, prefix, line, uses);
+            }
+        }
+        "scoped_use_list" => {
+            let path = joined(node.child_by_field_name("path"));
+            if let Some(list) = node.child_by_field_name("list") {
+                expand_use_tree(list, source, &path, line, uses);
+            }
+        }
+        "use_wildcard" => uses.push(RustUse {
+            path: joined(node.named_child(0)),
+            glob: true,
+            line,
+        }),
+        // `self` in a list names the module of the list itself
+        "self" if !prefix.is_empty() => uses.push(RustUse {
+            path: prefix.to_vec(),
+            glob: false,
+            line,
+        }),
+        "identifier" | "scoped_identifier" | "crate" | "self" | "super" => uses.push(RustUse {
+            path: joined(Some(node)),
+            glob: false,
+            line,
+        }),
+        _ => {}
+    }
+}
+
+// Every path imported by the `use` declarations of a Rust file
+pub fn rust_use_paths(content: &str) -> Vec<RustUse> {
+    let mut uses = Vec::new();
+    let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.rs")) else {
+        return uses;
+    };
+
+    let declarations = common::execute_query(queries::USE_QUERY, &tree, content.as_bytes(), "use");
+    for declaration in declarations {
+        if let Some(argument) = declaration.child_by_field_name("argument") {
+            let line = declaration.start_position().row + 1;
+            expand_use_tree(argument, content, &[], line, &mut uses);
+        }
+    }
+
+    uses
+}
+
+// Attributes right before an item, without `#[` and `]`, skipping the doc
+// comments between them
+fn attributes(item: Node, source: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut sibling = item.prev_named_sibling();
+    while let Some(node) = sibling.filter(|node| {
+        matches!(
+            node.kind(),
+            "attribute_item" | "line_comment" | "block_comment"
+        )
+    }) {
+        if node.kind() == "attribute_item" {
+            let text = common::get_node_text(node, source);
+            let inner = text.trim_start_matches("#[").trim_end_matches(']').trim();
+            found.insert(0, inner.to_string());
+        }
+        sibling = node.prev_named_sibling();
+    }
+    found
+}
+
+// Macro invocations of a Rust file, `name!(...)` and `path::name!(...)`
+pub fn rust_macro_invocations(content: &str) -> Vec<CallSite> {
+    let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.rs")) else {
+        return Vec::new();
+    };
+    common::execute_query(
+        queries::MACRO_INVOCATION_QUERY,
+        &tree,
+        content.as_bytes(),
+        "name",
+    )
+    .into_iter()
+    .map(|node| common::create_call_site(node, content))
+    .collect()
+}
+
+// `#[path = "other.rs"]` among the attributes right before an item
+fn path_attribute(item: Node, source: &str) -> Option<String> {
+    attributes(item, source).into_iter().find_map(|attribute| {
+        let value = attribute
+            .strip_prefix("path")?
+            .trim_start()
+            .strip_prefix('=')?;
+        Some(value.trim().trim_matches('"').to_string())
+    })
+}
+
+// Traits the `#[derive(...)]` attributes of an item derive
+fn derives(item: Node, source: &str) -> Vec<String> {
+    attributes(item, source)
+        .iter()
+        .filter_map(|attribute| attribute.strip_prefix("derive")?.trim().strip_prefix('('))
+        .flat_map(|list| list.trim_end_matches(')').split(','))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+// Macro invocations in item position, in a file or in a module or impl body
+fn collect_item_macros<'a>(node: Node<'a>, invocations: &mut Vec<Node<'a>>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == "macro_invocation" {
+            invocations.push(child);
+        } else if matches!(
+            child.kind(),
+            "mod_item" | "impl_item" | "trait_item" | "declaration_list"
+        ) {
+            collect_item_macros(child, invocations);
+        }
+    }
+}
+
+// The body of an item-level macro invocation such as `cfg_if! { ... }` or
+// `lazy_static! { ... }` as source of its own, with everything around it
+// blanked out so the items inside keep their lines. The `static ref`
+// declarations of `lazy_static!` become plain statics.
+fn wrapped_items(invocation: Node, source: &str) -> Option<(String, String)> {
+    let name = common::get_node_text(invocation.child_by_field_name("macro")?, source);
+    let mut cursor = invocation.walk();
+    let body = invocation
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "token_tree")?;
+    let inner = body.start_byte() + 1..body.end_byte().saturating_sub(1);
+    if inner.is_empty() {
+        return None;
+    }
+
+    let mut code = String::with_capacity(source.len());
+    for (offset, c) in source.char_indices() {
+        if inner.contains(&offset) || c == '\n' {
+            code.push(c);
+        } else {
+            code.extend(std::iter::repeat_n(' ', c.len_utf8()));
+        }
+    }
+    if name.rsplit("::").next() == Some("lazy_static") {
+        code = code.replace("static ref ", "static     ");
+    }
+    Some((name, code))
+}
+
+fn collect_modules(node: Node, source: &str, parents: &[String], modules: &mut Vec<RustModule>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() != "mod_item" {
+            continue;
+        }
+        let Some(name) = child.child_by_field_name("name") else {
+            continue;
+        };
+        let name = common::get_node_text(name, source);
+        let body = child.child_by_field_name("body");
+        modules.push(RustModule {
+            name: name.clone(),
+            parents: parents.to_vec(),
+            path: path_attribute(child, source),
+            inline: body.map(|body| (body.start_position().row + 1, body.end_position().row + 1)),
+        });
+
+        if let Some(body) = body {
+            let mut nested = parents.to_vec();
+            nested.push(name);
+            collect_modules(body, source, &nested, modules);
+        }
+    }
+}
+
+// Every `mod` declaration of a Rust file, inline or not
+pub fn rust_module_declarations(content: &str) -> Vec<RustModule> {
+    let mut modules = Vec::new();
+    if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.rs")) {
+        collect_modules(tree.root_node(), content, &[], &mut modules);
+    }
+    modules
+}
+
 pub struct RustExtractor;
 
 impl RustExtractor {
@@ -48,46 +287,59 @@ impl RustExtractor {
         None
     }
 
-    fn is_inside_impl_block(&self, node: Node) -> bool {
-        let mut current = node;
-        let mut parent_iter = current.parent();
+    // The impl block or trait a function is declared in
+    fn find_impl_or_trait<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        let mut parent_iter = node.parent();
 
         while let Some(parent) = parent_iter {
-            if parent.kind() == "impl_item" {
-                return true;
+            if matches!(parent.kind(), "impl_item" | "trait_item") {
+                return Some(parent);
             }
-
-            current = parent;
-            parent_iter = current.parent();
+            parent_iter = parent.parent();
         }
 
-        false
+        None
     }
 
-    fn find_parent_class(&self, node: Node, source: &str) -> Option<String> {
-        let mut current = node;
-        let mut parent_iter = current.parent();
-
-        while let Some(parent) = parent_iter {
-            if parent.kind() == "impl_item" {
-                for i in 0..parent.named_child_count() {
-                    if let Some(child) = parent.named_child(i) {
-                        if child.kind() == "type_identifier" {
-                            return Some(common::get_node_text(child, source));
-                        }
-                    }
-                }
-            }
-
-            current = parent;
-            parent_iter = current.parent();
-        }
-
-        None
+    // The type of an impl block, `Point` in `impl Display for Point<T>`, or
+    // the name of a trait
+    fn find_parent_class(&self, owner: Node, source: &str) -> Option<String> {
+        let name = match owner.kind() {
+            "impl_item" => owner.child_by_field_name("type")?,
+            _ => owner.child_by_field_name("name")?,
+        };
+        Some(common::simple_type_name(&common::get_node_text(
+            name, source,
+        )))
     }
 }
 
 impl LanguageExtractor for RustExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Function,
+                NodeType::Method,
+                NodeType::Class,
+                NodeType::Interface,
+                NodeType::Variable,
+                NodeType::Field,
+                NodeType::Macro,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+                Feature::Visibility,
+                Feature::Fields,
+                Feature::Implementations,
+                Feature::Tests,
+                Feature::Decorators,
+            ],
+        )
+    }
+
     fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
         let mut code_units = Vec::new();
 
@@ -102,20 +354,34 @@ impl LanguageExtractor for RustExtractor {
                     let end_line = node.end_position().row + 1;
                     let node_content = common::get_node_text(node, content);
 
-                    let is_method = self.is_inside_impl_block(node);
-                    let node_type = if is_method {
+                    let owner = self.find_impl_or_trait(node);
+                    let node_type = if owner.is_some() {
                         NodeType::Method
                     } else {
                         NodeType::Function
                     };
 
                     let mut metadata = HashMap::new();
-
-                    if is_method {
-                        if let Some(parent_class) = self.find_parent_class(node, content) {
-                            metadata.insert("parent_class".to_string(), parent_class);
+                    let mut parent_class = None;
+
+                    if let Some(owner) = owner {
+                        parent_class = self.find_parent_class(owner, content);
+                        // `impl Trait for Type` methods implement the trait's
+                        if let Some(implemented) = owner.child_by_field_name("trait") {
+                            metadata.insert(
+                                "implements".to_string(),
+                                common::simple_type_name(&common::get_node_text(
+                                    implemented,
+                                    content,
+                                )),
+                            );
                         }
                     }
+                    // Trait methods and functions of `extern` blocks
+                    // without a body
+                    if node.kind() == "function_signature_item" {
+                        metadata.insert("declaration".to_string(), "true".to_string());
+                    }
 
                     let mut code_node = common::create_node(
                         node_type,
@@ -125,6 +391,7 @@ impl LanguageExtractor for RustExtractor {
                         node_content,
                     );
 
+                    code_node.parent_class = parent_class;
                     for (key, value) in metadata {
                         code_node.add_metadata(key, value);
                     }
@@ -133,7 +400,7 @@ impl LanguageExtractor for RustExtractor {
                 }
             }
 
-            // Extract structs/classes
+            // Extract structs and enums
             let struct_nodes =
                 common::execute_query(queries::CLASS_QUERY, &tree, content.as_bytes(), "node");
 
@@ -143,7 +410,7 @@ impl LanguageExtractor for RustExtractor {
                     let end_line = node.end_position().row + 1;
                     let node_content = common::get_node_text(node, content);
 
-                    let code_node = common::create_node(
+                    let mut code_node = common::create_node(
                         NodeType::Class,
                         name,
                         file_path.to_str().unwrap_or(""),
@@ -151,9 +418,86 @@ impl LanguageExtractor for RustExtractor {
                         node_content,
                     );
 
+                    let derived = derives(node, content);
+                    if !derived.is_empty() {
+                        code_node.add_metadata("derives".to_string(), derived.join(","));
+                    }
+
                     code_units.push(code_node);
                 }
             }
+
+            // Extract traits as interfaces
+            let trait_nodes =
+                common::execute_query(queries::TRAIT_QUERY, &tree, content.as_bytes(), "node");
+
+            for node in trait_nodes {
+                if let Some(name) = self.find_node_name(node, content, &NodeType::Interface) {
+                    code_units.push(common::create_node(
+                        NodeType::Interface,
+                        name,
+                        file_path.to_str().unwrap_or(""),
+                        (node.start_position().row + 1, node.end_position().row + 1),
+                        common::get_node_text(node, content),
+                    ));
+                }
+            }
+
+            // Extract `macro_rules!` definitions
+            let macro_nodes =
+                common::execute_query(queries::MACRO_QUERY, &tree, content.as_bytes(), "node");
+
+            for node in macro_nodes {
+                let Some(name) = node.child_by_field_name("name") else {
+                    continue;
+                };
+                let mut code_node = common::create_node(
+                    NodeType::Macro,
+                    common::get_node_text(name, content),
+                    file_path.to_str().unwrap_or(""),
+                    (node.start_position().row + 1, node.end_position().row + 1),
+                    common::get_node_text(node, content),
+                );
+                if attributes(node, content)
+                    .iter()
+                    .any(|attribute| attribute == "macro_export")
+                {
+                    code_node.add_metadata("macro_export".to_string(), "true".to_string());
+                }
+                code_units.push(code_node);
+            }
+
+            // Extract fields and properties
+            code_units.extend(common::extract_fields(
+                queries::FIELD_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
+
+            // Extract module-level constants and globals
+            code_units.extend(common::extract_global_variables(
+                queries::GLOBAL_VARIABLE_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
+
+            // Extract the items wrapped by item-level macros, which the
+            // parser leaves as plain tokens
+            let mut invocations = Vec::new();
+            collect_item_macros(tree.root_node(), &mut invocations);
+            for invocation in invocations {
+                let Some((name, code)) = wrapped_items(invocation, content) else {
+                    continue;
+                };
+                for mut unit in self.extract_code_units(&code, file_path) {
+                    if !unit.metadata.contains_key("macro") {
+                        unit.add_metadata("macro".to_string(), name.clone());
+                    }
+                    code_units.push(unit);
+                }
+            }
         } else {
             warn!("Failed to parse Rust file: {:?}", file_path);
         }
@@ -166,7 +510,7 @@ impl LanguageExtractor for RustExtractor {
         content: &str,
         func_range: (usize, usize),
         _func_name: &str,
-    ) -> Vec<String> {
+    ) -> Vec<CallSite> {
         let mut calls = Vec::new();
 
         if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.rs")) {
@@ -178,9 +522,9 @@ impl LanguageExtractor for RustExtractor {
 
                 // Check if call is within function range
                 if call_line >= func_range.0 && call_line <= func_range.1 {
-                    let call_name = common::get_node_text(node, content);
-                    if !call_name.is_empty() {
-                        calls.push(call_name);
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
                     }
                 }
             }
@@ -189,36 +533,8 @@ impl LanguageExtractor for RustExtractor {
         calls
     }
 
-    fn extract_variable_references(
-        &self,
-        content: &str,
-        func_range: (usize, usize),
-        var_name: &str,
-    ) -> Vec<(usize, usize)> {
-        let mut references = Vec::new();
-
-        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.rs")) {
-            let reference_nodes = common::execute_query(
-                queries::REFERENCE_QUERY,
-                &tree,
-                content.as_bytes(),
-                "reference",
-            );
-
-            for node in reference_nodes {
-                let ref_line = node.start_position().row + 1;
-
-                // Check if reference is within function range
-                if ref_line >= func_range.0 && ref_line <= func_range.1 {
-                    let ref_name = common::get_node_text(node, content);
-                    if ref_name == var_name {
-                        references.push((ref_line, node.end_position().row + 1));
-                    }
-                }
-            }
-        }
-
-        references
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.rs"))
     }
 
     fn extract_imported_modules(&self, content: &str) -> Vec<String> {
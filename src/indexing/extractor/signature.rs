@@ -0,0 +1,112 @@
+use super::complexity::{collect_functions, function_of, parameter_list};
+use crate::code_graph::{CodeNode, Signature};
+use crate::indexing::extractor::common;
+use tree_sitter::{Node, Tree};
+
+// Parameters that take any number of arguments
+const VARIADIC_KINDS: &[&str] = &[
+    "list_splat_pattern",
+    "dictionary_splat_pattern",
+    "rest_pattern",
+    "spread_parameter",
+    "variadic_parameter",
+    "variadic_parameter_declaration",
+    "splat_parameter",
+    "hash_splat_parameter",
+    "block_parameter",
+];
+
+// Parameters that may be left out of a call
+const OPTIONAL_KINDS: &[&str] = &[
+    "default_parameter",
+    "typed_default_parameter",
+    "optional_parameter",
+    "optional_parameter_declaration",
+    "assignment_pattern",
+    "keyword_parameter",
+];
+
+// Markers in a parameter list that take no argument: Rust's `self`, the
+// bare `*` and `/` of Python, Java's explicit `this`
+const RECEIVER_KINDS: &[&str] = &[
+    "self_parameter",
+    "keyword_separator",
+    "positional_separator",
+    "receiver_parameter",
+];
+
+// The parameters a call passes arguments for, each with its type when the
+// declaration gives one; Go's `a, b int` is two parameters
+fn parameters<'a>(list: Node<'a>, source: &str) -> (Vec<(Node<'a>, Option<String>)>, bool) {
+    let mut found = Vec::new();
+    let mut variadic = false;
+    let mut cursor = list.walk();
+    for (index, parameter) in list.named_children(&mut cursor).enumerate() {
+        let kind = parameter.kind();
+        if kind.contains("comment") || RECEIVER_KINDS.contains(&kind) {
+            continue;
+        }
+        let text = common::get_node_text(parameter, source);
+        // Python's explicit receiver, and C's `(void)`
+        if (index == 0 && kind == "identifier" && matches!(text.as_str(), "self" | "cls"))
+            || text == "void"
+        {
+            continue;
+        }
+        let pattern_is_rest = parameter
+            .child_by_field_name("pattern")
+            .is_some_and(|pattern| pattern.kind() == "rest_pattern");
+        if VARIADIC_KINDS.contains(&kind) || pattern_is_rest {
+            variadic = true;
+            continue;
+        }
+
+        let parameter_type = parameter.child_by_field_name("type").map(|node| {
+            common::get_node_text(node, source)
+                .trim_start_matches(':')
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        let mut names = parameter.walk();
+        let count = parameter
+            .children_by_field_name("name", &mut names)
+            .count()
+            .max(1);
+        for _ in 0..count {
+            found.push((parameter, parameter_type.clone()));
+        }
+    }
+    (found, variadic)
+}
+
+// Record how a function can be called: `arity` is the number of parameters,
+// `min_arity` the number without a default, `variadic` whether it takes any
+// number beyond those, and `parameter_types` their types, `_` where not
+// declared
+pub fn annotate_signatures(tree: &Tree, source: &str, units: &mut [CodeNode]) {
+    let mut functions = Vec::new();
+    collect_functions(tree.root_node(), &mut functions);
+
+    for unit in units.iter_mut() {
+        let Some(list) = function_of(&functions, unit).and_then(parameter_list) else {
+            continue;
+        };
+        let (parameters, variadic) = parameters(list, source);
+        let required = parameters
+            .iter()
+            .filter(|(parameter, _)| !OPTIONAL_KINDS.contains(&parameter.kind()))
+            .count();
+        let types: Vec<String> = parameters
+            .iter()
+            .map(|(_, parameter_type)| parameter_type.as_deref().unwrap_or("_").to_string())
+            .collect();
+
+        unit.signature = Some(Signature {
+            arity: parameters.len(),
+            min_arity: required,
+            variadic,
+            parameter_types: types,
+        });
+    }
+}
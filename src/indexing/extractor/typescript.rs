@@ -1,11 +1,254 @@
 use crate::code_graph::{CodeNode, NodeType};
-use crate::indexing::extractor::{LanguageExtractor, common};
+use crate::indexing::extractor::{
+    CallSite, Feature, LanguageCapabilities, LanguageExtractor, common,
+};
 use crate::parsers::treesitter::queries::typescript as queries;
 use log::warn;
-use std::collections::HashMap;
 use std::path::Path;
 use tree_sitter::Node;
 
+// An `import` or re-exporting `export ... from` statement of an ES module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsImport {
+    // Module specifier without quotes: `./util`, `@app/http`, `react`
+    pub source: String,
+    // (name in the source module, name here): `default` for default imports,
+    // the exported name for re-exports
+    pub names: Vec<(String, String)>,
+    // `import * as ns` or `export * from`
+    pub all: bool,
+    pub reexport: bool,
+    pub line: usize,
+}
+
+// The import and export statements of an ES module (JavaScript or TypeScript)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EsModule {
+    pub imports: Vec<EsImport>,
+    // `export { local as exported }` without a source
+    pub exports: Vec<(String, String)>,
+    // Local name of the default export, when it has one
+    pub default_export: Option<String>,
+}
+
+fn string_value(node: Node, source: &str) -> String {
+    common::get_node_text(node, source)
+        .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .to_string()
+}
+
+// (name, alias) pairs of `{ a, b as c }` in imports and exports
+fn specifiers(clause: Node, source: &str) -> Vec<(String, String)> {
+    let mut cursor = clause.walk();
+    clause
+        .named_children(&mut cursor)
+        .filter(|child| matches!(child.kind(), "import_specifier" | "export_specifier"))
+        .filter_map(|specifier| {
+            let name = common::get_node_text(specifier.child_by_field_name("name")?, source);
+            let alias = specifier
+                .child_by_field_name("alias")
+                .map(|alias| common::get_node_text(alias, source))
+                .unwrap_or_else(|| name.clone());
+            Some((name, alias))
+        })
+        .collect()
+}
+
+fn declared_name(declaration: Node, source: &str) -> Option<String> {
+    declaration
+        .child_by_field_name("name")
+        .map(|name| common::get_node_text(name, source))
+}
+
+// Parse the module statements of a JavaScript or TypeScript file; the file
+// path selects the grammar
+pub fn es_module_statements(content: &str, file_path: &Path) -> EsModule {
+    let mut module = EsModule::default();
+    let Some((tree, _)) = common::parse_with_tree_sitter(content, file_path) else {
+        return module;
+    };
+
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor) {
+        let line = statement.start_position().row + 1;
+        let source_field = statement.child_by_field_name("source");
+        let mut inner = statement.walk();
+        let children: Vec<Node> = statement.named_children(&mut inner).collect();
+
+        match (statement.kind(), source_field) {
+            ("import_statement", Some(source)) => {
+                let mut import = EsImport {
+                    source: string_value(source, content),
+                    names: Vec::new(),
+                    all: false,
+                    reexport: false,
+                    line,
+                };
+                for clause in children.iter().filter(|c| c.kind() == "import_clause") {
+                    let mut clause_cursor = clause.walk();
+                    for part in clause.named_children(&mut clause_cursor) {
+                        match part.kind() {
+                            "identifier" => import.names.push((
+                                "default".to_string(),
+                                common::get_node_text(part, content),
+                            )),
+                            "namespace_import" => import.all = true,
+                            "named_imports" => import.names.extend(specifiers(part, content)),
+                            _ => {}
+                        }
+                    }
+                }
+                module.imports.push(import);
+            }
+            ("export_statement", Some(source)) => {
+                let clause = children.iter().find(|c| c.kind() == "export_clause");
+                module.imports.push(EsImport {
+                    source: string_value(source, content),
+                    names: clause
+                        .map(|clause| specifiers(*clause, content))
+                        .unwrap_or_default(),
+                    // `export * from` and `export * as ns from`
+                    all: clause.is_none(),
+                    reexport: true,
+                    line,
+                });
+            }
+            ("export_statement", None) => {
+                let is_default = common::get_node_text(statement, content)
+                    .trim_start_matches("export")
+                    .trim_start()
+                    .starts_with("default");
+                if let Some(clause) = children.iter().find(|c| c.kind() == "export_clause") {
+                    for (local, exported) in specifiers(*clause, content) {
+                        if exported == "default" {
+                            module.default_export = Some(local.clone());
+                        }
+                        module.exports.push((local, exported));
+                    }
+                } else if is_default {
+                    let value = statement
+                        .child_by_field_name("declaration")
+                        .or_else(|| statement.child_by_field_name("value"));
+                    module.default_export = value.and_then(|value| match value.kind() {
+                        "identifier" => Some(common::get_node_text(value, content)),
+                        _ => declared_name(value, content),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    module
+}
+
+// A field of a GraphQL resolver map, such as `user` in
+// `{ Query: { user: (parent, args) => ... } }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolverMapEntry {
+    pub type_name: String,
+    pub field: String,
+    pub line: usize,
+    // The function a field names (`users: listUsers`), when it is not
+    // defined inline
+    pub function: Option<String>,
+}
+
+// Object literals under a schema type name are resolver maps; `satisfies`
+// and `as` annotations around them are looked through
+fn resolver_object(node: Node) -> Option<Node> {
+    match node.kind() {
+        "object" => Some(node),
+        "satisfies_expression" | "as_expression" | "parenthesized_expression" => {
+            resolver_object(node.named_child(0)?)
+        }
+        _ => None,
+    }
+}
+
+fn collect_resolver_maps(
+    node: Node,
+    source: &str,
+    type_names: &[&str],
+    entries: &mut Vec<ResolverMapEntry>,
+) {
+    let (key, value) = match node.kind() {
+        "pair" => (
+            node.child_by_field_name("key"),
+            node.child_by_field_name("value"),
+        ),
+        "variable_declarator" => (
+            node.child_by_field_name("name"),
+            node.child_by_field_name("value"),
+        ),
+        _ => (None, None),
+    };
+    let type_name = key.map(|key| string_value(key, source));
+    if let (Some(type_name), Some(object)) = (type_name, value.and_then(resolver_object))
+        && type_names.contains(&type_name.as_str())
+    {
+        let mut cursor = object.walk();
+        for member in object.named_children(&mut cursor) {
+            let line = member.start_position().row + 1;
+            let (field, function) = match member.kind() {
+                "pair" => {
+                    let Some(field) = member.child_by_field_name("key") else {
+                        continue;
+                    };
+                    let function =
+                        member
+                            .child_by_field_name("value")
+                            .and_then(|value| match value.kind() {
+                                "identifier" => Some(common::get_node_text(value, source)),
+                                "member_expression" => value
+                                    .child_by_field_name("property")
+                                    .map(|property| common::get_node_text(property, source)),
+                                _ => None,
+                            });
+                    (string_value(field, source), function)
+                }
+                "method_definition" => match member.child_by_field_name("name") {
+                    Some(name) => (string_value(name, source), None),
+                    None => continue,
+                },
+                "shorthand_property_identifier" => {
+                    let name = common::get_node_text(member, source);
+                    (name.clone(), Some(name))
+                }
+                _ => continue,
+            };
+            entries.push(ResolverMapEntry {
+                type_name: type_name.clone(),
+                field,
+                line,
+                function,
+            });
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_resolver_maps(child, source, type_names, entries);
+    }
+}
+
+// Fields of the GraphQL resolver maps in a JavaScript or TypeScript file:
+// object literals keyed by one of `type_names`, whether nested in a
+// `resolvers` object or declared on their own as `const Query = { ... }`
+pub fn resolver_map_entries(
+    content: &str,
+    file_path: &Path,
+    type_names: &[&str],
+) -> Vec<ResolverMapEntry> {
+    let mut entries = Vec::new();
+    if let Some((tree, _)) = common::parse_with_tree_sitter(content, file_path) {
+        collect_resolver_maps(tree.root_node(), content, type_names, &mut entries);
+    }
+    entries
+}
+
 pub struct TypeScriptExtractor;
 
 impl TypeScriptExtractor {
@@ -65,10 +308,10 @@ impl TypeScriptExtractor {
             NodeType::Class => {
                 // First check for standard class declaration identifier
                 for i in 0..node.named_child_count() {
-                    if let Some(child) = node.named_child(i) {
-                        if child.kind() == "identifier" {
-                            return Some(common::get_node_text(child, source));
-                        }
+                    if let Some(child) = node.named_child(i)
+                        && matches!(child.kind(), "identifier" | "type_identifier")
+                    {
+                        return Some(common::get_node_text(child, source));
                     }
                 }
 
@@ -197,21 +440,13 @@ impl TypeScriptExtractor {
 
                 return Some("AnonymousClass".to_string());
             }
-            NodeType::Interface => {
-                for i in 0..node.named_child_count() {
-                    if let Some(child) = node.named_child(i) {
-                        if child.kind() == "identifier" {
-                            return Some(common::get_node_text(child, source));
-                        }
-                    }
-                }
-            }
-            NodeType::TypeDefinition => {
+            // Interfaces and type aliases are named by type identifiers
+            NodeType::Interface | NodeType::TypeDefinition => {
                 for i in 0..node.named_child_count() {
-                    if let Some(child) = node.named_child(i) {
-                        if child.kind() == "identifier" {
-                            return Some(common::get_node_text(child, source));
-                        }
+                    if let Some(child) = node.named_child(i)
+                        && matches!(child.kind(), "identifier" | "type_identifier")
+                    {
+                        return Some(common::get_node_text(child, source));
                     }
                 }
             }
@@ -230,6 +465,28 @@ impl TypeScriptExtractor {
         None
     }
 
+    // Simple names of the types in a class's `implements` clause
+    fn implemented_interfaces(&self, node: Node, source: &str) -> Vec<String> {
+        let mut interfaces = Vec::new();
+        let mut cursor = node.walk();
+        for heritage in node
+            .named_children(&mut cursor)
+            .filter(|child| child.kind() == "class_heritage")
+        {
+            let mut cursor = heritage.walk();
+            for clause in heritage
+                .named_children(&mut cursor)
+                .filter(|child| child.kind() == "implements_clause")
+            {
+                let mut cursor = clause.walk();
+                interfaces.extend(clause.named_children(&mut cursor).map(|interface| {
+                    common::simple_type_name(&common::get_node_text(interface, source))
+                }));
+            }
+        }
+        interfaces
+    }
+
     fn find_parent_class(&self, method_node: Node, source: &str) -> Option<String> {
         let mut current = method_node;
         let mut parent_iter = current.parent();
@@ -258,6 +515,31 @@ impl TypeScriptExtractor {
 }
 
 impl LanguageExtractor for TypeScriptExtractor {
+    fn capabilities(&self) -> LanguageCapabilities {
+        LanguageCapabilities::new(
+            &[
+                NodeType::Function,
+                NodeType::Method,
+                NodeType::Class,
+                NodeType::Interface,
+                NodeType::TypeDefinition,
+                NodeType::Variable,
+                NodeType::Field,
+            ],
+            &[
+                Feature::Calls,
+                Feature::Imports,
+                Feature::References,
+                Feature::GlobalVariables,
+                Feature::Visibility,
+                Feature::Fields,
+                Feature::Implementations,
+                Feature::Tests,
+                Feature::Decorators,
+            ],
+        )
+    }
+
     fn extract_code_units(&self, content: &str, file_path: &Path) -> Vec<CodeNode> {
         let mut code_units = Vec::new();
 
@@ -294,12 +576,6 @@ impl LanguageExtractor for TypeScriptExtractor {
                     let end_line = node.end_position().row + 1;
                     let node_content = common::get_node_text(node, content);
 
-                    let mut metadata = HashMap::new();
-
-                    if let Some(parent_class) = self.find_parent_class(node, content) {
-                        metadata.insert("parent_class".to_string(), parent_class);
-                    }
-
                     let mut code_node = common::create_node(
                         NodeType::Method,
                         name,
@@ -307,9 +583,10 @@ impl LanguageExtractor for TypeScriptExtractor {
                         (start_line, end_line),
                         node_content,
                     );
-
-                    for (key, value) in metadata {
-                        code_node.add_metadata(key, value);
+                    code_node.parent_class = self.find_parent_class(node, content);
+                    // Interface and abstract methods
+                    if node.kind() != "method_definition" {
+                        code_node.add_metadata("declaration".to_string(), "true".to_string());
                     }
 
                     code_units.push(code_node);
@@ -326,7 +603,7 @@ impl LanguageExtractor for TypeScriptExtractor {
                     let end_line = node.end_position().row + 1;
                     let node_content = common::get_node_text(node, content);
 
-                    let code_node = common::create_node(
+                    let mut code_node = common::create_node(
                         NodeType::Class,
                         name,
                         file_path.to_str().unwrap_or(""),
@@ -334,6 +611,11 @@ impl LanguageExtractor for TypeScriptExtractor {
                         node_content,
                     );
 
+                    let interfaces = self.implemented_interfaces(node, content);
+                    if !interfaces.is_empty() {
+                        code_node.add_metadata("implements".to_string(), interfaces.join(","));
+                    }
+
                     code_units.push(code_node);
                 }
             }
@@ -381,6 +663,30 @@ impl LanguageExtractor for TypeScriptExtractor {
                     code_units.push(code_node);
                 }
             }
+
+            // Extract fields and properties
+            code_units.extend(common::extract_fields(
+                queries::FIELD_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
+
+            // Extract module-level constants and globals
+            code_units.extend(common::extract_global_variables(
+                queries::GLOBAL_VARIABLE_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
+
+            // Test blocks have no declaration of their own
+            code_units.extend(common::extract_test_blocks(
+                queries::TEST_QUERY,
+                &tree,
+                content,
+                file_path,
+            ));
         } else {
             warn!("Failed to parse TypeScript file: {:?}", file_path);
         }
@@ -393,7 +699,7 @@ impl LanguageExtractor for TypeScriptExtractor {
         content: &str,
         func_range: (usize, usize),
         _func_name: &str,
-    ) -> Vec<String> {
+    ) -> Vec<CallSite> {
         let mut calls = Vec::new();
 
         if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.ts")) {
@@ -405,9 +711,9 @@ impl LanguageExtractor for TypeScriptExtractor {
 
                 // Check if call is within function range
                 if call_line >= func_range.0 && call_line <= func_range.1 {
-                    let call_name = common::get_node_text(node, content);
-                    if !call_name.is_empty() {
-                        calls.push(call_name);
+                    let call_site = common::create_call_site(node, content);
+                    if !call_site.name.is_empty() {
+                        calls.push(call_site);
                     }
                 }
             }
@@ -416,36 +722,8 @@ impl LanguageExtractor for TypeScriptExtractor {
         calls
     }
 
-    fn extract_variable_references(
-        &self,
-        content: &str,
-        func_range: (usize, usize),
-        var_name: &str,
-    ) -> Vec<(usize, usize)> {
-        let mut references = Vec::new();
-
-        if let Some((tree, _)) = common::parse_with_tree_sitter(content, Path::new("temp.ts")) {
-            let reference_nodes = common::execute_query(
-                queries::REFERENCE_QUERY,
-                &tree,
-                content.as_bytes(),
-                "reference",
-            );
-
-            for node in reference_nodes {
-                let ref_line = node.start_position().row + 1;
-
-                // Check if reference is within function range
-                if ref_line >= func_range.0 && ref_line <= func_range.1 {
-                    let ref_name = common::get_node_text(node, content);
-                    if ref_name == var_name {
-                        references.push((ref_line, node.end_position().row + 1));
-                    }
-                }
-            }
-        }
-
-        references
+    fn extract_references(&self, content: &str) -> Vec<(String, usize)> {
+        common::extract_references(queries::REFERENCE_QUERY, content, Path::new("temp.ts"))
     }
 
     fn extract_imported_modules(&self, content: &str) -> Vec<String> {
@@ -0,0 +1,311 @@
+use crate::code_graph::CodeGraph;
+use crate::indexing::config::IndexConfig;
+use crate::indexing::provider::FileProvider;
+use crate::utils::encoding::decode_source;
+use git2::{BlameOptions, Commit, ObjectType, Oid, Repository, Sort, TreeWalkMode, TreeWalkResult};
+use log::{debug, info, trace};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Files of a git repository: the tracked files of the working tree, or the
+// tree of a revision read straight from the object database, so history can
+// be indexed without a checkout. Paths are reported under `root_path` either way.
+pub struct GitProvider {
+    root_path: PathBuf,
+    // Location of `root_path` inside the repository
+    prefix: PathBuf,
+    // Repositories cannot be shared between threads
+    repo: Mutex<Repository>,
+    // Blob of every file at the selected revision; None reads the working tree
+    blobs: Option<BTreeMap<PathBuf, Oid>>,
+}
+
+impl GitProvider {
+    // Open the repository containing `root_path`, selecting a revision such
+    // as `HEAD~5` or a branch name, or the tracked files of the working tree
+    pub fn open(root_path: &Path, revision: Option<&str>) -> io::Result<Self> {
+        let repo = Repository::discover(root_path).map_err(io::Error::other)?;
+        let prefix = repo_prefix(&repo, root_path)?;
+
+        let blobs = match revision {
+            Some(revision) => {
+                let tree = repo
+                    .revparse_single(revision)
+                    .and_then(|object| object.peel_to_tree())
+                    .map_err(io::Error::other)?;
+                info!("Indexing git revision {} ({})", revision, tree.id());
+
+                let mut blobs = BTreeMap::new();
+                tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+                    if entry.kind() == Some(ObjectType::Blob)
+                        && let Some(name) = entry.name()
+                    {
+                        blobs.insert(Path::new(dir).join(name), entry.id());
+                    }
+                    TreeWalkResult::Ok
+                })
+                .map_err(io::Error::other)?;
+                Some(blobs)
+            }
+            None => None,
+        };
+
+        Ok(GitProvider {
+            root_path: root_path.to_path_buf(),
+            prefix,
+            repo: Mutex::new(repo),
+            blobs,
+        })
+    }
+
+    // Path reported for a file at `relative` inside the repository, if it is under the root
+    fn reported_path(&self, relative: &Path) -> Option<PathBuf> {
+        relative
+            .strip_prefix(&self.prefix)
+            .ok()
+            .map(|path| self.root_path.join(path))
+    }
+
+    fn tracked_files(&self) -> io::Result<Vec<PathBuf>> {
+        let repo = self
+            .repo
+            .lock()
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        let index = repo.index().map_err(io::Error::other)?;
+
+        let mut files = Vec::new();
+        for entry in index.iter() {
+            let relative = PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned());
+            let Some(path) = self.reported_path(&relative) else {
+                continue;
+            };
+            // Deleted in the working tree but not yet staged
+            if !path.is_file() {
+                trace!("Skipping tracked file missing on disk: {:?}", path);
+                continue;
+            }
+            files.push(path);
+        }
+
+        Ok(files)
+    }
+}
+
+// Location of `root_path` inside the working tree of `repo`
+fn repo_prefix(repo: &Repository, root_path: &Path) -> io::Result<PathBuf> {
+    match repo.workdir() {
+        Some(workdir) => {
+            let root = fs::canonicalize(root_path)?;
+            let workdir = fs::canonicalize(workdir)?;
+            Ok(root
+                .strip_prefix(&workdir)
+                .map(Path::to_path_buf)
+                .unwrap_or_default())
+        }
+        None => Ok(PathBuf::new()),
+    }
+}
+
+impl FileProvider for GitProvider {
+    fn list_files(&self) -> io::Result<Vec<PathBuf>> {
+        let files = match &self.blobs {
+            Some(blobs) => blobs
+                .keys()
+                .filter_map(|relative| self.reported_path(relative))
+                .collect(),
+            None => self.tracked_files()?,
+        };
+        debug!("Found {} files in git", files.len());
+
+        Ok(files)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        self.read_file_with_encoding(path)
+            .map(|(content, _)| content)
+    }
+
+    fn read_file_with_encoding(&self, path: &Path) -> io::Result<(String, &'static str)> {
+        let Some(blobs) = &self.blobs else {
+            return fs::read(path).map(|bytes| decode_source(&bytes));
+        };
+
+        let relative = path
+            .strip_prefix(&self.root_path)
+            .map(|path| self.prefix.join(path))
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("File outside the git root: {:?}", path),
+                )
+            })?;
+        let oid = blobs.get(&relative).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("File not in git revision: {:?}", path),
+            )
+        })?;
+
+        let repo = self
+            .repo
+            .lock()
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        let blob = repo.find_blob(*oid).map_err(io::Error::other)?;
+        Ok(decode_source(blob.content()))
+    }
+
+    fn roots(&self) -> Vec<PathBuf> {
+        vec![self.root_path.clone()]
+    }
+}
+
+// Commit a revision such as `HEAD` or a branch name resolves to in the
+// repository containing `root_path`
+pub fn resolve_commit(root_path: &Path, revision: &str) -> io::Result<String> {
+    let repo = Repository::discover(root_path).map_err(io::Error::other)?;
+    let commit = repo
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(io::Error::other)?;
+    Ok(commit.id().to_string())
+}
+
+// Annotate nodes under `root_path` with the last commit that touched their
+// lines (`git_commit`, `git_author`, `git_modified` as a unix timestamp) and
+// with change frequencies over the window ending at the indexed commit:
+// `git_changes` counts distinct commits behind the node's current lines and
+// `git_file_changes` counts commits that modified its file
+pub fn add_git_history(
+    graph: &mut CodeGraph,
+    root_path: &Path,
+    config: &IndexConfig,
+) -> io::Result<()> {
+    let Some(days) = config.git_history_days else {
+        return Ok(());
+    };
+    let repo = Repository::discover(root_path).map_err(io::Error::other)?;
+    let prefix = repo_prefix(&repo, root_path)?;
+    let head = repo
+        .revparse_single(config.git_revision.as_deref().unwrap_or("HEAD"))
+        .and_then(|object| object.peel_to_commit())
+        .map_err(io::Error::other)?;
+    let since = head.time().seconds() - i64::from(days) * 24 * 60 * 60;
+    info!(
+        "Annotating nodes with git history since {} ({} days before {})",
+        since,
+        days,
+        head.id()
+    );
+
+    let file_changes = file_change_counts(&repo, &head, since).map_err(io::Error::other)?;
+
+    let mut nodes_by_file: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for node in graph.all_nodes() {
+        nodes_by_file
+            .entry(node.file_path.clone())
+            .or_default()
+            .push(node.id.clone());
+    }
+
+    let mut commits: HashMap<Oid, (String, i64)> = HashMap::new();
+    let mut annotated = 0;
+    for (file_path, nodes) in nodes_by_file {
+        let Ok(relative) = Path::new(&file_path).strip_prefix(root_path) else {
+            continue;
+        };
+        let relative = prefix.join(relative);
+
+        let mut options = BlameOptions::new();
+        options.newest_commit(head.id());
+        let blame = match repo.blame_file(&relative, Some(&mut options)) {
+            Ok(blame) => blame,
+            Err(e) => {
+                trace!("No git history for {:?}: {}", relative, e.message());
+                continue;
+            }
+        };
+        let file_change_count = file_changes.get(&relative).copied().unwrap_or(0);
+
+        for id in nodes {
+            let Some((start, end)) = graph.get_node(&id).map(|node| node.line_range) else {
+                continue;
+            };
+            // Newest commit behind the node's lines, and the distinct commits
+            // within the window
+            let mut last: Option<(Oid, i64)> = None;
+            let mut recent = HashSet::new();
+            for line in start..=end {
+                let Some(hunk) = blame.get_line(line) else {
+                    continue;
+                };
+                let oid = hunk.final_commit_id();
+                let time = match commits.entry(oid) {
+                    Entry::Occupied(entry) => entry.get().1,
+                    Entry::Vacant(entry) => {
+                        let commit = repo.find_commit(oid).map_err(io::Error::other)?;
+                        let author = commit.author().name().unwrap_or_default().to_string();
+                        entry.insert((author, commit.time().seconds())).1
+                    }
+                };
+                if time >= since {
+                    recent.insert(oid);
+                }
+                if last.is_none_or(|(_, newest)| time > newest) {
+                    last = Some((oid, time));
+                }
+            }
+
+            let (Some((oid, time)), Some(node)) = (last, graph.get_node_mut(&id)) else {
+                continue;
+            };
+            node.add_metadata("git_commit".to_string(), oid.to_string());
+            node.add_metadata("git_author".to_string(), commits[&oid].0.clone());
+            node.add_metadata("git_modified".to_string(), time.to_string());
+            node.add_metadata("git_changes".to_string(), recent.len().to_string());
+            node.add_metadata(
+                "git_file_changes".to_string(),
+                file_change_count.to_string(),
+            );
+            annotated += 1;
+        }
+    }
+    debug!("Annotated {} nodes with git history", annotated);
+
+    Ok(())
+}
+
+// Number of commits since `since` that changed each repository path,
+// walking back from `head` and comparing every commit with its first parent
+fn file_change_counts(
+    repo: &Repository,
+    head: &Commit,
+    since: i64,
+) -> Result<HashMap<PathBuf, usize>, git2::Error> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TIME)?;
+    revwalk.push(head.id())?;
+
+    let mut counts: HashMap<PathBuf, usize> = HashMap::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.time().seconds() < since {
+            break;
+        }
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+        for delta in diff.deltas() {
+            if let Some(path) = delta.new_file().path() {
+                *counts.entry(path.to_path_buf()).or_default() += 1;
+            }
+        }
+    }
+
+    Ok(counts)
+}
@@ -0,0 +1,211 @@
+use crate::code_graph::{
+    CodeGraph, CodeNode, NodeType, Relationship, RelationshipType, provenance,
+};
+use crate::indexing::config::IndexConfig;
+use crate::indexing::extractor::common::{create_node, disambiguate_node_id};
+use log::{debug, info};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+// Give a node an ID from the configured scheme that no other node has yet
+pub(crate) fn add_unique_node(
+    graph: &mut CodeGraph,
+    mut node: CodeNode,
+    config: &IndexConfig,
+) -> String {
+    let base_id = config.node_id(&node);
+    node.id = base_id.clone();
+    let mut occurrence = 1;
+    while graph.get_node(&node.id).is_some() {
+        node.id = disambiguate_node_id(&base_id, occurrence);
+        occurrence += 1;
+    }
+    let id = node.id.clone();
+    graph.add_node(node);
+    id
+}
+
+fn contains(from: &str, to: &str) -> Relationship {
+    Relationship::new(RelationshipType::Contains, from.to_string(), to.to_string())
+        .with_provenance(provenance::DIRECTORY_HIERARCHY)
+}
+
+// IDs of the Directory nodes above a file, innermost first
+fn ancestor_directories<'a>(
+    file_path: &str,
+    directory_ids: &'a BTreeMap<PathBuf, String>,
+) -> Vec<&'a String> {
+    Path::new(file_path)
+        .ancestors()
+        .skip(1)
+        .map_while(|dir| directory_ids.get(dir))
+        .collect()
+}
+
+// Add a File node per indexed file and a Directory node per directory down
+// from the deepest directory shared by all files, joined by Contains edges
+// into a tree above the top-level symbols of each file. Relationships
+// between symbols of different files are rolled up into edges of the same
+// type between their files and between every pair of their directories
+// below the one they share, with the number of underlying edges as `count`.
+pub fn add_directory_hierarchy(graph: &mut CodeGraph, config: &IndexConfig) {
+    info!("Building the directory hierarchy...");
+
+    // Top-level symbols of every file: the ones no other symbol contains
+    let mut files: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut file_of: HashMap<String, String> = HashMap::new();
+    let mut last_lines: HashMap<String, usize> = HashMap::new();
+    let mut repos: HashMap<String, Option<String>> = HashMap::new();
+    for node in graph.all_nodes() {
+        if node.file_path.is_empty()
+            || matches!(
+                node.node_type,
+                NodeType::Dependency | NodeType::File | NodeType::Directory
+            )
+        {
+            continue;
+        }
+        let contained = graph
+            .incoming_relationships(&node.id)
+            .iter()
+            .any(|rel| rel.relationship_type == RelationshipType::Contains);
+        let top_level = files.entry(node.file_path.clone()).or_default();
+        if !contained {
+            top_level.push(node.id.clone());
+        }
+        file_of.insert(node.id.clone(), node.file_path.clone());
+        let last_line = last_lines.entry(node.file_path.clone()).or_default();
+        *last_line = (*last_line).max(node.line_range.1);
+        repos.insert(node.file_path.clone(), node.repo.clone());
+    }
+    if files.is_empty() {
+        return;
+    }
+
+    let mut root = PathBuf::from(files.keys().next().map_or("", |path| path.as_str()));
+    root.pop();
+    for file in files.keys() {
+        while !Path::new(file).starts_with(&root) && root.pop() {}
+    }
+
+    let mut relationships = Vec::new();
+    let mut directory_ids: BTreeMap<PathBuf, String> = BTreeMap::new();
+    let mut file_ids: HashMap<String, String> = HashMap::new();
+    for (file_path, top_level) in &files {
+        let path = Path::new(file_path);
+        let name = path.file_name().map_or(file_path.clone(), |name| {
+            name.to_string_lossy().into_owned()
+        });
+        let mut node = create_node(
+            NodeType::File,
+            name,
+            file_path,
+            (1, last_lines[file_path].max(1)),
+            String::new(),
+        );
+        node.repo = repos[file_path].clone();
+        node.language = crate::parsers::detect_language(path);
+        let file_id = add_unique_node(graph, node, config);
+        for id in top_level {
+            relationships.push(contains(&file_id, id));
+        }
+
+        // Directories from the file's own up to the root, each linked to
+        // the child it was reached from
+        let mut child = file_id.clone();
+        let mut directory = path.parent().map(Path::to_path_buf);
+        while let Some(dir) = directory {
+            if !dir.starts_with(&root) {
+                break;
+            }
+            // Its ancestors are linked already
+            if let Some(dir_id) = directory_ids.get(&dir) {
+                relationships.push(contains(dir_id, &child));
+                break;
+            }
+
+            let name = match dir.strip_prefix(&root) {
+                Ok(relative) if !relative.as_os_str().is_empty() => {
+                    relative.to_string_lossy().into_owned()
+                }
+                _ => dir
+                    .file_name()
+                    .map_or(".".to_string(), |name| name.to_string_lossy().into_owned()),
+            };
+            let mut node = create_node(
+                NodeType::Directory,
+                name,
+                &dir.to_string_lossy(),
+                (0, 0),
+                String::new(),
+            );
+            node.repo = repos[file_path].clone();
+            let dir_id = add_unique_node(graph, node, config);
+            directory_ids.insert(dir.clone(), dir_id.clone());
+            relationships.push(contains(&dir_id, &child));
+
+            child = dir_id;
+            directory = dir.parent().map(Path::to_path_buf);
+        }
+        file_ids.insert(file_path.clone(), file_id);
+    }
+    debug!(
+        "Added {} directory and {} file nodes",
+        directory_ids.len(),
+        file_ids.len()
+    );
+
+    // Relationships between files, counted per type at file and at
+    // directory granularity
+    let mut rolled_up: BTreeMap<(RelationshipType, String, String), usize> = BTreeMap::new();
+    for rel in graph.all_relationships() {
+        if rel.relationship_type == RelationshipType::Contains {
+            continue;
+        }
+        let (Some(from_file), Some(to_file)) = (file_of.get(&rel.from_id), file_of.get(&rel.to_id))
+        else {
+            continue;
+        };
+        if from_file == to_file {
+            continue;
+        }
+        *rolled_up
+            .entry((
+                rel.relationship_type.clone(),
+                file_ids[from_file].clone(),
+                file_ids[to_file].clone(),
+            ))
+            .or_default() += 1;
+
+        // Every directory above one end but not the other, so the edge
+        // counts at each granularity up to the shared ancestor
+        let from_dirs = ancestor_directories(from_file, &directory_ids);
+        let to_dirs = ancestor_directories(to_file, &directory_ids);
+        for from_dir in from_dirs.iter().filter(|dir| !to_dirs.contains(dir)) {
+            for to_dir in to_dirs.iter().filter(|dir| !from_dirs.contains(dir)) {
+                *rolled_up
+                    .entry((
+                        rel.relationship_type.clone(),
+                        (*from_dir).clone(),
+                        (*to_dir).clone(),
+                    ))
+                    .or_default() += 1;
+            }
+        }
+    }
+    for ((relationship_type, from, to), count) in rolled_up {
+        relationships.push(
+            Relationship::new(relationship_type, from, to)
+                .with_metadata("count".to_string(), count.to_string())
+                .with_provenance(provenance::DIRECTORY_HIERARCHY),
+        );
+    }
+
+    info!(
+        "Adding {} directory hierarchy relationships",
+        relationships.len()
+    );
+    for relationship in relationships {
+        graph.add_relationship(relationship);
+    }
+}
@@ -0,0 +1,290 @@
+use crate::code_graph::{CodeGraph, NodeType, Relationship, RelationshipType, provenance};
+use crate::indexing::config::IndexConfig;
+use crate::indexing::external::{UnresolvedReference, add_external_symbols};
+use crate::indexing::extractor::common::create_node;
+use crate::indexing::extractor::{CInclude, c_includes};
+use crate::indexing::hierarchy::add_unique_node;
+use crate::indexing::provider::FileProvider;
+use log::{debug, info, trace, warn};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+fn is_c_family(path: &Path) -> bool {
+    crate::parsers::detect_language(path)
+        .is_some_and(|language| language == "c" || language == "cpp")
+}
+
+fn is_header(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext, "h" | "hh" | "hpp" | "hxx"))
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+// The C and C++ files of a codebase, for resolving `#include` directives the
+// way a compiler searches for them
+struct SourceTree<'a> {
+    files: HashSet<PathBuf>,
+    include_dirs: &'a [PathBuf],
+}
+
+impl SourceTree<'_> {
+    // Quoted includes are searched next to the including file first; then
+    // both kinds are searched in the include directories, and finally a
+    // single file of the codebase ending with the included path is taken
+    fn resolve(&self, include: &CInclude, includer: &Path) -> Option<PathBuf> {
+        let wanted = Path::new(&include.path);
+        if !include.system
+            && let Some(dir) = includer.parent()
+        {
+            let candidate = normalize(&dir.join(wanted));
+            if self.files.contains(&candidate) {
+                return Some(candidate);
+            }
+        }
+
+        for dir in self.include_dirs {
+            if dir.is_absolute() {
+                let candidate = normalize(&dir.join(wanted));
+                if self.files.contains(&candidate) {
+                    return Some(candidate);
+                }
+                continue;
+            }
+            let suffix = dir.join(wanted);
+            let mut matches = self.files.iter().filter(|file| file.ends_with(&suffix));
+            if let Some(found) = matches.next() {
+                return Some(found.clone());
+            }
+        }
+
+        let mut matches = self.files.iter().filter(|file| file.ends_with(wanted));
+        match (matches.next(), matches.next()) {
+            (Some(found), None) => Some(found.clone()),
+            _ => None,
+        }
+    }
+}
+
+// Link C and C++ files to the headers they `#include` with file-level
+// Imports edges, adding File nodes where the hierarchy pass has not, and
+// link functions defined in source files to their prototypes in the headers
+// those files include with Implements edges
+pub fn add_include_relationships(
+    provider: &dyn FileProvider,
+    graph: &mut CodeGraph,
+    config: &IndexConfig,
+) {
+    let paths = provider.list_files().unwrap_or_else(|e| {
+        warn!("Failed to list files for include resolution: {}", e);
+        Vec::new()
+    });
+    let tree = SourceTree {
+        files: paths.into_iter().filter(|path| is_c_family(path)).collect(),
+        include_dirs: &config.include_dirs,
+    };
+    if tree.files.is_empty() {
+        return;
+    }
+    info!("Resolving includes of {} C/C++ files...", tree.files.len());
+
+    // Included files of every file, with the directive that names each
+    let mut includes: BTreeMap<PathBuf, Vec<(PathBuf, CInclude)>> = BTreeMap::new();
+    let mut unresolved_includes: Vec<(PathBuf, CInclude)> = Vec::new();
+    let mut line_counts: HashMap<PathBuf, usize> = HashMap::new();
+    for file in &tree.files {
+        let content = match provider.read_file(file) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Failed to read {:?}: {}", file, e);
+                continue;
+            }
+        };
+        line_counts.insert(file.clone(), content.lines().count().max(1));
+        for include in c_includes(&content) {
+            match tree.resolve(&include, file) {
+                Some(target) if target != *file => {
+                    trace!("{:?} includes {:?}", file, target);
+                    includes
+                        .entry(file.clone())
+                        .or_default()
+                        .push((target, include));
+                }
+                Some(_) => {}
+                None => {
+                    trace!("Unresolved include {} in {:?}", include.path, file);
+                    unresolved_includes.push((file.clone(), include));
+                }
+            }
+        }
+    }
+
+    let mut file_ids: HashMap<String, String> = graph
+        .find_nodes_by_type(&NodeType::File)
+        .into_iter()
+        .map(|node| (node.file_path.clone(), node.id.clone()))
+        .collect();
+    let mut file_id = |graph: &mut CodeGraph, path: &Path| -> String {
+        let key = path.to_string_lossy().into_owned();
+        if let Some(id) = file_ids.get(&key) {
+            return id.clone();
+        }
+        let name = path
+            .file_name()
+            .map_or(key.clone(), |name| name.to_string_lossy().into_owned());
+        let lines = line_counts.get(path).copied().unwrap_or(1);
+        let mut node = create_node(NodeType::File, name, &key, (1, lines), String::new());
+        node.repo = provider.repo_for(path);
+        node.language = crate::parsers::detect_language(path);
+        let id = add_unique_node(graph, node, config);
+        file_ids.insert(key, id.clone());
+        id
+    };
+
+    let mut relationships = Vec::new();
+    for (file, targets) in &includes {
+        let from = file_id(graph, file);
+        for (target, include) in targets {
+            let to = file_id(graph, target);
+            relationships.push(include_relationship(from.clone(), to, include));
+        }
+    }
+    let include_count = relationships.len();
+
+    relationships.extend(declaration_relationships(graph, &includes));
+    debug!(
+        "Found {} includes and {} definitions of declared functions",
+        include_count,
+        relationships.len() - include_count
+    );
+
+    // Headers outside the codebase, such as <stdio.h>, become external
+    // symbols named by the whole included path
+    if config.uses_external_symbols() && config.is_pass_enabled(provenance::EXTERNAL_SYMBOL) {
+        unresolved_includes.sort_by_key(|(file, include)| (file.clone(), include.line));
+        let references = unresolved_includes
+            .into_iter()
+            .filter_map(|(file, include)| {
+                let language = crate::parsers::detect_language(&file)?;
+                let from = file_id(graph, &file);
+                Some(UnresolvedReference {
+                    language,
+                    name: include.path.clone(),
+                    relationship: include_relationship(from, String::new(), &include),
+                })
+            })
+            .collect();
+        relationships.extend(add_external_symbols(provider, graph, references, config));
+    }
+
+    for relationship in relationships {
+        graph.add_relationship(relationship);
+    }
+}
+
+fn include_relationship(from: String, to: String, include: &CInclude) -> Relationship {
+    Relationship::new(RelationshipType::Imports, from, to)
+        .with_provenance(provenance::INCLUDE_RESOLUTION)
+        .with_metadata("module".to_string(), include.path.clone())
+        .with_metadata("system".to_string(), include.system.to_string())
+        .with_metadata("line".to_string(), include.line.to_string())
+}
+
+// Every file a file includes, directly or through other headers
+fn transitive_includes<'a>(
+    file: &'a Path,
+    includes: &'a BTreeMap<PathBuf, Vec<(PathBuf, CInclude)>>,
+) -> HashSet<&'a Path> {
+    let mut seen = HashSet::new();
+    let mut to_visit = vec![file];
+    while let Some(current) = to_visit.pop() {
+        for (target, _) in includes.get(current).into_iter().flatten() {
+            if seen.insert(target.as_path()) {
+                to_visit.push(target);
+            }
+        }
+    }
+    seen
+}
+
+// Definitions of functions declared in headers: in the source files that
+// include the header, else in a source file named like it (`math.c` for
+// `math.h`)
+fn declaration_relationships(
+    graph: &CodeGraph,
+    includes: &BTreeMap<PathBuf, Vec<(PathBuf, CInclude)>>,
+) -> Vec<Relationship> {
+    let mut declarations: HashMap<&str, Vec<(&str, &Path)>> = HashMap::new();
+    let mut definitions: HashMap<&str, Vec<(&str, &Path)>> = HashMap::new();
+    for node in graph.all_nodes() {
+        let path = Path::new(&node.file_path);
+        if node.node_type != NodeType::Function || !is_c_family(path) {
+            continue;
+        }
+        if node.metadata.contains_key("declaration") {
+            if is_header(path) {
+                declarations
+                    .entry(node.name.as_str())
+                    .or_default()
+                    .push((node.id.as_str(), path));
+            }
+        } else if !is_header(path) {
+            definitions
+                .entry(node.name.as_str())
+                .or_default()
+                .push((node.id.as_str(), path));
+        }
+    }
+
+    let mut included: HashMap<&Path, HashSet<&Path>> = HashMap::new();
+    let mut relationships = Vec::new();
+    for (name, headers) in &declarations {
+        let Some(candidates) = definitions.get(name) else {
+            continue;
+        };
+        for (declaration_id, header) in headers {
+            let mut found: Vec<&str> = candidates
+                .iter()
+                .filter(|(_, source)| {
+                    included
+                        .entry(source)
+                        .or_insert_with(|| transitive_includes(source, includes))
+                        .contains(header)
+                })
+                .map(|(id, _)| *id)
+                .collect();
+            if found.is_empty() {
+                found = candidates
+                    .iter()
+                    .filter(|(_, source)| source.file_stem() == header.file_stem())
+                    .map(|(id, _)| *id)
+                    .collect();
+            }
+
+            for definition_id in found {
+                relationships.push(
+                    Relationship::new(
+                        RelationshipType::Implements,
+                        definition_id.to_string(),
+                        declaration_id.to_string(),
+                    )
+                    .with_provenance(provenance::INCLUDE_RESOLUTION),
+                );
+            }
+        }
+    }
+    relationships
+}
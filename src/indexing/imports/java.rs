@@ -0,0 +1,378 @@
+use crate::code_graph::{CodeGraph, NodeType, Relationship, RelationshipType, provenance};
+use crate::indexing::external::UnresolvedReference;
+use crate::indexing::extractor::{JavaImport, JavaSource, java_source};
+use crate::indexing::provider::FileProvider;
+use log::{debug, trace, warn};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// A class or interface of a file, with the lines it spans
+type Declaration = (String, String, (usize, usize));
+
+// The classes of a Java codebase by fully qualified name, from the package
+// declaration of each file and the nesting of classes inside it
+pub struct JavaTypes {
+    sources: HashMap<PathBuf, JavaSource>,
+    // `com.foo.Outer.Inner` to node id
+    classes: HashMap<String, String>,
+    // Top-level classes of each package, by qualified name
+    packages: HashMap<String, Vec<String>>,
+    // Qualified names of the classes declared in each file, by simple name
+    file_classes: HashMap<String, Vec<(String, String)>>,
+    // Methods declared directly in each class: class id to (name, id)
+    members: HashMap<String, Vec<(String, String)>>,
+    // Innermost class of each method
+    owners: HashMap<String, String>,
+}
+
+impl JavaTypes {
+    pub fn new(provider: &dyn FileProvider, graph: &CodeGraph) -> Self {
+        let paths = provider.list_files().unwrap_or_else(|e| {
+            warn!("Failed to list files for Java import resolution: {}", e);
+            Vec::new()
+        });
+
+        let mut types = JavaTypes {
+            sources: HashMap::new(),
+            classes: HashMap::new(),
+            packages: HashMap::new(),
+            file_classes: HashMap::new(),
+            members: HashMap::new(),
+            owners: HashMap::new(),
+        };
+        for path in paths {
+            if path.extension().is_none_or(|ext| ext != "java") {
+                continue;
+            }
+            match provider.read_file(&path) {
+                Ok(content) => {
+                    types.sources.insert(path, java_source(&content));
+                }
+                Err(e) => warn!("Failed to read {:?}: {}", path, e),
+            }
+        }
+
+        let mut declarations: HashMap<&str, Vec<Declaration>> = HashMap::new();
+        let mut methods = Vec::new();
+        for node in graph.all_nodes() {
+            if !node.file_path.ends_with(".java") {
+                continue;
+            }
+            match node.node_type {
+                NodeType::Class | NodeType::Interface => declarations
+                    .entry(node.file_path.as_str())
+                    .or_default()
+                    .push((node.name.clone(), node.id.clone(), node.line_range)),
+                NodeType::Method => methods.push(node),
+                _ => {}
+            }
+        }
+
+        for (file, classes) in &declarations {
+            let package = types
+                .sources
+                .get(Path::new(file))
+                .and_then(|source| source.package.clone());
+            for (name, id, range) in classes {
+                // Enclosing classes, outermost first
+                let mut outer: Vec<&Declaration> = classes
+                    .iter()
+                    .filter(|(_, other, lines)| {
+                        other != id && lines.0 <= range.0 && range.1 <= lines.1
+                    })
+                    .collect();
+                outer.sort_by_key(|(_, _, lines)| (lines.0, std::cmp::Reverse(lines.1)));
+
+                let nested: Vec<&str> = outer
+                    .iter()
+                    .map(|(name, _, _)| name.as_str())
+                    .chain([name.as_str()])
+                    .collect();
+                let qualified = match &package {
+                    Some(package) => format!("{}.{}", package, nested.join(".")),
+                    None => nested.join("."),
+                };
+
+                if outer.is_empty() {
+                    types
+                        .packages
+                        .entry(package.clone().unwrap_or_default())
+                        .or_default()
+                        .push(qualified.clone());
+                }
+                types
+                    .file_classes
+                    .entry(file.to_string())
+                    .or_default()
+                    .push((name.clone(), qualified.clone()));
+                types.classes.entry(qualified).or_insert_with(|| id.clone());
+            }
+        }
+
+        for method in methods {
+            let owner = declarations
+                .get(method.file_path.as_str())
+                .and_then(|classes| {
+                    classes
+                        .iter()
+                        .filter(|(_, _, lines)| {
+                            lines.0 <= method.line_range.0 && method.line_range.1 <= lines.1
+                        })
+                        .min_by_key(|(_, _, lines)| lines.1 - lines.0)
+                });
+            if let Some((_, class_id, _)) = owner {
+                types
+                    .members
+                    .entry(class_id.clone())
+                    .or_default()
+                    .push((method.name.clone(), method.id.clone()));
+                types.owners.insert(method.id.clone(), class_id.clone());
+            }
+        }
+
+        debug!(
+            "Found {} Java classes in {} packages",
+            types.classes.len(),
+            types.packages.len()
+        );
+        types
+    }
+
+    fn methods_named(&self, class_id: &str, name: &str) -> Vec<&str> {
+        self.members
+            .get(class_id)
+            .into_iter()
+            .flatten()
+            .filter(|(method, _)| method == name)
+            .map(|(_, id)| id.as_str())
+            .collect()
+    }
+
+    // Node ids an import names: the class, the members of a static import,
+    // or every class of a package or nested in a class for wildcards. `None`
+    // when the path is not declared in the codebase.
+    fn import_targets(&self, import: &JavaImport) -> Option<Vec<&str>> {
+        if import.wildcard {
+            if import.is_static {
+                let class_id = self.classes.get(&import.path)?;
+                return Some(
+                    self.members
+                        .get(class_id)
+                        .into_iter()
+                        .flatten()
+                        .map(|(_, id)| id.as_str())
+                        .collect(),
+                );
+            }
+            if let Some(classes) = self.packages.get(&import.path) {
+                return Some(
+                    classes
+                        .iter()
+                        .filter_map(|class| self.classes.get(class))
+                        .map(String::as_str)
+                        .collect(),
+                );
+            }
+            // `import com.foo.Outer.*` takes the classes nested in Outer
+            let prefix = format!("{}.", import.path);
+            let nested: Vec<&str> = self
+                .classes
+                .iter()
+                .filter(|(name, _)| {
+                    name.strip_prefix(&prefix)
+                        .is_some_and(|rest| !rest.contains('.'))
+                })
+                .map(|(_, id)| id.as_str())
+                .collect();
+            return (!nested.is_empty()).then_some(nested);
+        }
+
+        if let Some(id) = self.classes.get(&import.path) {
+            return Some(vec![id.as_str()]);
+        }
+        if import.is_static {
+            let (class, member) = import.path.rsplit_once('.')?;
+            let class_id = self.classes.get(class)?;
+            return Some(self.methods_named(class_id, member));
+        }
+        None
+    }
+
+    // The class a simple or dotted type name refers to in a file: a class
+    // declared in the file, a single-type import, a class of the same
+    // package, then an on-demand import
+    pub fn visible_class(&self, file_path: &str, name: &str) -> Option<&str> {
+        let (first, rest) = match name.split_once('.') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (name, None),
+        };
+        let source = self.sources.get(Path::new(file_path));
+        let imports = source.map(|s| s.imports.as_slice()).unwrap_or_default();
+
+        let declared = self
+            .file_classes
+            .get(file_path)
+            .and_then(|classes| classes.iter().find(|(simple, _)| simple == first))
+            .map(|(_, qualified)| qualified.clone());
+        let imported = || {
+            imports
+                .iter()
+                .filter(|import| !import.wildcard && !import.is_static)
+                .find(|import| import.path.rsplit('.').next() == Some(first))
+                .map(|import| import.path.clone())
+        };
+        let same_package = || {
+            let package = source.and_then(|s| s.package.as_deref());
+            let qualified = match package {
+                Some(package) => format!("{}.{}", package, first),
+                None => first.to_string(),
+            };
+            self.classes.contains_key(&qualified).then_some(qualified)
+        };
+        let on_demand = || {
+            imports
+                .iter()
+                .filter(|import| import.wildcard && !import.is_static)
+                .map(|import| format!("{}.{}", import.path, first))
+                .find(|qualified| self.classes.contains_key(qualified))
+        };
+
+        let mut qualified = declared
+            .or_else(imported)
+            .or_else(same_package)
+            .or_else(on_demand)?;
+        if let Some(rest) = rest {
+            qualified = format!("{}.{}", qualified, rest);
+        }
+        self.classes.get(&qualified).map(String::as_str)
+    }
+
+    // Methods a file imports statically under a simple name
+    fn static_import_targets(&self, file_path: &str, name: &str) -> Option<Vec<&str>> {
+        let source = self.sources.get(Path::new(file_path))?;
+        let targets: Vec<&str> = source
+            .imports
+            .iter()
+            .filter(|import| import.is_static)
+            .filter_map(|import| match import.wildcard {
+                true => Some(import.path.as_str()),
+                false => import
+                    .path
+                    .strip_suffix(name)
+                    .and_then(|class| class.strip_suffix('.')),
+            })
+            .filter_map(|class| self.classes.get(class))
+            .flat_map(|class_id| self.methods_named(class_id, name))
+            .collect();
+        (!targets.is_empty()).then_some(targets)
+    }
+
+    // Narrow the methods a call may reach by its receiver: `Type.name(...)`
+    // and `Type::name` go to the methods of that class, unqualified and
+    // `this.` calls to the caller's own class when it declares the method,
+    // else to static imports. `None` leaves the call to name matching.
+    pub fn call_targets(
+        &self,
+        file_path: &str,
+        caller_id: &str,
+        name: &str,
+        expression: &str,
+    ) -> Option<Vec<&str>> {
+        let callee = expression.split('(').next().unwrap_or_default().trim();
+        let qualifier = callee
+            .strip_suffix(name)
+            .and_then(|rest| rest.strip_suffix('.').or_else(|| rest.strip_suffix("::")))
+            .map(str::trim);
+
+        let Some(qualifier) = qualifier else {
+            let own = self
+                .owners
+                .get(caller_id)
+                .map(|class_id| self.methods_named(class_id, name))
+                .unwrap_or_default();
+            if !own.is_empty() {
+                return Some(own);
+            }
+            return self.static_import_targets(file_path, name);
+        };
+
+        let class_id = match qualifier {
+            "this" => self.owners.get(caller_id)?,
+            // Only type names are resolved; variables and fields are not typed
+            _ if qualifier.starts_with(char::is_uppercase) => {
+                self.visible_class(file_path, qualifier)?
+            }
+            _ => return None,
+        };
+        let targets = self.methods_named(class_id, name);
+        (!targets.is_empty()).then_some(targets)
+    }
+}
+
+// Link the nodes of a Java file to the classes and static members its
+// imports name, using the package declarations of the codebase rather than
+// simple names. Imports outside the codebase are reported as unresolved by
+// package. Returns false for files that were not read.
+pub fn find_java_import_relationships(
+    file_path: &str,
+    nodes: &[(String, String, NodeType)],
+    types: &JavaTypes,
+    relationships: &mut Vec<Relationship>,
+    unresolved: &mut Vec<UnresolvedReference>,
+) -> bool {
+    let Some(source) = types.sources.get(Path::new(file_path)) else {
+        return false;
+    };
+
+    for import in &source.imports {
+        let module = if import.wildcard {
+            format!("{}.*", import.path)
+        } else {
+            import.path.clone()
+        };
+        let import_edge = |source_id: &str, target: &str| {
+            Relationship::new(
+                RelationshipType::Imports,
+                source_id.to_string(),
+                target.to_string(),
+            )
+            .with_provenance(provenance::IMPORT_MATCH)
+            .with_metadata("module".to_string(), module.clone())
+            .with_metadata("line".to_string(), import.line.to_string())
+        };
+
+        let Some(targets) = types.import_targets(import) else {
+            // The package: everything up to the first capitalised segment
+            let package: Vec<&str> = import
+                .path
+                .split('.')
+                .take_while(|segment| !segment.starts_with(char::is_uppercase))
+                .collect();
+            let package = if package.is_empty() {
+                import.path.clone()
+            } else {
+                package.join(".")
+            };
+            for (source_id, _, _) in nodes {
+                unresolved.push(UnresolvedReference {
+                    language: "java".to_string(),
+                    name: package.clone(),
+                    relationship: import_edge(source_id, ""),
+                });
+            }
+            continue;
+        };
+
+        for target in targets {
+            if nodes.iter().any(|(id, _, _)| id == target) {
+                continue;
+            }
+            trace!("Found import from {} to {}", file_path, target);
+            for (source_id, _, _) in nodes {
+                relationships.push(import_edge(source_id, target));
+            }
+        }
+    }
+
+    true
+}
@@ -0,0 +1,263 @@
+use crate::code_graph::{CodeGraph, NodeType, Relationship, RelationshipType, provenance};
+use crate::indexing::external::UnresolvedReference;
+use crate::indexing::extractor::{PythonImport, python_imports};
+use crate::indexing::provider::FileProvider;
+use log::{trace, warn};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// Top-level definition of a module: (name, node id, first line)
+type Definition = (String, String, usize);
+
+// Possible definition while looking for the top-level ones: (name, id, lines)
+type Candidate<'a> = (&'a str, &'a str, (usize, usize));
+
+// The Python files of a codebase, laid out as packages, with the top-level
+// definitions of each file
+pub struct PythonModules {
+    files: HashSet<PathBuf>,
+    // Directories holding an `__init__.py`, i.e. regular packages
+    packages: HashSet<PathBuf>,
+    // Files by module name: the file stem, or the package name for `__init__.py`
+    by_name: HashMap<String, Vec<PathBuf>>,
+    definitions: HashMap<String, Vec<Definition>>,
+}
+
+impl PythonModules {
+    pub fn new(provider: &dyn FileProvider, graph: &CodeGraph) -> Self {
+        let paths = provider.list_files().unwrap_or_else(|e| {
+            warn!("Failed to list files for Python import resolution: {}", e);
+            Vec::new()
+        });
+
+        let mut modules = PythonModules {
+            files: HashSet::new(),
+            packages: HashSet::new(),
+            by_name: HashMap::new(),
+            definitions: HashMap::new(),
+        };
+        for path in paths {
+            if path.extension().is_none_or(|ext| ext != "py") {
+                continue;
+            }
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let name = if stem == "__init__" {
+                let Some(parent) = path.parent() else {
+                    continue;
+                };
+                modules.packages.insert(parent.to_path_buf());
+                match parent.file_name().and_then(|s| s.to_str()) {
+                    Some(name) => name.to_string(),
+                    None => continue,
+                }
+            } else {
+                stem.to_string()
+            };
+            modules.by_name.entry(name).or_default().push(path.clone());
+            modules.files.insert(path);
+        }
+
+        // Functions and classes nested in other ones cannot be imported
+        let mut by_file: HashMap<&str, Vec<Candidate>> = HashMap::new();
+        for node in graph.all_nodes() {
+            if node.file_path.ends_with(".py")
+                && matches!(
+                    node.node_type,
+                    NodeType::Function | NodeType::Class | NodeType::Variable
+                )
+            {
+                by_file.entry(node.file_path.as_str()).or_default().push((
+                    node.name.as_str(),
+                    node.id.as_str(),
+                    node.line_range,
+                ));
+            }
+        }
+        for (file, nodes) in by_file {
+            let mut definitions: Vec<Definition> = nodes
+                .iter()
+                .filter(|(_, id, range)| {
+                    !nodes.iter().any(|(_, other, outer)| {
+                        other != id
+                            && outer.0 <= range.0
+                            && range.1 <= outer.1
+                            && (outer.0, outer.1) != (range.0, range.1)
+                    })
+                })
+                .map(|(name, id, range)| (name.to_string(), id.to_string(), range.0))
+                .collect();
+            definitions.sort_by_key(|(_, _, line)| *line);
+            modules.definitions.insert(file.to_string(), definitions);
+        }
+
+        modules
+    }
+
+    // File of `a.b.c` relative to a directory: `a/b/c.py` or `a/b/c/__init__.py`
+    fn module_in(&self, base: &Path, parts: &[&str]) -> Option<PathBuf> {
+        let mut path = base.to_path_buf();
+        path.extend(parts);
+        let file = path.with_extension("py");
+        if !parts.is_empty() && self.files.contains(&file) {
+            return Some(file);
+        }
+        let init = path.join("__init__.py");
+        self.files.contains(&init).then_some(init)
+    }
+
+    // Source root that a file would have to sit under to be module `parts`:
+    // the directory above its top-level package
+    fn source_root<'a>(&self, file: &'a Path, parts: &[&str]) -> Option<&'a Path> {
+        let module = if file.file_stem().is_some_and(|stem| stem == "__init__") {
+            file.parent()?
+        } else {
+            file
+        };
+        let mut root = module;
+        for part in parts.iter().rev() {
+            let name = root.file_stem().and_then(|s| s.to_str())?;
+            if name != *part {
+                return None;
+            }
+            root = root.parent()?;
+        }
+        Some(root)
+    }
+
+    // Resolve an absolute module path from any source root: the project
+    // root, a `src/` layout or any directory that is not itself a package,
+    // which also covers namespace packages without `__init__.py`. Among
+    // several roots the one closest to the importing file wins.
+    pub fn resolve_absolute(&self, module: &str, importer: &Path) -> Option<&Path> {
+        let parts: Vec<&str> = module.split('.').collect();
+        let last = parts.last()?;
+
+        self.by_name
+            .get(*last)?
+            .iter()
+            .filter(|file| {
+                self.source_root(file, &parts)
+                    .is_some_and(|root| !self.packages.contains(root))
+            })
+            .max_by(|a, b| {
+                shared_components(a, importer)
+                    .cmp(&shared_components(b, importer))
+                    .then_with(|| b.cmp(a))
+            })
+            .map(PathBuf::as_path)
+    }
+
+    // Resolve `from ..a import b` against the package of the importing file
+    pub fn resolve_relative(&self, level: usize, module: &str, importer: &Path) -> Option<PathBuf> {
+        let mut base = importer.parent()?;
+        for _ in 1..level {
+            base = base.parent()?;
+        }
+        let parts: Vec<&str> = module.split('.').filter(|p| !p.is_empty()).collect();
+        self.module_in(base, &parts)
+    }
+
+    fn resolve(&self, import: &PythonImport, importer: &Path) -> Option<PathBuf> {
+        if import.level > 0 {
+            self.resolve_relative(import.level, &import.module, importer)
+        } else {
+            self.resolve_absolute(&import.module, importer)
+                .map(Path::to_path_buf)
+        }
+    }
+
+    // Nodes an import names: the definitions taken by a `from` import, or
+    // every public top-level definition for `import *`. Names that are
+    // submodules rather than definitions do not produce a target.
+    fn targets(&self, module_file: &Path, import: &PythonImport) -> Vec<&str> {
+        let definitions = self
+            .definitions
+            .get(module_file.to_string_lossy().as_ref())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let mut targets = Vec::new();
+        for name in &import.names {
+            if name == "*" {
+                targets.extend(
+                    definitions
+                        .iter()
+                        .filter(|(name, _, _)| !name.starts_with('_'))
+                        .map(|(_, id, _)| id.as_str()),
+                );
+            } else if let Some((_, id, _)) = definitions.iter().find(|(n, _, _)| n == name) {
+                targets.push(id.as_str());
+            }
+        }
+        targets
+    }
+}
+
+fn shared_components(a: &Path, b: &Path) -> usize {
+    a.components()
+        .zip(b.components())
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+// Link the nodes of a Python file to the definitions its imports name,
+// resolving module paths through the package layout instead of by name.
+// Plain `import a.b` statements resolve to a file but name no definition, so
+// they add no edges; modules that are not part of the codebase are reported
+// as unresolved by their top-level package.
+pub fn find_python_import_relationships(
+    file_path: &str,
+    content: &str,
+    nodes: &[(String, String, NodeType)],
+    modules: &PythonModules,
+    relationships: &mut Vec<Relationship>,
+    unresolved: &mut Vec<UnresolvedReference>,
+) {
+    let importer = Path::new(file_path);
+
+    for import in python_imports(content) {
+        let dotted = format!("{}{}", ".".repeat(import.level), import.module);
+        let import_edge = |source_id: &str, target: &str| {
+            Relationship::new(
+                RelationshipType::Imports,
+                source_id.to_string(),
+                target.to_string(),
+            )
+            .with_provenance(provenance::IMPORT_MATCH)
+            .with_metadata("module".to_string(), dotted.clone())
+            .with_metadata("line".to_string(), import.line.to_string())
+        };
+
+        let Some(module_file) = modules.resolve(&import, importer) else {
+            let package = match import.level {
+                0 => import
+                    .module
+                    .split('.')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                _ => dotted.clone(),
+            };
+            for (source_id, _, _) in nodes {
+                unresolved.push(UnresolvedReference {
+                    language: "python".to_string(),
+                    name: package.clone(),
+                    relationship: import_edge(source_id, ""),
+                });
+            }
+            continue;
+        };
+
+        for target in modules.targets(&module_file, &import) {
+            if nodes.iter().any(|(id, _, _)| id == target) {
+                continue;
+            }
+            trace!("Found import from {} to {}", file_path, target);
+            for (source_id, _, _) in nodes {
+                relationships.push(import_edge(source_id, target));
+            }
+        }
+    }
+}
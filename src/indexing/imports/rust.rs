@@ -0,0 +1,416 @@
+use crate::code_graph::{CodeGraph, NodeType, Relationship, RelationshipType, provenance};
+use crate::indexing::external::UnresolvedReference;
+use crate::indexing::extractor::{RustUse, rust_module_declarations, rust_use_paths};
+use crate::indexing::provider::FileProvider;
+use log::{debug, trace, warn};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+// A module of a crate: the crate root file and the module path below it
+type ModuleKey = (PathBuf, Vec<String>);
+
+// Where a module's items are written: a file, or the body lines of an inline
+// module in it. Files shared by several crates hold the same items for each.
+type Location = (PathBuf, Option<(usize, usize)>);
+
+// An inline module by the lines of its body
+type InlineModule = ((usize, usize), ModuleKey);
+
+// Directories whose files are each the root of a crate
+const CRATE_DIRS: &[&str] = &["bin", "tests", "examples", "benches"];
+
+fn is_crate_root(path: &Path) -> bool {
+    let in_crate_dir = path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| CRATE_DIRS.contains(&name));
+    // `src/bin/name/main.rs`
+    let bin_main = path.ends_with("main.rs")
+        && path
+            .parent()
+            .and_then(Path::parent)
+            .is_some_and(|dir| dir.ends_with("bin"));
+    in_crate_dir || bin_main || path.ends_with("src/lib.rs") || path.ends_with("src/main.rs")
+}
+
+// Drop `.` and fold `..` components, as `#[path]` values may contain them
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+// Name a library crate is used by from other crates: `[lib] name`, else the
+// package name with dashes as underscores
+fn library_name(provider: &dyn FileProvider, lib_root: &Path) -> Option<String> {
+    let manifest = lib_root.parent()?.parent()?.join("Cargo.toml");
+    let value: toml::Value = toml::from_str(&provider.read_file(&manifest).ok()?).ok()?;
+    let name = value
+        .get("lib")
+        .and_then(|lib| lib.get("name"))
+        .or_else(|| value.get("package").and_then(|p| p.get("name")))?
+        .as_str()?;
+    Some(name.replace('-', "_"))
+}
+
+// What a `use` path points at
+enum Resolution<'a> {
+    // Nodes inside the indexed crates; empty for modules and for items that
+    // are not nodes, such as enums and traits
+    Items(Vec<&'a str>),
+    // Path into another crate, by crate name
+    External(String),
+}
+
+// The module trees of the crates in a codebase, built by following `mod`
+// declarations from each crate root
+pub struct RustModules {
+    modules: HashMap<ModuleKey, Location>,
+    // The module a file holds, from the first crate that reaches it
+    file_modules: HashMap<PathBuf, ModuleKey>,
+    // Inline `mod name { ... }` blocks by file, with their body lines
+    inline_modules: HashMap<PathBuf, Vec<InlineModule>>,
+    // Library crate roots by crate name
+    crates: HashMap<String, PathBuf>,
+    // Items defined directly in each module: (name, node id)
+    items: HashMap<Location, Vec<(String, String)>>,
+}
+
+impl RustModules {
+    pub fn new(provider: &dyn FileProvider, graph: &CodeGraph) -> Self {
+        let files: HashSet<PathBuf> = provider
+            .list_files()
+            .unwrap_or_else(|e| {
+                warn!("Failed to list files for Rust module resolution: {}", e);
+                Vec::new()
+            })
+            .into_iter()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .collect();
+
+        let mut modules = RustModules {
+            modules: HashMap::new(),
+            file_modules: HashMap::new(),
+            inline_modules: HashMap::new(),
+            crates: HashMap::new(),
+            items: HashMap::new(),
+        };
+
+        // Sorted so `lib.rs` claims modules shared with `main.rs`
+        let mut roots: Vec<&PathBuf> = files.iter().filter(|path| is_crate_root(path)).collect();
+        roots.sort();
+        for root in roots {
+            if root.ends_with("src/lib.rs")
+                && let Some(name) = library_name(provider, root)
+            {
+                modules.crates.entry(name).or_insert_with(|| root.clone());
+            }
+            let Some(dir) = root.parent() else {
+                continue;
+            };
+            let mut visited = HashSet::new();
+            modules.add_module_file(
+                provider,
+                &files,
+                &mut visited,
+                root,
+                (root.clone(), Vec::new()),
+                dir,
+            );
+        }
+        debug!(
+            "Found {} Rust modules in {} crates",
+            modules.modules.len(),
+            modules
+                .modules
+                .keys()
+                .filter(|(_, path)| path.is_empty())
+                .count()
+        );
+
+        modules.add_items(graph);
+        modules
+    }
+
+    // Record a module held by `file` and follow its `mod` declarations.
+    // `dir` is where the files of its child modules live; `visited` holds
+    // the files already in this crate, which `#[path]` loops could revisit.
+    fn add_module_file(
+        &mut self,
+        provider: &dyn FileProvider,
+        files: &HashSet<PathBuf>,
+        visited: &mut HashSet<PathBuf>,
+        file: &Path,
+        key: ModuleKey,
+        dir: &Path,
+    ) {
+        if !visited.insert(file.to_path_buf()) {
+            return;
+        }
+        self.modules.insert(key.clone(), (file.to_path_buf(), None));
+        self.file_modules
+            .entry(file.to_path_buf())
+            .or_insert_with(|| key.clone());
+
+        let content = match provider.read_file(file) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Failed to read Rust module {:?}: {}", file, e);
+                return;
+            }
+        };
+
+        for declaration in rust_module_declarations(&content) {
+            let mut path = key.1.clone();
+            path.extend(declaration.parents.iter().cloned());
+            path.push(declaration.name.clone());
+            let child = (key.0.clone(), path);
+
+            if let Some(lines) = declaration.inline {
+                self.modules
+                    .insert(child.clone(), (file.to_path_buf(), Some(lines)));
+                self.inline_modules
+                    .entry(file.to_path_buf())
+                    .or_default()
+                    .push((lines, child));
+                continue;
+            }
+
+            // Files of modules declared inside inline modules live in
+            // directories named after them
+            let mut base = dir.to_path_buf();
+            base.extend(&declaration.parents);
+
+            // Files named by `#[path]` are relative to the declaring file and
+            // hold their own child modules next to them, like `mod.rs`
+            let (child_file, child_dir) = match &declaration.path {
+                Some(relative) => {
+                    let mut attributed = file.parent().unwrap_or(dir).to_path_buf();
+                    attributed.extend(&declaration.parents);
+                    let attributed = normalize(&attributed.join(relative));
+                    let child_dir = attributed.parent().unwrap_or(dir).to_path_buf();
+                    (attributed, child_dir)
+                }
+                None => {
+                    let named = base.join(format!("{}.rs", declaration.name));
+                    if files.contains(&named) {
+                        (named, base.join(&declaration.name))
+                    } else {
+                        let nested = base.join(&declaration.name);
+                        (nested.join("mod.rs"), nested)
+                    }
+                }
+            };
+
+            if files.contains(&child_file) {
+                self.add_module_file(provider, files, visited, &child_file, child, &child_dir);
+            } else {
+                trace!(
+                    "No file for module {} declared in {:?}",
+                    declaration.name, file
+                );
+            }
+        }
+    }
+
+    // Module of a line of a file: the innermost inline module around it, or
+    // the module the file holds
+    fn module_at(&self, file: &Path, line: usize) -> Option<&ModuleKey> {
+        self.inline_lines(file, line)
+            .map(|(_, key)| key)
+            .or_else(|| self.file_modules.get(file))
+    }
+
+    // Innermost inline module body around a line of a file
+    fn inline_lines(&self, file: &Path, line: usize) -> Option<((usize, usize), &ModuleKey)> {
+        self.inline_modules
+            .get(file)?
+            .iter()
+            .filter(|((start, end), _)| *start <= line && line <= *end)
+            .min_by_key(|((start, end), _)| end - start)
+            .map(|(lines, key)| (*lines, key))
+    }
+
+    // Items of each module: functions, structs and constants that are not
+    // methods and not nested in function bodies
+    fn add_items(&mut self, graph: &CodeGraph) {
+        let mut functions: HashMap<&str, Vec<(usize, usize)>> = HashMap::new();
+        for node in graph.all_nodes() {
+            if node.node_type == NodeType::Function {
+                functions
+                    .entry(node.file_path.as_str())
+                    .or_default()
+                    .push(node.line_range);
+            }
+        }
+
+        let mut items: HashMap<Location, Vec<(String, String)>> = HashMap::new();
+        for node in graph.all_nodes() {
+            if !matches!(
+                node.node_type,
+                NodeType::Function | NodeType::Class | NodeType::Variable
+            ) {
+                continue;
+            }
+            let nested = functions
+                .get(node.file_path.as_str())
+                .is_some_and(|ranges| {
+                    ranges.iter().any(|range| {
+                        *range != node.line_range
+                            && range.0 <= node.line_range.0
+                            && node.line_range.1 <= range.1
+                    })
+                });
+            if nested {
+                continue;
+            }
+            let file = Path::new(&node.file_path);
+            if !self.file_modules.contains_key(file) {
+                continue;
+            }
+            let lines = self
+                .inline_lines(file, node.line_range.0)
+                .map(|(lines, _)| lines);
+            items
+                .entry((file.to_path_buf(), lines))
+                .or_default()
+                .push((node.name.clone(), node.id.clone()));
+        }
+        for module_items in items.values_mut() {
+            module_items.sort();
+        }
+        self.items = items;
+    }
+
+    pub fn contains_file(&self, file: &Path) -> bool {
+        self.file_modules.contains_key(file)
+    }
+
+    // Resolve a `use` path written in `file`: `crate`, `self` and `super`
+    // paths, paths through other crates of the codebase by name, and 2018
+    // paths starting at a child module
+    fn resolve(&self, file: &Path, import: &RustUse) -> Option<Resolution<'_>> {
+        let importer = self.module_at(file, import.line)?;
+        let first = import.path.first()?;
+
+        let (mut module, rest) = match first.as_str() {
+            "crate" => ((importer.0.clone(), Vec::new()), &import.path[1..]),
+            "self" => (importer.clone(), &import.path[1..]),
+            "super" => {
+                let supers = import.path.iter().take_while(|s| *s == "super").count();
+                let mut path = importer.1.clone();
+                path.truncate(path.len().saturating_sub(supers));
+                ((importer.0.clone(), path), &import.path[supers..])
+            }
+            _ => {
+                let mut child = importer.1.clone();
+                child.push(first.clone());
+                if self.modules.contains_key(&(importer.0.clone(), child)) {
+                    (importer.clone(), &import.path[..])
+                } else if let Some(root) = self.crates.get(first) {
+                    ((root.clone(), Vec::new()), &import.path[1..])
+                } else {
+                    return Some(Resolution::External(first.clone()));
+                }
+            }
+        };
+
+        // Descend through modules as far as the path names them
+        let mut remaining = rest;
+        while let Some((segment, tail)) = remaining.split_first() {
+            let mut path = module.1.clone();
+            path.push(segment.clone());
+            let child = (module.0.clone(), path);
+            if !self.modules.contains_key(&child) {
+                break;
+            }
+            module = child;
+            remaining = tail;
+        }
+
+        let items = self
+            .modules
+            .get(&module)
+            .and_then(|location| self.items.get(location))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let targets = match remaining.first() {
+            None if import.glob => items.iter().map(|(_, id)| id.as_str()).collect(),
+            None => Vec::new(),
+            // Paths past an item (`Enum::Variant`) import from that item
+            Some(name) => items
+                .iter()
+                .filter(|(item, _)| item == name)
+                .map(|(_, id)| id.as_str())
+                .take(1)
+                .collect(),
+        };
+        Some(Resolution::Items(targets))
+    }
+}
+
+// Link the nodes of a Rust file to the items its `use` declarations name,
+// following the module tree of its crate instead of matching file names.
+// Returns false when the file is not reachable from any crate root, so the
+// caller can fall back to name matching.
+pub fn find_rust_import_relationships(
+    file_path: &str,
+    content: &str,
+    nodes: &[(String, String, NodeType)],
+    modules: &RustModules,
+    relationships: &mut Vec<Relationship>,
+    unresolved: &mut Vec<UnresolvedReference>,
+) -> bool {
+    let file = Path::new(file_path);
+    if !modules.contains_file(file) {
+        return false;
+    }
+
+    for import in rust_use_paths(content) {
+        let import_edge = |source_id: &str, target: &str| {
+            Relationship::new(
+                RelationshipType::Imports,
+                source_id.to_string(),
+                target.to_string(),
+            )
+            .with_provenance(provenance::IMPORT_MATCH)
+            .with_metadata("module".to_string(), import.path.join("::"))
+            .with_metadata("line".to_string(), import.line.to_string())
+        };
+
+        match modules.resolve(file, &import) {
+            Some(Resolution::Items(targets)) => {
+                for target in targets {
+                    if nodes.iter().any(|(id, _, _)| id == target) {
+                        continue;
+                    }
+                    trace!("Found import from {} to {}", file_path, target);
+                    for (source_id, _, _) in nodes {
+                        relationships.push(import_edge(source_id, target));
+                    }
+                }
+            }
+            Some(Resolution::External(name)) => {
+                for (source_id, _, _) in nodes {
+                    unresolved.push(UnresolvedReference {
+                        language: "rust".to_string(),
+                        name: name.clone(),
+                        relationship: import_edge(source_id, ""),
+                    });
+                }
+            }
+            None => {}
+        }
+    }
+
+    true
+}
@@ -0,0 +1,576 @@
+use crate::code_graph::{CodeGraph, NodeType, Relationship, RelationshipType, provenance};
+use crate::indexing::external::UnresolvedReference;
+use crate::indexing::extractor::{EsImport, EsModule, es_module_statements};
+use crate::indexing::provider::FileProvider;
+use log::{debug, trace, warn};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+// Extensions tried, in order, for specifiers written without one
+const EXTENSIONS: &[&str] = &["ts", "tsx", "d.ts", "js", "jsx", "mjs", "cjs"];
+
+// Export conditions tried, in order, when a package.json `exports` entry
+// has several; source-like conditions first
+const CONDITIONS: &[&str] = &["source", "import", "module", "default", "require", "types"];
+
+// Re-export chains longer than this are not followed
+const MAX_REEXPORT_DEPTH: usize = 16;
+
+fn is_script(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext, "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs"))
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+// tsconfig files are JSON with comments and trailing commas
+fn parse_jsonc(text: &str) -> Option<Value> {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            if c == '\\' {
+                stripped.extend(chars.next());
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            stripped.push(c);
+        } else if c == '/' && chars.peek() == Some(&'/') {
+            while chars.next_if(|c| *c != '\n').is_some() {}
+        } else if c == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            let mut previous = ' ';
+            for c in chars.by_ref() {
+                if previous == '*' && c == '/' {
+                    break;
+                }
+                previous = c;
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+
+    // Drop commas followed only by whitespace before a closing bracket
+    let mut cleaned = String::with_capacity(stripped.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in stripped.char_indices() {
+        if in_string {
+            in_string = escaped || c != '"';
+            escaped = !escaped && c == '\\';
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' && stripped[i + 1..].trim_start().starts_with(['}', ']']) {
+            continue;
+        }
+        cleaned.push(c);
+    }
+
+    serde_json::from_str(&cleaned).ok()
+}
+
+// The `baseUrl` and `paths` of a tsconfig.json or jsconfig.json, with
+// `extends` applied
+#[derive(Debug, Clone, Default)]
+struct CompilerPaths {
+    base_url: Option<PathBuf>,
+    // Pattern and replacements, relative to `paths_base`
+    paths: Vec<(String, Vec<String>)>,
+    paths_base: PathBuf,
+}
+
+fn load_compiler_paths(
+    provider: &dyn FileProvider,
+    config: &Path,
+    depth: usize,
+) -> Option<CompilerPaths> {
+    let value = parse_jsonc(&provider.read_file(config).ok()?)?;
+    let dir = config.parent()?;
+
+    // Only relative `extends` are followed; package presets hold no paths
+    let mut loaded = value
+        .get("extends")
+        .and_then(Value::as_str)
+        .filter(|extends| extends.starts_with('.') && depth < 8)
+        .and_then(|extends| {
+            let mut parent = normalize(&dir.join(extends));
+            if parent.extension().is_none() {
+                parent.set_extension("json");
+            }
+            load_compiler_paths(provider, &parent, depth + 1)
+        })
+        .unwrap_or_default();
+
+    let options = value.get("compilerOptions");
+    if let Some(base_url) = options
+        .and_then(|o| o.get("baseUrl"))
+        .and_then(Value::as_str)
+    {
+        loaded.base_url = Some(normalize(&dir.join(base_url)));
+    }
+    if let Some(paths) = options
+        .and_then(|o| o.get("paths"))
+        .and_then(Value::as_object)
+    {
+        loaded.paths = paths
+            .iter()
+            .map(|(pattern, targets)| {
+                let targets = targets
+                    .as_array()
+                    .map(|targets| {
+                        targets
+                            .iter()
+                            .filter_map(|t| t.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (pattern.clone(), targets)
+            })
+            .collect();
+        // Longest prefix first, as the compiler prefers the most specific
+        loaded.paths.sort_by_key(|(pattern, _)| {
+            std::cmp::Reverse(pattern.find('*').unwrap_or(pattern.len()))
+        });
+        loaded.paths_base = loaded.base_url.clone().unwrap_or_else(|| dir.to_path_buf());
+    }
+
+    Some(loaded)
+}
+
+// What a `*` in a paths pattern matched, if the specifier fits the pattern
+fn match_pattern<'a>(pattern: &str, specifier: &'a str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => specifier
+            .strip_prefix(prefix)?
+            .strip_suffix(suffix)
+            .filter(|_| specifier.len() >= prefix.len() + suffix.len()),
+        None => (pattern == specifier).then_some(""),
+    }
+}
+
+// A package of the codebase, by its package.json
+#[derive(Debug, Clone)]
+struct WorkspacePackage {
+    dir: PathBuf,
+    exports: Option<Value>,
+    main: Option<String>,
+}
+
+// Pick a path from an `exports` value: a string, or conditions to choose from
+fn export_target(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(target) => Some(target),
+        Value::Object(conditions) => CONDITIONS
+            .iter()
+            .find_map(|condition| conditions.get(*condition).and_then(export_target)),
+        Value::Array(targets) => targets.iter().find_map(export_target),
+        _ => None,
+    }
+}
+
+// Where a subpath such as `.` or `./utils` of a package points
+fn package_target(package: &WorkspacePackage, subpath: &str) -> Option<String> {
+    let Some(exports) = &package.exports else {
+        return match subpath {
+            "." => package.main.clone().or_else(|| Some("./index".to_string())),
+            _ => Some(subpath.to_string()),
+        };
+    };
+
+    let entries = match exports.as_object() {
+        Some(map) if map.keys().any(|key| key.starts_with('.')) => map,
+        // Conditions or a single path for the main entry only
+        _ => {
+            return (subpath == ".")
+                .then(|| export_target(exports))
+                .flatten()
+                .map(str::to_string);
+        }
+    };
+    if let Some(target) = entries.get(subpath).and_then(export_target) {
+        return Some(target.to_string());
+    }
+    entries.iter().find_map(|(key, value)| {
+        let matched = match_pattern(key, subpath)?;
+        Some(export_target(value)?.replace('*', matched))
+    })
+}
+
+// JavaScript and TypeScript modules of a codebase, with the compiler and
+// package settings needed to resolve their specifiers
+pub struct ScriptModules {
+    files: HashSet<PathBuf>,
+    // Module statements of every script, for following re-exports
+    modules: HashMap<PathBuf, EsModule>,
+    // Top-level definitions by file: name to node id
+    definitions: HashMap<String, HashMap<String, String>>,
+    // tsconfig/jsconfig settings by the directory holding them
+    configs: HashMap<PathBuf, CompilerPaths>,
+    packages: HashMap<String, WorkspacePackage>,
+}
+
+impl ScriptModules {
+    pub fn new(provider: &dyn FileProvider, graph: &CodeGraph) -> Self {
+        let paths = provider.list_files().unwrap_or_else(|e| {
+            warn!("Failed to list files for script import resolution: {}", e);
+            Vec::new()
+        });
+
+        let mut modules = ScriptModules {
+            files: HashSet::new(),
+            modules: HashMap::new(),
+            definitions: HashMap::new(),
+            configs: HashMap::new(),
+            packages: HashMap::new(),
+        };
+        for path in paths {
+            let skipped = path
+                .components()
+                .any(|component| component.as_os_str() == "node_modules");
+            if skipped {
+                continue;
+            }
+            let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            // Keys are normalized like the paths they are looked up by, so
+            // `./src/a.ts` from a `.` root matches `src/a.ts`
+            let key = normalize(&path);
+            let dir = key.parent().unwrap_or(Path::new("")).to_path_buf();
+
+            if matches!(file_name, "tsconfig.json" | "jsconfig.json") {
+                // tsconfig.json wins over jsconfig.json in the same directory
+                if (file_name == "tsconfig.json" || !modules.configs.contains_key(&dir))
+                    && let Some(config) = load_compiler_paths(provider, &path, 0)
+                {
+                    modules.configs.insert(dir, config);
+                }
+            } else if file_name == "package.json" {
+                let manifest = provider
+                    .read_file(&path)
+                    .ok()
+                    .and_then(|text| serde_json::from_str::<Value>(&text).ok());
+                if let Some(manifest) = manifest
+                    && let Some(name) = manifest.get("name").and_then(Value::as_str)
+                {
+                    let main = ["source", "module", "main"]
+                        .iter()
+                        .find_map(|key| manifest.get(*key).and_then(Value::as_str))
+                        .map(str::to_string);
+                    modules.packages.insert(
+                        name.to_string(),
+                        WorkspacePackage {
+                            dir,
+                            exports: manifest.get("exports").cloned(),
+                            main,
+                        },
+                    );
+                }
+            } else if is_script(&path) {
+                match provider.read_file(&path) {
+                    Ok(content) => {
+                        modules
+                            .modules
+                            .insert(key.clone(), es_module_statements(&content, &path));
+                    }
+                    Err(e) => warn!("Failed to read {:?}: {}", path, e),
+                }
+                modules.files.insert(key);
+            }
+        }
+
+        // Functions nested in other functions are not module exports
+        let mut functions: HashMap<&str, Vec<(usize, usize)>> = HashMap::new();
+        for node in graph.all_nodes() {
+            if node.node_type == NodeType::Function {
+                functions
+                    .entry(node.file_path.as_str())
+                    .or_default()
+                    .push(node.line_range);
+            }
+        }
+        for node in graph.all_nodes() {
+            if !is_script(Path::new(&node.file_path))
+                || !matches!(
+                    node.node_type,
+                    NodeType::Function
+                        | NodeType::Class
+                        | NodeType::Interface
+                        | NodeType::TypeDefinition
+                        | NodeType::Variable
+                )
+            {
+                continue;
+            }
+            let nested = functions
+                .get(node.file_path.as_str())
+                .is_some_and(|ranges| {
+                    ranges.iter().any(|range| {
+                        *range != node.line_range
+                            && range.0 <= node.line_range.0
+                            && node.line_range.1 <= range.1
+                    })
+                });
+            if !nested {
+                modules
+                    .definitions
+                    .entry(
+                        normalize(Path::new(&node.file_path))
+                            .to_string_lossy()
+                            .into_owned(),
+                    )
+                    .or_default()
+                    .entry(node.name.clone())
+                    .or_insert_with(|| node.id.clone());
+            }
+        }
+
+        debug!(
+            "Found {} scripts, {} compiler configs and {} packages",
+            modules.files.len(),
+            modules.configs.len(),
+            modules.packages.len()
+        );
+        modules
+    }
+
+    // The script a path names, trying the usual extensions and index files.
+    // TypeScript's ESM style names `./a.js` for the source `./a.ts`.
+    fn probe(&self, path: &Path) -> Option<PathBuf> {
+        let path = normalize(path);
+        if self.files.contains(&path) {
+            return Some(path);
+        }
+        let file_name = path.file_name()?.to_str()?;
+        let stem = file_name
+            .strip_suffix(".js")
+            .or_else(|| file_name.strip_suffix(".mjs"))
+            .or_else(|| file_name.strip_suffix(".jsx"));
+        for base in stem.into_iter().chain([file_name]) {
+            for ext in EXTENSIONS {
+                let candidate = path.with_file_name(format!("{}.{}", base, ext));
+                if self.files.contains(&candidate) {
+                    return Some(candidate);
+                }
+            }
+        }
+        EXTENSIONS
+            .iter()
+            .map(|ext| path.join(format!("index.{}", ext)))
+            .find(|candidate| self.files.contains(candidate))
+    }
+
+    // Nearest compiler config in or above the importing file's directory
+    fn config_for(&self, importer: &Path) -> Option<&CompilerPaths> {
+        importer
+            .ancestors()
+            .skip(1)
+            .find_map(|dir| self.configs.get(dir))
+    }
+
+    // Resolve a specifier: relative paths, tsconfig `paths` aliases and
+    // `baseUrl`, then packages of the codebase by name and `exports`
+    fn resolve_specifier(&self, specifier: &str, importer: &Path) -> Option<PathBuf> {
+        if specifier.starts_with('.') || specifier.starts_with('/') {
+            return self.probe(&importer.parent()?.join(specifier));
+        }
+
+        if let Some(config) = self.config_for(importer) {
+            for (pattern, targets) in &config.paths {
+                let Some(matched) = match_pattern(pattern, specifier) else {
+                    continue;
+                };
+                let resolved = targets.iter().find_map(|target| {
+                    self.probe(&config.paths_base.join(target.replace('*', matched)))
+                });
+                if resolved.is_some() {
+                    return resolved;
+                }
+            }
+            if let Some(resolved) = config
+                .base_url
+                .as_ref()
+                .and_then(|base_url| self.probe(&base_url.join(specifier)))
+            {
+                return Some(resolved);
+            }
+        }
+
+        let (name, package) = self
+            .packages
+            .iter()
+            .filter(|(name, _)| {
+                specifier == name.as_str()
+                    || specifier
+                        .strip_prefix(name.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|(name, _)| name.len())?;
+        let subpath = format!(".{}", &specifier[name.len()..]);
+        let target = package_target(package, &subpath)?;
+        self.probe(&package.dir.join(target))
+    }
+
+    // Node behind an exported name of a module, following re-exports and
+    // imported-then-exported names through barrel files
+    fn exported_node(
+        &self,
+        file: &Path,
+        name: &str,
+        visited: &mut HashSet<PathBuf>,
+    ) -> Option<&str> {
+        if visited.len() > MAX_REEXPORT_DEPTH || !visited.insert(file.to_path_buf()) {
+            return None;
+        }
+        let module = self.modules.get(file);
+
+        let local = match module {
+            Some(module) if name == "default" => module.default_export.clone()?,
+            Some(module) => module
+                .exports
+                .iter()
+                .find(|(_, exported)| exported == name)
+                .map(|(local, _)| local.clone())
+                .unwrap_or_else(|| name.to_string()),
+            None => name.to_string(),
+        };
+        if let Some(id) = self
+            .definitions
+            .get(file.to_string_lossy().as_ref())
+            .and_then(|definitions| definitions.get(&local))
+        {
+            return Some(id);
+        }
+
+        for import in module.map(|m| m.imports.as_slice()).unwrap_or_default() {
+            // Re-exports are matched on the exported name, imports on the
+            // local one
+            let wanted = if import.reexport {
+                name
+            } else {
+                local.as_str()
+            };
+            let source_name = import
+                .names
+                .iter()
+                .find(|(_, here)| here == wanted)
+                .map(|(there, _)| there.as_str())
+                .or_else(|| (import.reexport && import.all && name != "default").then_some(name));
+            let Some(source_name) = source_name else {
+                continue;
+            };
+            let Some(source) = self.resolve_specifier(&import.source, file) else {
+                continue;
+            };
+            if let Some(id) = self.exported_node(&source, source_name, &mut visited.clone()) {
+                return Some(id);
+            }
+        }
+
+        None
+    }
+
+    fn targets(&self, import: &EsImport, importer: &Path) -> Option<Vec<&str>> {
+        let source = self.resolve_specifier(&import.source, importer)?;
+        Some(
+            import
+                .names
+                .iter()
+                .filter_map(|(name, _)| self.exported_node(&source, name, &mut HashSet::new()))
+                .collect(),
+        )
+    }
+}
+
+// Package a bare specifier belongs to: `@scope/pkg/sub` is `@scope/pkg`
+fn package_name(specifier: &str) -> String {
+    let take = if specifier.starts_with('@') { 2 } else { 1 };
+    specifier
+        .split('/')
+        .take(take)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Link the nodes of a JavaScript or TypeScript file to the definitions its
+// imports name, resolving specifiers through tsconfig `paths` and `baseUrl`
+// and package.json `exports`, and following barrel files to the module that
+// defines each name. Bare specifiers outside the codebase are reported as
+// unresolved by package. Returns false for files the module map does not
+// know, which are left to name matching.
+pub fn find_script_import_relationships(
+    language: &str,
+    file_path: &str,
+    nodes: &[(String, String, NodeType)],
+    modules: &ScriptModules,
+    relationships: &mut Vec<Relationship>,
+    unresolved: &mut Vec<UnresolvedReference>,
+) -> bool {
+    let importer = &normalize(Path::new(file_path));
+    let Some(module) = modules.modules.get(importer) else {
+        return false;
+    };
+
+    for import in &module.imports {
+        let import_edge = |source_id: &str, target: &str| {
+            Relationship::new(
+                RelationshipType::Imports,
+                source_id.to_string(),
+                target.to_string(),
+            )
+            .with_provenance(provenance::IMPORT_MATCH)
+            .with_metadata("module".to_string(), import.source.clone())
+            .with_metadata("line".to_string(), import.line.to_string())
+        };
+
+        match modules.targets(import, importer) {
+            Some(targets) => {
+                for target in targets {
+                    if nodes.iter().any(|(id, _, _)| id == target) {
+                        continue;
+                    }
+                    trace!("Found import from {} to {}", file_path, target);
+                    for (source_id, _, _) in nodes {
+                        relationships.push(import_edge(source_id, target));
+                    }
+                }
+            }
+            // Relative paths outside the indexed files keep their whole path
+            None => {
+                let package = if import.source.starts_with('.') {
+                    import.source.clone()
+                } else {
+                    package_name(&import.source)
+                };
+                for (source_id, _, _) in nodes {
+                    unresolved.push(UnresolvedReference {
+                        language: language.to_string(),
+                        name: package.clone(),
+                        relationship: import_edge(source_id, ""),
+                    });
+                }
+            }
+        }
+    }
+
+    true
+}
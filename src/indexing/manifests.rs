@@ -0,0 +1,576 @@
+use crate::code_graph::{
+    CodeGraph, CodeNode, NodeType, Relationship, RelationshipType, provenance,
+};
+use crate::indexing::config::IndexConfig;
+use crate::indexing::extractor::common::create_node;
+use crate::indexing::provider::FileProvider;
+use log::{debug, info, trace, warn};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+// Directories that hold installed or generated copies of dependencies
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", ".git", "vendor", "dist", "build"];
+
+struct ManifestDependency {
+    name: String,
+    version: Option<String>,
+    scope: &'static str,
+}
+
+struct Manifest {
+    ecosystem: &'static str,
+    package_name: String,
+    dependencies: Vec<ManifestDependency>,
+}
+
+// Create a Module node per package manifest and a Dependency node per declared
+// third-party package, linked with DependsOn edges
+pub fn add_manifest_dependencies(
+    provider: &dyn FileProvider,
+    graph: &mut CodeGraph,
+    config: &IndexConfig,
+) {
+    info!("Scanning package manifests for dependencies...");
+
+    let mut dependency_ids: HashMap<(&'static str, String), String> = HashMap::new();
+    let mut manifest_count = 0;
+    let mut relationships = Vec::new();
+
+    let files = match provider.list_files() {
+        Ok(files) => files,
+        Err(e) => {
+            warn!("Failed to list files for manifest scan: {}", e);
+            return;
+        }
+    };
+
+    let roots = provider.roots();
+    for path in files {
+        let path = path.as_path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+
+        // Only directories below the root count, so a checkout that itself
+        // sits under `build/` or `vendor/` is still scanned
+        let relative = roots
+            .iter()
+            .filter_map(|root| path.strip_prefix(root).ok())
+            .min_by_key(|relative| relative.components().count())
+            .unwrap_or(path);
+        let is_skipped = relative.components().any(|component| {
+            component
+                .as_os_str()
+                .to_str()
+                .is_some_and(|name| SKIPPED_DIRS.contains(&name))
+        });
+        if is_skipped {
+            continue;
+        }
+
+        let content = match file_name {
+            "Cargo.toml" | "package.json" | "go.mod" | "requirements.txt" | "pyproject.toml"
+            | "pom.xml" | "build.gradle" | "build.gradle.kts" => match provider.read_file(path) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Failed to read manifest {:?}: {}", path, e);
+                    continue;
+                }
+            },
+            _ => continue,
+        };
+
+        let manifest = match file_name {
+            "Cargo.toml" => parse_cargo_toml(&content),
+            "package.json" => parse_package_json(&content),
+            "go.mod" => parse_go_mod(&content),
+            "requirements.txt" => parse_requirements_txt(&content, path),
+            "pyproject.toml" => parse_pyproject_toml(&content),
+            "pom.xml" => parse_pom_xml(&content),
+            _ => parse_build_gradle(&content, path),
+        };
+
+        let Some(manifest) = manifest else {
+            warn!("Failed to parse manifest {:?}", path);
+            continue;
+        };
+
+        debug!(
+            "Found {} dependencies in {:?}",
+            manifest.dependencies.len(),
+            path
+        );
+        manifest_count += 1;
+
+        let file_path = path.to_str().unwrap_or("");
+        let mut module_node = create_node(
+            NodeType::Module,
+            manifest.package_name.clone(),
+            file_path,
+            (1, content.lines().count().max(1)),
+            content.clone(),
+        )
+        .with_metadata("manifest".to_string(), manifest.ecosystem.to_string());
+        module_node.repo = provider.repo_for(path);
+        module_node.language = ecosystem_language(manifest.ecosystem);
+        module_node.id = config.node_id(&module_node);
+        let module_id = module_node.id.clone();
+        graph.add_node(module_node);
+
+        for dependency in manifest.dependencies {
+            let key = (manifest.ecosystem, dependency.name.clone());
+            let dependency_id = match dependency_ids.get(&key) {
+                Some(id) => id.clone(),
+                None => {
+                    let mut node = create_dependency_node(
+                        &dependency,
+                        manifest.ecosystem,
+                        file_path,
+                        &content,
+                    );
+                    node.id = config.node_id(&node);
+                    let id = node.id.clone();
+                    graph.add_node(node);
+                    dependency_ids.insert(key, id.clone());
+                    id
+                }
+            };
+
+            trace!(
+                "Found dependency: {} -> {}",
+                manifest.package_name, dependency.name
+            );
+            let mut relationship = Relationship::new(
+                RelationshipType::DependsOn,
+                module_id.clone(),
+                dependency_id,
+            )
+            .with_provenance(provenance::PACKAGE_MANIFEST)
+            .with_metadata("scope".to_string(), dependency.scope.to_string());
+            if let Some(version) = dependency.version {
+                relationship.add_metadata("version".to_string(), version);
+            }
+            relationships.push(relationship);
+        }
+    }
+
+    info!(
+        "Adding {} dependency relationships from {} manifests",
+        relationships.len(),
+        manifest_count
+    );
+    for relationship in relationships {
+        graph.add_relationship(relationship);
+    }
+}
+
+fn create_dependency_node(
+    dependency: &ManifestDependency,
+    ecosystem: &'static str,
+    file_path: &str,
+    content: &str,
+) -> CodeNode {
+    // Point at the first line mentioning the dependency
+    let (line_number, line) = content
+        .lines()
+        .enumerate()
+        .find(|(_, line)| line.contains(dependency.name.as_str()))
+        .map(|(idx, line)| (idx + 1, line.trim().to_string()))
+        .unwrap_or((1, dependency.name.clone()));
+
+    let mut node = create_node(
+        NodeType::Dependency,
+        dependency.name.clone(),
+        file_path,
+        (line_number, line_number),
+        line,
+    )
+    .with_metadata("ecosystem".to_string(), ecosystem.to_string());
+    if let Some(version) = &dependency.version {
+        node.add_metadata("version".to_string(), version.clone());
+    }
+    node.language = ecosystem_language(ecosystem);
+
+    node
+}
+
+// Language of the packages an ecosystem publishes; npm covers TypeScript too
+fn ecosystem_language(ecosystem: &str) -> Option<String> {
+    let language = match ecosystem {
+        "cargo" => "rust",
+        "npm" => "javascript",
+        "go" => "go",
+        "pypi" => "python",
+        "maven" => "java",
+        _ => return None,
+    };
+    Some(language.to_string())
+}
+
+fn toml_dependency_table(
+    table: Option<&toml::Value>,
+    scope: &'static str,
+    dependencies: &mut Vec<ManifestDependency>,
+) {
+    let Some(table) = table.and_then(|t| t.as_table()) else {
+        return;
+    };
+
+    for (name, spec) in table {
+        let version = match spec {
+            toml::Value::String(version) => Some(version.clone()),
+            toml::Value::Table(fields) => fields
+                .get("version")
+                .and_then(|v| v.as_str())
+                .map(|v| v.to_string()),
+            _ => None,
+        };
+        dependencies.push(ManifestDependency {
+            name: name.clone(),
+            version,
+            scope,
+        });
+    }
+}
+
+fn parse_cargo_toml(content: &str) -> Option<Manifest> {
+    let value: toml::Value = toml::from_str(content).ok()?;
+
+    let package_name = value
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .unwrap_or("workspace")
+        .to_string();
+
+    let mut dependencies = Vec::new();
+    toml_dependency_table(value.get("dependencies"), "normal", &mut dependencies);
+    toml_dependency_table(value.get("dev-dependencies"), "dev", &mut dependencies);
+    toml_dependency_table(value.get("build-dependencies"), "build", &mut dependencies);
+    toml_dependency_table(
+        value.get("workspace").and_then(|w| w.get("dependencies")),
+        "workspace",
+        &mut dependencies,
+    );
+
+    Some(Manifest {
+        ecosystem: "cargo",
+        package_name,
+        dependencies,
+    })
+}
+
+fn parse_package_json(content: &str) -> Option<Manifest> {
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+
+    let package_name = value
+        .get("name")
+        .and_then(|n| n.as_str())
+        .unwrap_or("package")
+        .to_string();
+
+    let mut dependencies = Vec::new();
+    for (key, scope) in [
+        ("dependencies", "normal"),
+        ("devDependencies", "dev"),
+        ("peerDependencies", "peer"),
+        ("optionalDependencies", "optional"),
+    ] {
+        if let Some(table) = value.get(key).and_then(|t| t.as_object()) {
+            for (name, version) in table {
+                dependencies.push(ManifestDependency {
+                    name: name.clone(),
+                    version: version.as_str().map(|v| v.to_string()),
+                    scope,
+                });
+            }
+        }
+    }
+
+    Some(Manifest {
+        ecosystem: "npm",
+        package_name,
+        dependencies,
+    })
+}
+
+fn parse_go_mod(content: &str) -> Option<Manifest> {
+    let mut package_name = None;
+    let mut dependencies = Vec::new();
+    let mut in_require_block = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+
+        if let Some(module) = line.strip_prefix("module ") {
+            package_name = Some(module.trim().to_string());
+        } else if line == "require (" {
+            in_require_block = true;
+        } else if in_require_block && line == ")" {
+            in_require_block = false;
+        } else {
+            let spec = if in_require_block {
+                Some(line)
+            } else {
+                line.strip_prefix("require ")
+            };
+
+            let mut parts = spec.unwrap_or("").split_whitespace();
+            if let Some(name) = parts.next() {
+                dependencies.push(ManifestDependency {
+                    name: name.to_string(),
+                    version: parts.next().map(|v| v.to_string()),
+                    scope: "normal",
+                });
+            }
+        }
+    }
+
+    Some(Manifest {
+        ecosystem: "go",
+        package_name: package_name?,
+        dependencies,
+    })
+}
+
+// Split a PEP 508 requirement such as `requests[socks]>=2.0; python_version>"3"`
+fn parse_python_requirement(requirement: &str, scope: &'static str) -> Option<ManifestDependency> {
+    let requirement = requirement.split(';').next()?.trim();
+    let name_end = requirement
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(requirement.len());
+
+    let name = &requirement[..name_end];
+    if name.is_empty() {
+        return None;
+    }
+
+    let version = requirement[name_end..]
+        .trim_start_matches(|c: char| c == '[' || c.is_whitespace())
+        .split(']')
+        .next_back()
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_string());
+
+    Some(ManifestDependency {
+        name: name.to_string(),
+        version,
+        scope,
+    })
+}
+
+fn parse_requirements_txt(content: &str, path: &Path) -> Option<Manifest> {
+    let package_name = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or("requirements")
+        .to_string();
+
+    let dependencies = content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('-'))
+        .filter_map(|line| parse_python_requirement(line, "normal"))
+        .collect();
+
+    Some(Manifest {
+        ecosystem: "pypi",
+        package_name,
+        dependencies,
+    })
+}
+
+fn parse_pyproject_toml(content: &str) -> Option<Manifest> {
+    let value: toml::Value = toml::from_str(content).ok()?;
+    let project = value.get("project");
+    let poetry = value.get("tool").and_then(|t| t.get("poetry"));
+
+    let package_name = project
+        .or(poetry)
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .unwrap_or("project")
+        .to_string();
+
+    let mut dependencies = Vec::new();
+
+    if let Some(requirements) = project
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_array())
+    {
+        dependencies.extend(
+            requirements
+                .iter()
+                .filter_map(|r| r.as_str())
+                .filter_map(|r| parse_python_requirement(r, "normal")),
+        );
+    }
+
+    if let Some(groups) = project
+        .and_then(|p| p.get("optional-dependencies"))
+        .and_then(|d| d.as_table())
+    {
+        dependencies.extend(
+            groups
+                .values()
+                .filter_map(|g| g.as_array())
+                .flatten()
+                .filter_map(|r| r.as_str())
+                .filter_map(|r| parse_python_requirement(r, "optional")),
+        );
+    }
+
+    let mut poetry_dependencies = Vec::new();
+    toml_dependency_table(
+        poetry.and_then(|p| p.get("dependencies")),
+        "normal",
+        &mut poetry_dependencies,
+    );
+    toml_dependency_table(
+        poetry.and_then(|p| p.get("dev-dependencies")),
+        "dev",
+        &mut poetry_dependencies,
+    );
+    dependencies.extend(
+        poetry_dependencies
+            .into_iter()
+            .filter(|d| d.name != "python"),
+    );
+
+    Some(Manifest {
+        ecosystem: "pypi",
+        package_name,
+        dependencies,
+    })
+}
+
+fn parse_pom_xml(content: &str) -> Option<Manifest> {
+    let dependency_regex = Regex::new(r"(?s)<dependency>(.*?)</dependency>").ok()?;
+    let block_regex = Regex::new(
+        r"(?s)<parent>.*?</parent>|<dependencyManagement>.*?</dependencyManagement>|<dependencies>.*?</dependencies>|<build>.*?</build>",
+    )
+    .ok();
+
+    let mut dependencies = Vec::new();
+    for capture in dependency_regex.captures_iter(content) {
+        let block = &capture[1];
+        let Some(artifact_id) = xml_tag(block, "artifactId") else {
+            continue;
+        };
+        let name = match xml_tag(block, "groupId") {
+            Some(group_id) => format!("{}:{}", group_id, artifact_id),
+            None => artifact_id,
+        };
+        let scope = match xml_tag(block, "scope").as_deref() {
+            Some("test") => "dev",
+            Some("provided") => "provided",
+            _ => "normal",
+        };
+
+        dependencies.push(ManifestDependency {
+            name,
+            version: xml_tag(block, "version"),
+            scope,
+        });
+    }
+
+    // The project's own artifactId is the first one outside nested blocks
+    let project_section = match &block_regex {
+        Some(regex) => regex.replace_all(content, "").into_owned(),
+        None => content.to_string(),
+    };
+    let package_name = xml_tag(&project_section, "artifactId").unwrap_or("project".to_string());
+
+    Some(Manifest {
+        ecosystem: "maven",
+        package_name,
+        dependencies,
+    })
+}
+
+// Scope of a Gradle dependency configuration, or None for anything else
+fn gradle_scope(configuration: &str) -> Option<&'static str> {
+    match configuration {
+        "implementation" | "api" | "compile" | "runtimeOnly" | "runtime" => Some("normal"),
+        "compileOnly" | "compileOnlyApi" | "annotationProcessor" | "kapt" => Some("provided"),
+        "classpath" => Some("build"),
+        c if c.starts_with("test") || c.starts_with("androidTest") => Some("dev"),
+        _ => None,
+    }
+}
+
+// Handles both the Groovy and the Kotlin DSL. Subproject dependencies such as
+// `project(':core')` are not third-party packages and are left out.
+fn parse_build_gradle(content: &str, path: &Path) -> Option<Manifest> {
+    let declaration_regex = Regex::new(r"^(\w+)\s*\(?\s*(.*)$").ok()?;
+    let coordinate_regex =
+        Regex::new(r#"^(?:(?:enforcedPlatform|platform)\s*\(\s*)?['"]([^'"]+)['"]"#).ok()?;
+    let map_regex = Regex::new(r#"\b(group|name|version)\s*[:=]\s*['"]([^'"]+)['"]"#).ok()?;
+
+    let package_name = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or("project")
+        .to_string();
+
+    let mut dependencies = Vec::new();
+    for line in content.lines().map(str::trim) {
+        let Some(capture) = declaration_regex.captures(line) else {
+            continue;
+        };
+        let Some(scope) = gradle_scope(&capture[1]) else {
+            continue;
+        };
+        let declaration = &capture[2];
+
+        // `'group:artifact:version'`
+        if let Some(coordinate) = coordinate_regex.captures(declaration) {
+            let parts: Vec<&str> = coordinate[1].split(':').collect();
+            if parts.len() < 2 {
+                continue;
+            }
+            dependencies.push(ManifestDependency {
+                name: format!("{}:{}", parts[0], parts[1]),
+                version: parts.get(2).map(|v| v.to_string()),
+                scope,
+            });
+            continue;
+        }
+
+        // `group: 'g', name: 'a', version: 'v'`
+        let fields: HashMap<String, String> = map_regex
+            .captures_iter(declaration)
+            .map(|c| (c[1].to_string(), c[2].to_string()))
+            .collect();
+        if let Some(artifact) = fields.get("name") {
+            let name = match fields.get("group") {
+                Some(group) => format!("{}:{}", group, artifact),
+                None => artifact.clone(),
+            };
+            dependencies.push(ManifestDependency {
+                name,
+                version: fields.get("version").cloned(),
+                scope,
+            });
+        }
+    }
+
+    Some(Manifest {
+        ecosystem: "maven",
+        package_name,
+        dependencies,
+    })
+}
+
+fn xml_tag(content: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = content.find(&open)? + open.len();
+    let end = content[start..].find(&close)? + start;
+
+    Some(content[start..end].trim().to_string())
+}
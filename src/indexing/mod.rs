@@ -1,6 +1,37 @@
 pub mod analyzer;
+pub mod cache;
+pub mod config;
+pub mod endpoints;
+pub mod external;
 pub mod extractor;
+#[cfg(feature = "git")]
+pub mod git;
+pub mod hierarchy;
+pub mod imports;
+pub mod manifests;
+pub mod ownership;
 pub mod processor;
+pub mod provider;
+pub mod receivers;
+pub mod summaries;
 
-pub use analyzer::{enhance_method_names, generate_summaries, identify_relationships};
-pub use processor::process_codebase_parallel;
+pub use analyzer::{
+    enhance_method_names, generate_summaries, identify_references,
+    identify_references_with_provider, identify_relationships,
+    identify_relationships_with_provider,
+};
+pub use cache::IndexCache;
+pub use config::{
+    ContainerContent, DuplicatePolicy, GeneratedPolicy, IdScheme, IndexConfig, NodeCategory,
+    SummaryBackend,
+};
+pub use extractor::{
+    Feature, LanguageCapabilities, capabilities_for_language, language_capabilities,
+};
+#[cfg(feature = "git")]
+pub use git::GitProvider;
+pub use processor::{process_codebase_parallel, process_files_parallel};
+pub use provider::{FileProvider, FsProvider, MemoryProvider, MultiRootProvider};
+#[cfg(feature = "llm")]
+pub use summaries::LlmSummarizer;
+pub use summaries::{Summarizer, TemplateSummarizer, summarize_graph, summarizer_for};
@@ -0,0 +1,554 @@
+use crate::code_graph::{CodeGraph, NodeType, RelationshipType};
+use crate::indexing::external::UnresolvedReference;
+use crate::indexing::extractor::extract_code_units_from_content;
+use crate::indexing::provider::FileProvider;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use log::{debug, info, warn};
+use regex::Regex;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+// Where GitHub and GitLab look for a CODEOWNERS file, relative to the root
+// of the repository it covers; the nested locations come first so their root is not taken
+// to be `.github` or `docs`
+const CODEOWNERS_LOCATIONS: &[&str] = &[".github/CODEOWNERS", "docs/CODEOWNERS", "CODEOWNERS"];
+
+// Lines at the top of a file searched for a license header
+const LICENSE_HEADER_LINES: usize = 30;
+
+// Package manifests with a `license` field
+const LICENSED_MANIFESTS: &[&str] = &["Cargo.toml", "package.json", "pyproject.toml"];
+
+// Well-known license texts without an SPDX tag, by a phrase of their header
+const LICENSE_PHRASES: &[(&str, &str)] = &[
+    (
+        "Licensed under the Apache License, Version 2.0",
+        "Apache-2.0",
+    ),
+    ("Permission is hereby granted, free of charge", "MIT"),
+    ("GNU Lesser General Public License", "LGPL"),
+    ("GNU Affero General Public License", "AGPL"),
+    ("GNU General Public License", "GPL"),
+    ("Mozilla Public License, v. 2.0", "MPL-2.0"),
+    ("Redistribution and use in source and binary forms", "BSD"),
+];
+
+struct LicensePatterns {
+    spdx: Regex,
+    // `license = "MIT"` in Cargo.toml and pyproject.toml, `"license": "MIT"`
+    // in package.json
+    manifest: Regex,
+}
+
+impl LicensePatterns {
+    fn new() -> Option<Self> {
+        Some(LicensePatterns {
+            spdx: Regex::new(
+                r"SPDX-License-Identifier:\s*([A-Za-z0-9.+\-]+(?:\s+(?:AND|OR|WITH)\s+[A-Za-z0-9.+\-]+)*)",
+            )
+            .ok()?,
+            manifest: Regex::new(r#"(?m)^\s*"?license"?\s*[:=]\s*(?:\{\s*text\s*=\s*)?"([^"]+)""#)
+                .ok()?,
+        })
+    }
+
+    // License named in the header of a source file: an SPDX identifier, or
+    // a well-known license text
+    fn detect(&self, content: &str) -> Option<String> {
+        let header: String = content
+            .lines()
+            .take(LICENSE_HEADER_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if let Some(captures) = self.spdx.captures(&header) {
+            return Some(captures[1].to_string());
+        }
+        LICENSE_PHRASES
+            .iter()
+            .find(|(phrase, _)| header.contains(phrase))
+            .map(|(_, license)| license.to_string())
+    }
+
+    fn detect_in_manifest(&self, content: &str) -> Option<String> {
+        self.manifest
+            .captures(content)
+            .map(|captures| captures[1].to_string())
+    }
+}
+
+// The rules of one CODEOWNERS file. As on GitHub, the last rule matching a
+// path decides its owners, and a rule without owners leaves it unowned.
+#[derive(Debug, Clone)]
+pub struct CodeOwners {
+    root: PathBuf,
+    rules: Vec<(GlobSet, Vec<String>)>,
+}
+
+impl CodeOwners {
+    // Parse CODEOWNERS content whose patterns are relative to `root`
+    pub fn parse(root: &Path, content: &str) -> Self {
+        let mut rules = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let Some(pattern) = fields.next() else {
+                continue;
+            };
+            let owners: Vec<String> = fields
+                .take_while(|field| !field.starts_with('#'))
+                .map(str::to_string)
+                .collect();
+            match owner_pattern(pattern) {
+                Some(globs) => rules.push((globs, owners)),
+                None => warn!("Skipping invalid CODEOWNERS pattern: {}", pattern),
+            }
+        }
+        CodeOwners {
+            root: root.to_path_buf(),
+            rules,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    // Owners of a file under the root; None when no rule assigns any
+    pub fn owners_of(&self, path: &Path) -> Option<&[String]> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        self.rules
+            .iter()
+            .rev()
+            .find(|(globs, _)| globs.is_match(relative))
+            .map(|(_, owners)| owners.as_slice())
+            .filter(|owners| !owners.is_empty())
+    }
+}
+
+// Globs for a gitignore-style pattern: anchored to the root when it starts
+// with or contains a slash, matching at any depth otherwise, and covering
+// everything below a matching directory
+fn owner_pattern(pattern: &str) -> Option<GlobSet> {
+    let directory_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_start_matches('/').trim_end_matches('/');
+    let anchored = pattern.starts_with('/') || trimmed.contains('/');
+    let base = match (trimmed, anchored) {
+        ("", _) | ("*", false) => "**".to_string(),
+        (trimmed, true) => trimmed.to_string(),
+        (trimmed, false) => format!("**/{}", trimmed),
+    };
+
+    let mut globs = vec![format!("{}/**", base)];
+    if !directory_only {
+        globs.push(base);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(
+            GlobBuilder::new(&glob)
+                .literal_separator(true)
+                .build()
+                .ok()?,
+        );
+    }
+    builder.build().ok()
+}
+
+// Every CODEOWNERS file the provider lists, each covering the repository
+// root it sits in
+pub fn find_codeowners(provider: &dyn FileProvider) -> Vec<CodeOwners> {
+    let files = match provider.list_files() {
+        Ok(files) => files,
+        Err(e) => {
+            warn!("Failed to list files for CODEOWNERS: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut codeowners = Vec::new();
+    for path in files {
+        let Some(root) = CODEOWNERS_LOCATIONS.iter().find_map(|location| {
+            let mut root = path.to_str()?.strip_suffix(location)?;
+            if !root.is_empty() {
+                root = root.strip_suffix('/')?;
+            }
+            Some(PathBuf::from(root))
+        }) else {
+            continue;
+        };
+        match provider.read_file(&path) {
+            Ok(content) => {
+                debug!("Using {:?} for files under {:?}", path, root);
+                codeowners.push(CodeOwners::parse(&root, &content));
+            }
+            Err(e) => warn!("Failed to read {:?}: {}", path, e),
+        }
+    }
+    // The deepest root wins for nested repositories
+    codeowners.sort_by_key(|owners| std::cmp::Reverse(owners.root.components().count()));
+    codeowners
+}
+
+// Whether two CODEOWNERS owners are the same, as GitHub compares them:
+// ignoring case, and here the leading `@` too
+fn same_owner(a: &str, b: &str) -> bool {
+    a.trim_start_matches('@')
+        .eq_ignore_ascii_case(b.trim_start_matches('@'))
+}
+
+fn is_codeowners_file(path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    CODEOWNERS_LOCATIONS
+        .iter()
+        .any(|location| path == *location || path.ends_with(&format!("/{}", location)))
+}
+
+// Lists only the files CODEOWNERS assigns to one owner, so a team can index
+// its part of a monorepo. The CODEOWNERS files themselves stay listed for
+// the ownership pass; everything else is read through the wrapped provider.
+pub struct OwnedFilesProvider<'a> {
+    inner: &'a dyn FileProvider,
+    owner: String,
+    codeowners: Vec<CodeOwners>,
+}
+
+impl<'a> OwnedFilesProvider<'a> {
+    pub fn new(inner: &'a dyn FileProvider, owner: &str) -> Self {
+        let codeowners = find_codeowners(inner);
+        if codeowners.is_empty() {
+            warn!("No CODEOWNERS file found, so no file is owned by {}", owner);
+        }
+        OwnedFilesProvider {
+            inner,
+            owner: owner.to_string(),
+            codeowners,
+        }
+    }
+
+    pub fn owns(&self, path: &Path) -> bool {
+        self.codeowners
+            .iter()
+            .find(|owners| path.starts_with(owners.root()))
+            .and_then(|owners| owners.owners_of(path))
+            .is_some_and(|owners| owners.iter().any(|owner| same_owner(owner, &self.owner)))
+    }
+}
+
+impl FileProvider for OwnedFilesProvider<'_> {
+    fn list_files(&self) -> io::Result<Vec<PathBuf>> {
+        let files = self.inner.list_files()?;
+        let total = files.len();
+        let owned: Vec<PathBuf> = files
+            .into_iter()
+            .filter(|path| self.owns(path) || is_codeowners_file(path))
+            .collect();
+        debug!(
+            "{} of {} files are owned by {}",
+            owned.len(),
+            total,
+            self.owner
+        );
+        Ok(owned)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        self.inner.read_file(path)
+    }
+
+    fn read_file_with_encoding(&self, path: &Path) -> io::Result<(String, &'static str)> {
+        self.inner.read_file_with_encoding(path)
+    }
+
+    fn repo_for(&self, path: &Path) -> Option<String> {
+        self.inner.repo_for(path)
+    }
+
+    fn roots(&self) -> Vec<PathBuf> {
+        self.inner.roots()
+    }
+
+    fn unlisted_files(&self) -> Vec<PathBuf> {
+        match self.inner.list_files() {
+            Ok(files) => files
+                .into_iter()
+                .filter(|path| !self.owns(path) && !is_codeowners_file(path))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to list files outside the owned set: {}", e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+// The files left out when indexing one owner's files, for telling
+// references into another team's code from third-party ones
+pub struct OwnershipBoundary {
+    files: Vec<PathBuf>,
+    roots: Vec<PathBuf>,
+    codeowners: Vec<CodeOwners>,
+}
+
+// A reference into an unlisted file, named by the whole module path it
+// imports or by the function it calls
+pub struct CrossingReference {
+    pub reference: UnresolvedReference,
+    pub target_file: PathBuf,
+}
+
+impl OwnershipBoundary {
+    // None when the provider leaves no file out
+    pub fn new(provider: &dyn FileProvider) -> Option<Self> {
+        let mut files = provider.unlisted_files();
+        if files.is_empty() {
+            return None;
+        }
+        files.sort();
+        Some(OwnershipBoundary {
+            files,
+            roots: provider.roots(),
+            codeowners: find_codeowners(provider),
+        })
+    }
+
+    // Comma-separated owners of an unlisted file
+    pub fn owners_of(&self, path: &Path) -> Option<String> {
+        self.codeowners
+            .iter()
+            .find(|owners| path.starts_with(owners.root()))
+            .and_then(|owners| owners.owners_of(path))
+            .map(|owners| owners.join(","))
+    }
+
+    // The unlisted file a module path names: relative to the importer for
+    // Python's leading dots and `./` or `../` paths, otherwise by the file
+    // path ending with its segments. Paths of more than one segment may end
+    // with the imported definition (`crate::a::Thing`, `com.acme.Util.run`).
+    fn module_file(&self, module: &str, importer: &Path) -> Option<&Path> {
+        let directory = importer.parent().unwrap_or(Path::new(""));
+        let (base, rest) = if module.starts_with("./") || module.starts_with("../") {
+            (Some(normalize(&directory.join(module))), "")
+        } else if module.starts_with('.') {
+            let rest = module.trim_start_matches('.');
+            let mut base = directory;
+            for _ in 1..module.len() - rest.len() {
+                base = base.parent()?;
+            }
+            (Some(base.to_path_buf()), rest)
+        } else {
+            (None, module.strip_prefix("crate::").unwrap_or(module))
+        };
+        let segments: Vec<&str> = rest
+            .split(['.', '/', ':'])
+            .filter(|segment| !segment.is_empty())
+            .collect();
+
+        let shortest = match &base {
+            Some(_) => segments.len().saturating_sub(1),
+            None => segments.len().saturating_sub(1).max(1),
+        };
+        (shortest..=segments.len())
+            .rev()
+            .find_map(|length| {
+                let suffix: PathBuf = segments[..length].iter().collect();
+                let found = self.files.iter().filter(|file| {
+                    let stem = file.with_extension("");
+                    let stem = match stem.file_name().and_then(|name| name.to_str()) {
+                        Some("__init__" | "index" | "mod") => {
+                            stem.parent().unwrap_or(&stem).to_path_buf()
+                        }
+                        _ => stem,
+                    };
+                    match &base {
+                        Some(base) => stem == base.join(&suffix),
+                        // A lone segment only names a top-level module
+                        None if length == 1 => {
+                            stem.ends_with(&suffix)
+                                && stem.parent().is_some_and(|parent| {
+                                    self.roots.iter().any(|root| root == parent)
+                                })
+                        }
+                        None => stem.ends_with(&suffix),
+                    }
+                });
+                // The candidate closest to the importer, like a package search
+                found.max_by(|a, b| {
+                    shared_components(a, importer)
+                        .cmp(&shared_components(b, importer))
+                        .then_with(|| b.cmp(a))
+                })
+            })
+            .map(PathBuf::as_path)
+    }
+
+    // Take out the references that point into unlisted files: imports by
+    // their module path, and calls by the definitions of the unlisted files
+    // their file imports
+    pub fn split(
+        &self,
+        provider: &dyn FileProvider,
+        graph: &CodeGraph,
+        references: Vec<UnresolvedReference>,
+    ) -> (Vec<CrossingReference>, Vec<UnresolvedReference>) {
+        let importer_of = |reference: &UnresolvedReference| {
+            graph
+                .get_node(&reference.relationship.from_id)
+                .map(|node| PathBuf::from(&node.file_path))
+        };
+
+        let mut crossing = Vec::new();
+        let mut calls = Vec::new();
+        let mut remaining = Vec::new();
+        // Unlisted files each indexed file imports
+        let mut imported: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+        for mut reference in references {
+            if reference.relationship.relationship_type != RelationshipType::Imports {
+                calls.push(reference);
+                continue;
+            }
+            let module = reference
+                .relationship
+                .metadata
+                .get("module")
+                .cloned()
+                .unwrap_or_else(|| reference.name.clone());
+            let target = importer_of(&reference)
+                .and_then(|importer| Some((self.module_file(&module, &importer)?, importer)));
+            match target {
+                Some((target_file, importer)) => {
+                    let files = imported.entry(importer).or_default();
+                    if !files.iter().any(|file| file == target_file) {
+                        files.push(target_file.to_path_buf());
+                    }
+                    reference.name = module;
+                    crossing.push(CrossingReference {
+                        reference,
+                        target_file: target_file.to_path_buf(),
+                    });
+                }
+                None => remaining.push(reference),
+            }
+        }
+
+        let mut definitions: HashMap<PathBuf, Vec<String>> = HashMap::new();
+        for reference in calls {
+            let target_file = importer_of(&reference).and_then(|importer| {
+                imported.get(&importer)?.iter().find(|file| {
+                    definitions
+                        .entry(file.to_path_buf())
+                        .or_insert_with(|| definition_names(provider, file))
+                        .contains(&reference.name)
+                })
+            });
+            match target_file {
+                Some(target_file) => crossing.push(CrossingReference {
+                    target_file: target_file.clone(),
+                    reference,
+                }),
+                None => remaining.push(reference),
+            }
+        }
+
+        debug!(
+            "{} unresolved references point into files of other owners",
+            crossing.len()
+        );
+        (crossing, remaining)
+    }
+}
+
+// Names defined in a file, extracted without adding it to the graph
+fn definition_names(provider: &dyn FileProvider, path: &Path) -> Vec<String> {
+    match provider.read_file(path) {
+        Ok(content) => extract_code_units_from_content(path, &content)
+            .into_iter()
+            .map(|node| node.name)
+            .collect(),
+        Err(e) => {
+            warn!("Failed to read {:?}: {}", path, e);
+            Vec::new()
+        }
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn shared_components(a: &Path, b: &Path) -> usize {
+    a.components()
+        .zip(b.components())
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+// Set `owners` (comma separated, from CODEOWNERS) and `license` (from the
+// file header, or the `license` field of a package manifest) on every node
+// of a file, so that symbols can be selected by owner as well as files
+pub fn add_file_ownership(provider: &dyn FileProvider, graph: &mut CodeGraph) {
+    info!("Annotating files with owners and licenses...");
+
+    let Some(patterns) = LicensePatterns::new() else {
+        warn!("Failed to compile license patterns");
+        return;
+    };
+    let codeowners = find_codeowners(provider);
+    // Owners and license of each file, looked up once for all its nodes
+    let mut files: HashMap<String, (Option<String>, Option<String>)> = HashMap::new();
+    let mut annotated = 0;
+
+    for node in graph.all_nodes_mut() {
+        if matches!(node.node_type, NodeType::Directory) || node.file_path.is_empty() {
+            continue;
+        }
+
+        let (owners, license) = files.entry(node.file_path.clone()).or_insert_with(|| {
+            let path = PathBuf::from(&node.file_path);
+            let owners = codeowners
+                .iter()
+                .find(|owners| path.starts_with(owners.root()))
+                .and_then(|owners| owners.owners_of(&path))
+                .map(|owners| owners.join(","));
+            let license = provider.read_file(&path).ok().and_then(|content| {
+                let file_name = path.file_name().and_then(|name| name.to_str());
+                if file_name.is_some_and(|name| LICENSED_MANIFESTS.contains(&name)) {
+                    patterns.detect_in_manifest(&content)
+                } else {
+                    patterns.detect(&content)
+                }
+            });
+            (owners, license)
+        });
+        if let Some(owners) = owners {
+            node.add_metadata("owners".to_string(), owners.clone());
+        }
+        if let Some(license) = license {
+            node.add_metadata("license".to_string(), license.clone());
+        }
+
+        if owners.is_some() || license.is_some() {
+            annotated += 1;
+        }
+    }
+
+    info!(
+        "Annotated {} nodes of {} files from {} CODEOWNERS files",
+        annotated,
+        files.len(),
+        codeowners.len()
+    );
+}
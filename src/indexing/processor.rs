@@ -1,57 +1,310 @@
-use crate::code_graph::CodeGraph;
-use crate::indexing::extractor::extract_code_units;
+use crate::code_graph::{CodeGraph, CodeNode, NodeType};
+use crate::indexing::cache::IndexCache;
+use crate::indexing::config::{
+    ContainerContent, DuplicatePolicy, GeneratedPolicy, IndexConfig, NodeCategory,
+};
+use crate::indexing::extractor::{common, extract_code_units_from_content};
+use crate::indexing::provider::{FileProvider, FsProvider};
+use crate::utils::hash::content_hash;
 use log::{debug, error, info, trace, warn};
 use rayon::prelude::*;
-use std::collections::HashSet;
-use std::fs;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
-use walkdir::WalkDir;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-pub fn process_codebase_parallel(root_path: &Path, num_threads: usize) -> io::Result<CodeGraph> {
+pub fn process_codebase_parallel(root_path: &Path, config: &IndexConfig) -> io::Result<CodeGraph> {
+    process_files_parallel(&FsProvider::with_config(root_path, config), config, None)
+}
+
+// Extract nodes from every supported file. When a previous graph is given,
+// files whose content hash is unchanged reuse its nodes instead of being
+// parsed; otherwise they are looked up in the index cache, if one is configured.
+pub fn process_files_parallel(
+    provider: &dyn FileProvider,
+    config: &IndexConfig,
+    previous: Option<&CodeGraph>,
+) -> io::Result<CodeGraph> {
     info!(
         "Starting parallel codebase processing with {} threads",
-        num_threads
+        config.num_threads
     );
 
-    let graph = Arc::new(Mutex::new(CodeGraph::new()));
+    run_in_pool(config.num_threads, || {
+        extract_files(provider, config, previous)
+    })
+}
 
-    rayon::ThreadPoolBuilder::new()
+// A pool per run rather than the global one, which can only be set up once
+// per process, so repeated runs each get their own thread count
+#[cfg(not(target_arch = "wasm32"))]
+fn run_in_pool<T: Send>(
+    num_threads: usize,
+    run: impl FnOnce() -> io::Result<T> + Send,
+) -> io::Result<T> {
+    let pool = rayon::ThreadPoolBuilder::new()
         .num_threads(num_threads)
-        .build_global()
-        .unwrap();
+        .build()
+        .map_err(io::Error::other)?;
+    pool.install(run)
+}
 
-    let visited_files = Arc::new(Mutex::new(HashSet::new()));
-    let supported_extensions = get_supported_extensions();
+// wasm32 cannot spawn threads, so no pool can be built; rayon's global pool
+// falls back to running everything on the current thread
+#[cfg(target_arch = "wasm32")]
+fn run_in_pool<T: Send>(
+    _num_threads: usize,
+    run: impl FnOnce() -> io::Result<T> + Send,
+) -> io::Result<T> {
+    run()
+}
+
+fn extract_files(
+    provider: &dyn FileProvider,
+    config: &IndexConfig,
+    previous: Option<&CodeGraph>,
+) -> io::Result<CodeGraph> {
+    let supported_extensions = get_supported_extensions(config);
 
-    info!("Scanning directory for supported files...");
-    let files_to_process =
-        collect_files_to_process(root_path, &supported_extensions, &visited_files)?;
+    info!("Scanning for supported files...");
+    let files_to_process = collect_files_to_process(provider, &supported_extensions)?;
     info!("Found {} files to process", files_to_process.len());
 
-    files_to_process.par_iter().for_each(|path| {
-        debug!("Processing file: {:?}", path);
-        match extract_code_units(path) {
-            Ok(units) => {
+    // Hashes are needed before extraction only to find duplicates, reuse a
+    // previous graph or look files up in the cache; otherwise each file is
+    // hashed when it is parsed
+    let needs_hashes = config.duplicate_files != DuplicatePolicy::Keep
+        || previous.is_some()
+        || config.cache_dir.is_some();
+    let read_files = if needs_hashes {
+        read_files(provider, &files_to_process)
+    } else {
+        HashMap::new()
+    };
+
+    let duplicates = if config.duplicate_files == DuplicatePolicy::Keep {
+        HashMap::new()
+    } else {
+        find_duplicate_files(provider, &read_files)
+    };
+    if !duplicates.is_empty() {
+        info!(
+            "Found {} duplicate files ({:?} policy)",
+            duplicates.len(),
+            config.duplicate_files
+        );
+    }
+
+    let mut aliases: HashMap<&PathBuf, Vec<String>> = HashMap::new();
+    for (duplicate, canonical) in &duplicates {
+        aliases
+            .entry(canonical)
+            .or_default()
+            .push(duplicate.to_string_lossy().into_owned());
+    }
+    for paths in aliases.values_mut() {
+        paths.sort();
+    }
+
+    let cache = config
+        .cache_dir
+        .as_deref()
+        .and_then(|dir| match IndexCache::open(dir, config) {
+            Ok(cache) => Some(cache),
+            Err(e) => {
+                warn!("Failed to open index cache {:?}: {}", dir, e);
+                None
+            }
+        });
+
+    let reused_files = AtomicUsize::new(0);
+    let cached_files = AtomicUsize::new(0);
+    let skipped_files = AtomicUsize::new(0);
+
+    let process_file = |path: &PathBuf| -> Vec<CodeNode> {
+        let canonical = duplicates.get(path);
+        if canonical.is_some() && config.duplicate_files == DuplicatePolicy::Alias {
+            trace!("Skipping duplicate file: {:?}", path);
+            return Vec::new();
+        }
+
+        let read_file = read_files.get(path);
+        // Checked before any lookup, so a lowered limit drops files whose
+        // nodes were reused or cached under a higher one
+        if read_file.is_some_and(|file| is_oversized(&file.content, config)) {
+            debug!("Skipping oversized file: {:?}", path);
+            skipped_files.fetch_add(1, Ordering::Relaxed);
+            return Vec::new();
+        }
+        let file_hash = read_file.map(|file| file.hash.as_str());
+        let reused = previous
+            .zip(file_hash)
+            .and_then(|(previous, hash)| reusable_nodes(previous, path, hash))
+            // Truncated content cannot be restored for a full-content run
+            .filter(|units| {
+                config.container_content != ContainerContent::Full
+                    || units
+                        .iter()
+                        .all(|unit| !unit.metadata.contains_key("content_scope"))
+            });
+        let cached = || {
+            cache
+                .as_ref()
+                .zip(file_hash)
+                .and_then(|(cache, hash)| cache.load(path, hash))
+        };
+
+        let units = match (reused, file_hash) {
+            (Some(units), Some(hash)) => {
+                trace!(
+                    "Reusing {} nodes for unchanged file {:?}",
+                    units.len(),
+                    path
+                );
+                reused_files.fetch_add(1, Ordering::Relaxed);
+                Ok((units, hash.to_string()))
+            }
+            _ => match cached().zip(file_hash) {
+                Some((units, hash)) => {
+                    trace!("Loaded {} cached nodes for {:?}", units.len(), path);
+                    cached_files.fetch_add(1, Ordering::Relaxed);
+                    Ok((units, hash.to_string()))
+                }
+                None => {
+                    debug!("Processing file: {:?}", path);
+                    // Files read up front are not read again
+                    let read = match read_file {
+                        Some(file) => Ok((Cow::Borrowed(file.content.as_str()), file.encoding)),
+                        None => provider
+                            .read_file_with_encoding(path)
+                            .map(|(content, encoding)| (Cow::Owned(content), encoding)),
+                    };
+                    read.map(|(content, encoding)| {
+                        let hash = file_hash
+                            .map_or_else(|| content_hash(content.as_bytes()), str::to_string);
+                        let units = match extract_file(path, &content, encoding, config) {
+                            Some(units) => {
+                                if let Some(cache) = &cache
+                                    && let Err(e) = cache.store(path, &hash, &units)
+                                {
+                                    warn!("Failed to cache nodes for {:?}: {}", path, e);
+                                }
+                                units
+                            }
+                            None => {
+                                skipped_files.fetch_add(1, Ordering::Relaxed);
+                                Vec::new()
+                            }
+                        };
+                        (units, hash)
+                    })
+                }
+            },
+        };
+
+        match units {
+            Ok((mut units, file_hash)) => {
                 debug!("Extracted {} code units from {:?}", units.len(), path);
-                let mut graph = graph.lock().unwrap();
-                for unit in units {
-                    trace!("Adding node: {} ({:?})", unit.name, unit.node_type);
-                    graph.add_node(unit);
+                let language = crate::parsers::detect_language(path);
+                exclude_nodes(&mut units, language.as_deref().unwrap_or_default(), config);
+                let repo = provider.repo_for(path);
+                for unit in units.iter_mut() {
+                    unit.repo = repo.clone();
+                    unit.language = language.clone();
+                    if !unit.content.is_empty() {
+                        unit.content_hash = Some(content_hash(unit.content.as_bytes()));
+                    }
+                    truncate_container_content(unit, config.container_content, language.as_deref());
+                    unit.metadata.remove("duplicate_of");
+                    unit.metadata.remove("aliases");
+                    unit.add_metadata("file_hash".to_string(), file_hash.clone());
+                    if let Some(canonical) = canonical {
+                        unit.add_metadata(
+                            "duplicate_of".to_string(),
+                            canonical.to_string_lossy().into_owned(),
+                        );
+                    } else if let Some(paths) = aliases.get(path) {
+                        unit.add_metadata("aliases".to_string(), paths.join(","));
+                    }
                 }
+                units
             }
             Err(e) => {
                 error!("Error processing file {:?}: {}", path, e);
+                Vec::new()
             }
         }
+    };
+
+    // Each directory is one unit of work; rayon steals whole directories
+    // between threads and `collect` keeps the chunks in directory order
+    let mut files_by_dir: BTreeMap<&Path, Vec<&PathBuf>> = BTreeMap::new();
+    for path in &files_to_process {
+        files_by_dir
+            .entry(path.parent().unwrap_or(Path::new("")))
+            .or_default()
+            .push(path);
+    }
+    debug!("Processing {} directories", files_by_dir.len());
+
+    let chunks: Vec<Vec<CodeNode>> = files_by_dir
+        .par_iter()
+        .map(|(_, files)| files.iter().flat_map(|path| process_file(path)).collect())
+        .collect();
+
+    if cache.is_some() {
+        info!(
+            "Loaded nodes from the index cache for {} files",
+            cached_files.into_inner()
+        );
+    }
+
+    let skipped_files = skipped_files.into_inner();
+    if skipped_files > 0 {
+        info!(
+            "Skipped {} oversized, minified or generated files",
+            skipped_files
+        );
+    }
+
+    if previous.is_some() {
+        info!(
+            "Reused nodes from previous graph for {} unchanged files",
+            reused_files.into_inner()
+        );
+    }
+
+    // Sort by file, then position, so insertion order never depends on scheduling
+    let mut units: Vec<CodeNode> = chunks.into_iter().flatten().collect();
+    units.sort_by(|a, b| {
+        (&a.file_path, a.line_range, &a.node_type, &a.name).cmp(&(
+            &b.file_path,
+            b.line_range,
+            &b.node_type,
+            &b.name,
+        ))
     });
 
+    let mut final_graph = CodeGraph::new();
+    let mut seen_ids = HashSet::new();
+    for mut unit in units {
+        // Colliding nodes get a deterministic suffix
+        let base_id = config.node_id(&unit);
+        unit.id = base_id.clone();
+        let mut occurrence = 1;
+        while seen_ids.contains(&unit.id) {
+            unit.id = common::disambiguate_node_id(&base_id, occurrence);
+            occurrence += 1;
+        }
+        seen_ids.insert(unit.id.clone());
+
+        trace!("Adding node: {} ({:?})", unit.name, unit.node_type);
+        final_graph.add_node(unit);
+    }
+    link_local_variables(&mut final_graph);
+
     info!("File processing complete");
-    let final_graph = Arc::try_unwrap(graph)
-        .expect("Failed to unwrap Arc")
-        .into_inner()
-        .expect("Failed to unwrap Mutex");
 
     let mut node_type_counts = std::collections::HashMap::new();
     for node in final_graph.all_nodes() {
@@ -67,19 +320,12 @@ pub fn process_codebase_parallel(root_path: &Path, num_threads: usize) -> io::Re
 }
 
 fn collect_files_to_process(
-    root_path: &Path,
+    provider: &dyn FileProvider,
     supported_extensions: &HashSet<&'static str>,
-    visited_files: &Arc<Mutex<HashSet<PathBuf>>>,
 ) -> io::Result<Vec<PathBuf>> {
     let mut files_to_process = Vec::new();
 
-    for entry in WalkDir::new(root_path).into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-
-        if path.is_dir() {
-            continue;
-        }
-
+    for path in provider.list_files()? {
         if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
             if !supported_extensions.contains(ext) {
                 trace!("Skipping unsupported file: {:?}", path);
@@ -90,32 +336,341 @@ fn collect_files_to_process(
             continue;
         }
 
-        let canonical_path = match fs::canonicalize(path) {
-            Ok(p) => p,
+        files_to_process.push(path);
+    }
+
+    Ok(files_to_process)
+}
+
+fn is_oversized(content: &str, config: &IndexConfig) -> bool {
+    config
+        .max_file_size
+        .is_some_and(|max_size| content.len() > max_size)
+}
+
+// Nodes of a freshly read file, or None when the file is skipped
+fn extract_file(
+    path: &Path,
+    content: &str,
+    encoding: &str,
+    config: &IndexConfig,
+) -> Option<Vec<CodeNode>> {
+    if is_oversized(content, config) {
+        debug!("Skipping oversized file: {:?}", path);
+        return None;
+    }
+
+    let generated = generated_kind(path, content);
+    if let Some(kind) = generated
+        && config.generated_files == GeneratedPolicy::Skip
+    {
+        debug!("Skipping {} file: {:?}", kind, path);
+        return None;
+    }
+
+    let mut units = extract_code_units_from_content(path, content);
+    for unit in units.iter_mut() {
+        if generated.is_some() && config.generated_files == GeneratedPolicy::Tag {
+            unit.add_metadata("generated".to_string(), "true".to_string());
+        }
+        // Only files that were not UTF-8 record their encoding
+        if encoding != "utf-8" {
+            unit.add_metadata("encoding".to_string(), encoding.to_string());
+        }
+    }
+
+    Some(units)
+}
+
+// File name endings of code generators' output
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    ".pb.cc",
+    ".pb.h",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    ".g.dart",
+    ".min.js",
+    ".bundle.js",
+];
+
+// Comments generators put at the top of their output
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "Code generated",
+    "DO NOT EDIT",
+    "<auto-generated",
+];
+
+// Average line length above which a file is taken to be minified
+const MINIFIED_LINE_LENGTH: usize = 500;
+
+// Whether a file is tool output rather than hand-written source: "generated"
+// for code generators' output, "minified" for bundles. Such files inflate
+// the graph without adding anything worth navigating.
+fn generated_kind(path: &Path, content: &str) -> Option<&'static str> {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    if GENERATED_SUFFIXES
+        .iter()
+        .any(|suffix| file_name.ends_with(suffix))
+    {
+        return Some("generated");
+    }
+
+    let header: String = content.lines().take(10).collect::<Vec<_>>().join("\n");
+    if GENERATED_MARKERS
+        .iter()
+        .any(|marker| header.contains(marker))
+    {
+        return Some("generated");
+    }
+
+    let line_count = content.lines().count().max(1);
+    if content.len() > 4096 && content.len() / line_count > MINIFIED_LINE_LENGTH {
+        return Some("minified");
+    }
+
+    None
+}
+
+// Drop the nodes of categories the config leaves out
+fn exclude_nodes(units: &mut Vec<CodeNode>, language: &str, config: &IndexConfig) {
+    let enabled = |category| config.is_node_category_enabled(language, category);
+    let before = units.len();
+    units.retain(|unit| match unit.node_type {
+        NodeType::Variable => {
+            enabled(NodeCategory::Variables)
+                && (enabled(NodeCategory::LocalVariables) || unit.scope.as_deref() != Some("local"))
+        }
+        NodeType::Function => {
+            enabled(NodeCategory::Closures) || !unit.metadata.contains_key("closure")
+        }
+        NodeType::Field => enabled(NodeCategory::Fields),
+        _ => true,
+    });
+    if units.len() < before {
+        trace!("Left out {} excluded nodes", before - units.len());
+    }
+}
+
+// Record on each local variable the ID of the innermost function that
+// declares it, as `function_id`
+fn link_local_variables(graph: &mut CodeGraph) {
+    let mut functions: HashMap<&str, Vec<&CodeNode>> = HashMap::new();
+    let mut locals = Vec::new();
+    for node in graph.all_nodes() {
+        match node.node_type {
+            NodeType::Function | NodeType::Method => functions
+                .entry(node.file_path.as_str())
+                .or_default()
+                .push(node),
+            NodeType::Variable if node.scope.as_deref() == Some("local") => locals.push(node),
+            _ => {}
+        }
+    }
+
+    let owners: Vec<(String, String)> = locals
+        .iter()
+        .filter_map(|local| {
+            let function = functions
+                .get(local.file_path.as_str())?
+                .iter()
+                .filter(|function| {
+                    function.line_range.0 <= local.line_range.0
+                        && local.line_range.1 <= function.line_range.1
+                })
+                .min_by_key(|function| function.line_range.1 - function.line_range.0)?;
+            Some((local.id.clone(), function.id.clone()))
+        })
+        .collect();
+    for (local_id, function_id) in owners {
+        if let Some(local) = graph.get_node_mut(&local_id) {
+            local.add_metadata("function_id".to_string(), function_id);
+        }
+    }
+}
+
+// A file read before extraction, kept so it is parsed without reading it
+// again
+struct ReadFile {
+    content: String,
+    encoding: &'static str,
+    hash: String,
+}
+
+fn read_files(provider: &dyn FileProvider, files: &[PathBuf]) -> HashMap<PathBuf, ReadFile> {
+    files
+        .par_iter()
+        .filter_map(|path| match provider.read_file_with_encoding(path) {
+            Ok((content, encoding)) => {
+                let hash = content_hash(content.as_bytes());
+                Some((
+                    path.clone(),
+                    ReadFile {
+                        content,
+                        encoding,
+                        hash,
+                    },
+                ))
+            }
             Err(e) => {
-                warn!("Failed to canonicalize path {:?}: {}", path, e);
-                continue;
+                warn!("Failed to read file {:?}: {}", path, e);
+                None
             }
-        };
+        })
+        .collect()
+}
 
-        if visited_files.lock().unwrap().contains(&canonical_path) {
-            trace!("Skipping already visited file: {:?}", path);
-            continue;
+// Metadata later passes derive from the whole graph or from files other
+// than the node's own; they set it again when enabled
+const DERIVED_METADATA: &[&str] = &[
+    "fan_in",
+    "fan_out",
+    "pagerank",
+    "token_count",
+    "owners",
+    "license",
+    "reachable",
+    "git_commit",
+    "git_author",
+    "git_modified",
+    "git_changes",
+    "git_file_changes",
+];
+
+// Nodes of a previous graph for a file, if they were extracted from the same
+// content, with only what extraction produced. File and Directory nodes are
+// left out, as the hierarchy pass adds them again, and so are files whose
+// content was redacted, which a fresh parse restores.
+fn reusable_nodes(previous: &CodeGraph, path: &Path, hash: &str) -> Option<Vec<CodeNode>> {
+    let nodes: Vec<&CodeNode> = previous
+        .find_nodes_in_file(path.to_str()?)
+        .into_iter()
+        .filter(|node| !matches!(node.node_type, NodeType::File | NodeType::Directory))
+        .collect();
+
+    let unchanged = !nodes.is_empty()
+        && nodes
+            .iter()
+            .all(|node| node.metadata.get("file_hash").map(|h| h.as_str()) == Some(hash));
+
+    if !unchanged
+        || nodes
+            .iter()
+            .any(|node| node.metadata.contains_key("redacted"))
+    {
+        return None;
+    }
+
+    // Undo method name enhancement, the class prefix and the parameter types
+    // of overloads, so the analyzer sees the same names as on a fresh parse
+    let nodes = nodes
+        .into_iter()
+        .cloned()
+        .map(|mut node| {
+            node.metadata
+                .retain(|key, _| !DERIVED_METADATA.contains(&key.as_str()));
+            node.summary = None;
+            if let Some(signature) = &node.signature
+                && let Some(name) = node
+                    .name
+                    .strip_suffix(&format!("({})", signature.parameter_types.join(", ")))
+            {
+                node.name = name.to_string();
+            }
+            if let Some(parent_class) = &node.parent_class
+                && let Some(name) = node.name.strip_prefix(&format!("{}::", parent_class))
+            {
+                node.name = name.to_string();
+            }
+            node
+        })
+        .collect();
+
+    Some(nodes)
+}
+
+// Shorten the content of class-like nodes according to the configured mode,
+// recording what was kept in `content_scope`. Applying it twice is a no-op.
+fn truncate_container_content(unit: &mut CodeNode, mode: ContainerContent, language: Option<&str>) {
+    if !matches!(
+        unit.node_type,
+        NodeType::Class | NodeType::Interface | NodeType::Module
+    ) {
+        return;
+    }
+
+    let (content, scope) = match mode {
+        ContainerContent::Full => return,
+        ContainerContent::Header => (container_header(&unit.content, language), "header"),
+        ContainerContent::FirstLines(n) => (
+            unit.content.lines().take(n).collect::<Vec<_>>().join("\n"),
+            "lines",
+        ),
+    };
+
+    if content.len() < unit.content.len() {
+        unit.content = content;
+        unit.add_metadata("content_scope".to_string(), scope.to_string());
+    }
+}
+
+// The declaration of a container without its body: everything up to the
+// opening brace, or the first line for indentation-based languages
+fn container_header(content: &str, language: Option<&str>) -> String {
+    let first_line = content.lines().next().unwrap_or("");
+    match language {
+        Some("python") | Some("ruby") | Some("bash") | Some("ocaml") | None => {
+            first_line.to_string()
         }
+        Some(_) => match content.find('{') {
+            Some(brace) => content[..=brace].to_string(),
+            None => first_line.to_string(),
+        },
+    }
+}
 
-        visited_files.lock().unwrap().insert(canonical_path);
-        files_to_process.push(path.to_path_buf());
+// Map every duplicate file to the canonical copy sharing its content hash.
+// The lexicographically smallest path is canonical so the choice is stable.
+// Copies are only looked for within one repository, as identical files in
+// two repositories are separate code.
+fn find_duplicate_files(
+    provider: &dyn FileProvider,
+    files: &HashMap<PathBuf, ReadFile>,
+) -> HashMap<PathBuf, PathBuf> {
+    let mut files_by_hash: HashMap<(Option<String>, &str), Vec<&PathBuf>> = HashMap::new();
+    for (path, file) in files {
+        files_by_hash
+            .entry((provider.repo_for(path), &file.hash))
+            .or_default()
+            .push(path);
     }
 
-    Ok(files_to_process)
+    let mut duplicates = HashMap::new();
+    for mut paths in files_by_hash.into_values() {
+        if paths.len() < 2 {
+            continue;
+        }
+        paths.sort();
+        let canonical = paths[0].clone();
+        for duplicate in &paths[1..] {
+            duplicates.insert((*duplicate).clone(), canonical.clone());
+        }
+    }
+
+    duplicates
 }
 
-fn get_supported_extensions() -> HashSet<&'static str> {
+fn get_supported_extensions(config: &IndexConfig) -> HashSet<&'static str> {
     let mut extensions = HashSet::new();
 
-    // Add all language extensions
+    // Add the extensions of every enabled language
     for ext in crate::parsers::common::get_supported_extensions() {
-        extensions.insert(ext);
+        let enabled = crate::parsers::treesitter::languages::detect_language_from_extension(ext)
+            .is_some_and(|language| config.is_language_enabled(&language));
+        if enabled {
+            extensions.insert(ext);
+        }
     }
 
     extensions
@@ -0,0 +1,250 @@
+use crate::indexing::config::IndexConfig;
+use crate::utils::encoding::decode_source;
+use log::{debug, trace, warn};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+// Source of the files to index: the local filesystem, an in-memory set
+// received over the network, a git object database, ...
+pub trait FileProvider: Sync {
+    // Every candidate file; the pipeline filters by extension and name
+    fn list_files(&self) -> io::Result<Vec<PathBuf>>;
+    fn read_file(&self, path: &Path) -> io::Result<String>;
+
+    // Content along with the encoding it was decoded from
+    fn read_file_with_encoding(&self, path: &Path) -> io::Result<(String, &'static str)> {
+        self.read_file(path).map(|content| (content, "utf-8"))
+    }
+
+    // Name of the repository a file belongs to, for providers spanning several
+    fn repo_for(&self, _path: &Path) -> Option<String> {
+        None
+    }
+
+    // Directories the files are listed from, empty for in-memory files
+    fn roots(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    // Files of the codebase left out of the listing, which listed files may
+    // still reference; empty unless the provider narrows another one
+    fn unlisted_files(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
+
+// Walks a directory tree on disk
+pub struct FsProvider {
+    root_path: PathBuf,
+    follow_symlinks: bool,
+    allow_outside_root: bool,
+}
+
+impl FsProvider {
+    pub fn new(root_path: &Path) -> Self {
+        FsProvider {
+            root_path: root_path.to_path_buf(),
+            follow_symlinks: false,
+            allow_outside_root: false,
+        }
+    }
+
+    // Applies the symlink policy of an index configuration
+    pub fn with_config(root_path: &Path, config: &IndexConfig) -> Self {
+        FsProvider {
+            follow_symlinks: config.follow_symlinks,
+            allow_outside_root: config.allow_outside_root,
+            ..FsProvider::new(root_path)
+        }
+    }
+}
+
+impl FileProvider for FsProvider {
+    fn list_files(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let mut visited_files = HashSet::new();
+        let canonical_root =
+            fs::canonicalize(&self.root_path).unwrap_or_else(|_| self.root_path.clone());
+
+        // With symlinks followed, walkdir detects cycles and reports them as errors
+        let walker = WalkDir::new(&self.root_path)
+            .follow_links(self.follow_symlinks)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| entry.file_name() != ".git");
+
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    if let Some(ancestor) = e.loop_ancestor() {
+                        warn!("Skipping symlink loop back to {:?}", ancestor);
+                    } else {
+                        trace!("Skipping unreadable entry: {}", e);
+                    }
+                    continue;
+                }
+            };
+            let path = entry.path();
+
+            if entry.file_type().is_dir() {
+                continue;
+            }
+
+            if entry.path_is_symlink() && !self.follow_symlinks {
+                trace!("Skipping symlink: {:?}", path);
+                continue;
+            }
+
+            let canonical_path = match fs::canonicalize(path) {
+                Ok(p) => p,
+                Err(e) => {
+                    warn!("Failed to canonicalize path {:?}: {}", path, e);
+                    continue;
+                }
+            };
+
+            if !self.allow_outside_root && !canonical_path.starts_with(&canonical_root) {
+                debug!("Skipping file outside the root: {:?}", path);
+                continue;
+            }
+
+            if !visited_files.insert(canonical_path) {
+                trace!("Skipping already visited file: {:?}", path);
+                continue;
+            }
+
+            files.push(path.to_path_buf());
+        }
+
+        Ok(files)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        self.read_file_with_encoding(path)
+            .map(|(content, _)| content)
+    }
+
+    fn read_file_with_encoding(&self, path: &Path) -> io::Result<(String, &'static str)> {
+        fs::read(path).map(|bytes| decode_source(&bytes))
+    }
+
+    fn roots(&self) -> Vec<PathBuf> {
+        vec![self.root_path.clone()]
+    }
+}
+
+// Walks several directory trees, e.g. the repositories of a microservice
+// system, naming each after its directory
+pub struct MultiRootProvider {
+    roots: Vec<(String, FsProvider)>,
+}
+
+impl MultiRootProvider {
+    pub fn new<P: AsRef<Path>>(root_paths: &[P]) -> Self {
+        Self::with_config(root_paths, &IndexConfig::new())
+    }
+
+    pub fn with_config<P: AsRef<Path>>(root_paths: &[P], config: &IndexConfig) -> Self {
+        let mut name_counts: HashMap<String, usize> = HashMap::new();
+        let roots = root_paths
+            .iter()
+            .map(|root_path| {
+                let root_path = root_path.as_ref();
+                let name = fs::canonicalize(root_path)
+                    .unwrap_or_else(|_| root_path.to_path_buf())
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| root_path.to_string_lossy().into_owned());
+
+                // Roots sharing a directory name get a numeric suffix
+                let count = name_counts.entry(name.clone()).or_default();
+                *count += 1;
+                let name = match *count {
+                    1 => name,
+                    n => format!("{}-{}", name, n),
+                };
+                (name, FsProvider::with_config(root_path, config))
+            })
+            .collect();
+
+        MultiRootProvider { roots }
+    }
+
+    pub fn repo_names(&self) -> Vec<&str> {
+        self.roots.iter().map(|(name, _)| name.as_str()).collect()
+    }
+}
+
+impl FileProvider for MultiRootProvider {
+    fn list_files(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for (_, provider) in &self.roots {
+            files.extend(provider.list_files()?);
+        }
+        Ok(files)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        self.read_file_with_encoding(path)
+            .map(|(content, _)| content)
+    }
+
+    fn read_file_with_encoding(&self, path: &Path) -> io::Result<(String, &'static str)> {
+        fs::read(path).map(|bytes| decode_source(&bytes))
+    }
+
+    fn repo_for(&self, path: &Path) -> Option<String> {
+        // The most specific root wins when roots are nested
+        self.roots
+            .iter()
+            .filter(|(_, provider)| path.starts_with(&provider.root_path))
+            .max_by_key(|(_, provider)| provider.root_path.components().count())
+            .map(|(name, _)| name.clone())
+    }
+
+    fn roots(&self) -> Vec<PathBuf> {
+        self.roots
+            .iter()
+            .map(|(_, provider)| provider.root_path.clone())
+            .collect()
+    }
+}
+
+// Serves a fixed set of files held in memory
+pub struct MemoryProvider {
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl MemoryProvider {
+    pub fn new<P: Into<PathBuf>>(files: Vec<(P, String)>) -> Self {
+        MemoryProvider {
+            files: files
+                .into_iter()
+                .map(|(path, content)| (path.into(), content))
+                .collect(),
+        }
+    }
+
+    pub fn add_file<P: Into<PathBuf>>(&mut self, path: P, content: String) {
+        self.files.insert(path.into(), content);
+    }
+}
+
+impl FileProvider for MemoryProvider {
+    fn list_files(&self) -> io::Result<Vec<PathBuf>> {
+        Ok(self.files.keys().cloned().collect())
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        self.files.get(path).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("File not in memory provider: {:?}", path),
+            )
+        })
+    }
+}
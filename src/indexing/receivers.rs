@@ -0,0 +1,197 @@
+use crate::code_graph::{CodeGraph, CodeNode, NodeType};
+use crate::indexing::analyzer::language_family;
+use crate::indexing::extractor::common::simple_type_name;
+use regex::Regex;
+use std::collections::HashMap;
+
+// Names a method uses for the instance or class it is called on
+const SELF_RECEIVERS: &[&str] = &["self", "this", "Self", "cls"];
+
+// The classes of one language with the methods declared in each, to resolve
+// calls on a receiver whose class is known to that class's methods rather
+// than to every method of the name
+pub struct Receivers<'a> {
+    classes: HashMap<&'a str, Vec<&'a CodeNode>>,
+    // Class of each method
+    owners: HashMap<&'a str, &'a str>,
+    // Methods and fields of each class by name
+    members: HashMap<(&'a str, &'a str), Vec<&'a CodeNode>>,
+    // Local variables with the type they are declared or constructed with:
+    // `x: Foo`, `x = new Foo(`, `let x = Foo::new(`, `Foo x =`, `var x Foo`
+    declarations: Vec<Regex>,
+}
+
+fn is_class(node: &CodeNode) -> bool {
+    matches!(node.node_type, NodeType::Class | NodeType::Interface)
+}
+
+impl<'a> Receivers<'a> {
+    pub fn new(graph: &'a CodeGraph, file_path: &str) -> Option<Self> {
+        let family = language_family(file_path)?;
+        let declarations = [
+            r"\b([a-z_]\w*)\s*:\s*&?(?:mut\s+)?([A-Z]\w*)",
+            r"\b([a-z_]\w*)\s*:?=\s*(?:new\s+|&)?([A-Z]\w*)\s*(?:\(|\{|::|\.)",
+            r"\b([A-Z]\w*)(?:<[^<>;]*>)?[\s*&]+([a-z_]\w*)\s*[=;,)]",
+            r"(?:\bvar\s+|[(,]\s*)([a-z_]\w*)\s+\*?([A-Z]\w*)",
+        ]
+        .iter()
+        .map(|pattern| Regex::new(pattern).ok())
+        .collect::<Option<Vec<_>>>()?;
+
+        let mut receivers = Receivers {
+            classes: HashMap::new(),
+            owners: HashMap::new(),
+            members: HashMap::new(),
+            declarations,
+        };
+        let nodes: Vec<&CodeNode> = graph
+            .all_nodes()
+            .filter(|node| language_family(&node.file_path).as_ref() == Some(&family))
+            .collect();
+        let mut file_classes: HashMap<&str, Vec<&CodeNode>> = HashMap::new();
+        for node in nodes.iter().copied().filter(|node| is_class(node)) {
+            receivers
+                .classes
+                .entry(node.name.as_str())
+                .or_default()
+                .push(node);
+            file_classes
+                .entry(node.file_path.as_str())
+                .or_default()
+                .push(node);
+        }
+
+        for node in &nodes {
+            if !matches!(
+                node.node_type,
+                NodeType::Function | NodeType::Method | NodeType::Field
+            ) || node.metadata.contains_key("duplicate_of")
+            {
+                continue;
+            }
+            // The class named by `parent_class`, else the innermost class of
+            // the file around the member
+            let owner = node
+                .parent_class
+                .as_ref()
+                .and_then(|parent| receivers.class_named(&node.file_path, parent))
+                .or_else(|| {
+                    file_classes
+                        .get(node.file_path.as_str())?
+                        .iter()
+                        .filter(|class| {
+                            class.line_range.0 <= node.line_range.0
+                                && node.line_range.1 <= class.line_range.1
+                        })
+                        .min_by_key(|class| class.line_range.1 - class.line_range.0)
+                        .map(|class| class.id.as_str())
+                });
+            if let Some(owner) = owner {
+                receivers.owners.insert(node.id.as_str(), owner);
+                receivers
+                    .members
+                    .entry((owner, node.name.as_str()))
+                    .or_default()
+                    .push(node);
+            }
+        }
+        Some(receivers)
+    }
+
+    // The class a type name refers to: one of the file, else the only one
+    // of the language
+    fn class_named(&self, file_path: &str, name: &str) -> Option<&'a str> {
+        let candidates = self.classes.get(simple_type_name(name).as_str())?;
+        if let Some(class) = candidates.iter().find(|class| class.file_path == file_path) {
+            return Some(class.id.as_str());
+        }
+        match candidates.as_slice() {
+            [class] => Some(class.id.as_str()),
+            _ => None,
+        }
+    }
+
+    // Types of the local variables and parameters a function body declares,
+    // the first declaration of each winning
+    pub fn local_types(&self, body: &str) -> HashMap<String, String> {
+        let mut types = HashMap::new();
+        for (index, pattern) in self.declarations.iter().enumerate() {
+            for captures in pattern.captures_iter(body) {
+                // `Foo x` names the type first
+                let (name, type_name) = match index {
+                    2 => (&captures[2], &captures[1]),
+                    _ => (&captures[1], &captures[2]),
+                };
+                types
+                    .entry(name.to_string())
+                    .or_insert_with(|| type_name.to_string());
+            }
+        }
+        types
+    }
+
+    // The class of a receiver expression: `self` or `this`, a class name, a
+    // field of the caller's class with a declared type, `self.repo`, or a
+    // typed local variable
+    fn receiver_class(
+        &self,
+        caller: &CodeNode,
+        locals: &HashMap<String, String>,
+        receiver: &str,
+    ) -> Option<&'a str> {
+        let own = || self.owners.get(caller.id.as_str()).copied();
+        if SELF_RECEIVERS.contains(&receiver) {
+            return own();
+        }
+        if let Some((object, field)) = receiver.split_once('.')
+            && SELF_RECEIVERS.contains(&object)
+        {
+            let field = self
+                .members
+                .get(&(own()?, field))?
+                .iter()
+                .find(|member| member.node_type == NodeType::Field)?;
+            // A declared type, else the class it is constructed with,
+            // `self.repo = Repo()`
+            let field_type = match field.metadata.get("field_type") {
+                Some(field_type) => field_type.clone(),
+                None => self.local_types(&field.content).remove(&field.name)?,
+            };
+            return self.class_named(&caller.file_path, &field_type);
+        }
+        if !receiver.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        if receiver.starts_with(char::is_uppercase) {
+            return self.class_named(&caller.file_path, receiver);
+        }
+        self.class_named(&caller.file_path, locals.get(receiver)?)
+    }
+
+    // Methods a call on a receiver may reach: those of the receiver's class
+    // when its class is known and declares the method. `None` leaves the
+    // call to name matching.
+    pub fn call_targets(
+        &self,
+        caller: &CodeNode,
+        locals: &HashMap<String, String>,
+        name: &str,
+        expression: &str,
+    ) -> Option<Vec<&'a str>> {
+        let callee = expression.split('(').next().unwrap_or_default().trim();
+        let receiver = callee.strip_suffix(name)?;
+        let receiver = ["?.", ".", "->", "::"]
+            .iter()
+            .find_map(|separator| receiver.strip_suffix(separator))?
+            .trim();
+        let class_id = self.receiver_class(caller, locals, receiver)?;
+        let targets: Vec<&str> = self
+            .members
+            .get(&(class_id, name))?
+            .iter()
+            .filter(|member| member.node_type != NodeType::Field)
+            .map(|member| member.id.as_str())
+            .collect();
+        (!targets.is_empty()).then_some(targets)
+    }
+}
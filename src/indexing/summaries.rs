@@ -0,0 +1,343 @@
+use crate::code_graph::{CodeGraph, CodeNode, NodeType, RelationshipType};
+use crate::indexing::config::SummaryBackend;
+use log::{debug, info, warn};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+
+// Members named in a template summary before the rest are only counted
+const MAX_LISTED_MEMBERS: usize = 5;
+
+// Writes the summary of a node from its own source, or for containers from
+// the summaries of their members, which are always written first
+pub trait Summarizer: Send + Sync {
+    fn summarize(&self, node: &CodeNode, members: &[(&CodeNode, &str)]) -> io::Result<String>;
+}
+
+// Sentences built from node types and names, needing no model
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TemplateSummarizer;
+
+// What a node is, from its type and name alone
+fn describe(node: &CodeNode) -> String {
+    let name = &node.name;
+    match node.node_type {
+        NodeType::Function => format!("Function that handles {}", name),
+        NodeType::Method => format!("Method that implements {}", name),
+        NodeType::Class => format!("Class that represents {}", name),
+        NodeType::Interface => format!("Interface for {}", name),
+        NodeType::Module => format!("Module containing {}", name),
+        NodeType::TypeDefinition => format!("Type definition for {}", name),
+        NodeType::Variable => format!("Variable holding {}", name),
+        NodeType::Field => format!("Field holding {}", name),
+        NodeType::Dependency => format!("External dependency on {}", name),
+        NodeType::Documentation => format!("Documentation for {}", name),
+        NodeType::File => format!("Source file {}", name),
+        NodeType::Directory => format!("Directory {}", name),
+        NodeType::ExternalSymbol => format!("Unresolved external symbol {}", name),
+        NodeType::Endpoint => format!("HTTP endpoint {}", name),
+        NodeType::Macro => format!("Macro that generates {}", name),
+        NodeType::Unknown => format!("Code unit: {}", name),
+    }
+}
+
+fn member_kind(node_type: &NodeType, count: usize) -> String {
+    let (one, many) = match node_type {
+        NodeType::Class => ("class", "classes"),
+        NodeType::TypeDefinition => ("type", "types"),
+        NodeType::Dependency => ("dependency", "dependencies"),
+        NodeType::Documentation => ("document", "documents"),
+        NodeType::Directory => ("directory", "directories"),
+        NodeType::ExternalSymbol => ("external symbol", "external symbols"),
+        NodeType::Unknown => ("member", "members"),
+        NodeType::Function => ("function", "functions"),
+        NodeType::Method => ("method", "methods"),
+        NodeType::Interface => ("interface", "interfaces"),
+        NodeType::Module => ("module", "modules"),
+        NodeType::Variable => ("variable", "variables"),
+        NodeType::Field => ("field", "fields"),
+        NodeType::File => ("file", "files"),
+        NodeType::Endpoint => ("endpoint", "endpoints"),
+        NodeType::Macro => ("macro", "macros"),
+    };
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+impl TemplateSummarizer {
+    // "Class that represents Parser, with 3 methods (parse, peek, next)"
+    pub fn template(&self, node: &CodeNode, members: &[(&CodeNode, &str)]) -> String {
+        let mut summary = describe(node);
+
+        let mut by_type: BTreeMap<&NodeType, Vec<&str>> = BTreeMap::new();
+        for (member, _) in members {
+            // Methods are named after their class, which is said already
+            let name = member.name.rsplit("::").next().unwrap_or(&member.name);
+            by_type.entry(&member.node_type).or_default().push(name);
+        }
+        let groups: Vec<String> = by_type
+            .into_iter()
+            .map(|(node_type, names)| {
+                let mut listed = names[..names.len().min(MAX_LISTED_MEMBERS)].join(", ");
+                if names.len() > MAX_LISTED_MEMBERS {
+                    listed.push_str(&format!(" and {} more", names.len() - MAX_LISTED_MEMBERS));
+                }
+                format!("{} ({})", member_kind(node_type, names.len()), listed)
+            })
+            .collect();
+        if !groups.is_empty() {
+            summary.push_str(", with ");
+            summary.push_str(&groups.join(", "));
+        }
+        summary
+    }
+}
+
+impl Summarizer for TemplateSummarizer {
+    fn summarize(&self, node: &CodeNode, members: &[(&CodeNode, &str)]) -> io::Result<String> {
+        Ok(self.template(node, members))
+    }
+}
+
+// Asks a chat completions endpoint (OpenAI-compatible) for every summary:
+// functions from their source, containers from what their members do
+#[cfg(feature = "llm")]
+pub struct LlmSummarizer {
+    url: String,
+    model: String,
+    // Sent as a bearer token; read from `LLM_API_KEY` by default
+    pub api_key: Option<String>,
+    // Source characters sent for a leaf node
+    pub max_source_chars: usize,
+    // Member summaries sent for a container
+    pub max_members: usize,
+    pub max_attempts: usize,
+}
+
+#[cfg(feature = "llm")]
+const SYSTEM_PROMPT: &str = "You summarize code for engineers searching a codebase. \
+Reply with one or two plain sentences saying what the code does and why it exists. \
+Do not repeat its name or signature and do not use markdown.";
+
+#[cfg(feature = "llm")]
+impl LlmSummarizer {
+    pub fn new(url: &str, model: &str) -> Self {
+        LlmSummarizer {
+            url: url.to_string(),
+            model: model.to_string(),
+            api_key: std::env::var("LLM_API_KEY").ok(),
+            max_source_chars: 6000,
+            max_members: 40,
+            max_attempts: 3,
+        }
+    }
+
+    fn prompt(&self, node: &CodeNode, members: &[(&CodeNode, &str)]) -> String {
+        let mut prompt = format!("{:?} `{}` in {}", node.node_type, node.name, node.file_path);
+        if members.is_empty() {
+            let source: String = node.content.chars().take(self.max_source_chars).collect();
+            prompt.push_str(&format!(":\n\n{}", source));
+        } else {
+            prompt.push_str(", whose members are:");
+            for (member, summary) in members.iter().take(self.max_members) {
+                prompt.push_str(&format!(
+                    "\n- {:?} `{}`: {}",
+                    member.node_type, member.name, summary
+                ));
+            }
+            if members.len() > self.max_members {
+                prompt.push_str(&format!(
+                    "\n- and {} more",
+                    members.len() - self.max_members
+                ));
+            }
+        }
+        prompt
+    }
+
+    fn complete(&self, body: &str) -> io::Result<String> {
+        let mut attempt = 1;
+        loop {
+            let mut request = ureq::post(&self.url).header("Content-Type", "application/json");
+            if let Some(api_key) = &self.api_key {
+                request = request.header("Authorization", &format!("Bearer {}", api_key));
+            }
+            let result = request
+                .send(body)
+                .and_then(|response| response.into_body().read_to_string());
+            match result {
+                Ok(response) => return Ok(response),
+                Err(e) if attempt >= self.max_attempts => {
+                    return Err(io::Error::other(format!("LLM {}: {}", self.url, e)));
+                }
+                Err(e) => {
+                    log::warn!("LLM request attempt {} failed: {}", attempt, e);
+                    std::thread::sleep(std::time::Duration::from_millis(500 << attempt));
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "llm")]
+impl Summarizer for LlmSummarizer {
+    fn summarize(&self, node: &CodeNode, members: &[(&CodeNode, &str)]) -> io::Result<String> {
+        // Declarations without a body are not worth a request
+        let trivial = node.content.trim().is_empty()
+            || matches!(
+                node.node_type,
+                NodeType::Variable
+                    | NodeType::Field
+                    | NodeType::ExternalSymbol
+                    | NodeType::Dependency
+            );
+        if members.is_empty() && trivial {
+            return TemplateSummarizer.summarize(node, members);
+        }
+
+        let body = serde_json::json!({
+            "model": self.model,
+            "messages": [
+                { "role": "system", "content": SYSTEM_PROMPT },
+                { "role": "user", "content": self.prompt(node, members) },
+            ],
+        });
+        let response: serde_json::Value = serde_json::from_str(&self.complete(&body.to_string())?)?;
+        response["choices"][0]["message"]["content"]
+            .as_str()
+            .map(|summary| summary.trim().to_string())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("LLM {} returned no message content", self.url),
+                )
+            })
+    }
+}
+
+// The summarizer a configuration asks for; LLM summaries need the `llm`
+// feature
+pub fn summarizer_for(backend: &SummaryBackend) -> io::Result<Box<dyn Summarizer>> {
+    match backend {
+        SummaryBackend::Template => Ok(Box::new(TemplateSummarizer)),
+        #[cfg(feature = "llm")]
+        SummaryBackend::Llm { url, model } => Ok(Box::new(LlmSummarizer::new(url, model))),
+        #[cfg(not(feature = "llm"))]
+        SummaryBackend::Llm { .. } => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "LLM summaries require the `llm` feature",
+        )),
+    }
+}
+
+// Summaries of nodes whose members are all summarized, written in parallel.
+// A node the summarizer fails on gets a template summary instead, so one
+// failed request does not lose the rest.
+fn summarize_level<'a>(
+    level: &[&'a CodeNode],
+    members: &HashMap<&str, Vec<&CodeNode>>,
+    summaries: &HashMap<&str, String>,
+    summarizer: &dyn Summarizer,
+) -> Vec<(&'a str, String)> {
+    level
+        .par_iter()
+        .map(|node| {
+            let known: Vec<(&CodeNode, &str)> = members
+                .get(node.id.as_str())
+                .into_iter()
+                .flatten()
+                .filter_map(|member| {
+                    let summary = summaries.get(member.id.as_str())?;
+                    Some((*member, summary.as_str()))
+                })
+                .collect();
+            let summary = summarizer.summarize(node, &known).unwrap_or_else(|e| {
+                warn!("Using a template summary for {}: {}", node.name, e);
+                TemplateSummarizer.template(node, &known)
+            });
+            (node.id.as_str(), summary)
+        })
+        .collect()
+}
+
+// Summarize every node after the nodes it contains: functions and fields
+// first, then classes from their members' summaries, then modules and files
+// from theirs, following Contains edges up to the root. Nodes of a level are
+// summarized in parallel.
+pub fn summarize_graph(graph: &mut CodeGraph, summarizer: &dyn Summarizer) {
+    info!("Summarizing {} nodes bottom-up", graph.node_count());
+
+    let mut members: HashMap<&str, Vec<&CodeNode>> = HashMap::new();
+    let mut containers: HashMap<&str, Vec<&CodeNode>> = HashMap::new();
+    for node in graph.all_nodes() {
+        let mut contained: Vec<&CodeNode> = graph
+            .outgoing_relationships(&node.id)
+            .iter()
+            .filter(|rel| rel.relationship_type == RelationshipType::Contains)
+            .filter(|rel| rel.to_id != node.id)
+            .filter_map(|rel| graph.get_node(&rel.to_id))
+            .collect();
+        contained.sort_by(|a, b| {
+            (&a.file_path, a.line_range, &a.id).cmp(&(&b.file_path, b.line_range, &b.id))
+        });
+        contained.dedup_by(|a, b| a.id == b.id);
+        for member in &contained {
+            containers.entry(member.id.as_str()).or_default().push(node);
+        }
+        members.insert(node.id.as_str(), contained);
+    }
+
+    let mut pending: HashMap<&str, usize> = members
+        .iter()
+        .map(|(id, contained)| (*id, contained.len()))
+        .collect();
+    let mut level: Vec<&CodeNode> = graph
+        .all_nodes()
+        .filter(|node| pending.get(node.id.as_str()) == Some(&0))
+        .collect();
+    let mut summaries: HashMap<&str, String> = HashMap::new();
+
+    let mut depth = 0;
+    while !level.is_empty() {
+        let written = summarize_level(&level, &members, &summaries, summarizer);
+        debug!("Summarized {} nodes at depth {}", written.len(), depth);
+
+        let mut next = Vec::new();
+        for (id, summary) in written {
+            for container in containers.get(id).into_iter().flatten() {
+                if let Some(count) = pending.get_mut(container.id.as_str()) {
+                    *count -= 1;
+                    if *count == 0 {
+                        next.push(*container);
+                    }
+                }
+            }
+            pending.remove(id);
+            summaries.insert(id, summary);
+        }
+        level = next;
+        depth += 1;
+    }
+
+    // Nodes on a Contains cycle never become ready; they get what their
+    // members have
+    let mut cyclic: Vec<&CodeNode> = pending
+        .into_keys()
+        .filter_map(|id| graph.get_node(id))
+        .collect();
+    if !cyclic.is_empty() {
+        cyclic.sort_by(|a, b| a.id.cmp(&b.id));
+        debug!("Summarizing {} nodes on Contains cycles", cyclic.len());
+        let written = summarize_level(&cyclic, &members, &summaries, summarizer);
+        summaries.extend(written);
+    }
+
+    let summaries: HashMap<String, String> = summaries
+        .into_iter()
+        .map(|(id, summary)| (id.to_string(), summary))
+        .collect();
+    for node in graph.all_nodes_mut() {
+        node.summary = summaries.get(&node.id).cloned();
+    }
+    info!("Summarized graph in {} levels", depth);
+}
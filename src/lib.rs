@@ -1,29 +1,240 @@
+pub mod analysis;
 pub mod code_graph;
 pub mod indexing;
+pub mod lsp;
 pub mod parsers;
+#[cfg(feature = "server")]
+pub mod server;
 pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-use log::{debug, info};
+use log::{debug, info, warn};
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub fn process_codebase(root_path: &Path, num_threads: usize) -> io::Result<code_graph::CodeGraph> {
+    let config = indexing::IndexConfig::new().num_threads(num_threads);
+    process_codebase_with_config(root_path, &config)
+}
+
+pub fn process_codebase_with_config(
+    root_path: &Path,
+    config: &indexing::IndexConfig,
+) -> io::Result<code_graph::CodeGraph> {
     info!(
         "Processing codebase at: {:?} with {} threads",
-        root_path, num_threads
+        root_path, config.num_threads
+    );
+    let mut graph = if config.uses_git() {
+        process_git_repository(root_path, config)?
+    } else {
+        process_with_provider(
+            &indexing::FsProvider::with_config(root_path, config),
+            config,
+        )?
+    };
+    add_git_history(&mut graph, root_path, config);
+    Ok(graph)
+}
+
+#[cfg(feature = "git")]
+fn process_git_repository(
+    root_path: &Path,
+    config: &indexing::IndexConfig,
+) -> io::Result<code_graph::CodeGraph> {
+    let provider = indexing::GitProvider::open(root_path, config.git_revision.as_deref())?;
+    process_with_provider(&provider, config)
+}
+
+#[cfg(not(feature = "git"))]
+fn process_git_repository(
+    _root_path: &Path,
+    _config: &indexing::IndexConfig,
+) -> io::Result<code_graph::CodeGraph> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "git-aware indexing requires the `git` feature",
+    ))
+}
+
+#[cfg(feature = "git")]
+fn add_git_history(
+    graph: &mut code_graph::CodeGraph,
+    root_path: &Path,
+    config: &indexing::IndexConfig,
+) {
+    if config.git_history_days.is_some()
+        && let Err(e) = indexing::git::add_git_history(graph, root_path, config)
+    {
+        warn!("Could not annotate nodes with git history: {}", e);
+    }
+}
+
+#[cfg(not(feature = "git"))]
+fn add_git_history(
+    _graph: &mut code_graph::CodeGraph,
+    _root_path: &Path,
+    config: &indexing::IndexConfig,
+) {
+    if config.git_history_days.is_some() {
+        warn!("Git history annotation requires the `git` feature");
+    }
+}
+
+#[cfg(feature = "git")]
+fn git_commit(root_path: &Path, config: &indexing::IndexConfig) -> Option<String> {
+    let revision = config.git_revision.as_deref().unwrap_or("HEAD");
+    indexing::git::resolve_commit(root_path, revision)
+        .inspect_err(|e| debug!("No git commit for {:?}: {}", root_path, e))
+        .ok()
+}
+
+#[cfg(not(feature = "git"))]
+fn git_commit(_root_path: &Path, _config: &indexing::IndexConfig) -> Option<String> {
+    None
+}
+
+// Index several codebases into one graph, tagging every node with the
+// repository it came from so calls and imports can be matched across them
+pub fn process_codebases<P: AsRef<Path>>(
+    root_paths: &[P],
+    config: &indexing::IndexConfig,
+) -> io::Result<code_graph::CodeGraph> {
+    let provider = indexing::MultiRootProvider::with_config(root_paths, config);
+    info!(
+        "Processing {} codebases ({}) with {} threads",
+        root_paths.len(),
+        provider.repo_names().join(", "),
+        config.num_threads
+    );
+    process_with_provider(&provider, config)
+}
+
+// Index an in-memory set of (path, content) pairs without touching the filesystem
+pub fn process_files(
+    files: Vec<(PathBuf, String)>,
+    config: &indexing::IndexConfig,
+) -> io::Result<code_graph::CodeGraph> {
+    info!("Processing {} in-memory files", files.len());
+    process_with_provider(&indexing::MemoryProvider::new(files), config)
+}
+
+// Re-index a codebase, reusing nodes of a previous export for unchanged files
+pub fn warm_start_codebase(
+    root_path: &Path,
+    previous: &code_graph::CodeGraph,
+    config: &indexing::IndexConfig,
+) -> io::Result<code_graph::CodeGraph> {
+    info!(
+        "Warm-start processing codebase at: {:?} from a graph of {} nodes",
+        root_path,
+        previous.node_count()
     );
-    let mut graph = indexing::processor::process_codebase_parallel(root_path, num_threads)?;
+    let mut graph = build_graph(
+        &indexing::FsProvider::with_config(root_path, config),
+        config,
+        Some(previous),
+    )?;
+    add_git_history(&mut graph, root_path, config);
+    Ok(graph)
+}
+
+pub fn process_with_provider(
+    provider: &dyn indexing::FileProvider,
+    config: &indexing::IndexConfig,
+) -> io::Result<code_graph::CodeGraph> {
+    build_graph(provider, config, None)
+}
+
+fn build_graph(
+    provider: &dyn indexing::FileProvider,
+    config: &indexing::IndexConfig,
+    previous: Option<&code_graph::CodeGraph>,
+) -> io::Result<code_graph::CodeGraph> {
+    let owned;
+    let provider: &dyn indexing::FileProvider = match &config.owned_by {
+        Some(owner) => {
+            info!("Indexing only files owned by {}", owner);
+            owned = indexing::ownership::OwnedFilesProvider::new(provider, owner);
+            &owned
+        }
+        None => provider,
+    };
+    let mut graph = indexing::processor::process_files_parallel(provider, config, previous)?;
 
     // Identify relationships between nodes
     info!(
         "Building relationships between {} nodes...",
         graph.node_count()
     );
-    indexing::analyzer::identify_relationships(&mut graph);
+    indexing::analyzer::identify_relationships_with_provider(&mut graph, provider, config);
+
+    if config.references && config.is_pass_enabled(code_graph::provenance::REFERENCE_QUERY) {
+        indexing::analyzer::identify_references_with_provider(&mut graph, provider);
+    }
+
+    // Link packages to their declared third-party dependencies
+    if config.is_pass_enabled(code_graph::provenance::PACKAGE_MANIFEST) {
+        indexing::manifests::add_manifest_dependencies(provider, &mut graph, config);
+    }
+
+    // Before method names are qualified, so routes name their handlers plainly
+    if config.is_pass_enabled(code_graph::provenance::ROUTE_DETECTION) {
+        indexing::endpoints::add_endpoints(provider, &mut graph, config);
+    }
+
+    if config.uses_external_symbols()
+        && config.is_pass_enabled(code_graph::provenance::EXTERNAL_SYMBOL)
+    {
+        indexing::external::link_external_dependencies(&mut graph);
+    }
 
     // Enhance method names with their parent class/struct
     indexing::analyzer::enhance_method_names(&mut graph);
 
+    if config.metrics {
+        analysis::compute_call_metrics(&mut graph);
+    }
+
+    if let Some(encoding) = config.token_encoding {
+        graph.add_token_counts(encoding.tokenizer()?.as_ref());
+    }
+
+    // After the metrics, which only concern symbols
+    if config.directory_hierarchy
+        && config.is_pass_enabled(code_graph::provenance::DIRECTORY_HIERARCHY)
+    {
+        indexing::hierarchy::add_directory_hierarchy(&mut graph, config);
+    }
+
+    // After the hierarchy, so includes link its File nodes
+    if config.is_pass_enabled(code_graph::provenance::INCLUDE_RESOLUTION) {
+        indexing::imports::c::add_include_relationships(provider, &mut graph, config);
+    }
+
+    // Last, once every File and Module node exists
+    if config.file_ownership {
+        indexing::ownership::add_file_ownership(provider, &mut graph);
+    }
+
+    // Every node carries the namespace its ID is in, whichever pass added it
+    if let Some(namespace) = &config.id_namespace {
+        for node in graph.all_nodes_mut() {
+            node.namespace = Some(namespace.clone());
+        }
+    }
+
+    let roots = provider
+        .roots()
+        .into_iter()
+        .map(|root| code_graph::IndexedRoot {
+            git_commit: git_commit(&root, config),
+            path: root.display().to_string(),
+        })
+        .collect();
+    graph.set_provenance(code_graph::IndexProvenance::new(roots, config.settings()));
+
     info!(
         "Code graph built with {} nodes and {} relationships",
         graph.node_count(),
@@ -37,17 +248,72 @@ pub fn analyze_codebase(
     root_path: &Path,
     output_path: &Path,
     num_threads: usize,
+) -> io::Result<()> {
+    let config = indexing::IndexConfig::new().num_threads(num_threads);
+    analyze_codebase_with_config(root_path, output_path, &config)
+}
+
+pub fn analyze_codebase_with_config(
+    root_path: &Path,
+    output_path: &Path,
+    config: &indexing::IndexConfig,
 ) -> io::Result<()> {
     info!("Starting codebase analysis");
     debug!("Root path: {:?}, Output path: {:?}", root_path, output_path);
 
-    let mut graph = process_codebase(root_path, num_threads)?;
+    let graph = process_codebase_with_config(root_path, config)?;
+    export_analysis(graph, output_path, config, utils::io::ExportFormat::Json)
+}
+
+pub fn analyze_codebases_with_config<P: AsRef<Path>>(
+    root_paths: &[P],
+    output_path: &Path,
+    config: &indexing::IndexConfig,
+) -> io::Result<()> {
+    info!("Starting analysis of {} codebases", root_paths.len());
+    debug!("Output path: {:?}", output_path);
+
+    let graph = process_codebases(root_paths, config)?;
+    export_analysis(graph, output_path, config, utils::io::ExportFormat::Json)
+}
 
-    info!("Generating summaries for {} nodes", graph.node_count());
-    indexing::analyzer::generate_summaries(&mut graph);
+// Index one or more codebases and write the graph in the given format
+pub fn analyze_codebases_with_format<P: AsRef<Path>>(
+    root_paths: &[P],
+    output_path: &Path,
+    config: &indexing::IndexConfig,
+    format: utils::io::ExportFormat,
+) -> io::Result<()> {
+    info!("Starting analysis of {} codebases", root_paths.len());
+    debug!("Output path: {:?}, format: {:?}", output_path, format);
+
+    let graph = match root_paths {
+        [root_path] => process_codebase_with_config(root_path.as_ref(), config)?,
+        _ => process_codebases(root_paths, config)?,
+    };
+    export_analysis(graph, output_path, config, format)
+}
+
+fn export_analysis(
+    mut graph: code_graph::CodeGraph,
+    output_path: &Path,
+    config: &indexing::IndexConfig,
+    format: utils::io::ExportFormat,
+) -> io::Result<()> {
+    // Before summarizing, so no credential reaches an LLM summarizer
+    if config.redact_secrets {
+        analysis::redact_secrets(&mut graph, &analysis::RedactionRules::default());
+    }
+    let summarizer = indexing::summarizer_for(&config.summaries)?;
+    indexing::summarize_graph(&mut graph, summarizer.as_ref());
+    graph.limit_content(config.export_content);
 
-    info!("Exporting graph to JSON at {:?}", output_path);
-    utils::io::export_graph_to_json(&graph, output_path)?;
+    info!("Exporting graph as {:?} to {:?}", format, output_path);
+    if config.shard_output {
+        utils::io::export_graph_shards(&graph, output_path, format, config.export_compression)?;
+    } else {
+        utils::io::export_graph_compressed(&graph, output_path, format, config.export_compression)?;
+    }
 
     info!(
         "Analysis complete: {} nodes and {} relationships",
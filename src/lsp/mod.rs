@@ -0,0 +1,439 @@
+use crate::code_graph::{
+    CodeGraph, CodeNode, NodeType, OutlineNode, Relationship, RelationshipType,
+};
+use crate::indexing::IndexConfig;
+use log::{debug, info, warn};
+use serde_json::{Value, json};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+// Hops searched by `codegraph/relatedCode` when no depth is given
+const DEFAULT_RELATED_DEPTH: usize = 1;
+// Upper bound on the locations returned by `codegraph/relatedCode`
+const MAX_RELATED: usize = 100;
+
+// JSON-RPC error codes used by the protocol
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+// Language server over stdin and stdout backed by a code graph: document
+// symbols, call hierarchy from Calls edges, and a `codegraph/relatedCode`
+// request returning the locations of nodes near the one at a position. The
+// codebase is re-indexed on save, reusing the nodes of unchanged files.
+pub struct LspServer {
+    root: Option<PathBuf>,
+    config: IndexConfig,
+    graph: CodeGraph,
+    // The client accepts nested document symbols
+    hierarchical_symbols: bool,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+fn invalid_params(message: &str) -> RpcError {
+    RpcError {
+        code: INVALID_PARAMS,
+        message: message.to_string(),
+    }
+}
+
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "Missing Content-Length header")
+    })?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    // Percent-decoded byte by byte so multi-byte characters survive
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = path
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(decoded).ok().map(PathBuf::from)
+}
+
+fn path_to_uri(path: &str) -> String {
+    let encoded: String = path
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect();
+    format!("file://{}", encoded)
+}
+
+fn symbol_kind(node_type: &NodeType) -> u32 {
+    match node_type {
+        NodeType::File => 1,
+        NodeType::Module | NodeType::Directory => 2,
+        NodeType::Dependency => 4,
+        NodeType::Class => 5,
+        NodeType::Method => 6,
+        NodeType::Interface => 11,
+        NodeType::Function | NodeType::Macro => 12,
+        NodeType::Field => 8,
+        NodeType::Variable => 13,
+        NodeType::Documentation => 15,
+        NodeType::TypeDefinition => 23,
+        NodeType::ExternalSymbol => 19,
+        NodeType::Endpoint => 24,
+        NodeType::Unknown => 13,
+    }
+}
+
+// LSP lines are 0-based; a node covers its lines from start to end
+fn range(node: &CodeNode) -> Value {
+    let start = node.line_range.0.saturating_sub(1);
+    let end = node.line_range.1.max(node.line_range.0);
+    json!({
+        "start": { "line": start, "character": 0 },
+        "end": { "line": end, "character": 0 },
+    })
+}
+
+fn location(node: &CodeNode) -> Value {
+    json!({ "uri": path_to_uri(&node.file_path), "range": range(node) })
+}
+
+// A node of the file outline with its children, as a DocumentSymbol
+fn document_symbol(item: &OutlineNode) -> Value {
+    let node = item.node;
+    json!({
+        "name": node.name,
+        "kind": symbol_kind(&node.node_type),
+        "range": range(node),
+        "selectionRange": range(node),
+        "children": item.children.iter().map(document_symbol).collect::<Vec<_>>(),
+    })
+}
+
+fn call_hierarchy_item(node: &CodeNode) -> Value {
+    json!({
+        "name": node.name,
+        "kind": symbol_kind(&node.node_type),
+        "detail": node.parent_class,
+        "uri": path_to_uri(&node.file_path),
+        "range": range(node),
+        "selectionRange": range(node),
+        "data": { "id": node.id },
+    })
+}
+
+// Lines of the call sites recorded on a Calls edge
+fn call_ranges(relationship: &Relationship) -> Vec<Value> {
+    let lines = relationship
+        .metadata
+        .get("lines")
+        .or_else(|| relationship.metadata.get("line"));
+    lines
+        .into_iter()
+        .flat_map(|lines| lines.split(','))
+        .filter_map(|line| line.trim().parse::<usize>().ok())
+        .map(|line| {
+            json!({
+                "start": { "line": line.saturating_sub(1), "character": 0 },
+                "end": { "line": line, "character": 0 },
+            })
+        })
+        .collect()
+}
+
+impl LspServer {
+    // Serve a graph that was already built; `root` and `config` are used
+    // to re-index it when files are saved
+    pub fn new(graph: CodeGraph, root: Option<PathBuf>, config: IndexConfig) -> Self {
+        LspServer {
+            root,
+            config,
+            graph,
+            hierarchical_symbols: false,
+        }
+    }
+
+    // Handle messages from stdin until the client sends `exit`
+    pub fn run_stdio(&mut self) -> io::Result<()> {
+        let stdin = io::stdin();
+        let mut reader = stdin.lock();
+        let mut stdout = io::stdout();
+        self.run(&mut reader, &mut stdout)
+    }
+
+    pub fn run(&mut self, reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<()> {
+        info!("Language server started");
+        while let Some(message) = read_message(reader)? {
+            let method = message["method"].as_str().unwrap_or_default();
+            let params = &message["params"];
+            debug!("Received {}", method);
+
+            if method == "exit" {
+                break;
+            }
+            let Some(id) = message.get("id") else {
+                self.handle_notification(method, params);
+                continue;
+            };
+
+            let response = match self.handle_request(method, params) {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err(error) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": error.code, "message": error.message },
+                }),
+            };
+            write_message(writer, &response)?;
+        }
+        info!("Language server stopped");
+
+        Ok(())
+    }
+
+    fn handle_request(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(self.initialize(params)),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/documentSymbol" => self.document_symbols(params),
+            "textDocument/prepareCallHierarchy" => self.prepare_call_hierarchy(params),
+            "callHierarchy/incomingCalls" => self.incoming_calls(params),
+            "callHierarchy/outgoingCalls" => self.outgoing_calls(params),
+            "codegraph/relatedCode" => self.related_code(params),
+            _ => Err(RpcError {
+                code: METHOD_NOT_FOUND,
+                message: format!("Unsupported method: {}", method),
+            }),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) {
+        if method == "textDocument/didSave" {
+            let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+            debug!("Saved {}", uri);
+            self.reindex();
+        }
+    }
+
+    fn initialize(&mut self, params: &Value) -> Value {
+        self.hierarchical_symbols = params["capabilities"]["textDocument"]["documentSymbol"]
+            ["hierarchicalDocumentSymbolSupport"]
+            .as_bool()
+            .unwrap_or(false);
+        // Without a codebase given up front, the workspace root is indexed
+        if self.root.is_none()
+            && let Some(root) = params["rootUri"].as_str().and_then(uri_to_path)
+        {
+            self.root = Some(root);
+            self.reindex();
+        }
+
+        json!({
+            "capabilities": {
+                "textDocumentSync": { "openClose": true, "save": true },
+                "documentSymbolProvider": true,
+                "callHierarchyProvider": true,
+                "experimental": { "relatedCodeProvider": true },
+            },
+            "serverInfo": { "name": "relik_codegraph", "version": crate::version() },
+        })
+    }
+
+    fn reindex(&mut self) {
+        let Some(root) = &self.root else {
+            return;
+        };
+        match crate::warm_start_codebase(root, &self.graph, &self.config) {
+            Ok(graph) => {
+                info!(
+                    "Re-indexed {:?}: {} nodes and {} relationships",
+                    root,
+                    graph.node_count(),
+                    graph.relationship_count()
+                );
+                self.graph = graph;
+            }
+            Err(e) => warn!("Failed to re-index {:?}: {}", root, e),
+        }
+    }
+
+    fn document_path(params: &Value) -> Result<String, RpcError> {
+        params["textDocument"]["uri"]
+            .as_str()
+            .and_then(uri_to_path)
+            .map(|path| path.to_string_lossy().into_owned())
+            .ok_or_else(|| invalid_params("Missing or unsupported textDocument.uri"))
+    }
+
+    // Innermost node of the given types around a 0-based position
+    fn node_at(&self, params: &Value, types: &[NodeType]) -> Result<Option<&CodeNode>, RpcError> {
+        let file_path = Self::document_path(params)?;
+        let line = params["position"]["line"]
+            .as_u64()
+            .ok_or_else(|| invalid_params("Missing position.line"))? as usize
+            + 1;
+        Ok(self
+            .graph
+            .find_nodes_in_file(&file_path)
+            .into_iter()
+            .filter(|node| types.is_empty() || types.contains(&node.node_type))
+            .filter(|node| node.line_range.0 <= line && line <= node.line_range.1)
+            .min_by_key(|node| (node.line_range.1 - node.line_range.0, node.id.clone())))
+    }
+
+    fn item_node(&self, params: &Value) -> Result<&CodeNode, RpcError> {
+        let id = params["item"]["data"]["id"]
+            .as_str()
+            .ok_or_else(|| invalid_params("Missing item.data.id"))?;
+        self.graph
+            .get_node(id)
+            .ok_or_else(|| invalid_params(&format!("Unknown node: {}", id)))
+    }
+
+    // Nested symbols from the file outline when the client supports them,
+    // a flat list naming each symbol's class otherwise
+    fn document_symbols(&self, params: &Value) -> Result<Value, RpcError> {
+        let file_path = Self::document_path(params)?;
+        if self.hierarchical_symbols {
+            let outline = self.graph.file_outline(&file_path);
+            return Ok(Value::Array(outline.iter().map(document_symbol).collect()));
+        }
+
+        let mut nodes = self.graph.find_nodes_in_file(&file_path);
+        nodes.sort_by_key(|node| (node.line_range, node.id.clone()));
+
+        let symbols: Vec<Value> = nodes
+            .into_iter()
+            .filter(|node| !matches!(node.node_type, NodeType::File | NodeType::Directory))
+            .map(|node| {
+                json!({
+                    "name": node.name,
+                    "kind": symbol_kind(&node.node_type),
+                    "location": location(node),
+                    "containerName": node.parent_class,
+                })
+            })
+            .collect();
+        Ok(Value::Array(symbols))
+    }
+
+    fn prepare_call_hierarchy(&self, params: &Value) -> Result<Value, RpcError> {
+        let node = self.node_at(params, &[NodeType::Function, NodeType::Method])?;
+        Ok(match node {
+            Some(node) => json!([call_hierarchy_item(node)]),
+            None => Value::Null,
+        })
+    }
+
+    fn incoming_calls(&self, params: &Value) -> Result<Value, RpcError> {
+        let node = self.item_node(params)?;
+        let calls: Vec<Value> = self
+            .graph
+            .incoming_relationships(&node.id)
+            .iter()
+            .filter(|rel| rel.relationship_type == RelationshipType::Calls)
+            .filter_map(|rel| {
+                let caller = self.graph.get_node(&rel.from_id)?;
+                Some(json!({
+                    "from": call_hierarchy_item(caller),
+                    "fromRanges": call_ranges(rel),
+                }))
+            })
+            .collect();
+        Ok(Value::Array(calls))
+    }
+
+    fn outgoing_calls(&self, params: &Value) -> Result<Value, RpcError> {
+        let node = self.item_node(params)?;
+        let calls: Vec<Value> = self
+            .graph
+            .outgoing_relationships(&node.id)
+            .iter()
+            .filter(|rel| rel.relationship_type == RelationshipType::Calls)
+            .filter_map(|rel| {
+                let callee = self.graph.get_node(&rel.to_id)?;
+                Some(json!({
+                    "to": call_hierarchy_item(callee),
+                    "fromRanges": call_ranges(rel),
+                }))
+            })
+            .collect();
+        Ok(Value::Array(calls))
+    }
+
+    // Locations of the nodes within `depth` edges of the node at a position
+    fn related_code(&self, params: &Value) -> Result<Value, RpcError> {
+        let Some(node) = self.node_at(params, &[])? else {
+            return Ok(json!([]));
+        };
+        let depth = params["depth"]
+            .as_u64()
+            .map_or(DEFAULT_RELATED_DEPTH, |depth| depth as usize);
+
+        let mut related: Vec<&CodeNode> = self
+            .graph
+            .find_related_nodes(&node.id, depth)
+            .into_iter()
+            .filter(|related| related.id != node.id && !related.file_path.is_empty())
+            .collect();
+        related.sort_by(|a, b| {
+            (&a.file_path, a.line_range, &a.id).cmp(&(&b.file_path, b.line_range, &b.id))
+        });
+        related.truncate(MAX_RELATED);
+
+        Ok(Value::Array(
+            related
+                .into_iter()
+                .map(|related| {
+                    json!({
+                        "name": related.name,
+                        "kind": symbol_kind(&related.node_type),
+                        "location": location(related),
+                    })
+                })
+                .collect(),
+        ))
+    }
+}
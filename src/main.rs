@@ -1,8 +1,38 @@
 use log::{error, info, warn};
-use relik_codegraph::{analyze_codebase, version};
-use std::path::Path;
+use relik_codegraph::analysis::{
+    DuplicateOptions, EntryPointRules, RedactionRules, SearchOptions, Severity, changed_nodes,
+    find_dead_code, find_duplicates, is_test, redact_secrets, search, top_n_by_centrality,
+    unresolved_report,
+};
+use relik_codegraph::code_graph::{
+    Aggregation, ChunkOptions, CodeGraph, CodeNode, ContentLimit, HashingEmbedder, HnswIndex,
+    NodeType, Query, RelationshipType, SubgraphFilter, TokenEncoding, ViewConfig, algorithms,
+    chunk_graph, provenance,
+};
+use relik_codegraph::indexing::{
+    ContainerContent, DuplicatePolicy, GeneratedPolicy, IdScheme, IndexConfig, NodeCategory,
+    SummaryBackend, language_capabilities, summarize_graph, summarizer_for,
+};
+use relik_codegraph::lsp::LspServer;
+use relik_codegraph::utils::diff::parse_unified_diff;
+use relik_codegraph::utils::events::sink_from_spec;
+use relik_codegraph::utils::io::{
+    Compression, ExportFormat, export_graph_compressed, export_graph_shards, export_graph_to_json,
+    import_graph, is_stdio,
+};
+use relik_codegraph::{analyze_codebases_with_format, version, warm_start_codebase};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+const DEFAULT_CONFIG_PATH: &str = "codegraph.config.json";
+const DEFAULT_CYCLE_RELATIONSHIPS: &str = "imports,calls,depends_on";
+const DEFAULT_IMPACT_DEPTH: usize = 10;
+const DEFAULT_CONTEXT_BUDGET: usize = 2000;
+const DEFAULT_SUBGRAPH_DEPTH: usize = 1;
+#[cfg(feature = "server")]
+const DEFAULT_SERVE_ADDRESS: &str = "127.0.0.1:8080";
+
 fn main() -> std::io::Result<()> {
     // Initialize logger
     if std::env::var_os("RUST_LOG").is_none() {
@@ -12,19 +42,129 @@ fn main() -> std::io::Result<()> {
     }
     env_logger::init();
 
-    let args: Vec<String> = std::env::args().collect();
+    let (flags, args): (Vec<String>, Vec<String>) =
+        std::env::args().partition(|arg| arg.starts_with("--"));
 
     if args.len() < 2 {
         error!("Not enough arguments provided");
         eprintln!(
-            "Usage: {} <codebase_path> [output_path] [num_threads] [format]",
+            "Usage: {} <codebase_path> [output_path|-] [num_threads] [json|html|d2|msgpack|parquet] [--references] [--metrics] [--hierarchy] [--external-symbols] [--ownership] [--owned-by=<owner>] [--root=<codebase_path>]... [--warm-start=<graph_path>] [--emit=file:<path>|webhook:<url>|kafka:<brokers>/<topic>] [--disable-pass=<name>] [--id-scheme=uuid|content-hash|qualified-name] [--id-namespace=<prefix>] [--container-content=full|header|lines:N] [--export-content=full|none|max-bytes:N] [--redact-secrets] [--compress=gzip|zstd] [--shard] [--langs=<lang,...>] [--exclude-langs=<lang,...>] [--exclude-nodes=[<lang>:]<category>,...] [--include-nodes=<category,...>] [--max-file-size=<bytes>] [--generated=skip|tag|keep] [--duplicates=keep|alias|tag] [--include-dirs=<dir,...>] [--follow-symlinks] [--allow-outside-root] [--cache-dir=<path>] [--git-tracked] [--rev=<revision>] [--git-history=<days>] [--token-counts[=approximate|cl100k_base|o200k_base]] [--summaries=template|llm:<model>@<url>]",
+            args[0]
+        );
+        eprintln!(
+            "       {} view <graph_path> <view_name> [output_path] [config_path]",
+            args[0]
+        );
+        eprintln!(
+            "       {} serve <graph_path|codebase_path> [address]",
+            args[0]
+        );
+        eprintln!("       {} lsp [codebase_path]", args[0]);
+        eprintln!(
+            "       {} query <graph_path> <query|text> [--limit=N]",
+            args[0]
+        );
+        eprintln!(
+            "       {} search <graph_path> <text> [--limit=N] [--depth=N] [--hnsw]",
+            args[0]
+        );
+        eprintln!(
+            "       {} stats <graph_path> [--json] [--unused-public] [--hotspots]",
+            args[0]
+        );
+        eprintln!(
+            "       {} context <graph_path> <node_id|name> [--budget=N]",
+            args[0]
+        );
+        eprintln!(
+            "       {} cycles <graph_path> [relationship_types]",
+            args[0]
+        );
+        eprintln!(
+            "       {} dead-code <graph_path> [report_path|-] [--no-public] [--annotate=<output_graph>]",
+            args[0]
+        );
+        eprintln!(
+            "       {} duplicates <graph_path> [report_path] [--threshold=<0..1>] [--min-tokens=N] [--annotate=<output_graph>]",
+            args[0]
+        );
+        eprintln!(
+            "       {} unresolved <graph_path> [report_path] [--limit=N] [--min-severity=low|medium|high]",
+            args[0]
+        );
+        eprintln!(
+            "       {} redact <graph_path> <output_path> [report_path] [--pattern=<kind>:<regex>] [--min-entropy=<bits>|off]",
+            args[0]
+        );
+        eprintln!(
+            "       {} validate <graph_path> [report_path] [--repair=<output_graph>]",
+            args[0]
+        );
+        eprintln!(
+            "       {} impact <graph_path> [diff_path|-] [--depth=N]",
+            args[0]
+        );
+        eprintln!(
+            "       {} subgraph <graph_path> <output_path> --path=<prefix>|--ids=<id,...>|--types=<type,...>|--seed=<id> [--depth=N] [--format=json|html|d2|msgpack|parquet] [--compress=gzip|zstd]",
+            args[0]
+        );
+        eprintln!(
+            "       {} aggregate <graph_path> <output_path> --by=file|directory|class [--format=json|html|d2|msgpack|parquet] [--compress=gzip|zstd]",
+            args[0]
+        );
+        eprintln!(
+            "       {} chunks <graph_path> [output_path] [--max-tokens=N] [--types=<type,...>] [--tokenizer=approximate|cl100k_base|o200k_base]",
+            args[0]
+        );
+        #[cfg(feature = "neo4j")]
+        eprintln!(
+            "       {} neo4j <graph_path> <bolt_uri> [--user=<name>] [--database=<name>] [--batch-size=N]",
             args[0]
         );
+        #[cfg(feature = "postgres")]
+        eprintln!(
+            "       {} postgres <graph_path> [connection_url] [--schema=<name>] [--batch-size=N] [--embed]",
+            args[0]
+        );
+        eprintln!("       {} languages", args[0]);
         eprintln!("Version: {}", version());
         return Ok(());
     }
 
-    let codebase_path = Path::new(&args[1]);
+    match args[1].as_str() {
+        "view" => return closed_pipe_is_success(run_view(&args)),
+        "query" => return closed_pipe_is_success(run_query(&args, &flags)),
+        "search" => return closed_pipe_is_success(run_search(&args, &flags)),
+        "stats" => return closed_pipe_is_success(run_stats(&args, &flags)),
+        "context" => return closed_pipe_is_success(run_context(&args, &flags)),
+        "cycles" => return closed_pipe_is_success(run_cycles(&args)),
+        "dead-code" => return closed_pipe_is_success(run_dead_code(&args, &flags)),
+        "duplicates" => return closed_pipe_is_success(run_duplicates(&args, &flags)),
+        "unresolved" => return closed_pipe_is_success(run_unresolved(&args, &flags)),
+        "redact" => return closed_pipe_is_success(run_redact(&args, &flags)),
+        "validate" => return closed_pipe_is_success(run_validate(&args, &flags)),
+        "impact" => return closed_pipe_is_success(run_impact(&args, &flags)),
+        "subgraph" => return closed_pipe_is_success(run_subgraph(&args, &flags)),
+        "aggregate" => return closed_pipe_is_success(run_aggregate(&args, &flags)),
+        "chunks" => return closed_pipe_is_success(run_chunks(&args, &flags)),
+        #[cfg(feature = "neo4j")]
+        "neo4j" => return closed_pipe_is_success(run_neo4j(&args, &flags)),
+        #[cfg(feature = "postgres")]
+        "postgres" => return closed_pipe_is_success(run_postgres(&args, &flags)),
+        "languages" => return closed_pipe_is_success(run_languages()),
+        "lsp" => return run_lsp(&args),
+        #[cfg(feature = "server")]
+        "serve" => return run_serve(&args),
+        _ => {}
+    }
+
+    // Each `--root` adds a codebase indexed into the same graph; a flag per
+    // root, so paths may contain any character
+    let codebase_paths: Vec<&Path> = std::iter::once(args[1].as_str())
+        .chain(flags.iter().filter_map(|flag| flag.strip_prefix("--root=")))
+        .map(Path::new)
+        .collect();
+    let codebase_path = codebase_paths[0];
     let output_path = if args.len() >= 3 {
         Path::new(&args[2])
     } else {
@@ -49,27 +189,1209 @@ fn main() -> std::io::Result<()> {
     let format = if args.len() >= 5 { &args[4] } else { "json" };
 
     info!("Relik Indexor v{}", version());
-    info!("Processing codebase at: {:?}", codebase_path);
+    info!("Processing codebase at: {:?}", codebase_paths);
     info!("Using {} threads", num_threads);
     info!("Output format: {}", format);
     info!("Parser: Tree-sitter");
 
+    let mut config = IndexConfig::new()
+        .num_threads(num_threads)
+        .references(flags.iter().any(|flag| flag == "--references"))
+        .metrics(flags.iter().any(|flag| flag == "--metrics"))
+        .directory_hierarchy(flags.iter().any(|flag| flag == "--hierarchy"))
+        .external_symbols(flags.iter().any(|flag| flag == "--external-symbols"))
+        .file_ownership(flags.iter().any(|flag| flag == "--ownership"))
+        .git_tracked_only(flags.iter().any(|flag| flag == "--git-tracked"))
+        .follow_symlinks(flags.iter().any(|flag| flag == "--follow-symlinks"))
+        .allow_outside_root(flags.iter().any(|flag| flag == "--allow-outside-root"))
+        .redact_secrets(flags.iter().any(|flag| flag == "--redact-secrets"))
+        .export_compression(compression_flag(&flags))
+        .shard_output(flags.iter().any(|flag| flag == "--shard"));
+    for pass in flags
+        .iter()
+        .filter_map(|flag| flag.strip_prefix("--disable-pass="))
+    {
+        if !provenance::ALL.contains(&pass) {
+            warn!(
+                "Unknown relationship pass: {} (known: {})",
+                pass,
+                provenance::ALL.join(", ")
+            );
+        }
+        config = config.disable_pass(pass);
+    }
+    for flag in &flags {
+        if let Some(scheme) = flag.strip_prefix("--id-scheme=") {
+            match IdScheme::parse(scheme) {
+                Some(scheme) => config = config.id_scheme(scheme),
+                None => warn!("Unknown ID scheme: {}, using uuid", scheme),
+            }
+        } else if let Some(namespace) = flag.strip_prefix("--id-namespace=") {
+            config = config.id_namespace(namespace);
+        } else if let Some(languages) = flag.strip_prefix("--langs=") {
+            let languages: Vec<&str> = languages.split(',').filter(|l| !l.is_empty()).collect();
+            warn_unknown_languages(&languages);
+            config = config.languages(&languages);
+        } else if let Some(languages) = flag.strip_prefix("--exclude-langs=") {
+            let languages: Vec<&str> = languages.split(',').filter(|l| !l.is_empty()).collect();
+            warn_unknown_languages(&languages);
+            for language in languages {
+                config = config.exclude_language(language);
+            }
+        } else if let Some(categories) = flag.strip_prefix("--exclude-nodes=") {
+            // `closures` for every language, `python:local-variables` for one
+            for entry in categories.split(',').filter(|entry| !entry.is_empty()) {
+                let (language, name) = match entry.split_once(':') {
+                    Some((language, name)) => (Some(language), name),
+                    None => (None, entry),
+                };
+                match (NodeCategory::parse(name), language) {
+                    (Some(category), Some(language)) => {
+                        warn_unknown_languages(&[language]);
+                        config = config.exclude_language_nodes(language, category);
+                    }
+                    (Some(category), None) => config = config.exclude_nodes(category),
+                    (None, _) => warn!(
+                        "Unknown node category: {} (known: variables, local-variables, closures, fields)",
+                        name
+                    ),
+                }
+            }
+        } else if let Some(categories) = flag.strip_prefix("--include-nodes=") {
+            for name in categories.split(',').filter(|name| !name.is_empty()) {
+                match NodeCategory::parse(name) {
+                    Some(category) => config = config.include_nodes(category),
+                    None => warn!(
+                        "Unknown node category: {} (known: variables, local-variables, closures, fields)",
+                        name
+                    ),
+                }
+            }
+        } else if let Some(policy) = flag.strip_prefix("--duplicates=") {
+            match DuplicatePolicy::parse(policy) {
+                Some(policy) => config = config.duplicate_files(policy),
+                None => warn!("Unknown duplicate file policy: {}, using keep", policy),
+            }
+        } else if let Some(policy) = flag.strip_prefix("--generated=") {
+            match GeneratedPolicy::parse(policy) {
+                Some(policy) => config = config.generated_files(policy),
+                None => warn!("Unknown generated file policy: {}, using skip", policy),
+            }
+        } else if let Some(backend) = flag.strip_prefix("--summaries=") {
+            match SummaryBackend::parse(backend) {
+                Some(backend) => config = config.summaries(backend),
+                None => warn!(
+                    "Unknown summary backend: {}, expected template or llm:<model>@<url>",
+                    backend
+                ),
+            }
+        } else if let Some(dirs) = flag.strip_prefix("--include-dirs=") {
+            for dir in dirs.split(',').filter(|dir| !dir.is_empty()) {
+                config = config.include_dir(Path::new(dir));
+            }
+        } else if let Some(dir) = flag.strip_prefix("--cache-dir=") {
+            config = config.cache_dir(Path::new(dir));
+        } else if let Some(owner) = flag.strip_prefix("--owned-by=") {
+            config = config.owned_by(owner);
+        } else if let Some(revision) = flag.strip_prefix("--rev=") {
+            config = config.git_revision(revision);
+        } else if let Some(days) = flag.strip_prefix("--git-history=") {
+            match days.parse::<u32>() {
+                Ok(days) => config = config.git_history(Some(days)),
+                Err(_) => warn!("Invalid git history window: {} days", days),
+            }
+        } else if flag == "--token-counts" {
+            config = config.token_counts(Some(TokenEncoding::default()));
+        } else if let Some(encoding) = flag.strip_prefix("--token-counts=") {
+            match TokenEncoding::parse(encoding) {
+                Some(encoding) => config = config.token_counts(Some(encoding)),
+                None => warn!("Unknown token encoding: {}", encoding),
+            }
+        } else if let Some(size) = flag.strip_prefix("--max-file-size=") {
+            match size.parse::<usize>() {
+                Ok(0) => config = config.max_file_size(None),
+                Ok(size) => config = config.max_file_size(Some(size)),
+                Err(_) => warn!("Invalid max file size: {}, using the default", size),
+            }
+        } else if let Some(mode) = flag.strip_prefix("--container-content=") {
+            match ContainerContent::parse(mode) {
+                Some(mode) => config = config.container_content(mode),
+                None => warn!("Unknown container content mode: {}, using full", mode),
+            }
+        } else if let Some(mode) = flag.strip_prefix("--export-content=") {
+            match ContentLimit::parse(mode) {
+                Some(limit) => config = config.export_content(limit),
+                None => warn!("Unknown export content mode: {}, using full", mode),
+            }
+        }
+    }
+    let export_format = ExportFormat::parse(format).unwrap_or_else(|| {
+        warn!("Unsupported format: {}. Using JSON instead.", format);
+        ExportFormat::Json
+    });
+    let warm_start_path = flags
+        .iter()
+        .find_map(|flag| flag.strip_prefix("--warm-start="));
+
     let start_time = Instant::now();
 
-    match format {
-        "json" => {
-            info!("Starting indexing with JSON output");
-            analyze_codebase(codebase_path, output_path, num_threads)?;
+    if let Some(previous_path) = warm_start_path {
+        if codebase_paths.len() > 1 {
+            error!("--warm-start supports a single codebase path");
+            std::process::exit(1);
+        }
+        info!("Warm-starting from previous graph: {}", previous_path);
+        let previous = import_graph(Path::new(previous_path))?;
+        let mut graph = warm_start_codebase(codebase_path, &previous, &config)?;
+        if config.redact_secrets {
+            redact_secrets(&mut graph, &RedactionRules::default());
+        }
+        summarize_graph(&mut graph, summarizer_for(&config.summaries)?.as_ref());
+        graph.limit_content(config.export_content);
+        if config.shard_output {
+            export_graph_shards(
+                &graph,
+                output_path,
+                export_format,
+                config.export_compression,
+            )?;
+        } else {
+            export_graph_compressed(
+                &graph,
+                output_path,
+                export_format,
+                config.export_compression,
+            )?;
         }
-        _ => {
-            warn!("Unsupported format: {}. Using JSON instead.", format);
-            analyze_codebase(codebase_path, output_path, num_threads)?;
+
+        if let Some(spec) = flags.iter().find_map(|flag| flag.strip_prefix("--emit=")) {
+            let events = graph.changes_since(&previous);
+            sink_from_spec(spec)?.send(&events)?;
+            info!("Emitted {} change events to {}", events.len(), spec);
         }
+
+        info!("Indexing completed in {:.2?}", start_time.elapsed());
+        info!("Output saved to: {:?}", output_path);
+        return Ok(());
     }
 
+    info!("Starting indexing with {:?} output", export_format);
+    analyze_codebases_with_format(&codebase_paths, output_path, &config, export_format)?;
+
     let elapsed = start_time.elapsed();
     info!("Indexing completed in {:.2?}", elapsed);
     info!("Output saved to: {:?}", output_path);
 
     Ok(())
 }
+
+fn warn_unknown_languages(languages: &[&str]) {
+    let known: Vec<String> = language_capabilities()
+        .into_iter()
+        .map(|capabilities| capabilities.language)
+        .collect();
+    for language in languages {
+        if !known.iter().any(|known| known == language) {
+            warn!(
+                "Unknown language: {} (known: {})",
+                language,
+                known.join(", ")
+            );
+        }
+    }
+}
+
+// A reader such as `head` that stops early closes the pipe; the report is
+// done as far as it is concerned
+fn closed_pipe_is_success(result: std::io::Result<()>) -> std::io::Result<()> {
+    match result {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+fn run_view(args: &[String]) -> std::io::Result<()> {
+    if args.len() < 4 {
+        eprintln!(
+            "Usage: {} view <graph_path> <view_name> [output_path] [config_path]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let graph_path = Path::new(&args[2]);
+    let view_name = &args[3];
+    let output_path = args
+        .get(4)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(format!("{}.json", view_name)));
+    let config_path = Path::new(args.get(5).map_or(DEFAULT_CONFIG_PATH, |p| p.as_str()));
+
+    let config = ViewConfig::load(config_path)?;
+    if config.get(view_name).is_none() {
+        let available: Vec<&str> = config.names().collect();
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!(
+                "View '{}' is not defined in {:?} (available: {})",
+                view_name,
+                config_path,
+                available.join(", ")
+            ),
+        ));
+    }
+
+    let graph = import_graph(graph_path)?;
+    let view_graph = config.materialize(view_name, &graph)?;
+    export_graph_to_json(&view_graph, &output_path)?;
+
+    info!("View '{}' saved to: {:?}", view_name, output_path);
+
+    Ok(())
+}
+
+fn run_query(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    if args.len() < 4 {
+        eprintln!(
+            "Usage: {} query <graph_path> <query|text> [--limit=N]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let graph_path = Path::new(&args[2]);
+    let input = args[3..].join(" ");
+    let graph = import_graph(graph_path)?;
+
+    // Text without `key:value` terms or arrows is a search
+    if !input.contains(':') && !input.contains("->") && !input.contains("<-") {
+        let mut options = SearchOptions::default();
+        if let Some(limit) = flags
+            .iter()
+            .find_map(|flag| flag.strip_prefix("--limit="))
+            .and_then(|limit| limit.parse().ok())
+        {
+            options.limit = limit;
+        }
+        let hits = search(&graph, &input, &options)?;
+        for hit in &hits {
+            writeln!(
+                out,
+                "{:.3}\t{:?}\t{}\t{}:{}\t{}",
+                hit.score, hit.node_type, hit.name, hit.file_path, hit.line_range.0, hit.id
+            )?;
+        }
+        info!("Search matched {} nodes", hits.len());
+        return Ok(());
+    }
+
+    let query = Query::parse(&input)?;
+    let results = query.execute(&graph);
+    for node in &results {
+        writeln!(
+            out,
+            "{:?}\t{}\t{}:{}\t{}",
+            node.node_type, node.name, node.file_path, node.line_range.0, node.id
+        )?;
+    }
+
+    info!("Query matched {} nodes", results.len());
+
+    Ok(())
+}
+
+// Nodes returned by `search` when no limit is given
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+// Rank nodes by similarity to a text, optionally followed by the nodes within
+// a number of edges of each match
+fn run_search(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    if args.len() < 4 {
+        eprintln!(
+            "Usage: {} search <graph_path> <text> [--limit=N] [--depth=N] [--hnsw]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let mut graph = import_graph(Path::new(&args[2]))?;
+    let text = args[3..].join(" ");
+    let flag_value = |name: &str| {
+        flags
+            .iter()
+            .find_map(|flag| flag.strip_prefix(name))
+            .and_then(|value| value.parse().ok())
+    };
+    let limit = flag_value("--limit=").unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let depth = flag_value("--depth=").unwrap_or(0);
+
+    // Nodes embedded by another model keep their vectors
+    let embedder = HashingEmbedder::default();
+    graph.add_embeddings(&embedder)?;
+    let index = flags
+        .iter()
+        .any(|flag| flag == "--hnsw")
+        .then(|| HnswIndex::build(&graph));
+
+    let results = graph.semantic_search(&embedder, &text, limit, index.as_ref())?;
+    for (node, similarity) in &results {
+        writeln!(
+            out,
+            "{:.3}\t{:?}\t{}\t{}:{}\t{}",
+            similarity, node.node_type, node.name, node.file_path, node.line_range.0, node.id
+        )?;
+        if depth > 0 {
+            let mut related: Vec<_> = graph
+                .find_related_nodes(&node.id, depth)
+                .into_iter()
+                .filter(|related| related.id != node.id)
+                .collect();
+            related.sort_by(|a, b| (&a.file_path, a.line_range).cmp(&(&b.file_path, b.line_range)));
+            for related in related {
+                writeln!(
+                    out,
+                    "\t{:?}\t{}\t{}:{}\t{}",
+                    related.node_type,
+                    related.name,
+                    related.file_path,
+                    related.line_range.0,
+                    related.id
+                )?;
+            }
+        }
+    }
+
+    info!("Search returned {} nodes", results.len());
+
+    Ok(())
+}
+
+// Number of most used symbols listed by `stats`
+const TOP_SYMBOLS: usize = 20;
+
+fn run_context(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    if args.len() < 4 {
+        eprintln!(
+            "Usage: {} context <graph_path> <node_id|name> [--budget=N]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let graph = import_graph(Path::new(&args[2]))?;
+    let mut budget = DEFAULT_CONTEXT_BUDGET;
+    if let Some(value) = flags.iter().find_map(|flag| flag.strip_prefix("--budget=")) {
+        match value.parse::<usize>() {
+            Ok(value) => budget = value,
+            Err(_) => warn!("Invalid budget: {}, using {}", value, budget),
+        }
+    }
+
+    // A node ID, or a name only one node has
+    let target = &args[3];
+    let id = match graph.get_node(target) {
+        Some(node) => node.id.clone(),
+        None => match graph.find_nodes_by_name(target).as_slice() {
+            [node] => node.id.clone(),
+            [] => {
+                error!("No node with ID or name {}", target);
+                std::process::exit(1);
+            }
+            nodes => {
+                error!(
+                    "{} nodes are named {}, pass one of their IDs:",
+                    nodes.len(),
+                    target
+                );
+                for node in nodes {
+                    eprintln!("{}\t{}:{}", node.id, node.file_path, node.line_range.0);
+                }
+                std::process::exit(1);
+            }
+        },
+    };
+    if let Some(context) = graph.context_for(&id, budget) {
+        write!(out, "{}", context)?;
+    }
+
+    Ok(())
+}
+
+fn run_stats(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} stats <graph_path> [--json] [--unused-public] [--hotspots]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let graph = import_graph(Path::new(&args[2]))?;
+    let json = flags.iter().any(|flag| flag == "--json");
+
+    if flags.iter().any(|flag| flag == "--unused-public") {
+        let unused = graph.unused_public_symbols();
+        if json {
+            let rows: Vec<serde_json::Value> = unused
+                .iter()
+                .map(|usage| {
+                    serde_json::json!({
+                        "id": usage.node.id,
+                        "name": usage.node.name,
+                        "node_type": usage.node.node_type,
+                        "file_path": usage.node.file_path,
+                        "line": usage.node.line_range.0,
+                        "internal": usage.internal,
+                    })
+                })
+                .collect();
+            let json = serde_json::to_string_pretty(&rows).map_err(std::io::Error::other)?;
+            writeln!(out, "{}", json)?;
+        } else {
+            for usage in &unused {
+                writeln!(
+                    out,
+                    "{:?}\t{}\t{}:{}\t{} internal uses",
+                    usage.node.node_type,
+                    usage.node.name,
+                    usage.node.file_path,
+                    usage.node.line_range.0,
+                    usage.internal
+                )?;
+            }
+        }
+        info!(
+            "{} public symbols are not used outside their package",
+            unused.len()
+        );
+        return Ok(());
+    }
+
+    if flags.iter().any(|flag| flag == "--hotspots") {
+        let hotspots: Vec<(&CodeNode, f64, usize)> = top_n_by_centrality(&graph, TOP_SYMBOLS)
+            .into_iter()
+            .map(|(node, score)| {
+                let fan_in = node
+                    .metadata
+                    .get("fan_in")
+                    .and_then(|fan_in| fan_in.parse().ok())
+                    .unwrap_or_else(|| graph.find_callers(&node.id).len());
+                (node, score, fan_in)
+            })
+            .collect();
+        if json {
+            let rows: Vec<serde_json::Value> = hotspots
+                .iter()
+                .map(|(node, score, fan_in)| {
+                    serde_json::json!({
+                        "id": node.id,
+                        "name": node.name,
+                        "node_type": node.node_type,
+                        "file_path": node.file_path,
+                        "line": node.line_range.0,
+                        "pagerank": score,
+                        "fan_in": fan_in,
+                    })
+                })
+                .collect();
+            let json = serde_json::to_string_pretty(&rows).map_err(std::io::Error::other)?;
+            writeln!(out, "{}", json)?;
+        } else {
+            for (node, score, fan_in) in &hotspots {
+                writeln!(
+                    out,
+                    "{:.6}\t{:?}\t{}\t{}:{}\tfan-in {}",
+                    score, node.node_type, node.name, node.file_path, node.line_range.0, fan_in
+                )?;
+            }
+        }
+        return Ok(());
+    }
+
+    let stats = graph.stats();
+    if json {
+        let json = serde_json::to_string_pretty(&stats).map_err(std::io::Error::other)?;
+        writeln!(out, "{}", json)?;
+        return Ok(());
+    }
+
+    writeln!(
+        out,
+        "{} nodes, {} relationships, {} files, average degree {:.2}",
+        stats.nodes, stats.relationships, stats.files, stats.average_degree
+    )?;
+    writeln!(out, "Nodes by type:")?;
+    for (node_type, count) in &stats.nodes_by_type {
+        writeln!(out, "{:>8}\t{}", count, node_type)?;
+    }
+    writeln!(out, "Nodes by language:")?;
+    for (language, count) in &stats.nodes_by_language {
+        writeln!(out, "{:>8}\t{}", count, language)?;
+    }
+    writeln!(out, "Relationships by type:")?;
+    for (relationship_type, count) in &stats.relationships_by_type {
+        writeln!(out, "{:>8}\t{}", count, relationship_type)?;
+    }
+    writeln!(out, "Largest files:")?;
+    for file in &stats.largest_files {
+        writeln!(
+            out,
+            "{:>8}\t{}\t({} lines)",
+            file.nodes, file.file_path, file.lines
+        )?;
+    }
+    writeln!(out, "{} orphaned nodes", stats.orphaned_nodes.len())?;
+    writeln!(out, "Most used symbols:")?;
+    for usage in graph.symbol_usage().iter().take(TOP_SYMBOLS) {
+        writeln!(
+            out,
+            "{:>6}\t{:?}\t{}\t{}:{}\t({} external)",
+            usage.total(),
+            usage.node.node_type,
+            usage.node.name,
+            usage.node.file_path,
+            usage.node.line_range.0,
+            usage.external
+        )?;
+    }
+
+    Ok(())
+}
+
+fn run_cycles(args: &[String]) -> std::io::Result<()> {
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} cycles <graph_path> [relationship_types]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let names = args
+        .get(3)
+        .map_or(DEFAULT_CYCLE_RELATIONSHIPS, |n| n.as_str());
+    let mut rel_types = Vec::new();
+    for name in names.split(',') {
+        match RelationshipType::parse(name) {
+            Some(rel_type) => rel_types.push(rel_type),
+            None => {
+                error!("Unknown relationship type: {}", name);
+                return Ok(());
+            }
+        }
+    }
+
+    let graph = import_graph(Path::new(&args[2]))?;
+    let cycles = algorithms::find_cycles(&graph, &rel_types);
+    for (number, cycle) in cycles.iter().enumerate() {
+        writeln!(out, "Cycle {} ({} nodes):", number + 1, cycle.len())?;
+        for node in cycle {
+            writeln!(
+                out,
+                "  {:?}\t{}\t{}:{}",
+                node.node_type, node.name, node.file_path, node.line_range.0
+            )?;
+        }
+    }
+
+    info!("Found {} cycles over {}", cycles.len(), names);
+
+    Ok(())
+}
+
+fn run_dead_code(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} dead-code <graph_path> [report_path|-] [--no-public] [--annotate=<output_graph>]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let mut graph = import_graph(Path::new(&args[2]))?;
+    let rules = EntryPointRules {
+        public: !flags.iter().any(|flag| flag == "--no-public"),
+        ..Default::default()
+    };
+    let report = find_dead_code(&mut graph, &rules);
+
+    let json = serde_json::to_string_pretty(&report).map_err(std::io::Error::other)?;
+    // `-` prints the report, as it does when no path is given
+    match args.get(3) {
+        Some(report_path) if report_path != "-" => {
+            std::fs::write(report_path, json)?;
+            info!("Dead code report saved to: {}", report_path);
+        }
+        _ => writeln!(out, "{}", json)?,
+    }
+
+    if let Some(output_path) = flags
+        .iter()
+        .find_map(|flag| flag.strip_prefix("--annotate="))
+    {
+        export_graph_to_json(&graph, Path::new(output_path))?;
+    }
+
+    Ok(())
+}
+
+fn run_duplicates(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} duplicates <graph_path> [report_path] [--threshold=<0..1>] [--min-tokens=N] [--annotate=<output_graph>]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let mut graph = import_graph(Path::new(&args[2]))?;
+    let mut options = DuplicateOptions::default();
+    for flag in flags {
+        if let Some(threshold) = flag.strip_prefix("--threshold=") {
+            match threshold.parse::<f64>() {
+                Ok(threshold) if (0.0..=1.0).contains(&threshold) => options.threshold = threshold,
+                _ => warn!(
+                    "Invalid similarity threshold: {}, using {}",
+                    threshold, options.threshold
+                ),
+            }
+        } else if let Some(tokens) = flag.strip_prefix("--min-tokens=") {
+            match tokens.parse::<usize>() {
+                Ok(tokens) => options.min_tokens = tokens,
+                Err(_) => warn!(
+                    "Invalid minimum token count: {}, using {}",
+                    tokens, options.min_tokens
+                ),
+            }
+        }
+    }
+    let report = find_duplicates(&mut graph, &options);
+
+    let json = serde_json::to_string_pretty(&report).map_err(std::io::Error::other)?;
+    match args.get(3) {
+        Some(report_path) => {
+            std::fs::write(report_path, json)?;
+            info!("Duplicate code report saved to: {}", report_path);
+        }
+        None => writeln!(out, "{}", json)?,
+    }
+
+    if let Some(output_path) = flags
+        .iter()
+        .find_map(|flag| flag.strip_prefix("--annotate="))
+    {
+        export_graph_to_json(&graph, Path::new(output_path))?;
+    }
+
+    Ok(())
+}
+
+fn run_unresolved(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} unresolved <graph_path> [report_path] [--limit=N] [--min-severity=low|medium|high]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let graph = import_graph(Path::new(&args[2]))?;
+    let mut limit = 50;
+    let mut min_severity = Severity::Low;
+    for flag in flags {
+        if let Some(value) = flag.strip_prefix("--limit=") {
+            match value.parse::<usize>() {
+                Ok(value) => limit = value,
+                Err(_) => warn!("Invalid limit: {}, using {}", value, limit),
+            }
+        } else if let Some(value) = flag.strip_prefix("--min-severity=") {
+            match Severity::parse(value) {
+                Some(severity) => min_severity = severity,
+                None => warn!("Unknown severity: {}, using low", value),
+            }
+        }
+    }
+    let report = unresolved_report(&graph, limit, min_severity);
+    if report.symbols == 0 {
+        warn!("The graph has no external symbols; index it with --external-symbols");
+    }
+
+    let json = serde_json::to_string_pretty(&report).map_err(std::io::Error::other)?;
+    match args.get(3) {
+        Some(report_path) => {
+            std::fs::write(report_path, json)?;
+            info!("Unresolved reference report saved to: {}", report_path);
+        }
+        None => writeln!(out, "{}", json)?,
+    }
+
+    Ok(())
+}
+
+fn run_validate(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} validate <graph_path> [report_path] [--repair=<output_graph>]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let mut graph = import_graph(Path::new(&args[2]))?;
+    let repair_path = flags.iter().find_map(|flag| flag.strip_prefix("--repair="));
+    let report = match repair_path {
+        Some(output_path) => {
+            let report = graph.repair();
+            export_graph_to_json(&graph, Path::new(output_path))?;
+            info!("Repaired graph saved to: {}", output_path);
+            report
+        }
+        None => graph.validate(),
+    };
+    if report.is_valid() {
+        info!("The graph has no integrity problems");
+    } else {
+        warn!(
+            "The graph has {} integrity problems",
+            report.problem_count()
+        );
+    }
+
+    let json = serde_json::to_string_pretty(&report).map_err(std::io::Error::other)?;
+    match args.get(3) {
+        Some(report_path) => {
+            std::fs::write(report_path, json)?;
+            info!("Validation report saved to: {}", report_path);
+        }
+        // The repaired graph already went to stdout
+        None if repair_path.is_some_and(|path| is_stdio(Path::new(path))) => {
+            eprintln!("{}", json)
+        }
+        None => writeln!(out, "{}", json)?,
+    }
+
+    Ok(())
+}
+
+fn run_redact(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    if args.len() < 4 {
+        eprintln!(
+            "Usage: {} redact <graph_path> <output_path> [report_path] [--pattern=<kind>:<regex>] [--min-entropy=<bits>|off]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let mut graph = import_graph(Path::new(&args[2]))?;
+    let mut rules = RedactionRules::default();
+    for flag in flags {
+        if let Some(pattern) = flag.strip_prefix("--pattern=") {
+            let Some((kind, pattern)) = pattern.split_once(':') else {
+                warn!("Invalid pattern: {}, expected <kind>:<regex>", pattern);
+                continue;
+            };
+            if let Err(e) = rules.add_pattern(kind, pattern) {
+                warn!("Invalid {} pattern: {}", kind, e);
+            }
+        } else if let Some(bits) = flag.strip_prefix("--min-entropy=") {
+            if bits == "off" {
+                rules.min_entropy = None;
+            } else {
+                match bits.parse::<f64>() {
+                    Ok(bits) => rules.min_entropy = Some(bits),
+                    Err(_) => warn!(
+                        "Invalid minimum entropy: {}, using {:?}",
+                        bits, rules.min_entropy
+                    ),
+                }
+            }
+        }
+    }
+    let report = redact_secrets(&mut graph, &rules);
+    let output_path = Path::new(&args[3]);
+    export_graph_to_json(&graph, output_path)?;
+
+    let json = serde_json::to_string_pretty(&report).map_err(std::io::Error::other)?;
+    match args.get(4) {
+        Some(report_path) => {
+            std::fs::write(report_path, json)?;
+            info!("Redaction report saved to: {}", report_path);
+        }
+        // The graph already went to stdout
+        None if is_stdio(output_path) => eprintln!("{}", json),
+        None => writeln!(out, "{}", json)?,
+    }
+
+    Ok(())
+}
+
+fn run_chunks(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} chunks <graph_path> [output_path] [--max-tokens=N] [--types=<type,...>] [--tokenizer=approximate|cl100k_base|o200k_base]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let graph = import_graph(Path::new(&args[2]))?;
+    let mut options = ChunkOptions::default();
+    for flag in flags {
+        if let Some(tokens) = flag.strip_prefix("--max-tokens=") {
+            match tokens.parse::<usize>() {
+                Ok(tokens) if tokens > 0 => options.max_tokens = tokens,
+                _ => warn!(
+                    "Invalid token budget: {}, using {}",
+                    tokens, options.max_tokens
+                ),
+            }
+        } else if let Some(types) = flag.strip_prefix("--types=") {
+            for name in types.split(',') {
+                match NodeType::parse(name.trim()) {
+                    Some(node_type) => options.node_types.push(node_type),
+                    None => warn!("Unknown node type: {}", name),
+                }
+            }
+        } else if let Some(encoding) = flag.strip_prefix("--tokenizer=") {
+            match TokenEncoding::parse(encoding) {
+                Some(encoding) => options.tokenizer = encoding.tokenizer()?,
+                None => warn!("Unknown token encoding: {}, using approximate", encoding),
+            }
+        }
+    }
+    let chunks = chunk_graph(&graph, &options);
+
+    let json = serde_json::to_string_pretty(&chunks).map_err(std::io::Error::other)?;
+    match args.get(3) {
+        Some(output_path) => {
+            std::fs::write(output_path, json)?;
+            info!("{} chunks saved to: {}", chunks.len(), output_path);
+        }
+        None => writeln!(out, "{}", json)?,
+    }
+
+    Ok(())
+}
+
+fn run_impact(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} impact <graph_path> [diff_path|-] [--depth=N]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout().lock();
+
+    let diff = match args.get(3).map(|p| p.as_str()) {
+        Some(path) if path != "-" => std::fs::read_to_string(path)?,
+        _ => std::io::read_to_string(std::io::stdin())?,
+    };
+    let depth = flags
+        .iter()
+        .find_map(|flag| flag.strip_prefix("--depth="))
+        .and_then(|depth| depth.parse().ok())
+        .unwrap_or(DEFAULT_IMPACT_DEPTH);
+
+    let graph = import_graph(Path::new(&args[2]))?;
+    let changed = changed_nodes(&graph, &parse_unified_diff(&diff));
+    let changed_ids: Vec<&str> = changed.iter().map(|node| node.id.as_str()).collect();
+    let impacted = graph.impacted_by(&changed_ids, depth);
+
+    writeln!(out, "Changed symbols:")?;
+    for node in &changed {
+        writeln!(
+            out,
+            "  {:?}\t{}\t{}:{}",
+            node.node_type, node.name, node.file_path, node.line_range.0
+        )?;
+    }
+
+    writeln!(out, "Impacted symbols:")?;
+    for impact in &impacted {
+        writeln!(
+            out,
+            "  {}\t{:?}\t{}\t{}:{}",
+            impact.depth,
+            impact.node.node_type,
+            impact.node.name,
+            impact.node.file_path,
+            impact.node.line_range.0
+        )?;
+    }
+
+    writeln!(out, "Impacted tests:")?;
+    let tests = changed
+        .iter()
+        .copied()
+        .chain(impacted.iter().map(|impact| impact.node))
+        .filter(|node| is_test(node));
+    for node in tests {
+        writeln!(
+            out,
+            "  {}\t{}:{}",
+            node.name, node.file_path, node.line_range.0
+        )?;
+    }
+
+    info!(
+        "{} changed symbols impact {} others",
+        changed.len(),
+        impacted.len()
+    );
+
+    Ok(())
+}
+
+fn run_subgraph(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    let flag_value = |name: &str| flags.iter().find_map(|flag| flag.strip_prefix(name));
+    let filter = if let Some(prefix) = flag_value("--path=") {
+        Some(SubgraphFilter::PathPrefix(prefix.to_string()))
+    } else if let Some(ids) = flag_value("--ids=") {
+        Some(SubgraphFilter::NodeIds(
+            ids.split(',').map(str::to_string).collect(),
+        ))
+    } else if let Some(names) = flag_value("--types=") {
+        let mut node_types = Vec::new();
+        for name in names.split(',') {
+            match NodeType::parse(name) {
+                Some(node_type) => node_types.push(node_type),
+                None => warn!("Ignoring unknown node type: {}", name),
+            }
+        }
+        Some(SubgraphFilter::NodeTypes(node_types))
+    } else {
+        flag_value("--seed=").map(|seed| SubgraphFilter::Neighborhood {
+            seed: seed.to_string(),
+            depth: flag_value("--depth=")
+                .and_then(|depth| depth.parse().ok())
+                .unwrap_or(DEFAULT_SUBGRAPH_DEPTH),
+        })
+    };
+
+    let (Some(filter), true) = (filter, args.len() >= 4) else {
+        eprintln!(
+            "Usage: {} subgraph <graph_path> <output_path> --path=<prefix>|--ids=<id,...>|--types=<type,...>|--seed=<id> [--depth=N] [--format=json|html|d2|msgpack|parquet] [--compress=gzip|zstd]",
+            args[0]
+        );
+        return Ok(());
+    };
+
+    let graph = import_graph(Path::new(&args[2]))?;
+    let subgraph = graph.subgraph(&filter);
+    let output_path = Path::new(&args[3]);
+    let format = match flag_value("--format=") {
+        Some(name) => ExportFormat::parse(name).unwrap_or_else(|| {
+            warn!("Unsupported format: {}. Using JSON instead.", name);
+            ExportFormat::Json
+        }),
+        None => ExportFormat::Json,
+    };
+    export_graph_compressed(&subgraph, output_path, format, compression_flag(flags))?;
+
+    info!(
+        "Subgraph with {} nodes and {} relationships saved to: {:?}",
+        subgraph.node_count(),
+        subgraph.relationship_count(),
+        output_path
+    );
+
+    Ok(())
+}
+
+fn run_aggregate(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    let flag_value = |name: &str| flags.iter().find_map(|flag| flag.strip_prefix(name));
+    let aggregation = flag_value("--by=").and_then(Aggregation::parse);
+
+    let (Some(aggregation), true) = (aggregation, args.len() >= 4) else {
+        eprintln!(
+            "Usage: {} aggregate <graph_path> <output_path> --by=file|directory|class [--format=json|html|d2|msgpack|parquet] [--compress=gzip|zstd]",
+            args[0]
+        );
+        return Ok(());
+    };
+
+    let graph = import_graph(Path::new(&args[2]))?;
+    let aggregated = graph.aggregate(aggregation);
+    let output_path = Path::new(&args[3]);
+    let format = match flag_value("--format=") {
+        Some(name) => ExportFormat::parse(name).unwrap_or_else(|| {
+            warn!("Unsupported format: {}. Using JSON instead.", name);
+            ExportFormat::Json
+        }),
+        None => ExportFormat::Json,
+    };
+    export_graph_compressed(&aggregated, output_path, format, compression_flag(flags))?;
+
+    info!(
+        "Aggregated graph with {} nodes and {} relationships saved to: {:?}",
+        aggregated.node_count(),
+        aggregated.relationship_count(),
+        output_path
+    );
+
+    Ok(())
+}
+
+fn compression_flag(flags: &[String]) -> Compression {
+    match flags
+        .iter()
+        .find_map(|flag| flag.strip_prefix("--compress="))
+    {
+        Some(name) => Compression::parse(name).unwrap_or_else(|| {
+            warn!(
+                "Unsupported compression: {}. Writing uncompressed output.",
+                name
+            );
+            Compression::None
+        }),
+        None => Compression::None,
+    }
+}
+
+fn run_languages() -> std::io::Result<()> {
+    let mut out = std::io::stdout().lock();
+    let json =
+        serde_json::to_string_pretty(&language_capabilities()).map_err(std::io::Error::other)?;
+    writeln!(out, "{}", json)?;
+    Ok(())
+}
+
+// Language server over stdin and stdout; without a codebase the client's
+// workspace root is indexed on initialize
+fn run_lsp(args: &[String]) -> std::io::Result<()> {
+    let config = IndexConfig::new();
+    let (graph, root) = match args.get(2) {
+        Some(path) => {
+            let root = Path::new(path).canonicalize()?;
+            let graph = relik_codegraph::process_codebase_with_config(&root, &config)?;
+            (graph, Some(root))
+        }
+        None => (CodeGraph::new(), None),
+    };
+
+    LspServer::new(graph, root, config).run_stdio()
+}
+
+// The password is read from NEO4J_PASSWORD so it stays out of the process
+// list; the user defaults to NEO4J_USER, then `neo4j`
+#[cfg(feature = "neo4j")]
+fn run_neo4j(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    use relik_codegraph::utils::neo4j::{Neo4jOptions, export_graph_to_neo4j};
+
+    if args.len() < 4 {
+        eprintln!(
+            "Usage: {} neo4j <graph_path> <bolt_uri> [--user=<name>] [--database=<name>] [--batch-size=N]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let graph = import_graph(Path::new(&args[2]))?;
+    let flag_value = |name: &str| flags.iter().find_map(|flag| flag.strip_prefix(name));
+    let user = flag_value("--user=")
+        .map(str::to_string)
+        .or_else(|| std::env::var("NEO4J_USER").ok())
+        .unwrap_or_else(|| "neo4j".to_string());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_default();
+
+    let mut options = Neo4jOptions::new(&args[3], &user, &password);
+    options.database = flag_value("--database=").map(str::to_string);
+    if let Some(size) = flag_value("--batch-size=") {
+        match size.parse::<usize>() {
+            Ok(size) if size > 0 => options.batch_size = size,
+            _ => warn!("Invalid batch size: {}, using {}", size, options.batch_size),
+        }
+    }
+
+    export_graph_to_neo4j(&graph, &options)
+}
+
+// Without a connection URL, DATABASE_URL is used
+#[cfg(feature = "postgres")]
+fn run_postgres(args: &[String], flags: &[String]) -> std::io::Result<()> {
+    use relik_codegraph::utils::postgres::{PostgresOptions, export_graph_to_postgres};
+
+    let url = args
+        .get(3)
+        .cloned()
+        .or_else(|| std::env::var("DATABASE_URL").ok());
+    let (true, Some(url)) = (args.len() >= 3, url) else {
+        eprintln!(
+            "Usage: {} postgres <graph_path> [connection_url] [--schema=<name>] [--batch-size=N] [--embed]",
+            args[0]
+        );
+        return Ok(());
+    };
+
+    let mut graph = import_graph(Path::new(&args[2]))?;
+    if flags.iter().any(|flag| flag == "--embed") {
+        graph.add_embeddings(&HashingEmbedder::default())?;
+    }
+
+    let flag_value = |name: &str| flags.iter().find_map(|flag| flag.strip_prefix(name));
+    let mut options = PostgresOptions::new(&url);
+    if let Some(schema) = flag_value("--schema=") {
+        options.schema = schema.to_string();
+    }
+    if let Some(size) = flag_value("--batch-size=") {
+        match size.parse::<usize>() {
+            Ok(size) if size > 0 => options.batch_size = size,
+            _ => warn!("Invalid batch size: {}, using {}", size, options.batch_size),
+        }
+    }
+
+    export_graph_to_postgres(&graph, &options)
+}
+
+#[cfg(feature = "server")]
+fn run_serve(args: &[String]) -> std::io::Result<()> {
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} serve <graph_path|codebase_path> [address]",
+            args[0]
+        );
+        return Ok(());
+    }
+
+    let input_path = Path::new(&args[2]);
+    let address = args.get(3).map_or(DEFAULT_SERVE_ADDRESS, |a| a.as_str());
+    let address = address.parse().map_err(|e| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid address {}: {}", address, e),
+        )
+    })?;
+
+    // A directory is indexed on startup; anything else is a saved graph
+    let graph = if input_path.is_dir() {
+        relik_codegraph::process_codebase_with_config(input_path, &IndexConfig::new())?
+    } else {
+        import_graph(input_path)?
+    };
+
+    relik_codegraph::server::serve(graph, address)
+}
@@ -10,6 +10,7 @@ pub fn detect_language(file_path: &Path) -> Option<String> {
 pub fn get_supported_extensions() -> Vec<&'static str> {
     vec![
         "py", "js", "ts", "jsx", "tsx", "java", "c", "cpp", "cc", "cxx", "hpp", "h", "rs", "go",
-        "rb", "php", "swift", "cs", "kt", "kts",
+        "rb", "php", "swift", "cs", "kt", "kts", "sh", "bash", "ml", "r", "R", "pl", "pm", "jl",
+        "erl", "hrl", "groovy", "gvy", "gradle", "graphql", "gql", "proto", "md", "markdown",
     ]
 }
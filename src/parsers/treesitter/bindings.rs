@@ -43,3 +43,27 @@ pub fn ruby_language() -> Language {
 pub fn php_language() -> Language {
     tree_sitter_php::LANGUAGE_PHP_ONLY.into()
 }
+
+pub fn bash_language() -> Language {
+    tree_sitter_bash::LANGUAGE.into()
+}
+
+pub fn ocaml_language() -> Language {
+    tree_sitter_ocaml::LANGUAGE_OCAML.into()
+}
+
+pub fn r_language() -> Language {
+    tree_sitter_r::LANGUAGE.into()
+}
+
+pub fn julia_language() -> Language {
+    tree_sitter_julia::LANGUAGE.into()
+}
+
+pub fn erlang_language() -> Language {
+    tree_sitter_erlang::LANGUAGE.into()
+}
+
+pub fn groovy_language() -> Language {
+    tree_sitter_groovy::LANGUAGE.into()
+}
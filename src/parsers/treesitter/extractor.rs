@@ -94,9 +94,9 @@ impl NodeExtractor {
 
         for node in code_units.iter_mut() {
             if let NodeType::Method = node.node_type {
-                if let Some(parent_class_name) = node.metadata.get("parent_class").cloned() {
-                    if let Some(class_id) = class_id_map.get(&parent_class_name) {
-                        node.add_metadata("parent_class_id".to_string(), class_id.clone());
+                if let Some(parent_class_name) = &node.parent_class {
+                    if let Some(class_id) = class_id_map.get(parent_class_name) {
+                        node.parent_class_id = Some(class_id.clone());
                     }
                 }
             }
@@ -162,30 +162,6 @@ impl NodeExtractor {
                                         actual_node_type, name, start_line, end_line
                                     );
 
-                                    let mut metadata = std::collections::HashMap::new();
-
-                                    if actual_node_type == NodeType::Method {
-                                        if let Some(parent_class) =
-                                            self.find_parent_class(captured_node)
-                                        {
-                                            metadata
-                                                .insert("parent_class".to_string(), parent_class);
-                                        }
-                                    }
-
-                                    if matches!(actual_node_type, NodeType::Variable) {
-                                        if let Some(var_type) =
-                                            self.find_variable_type(captured_node)
-                                        {
-                                            metadata.insert("type".to_string(), var_type);
-                                        }
-
-                                        if let Some(scope) = self.find_variable_scope(captured_node)
-                                        {
-                                            metadata.insert("scope".to_string(), scope);
-                                        }
-                                    }
-
                                     let mut code_node = CodeNode::new(
                                         Uuid::new_v4().to_string(),
                                         actual_node_type,
@@ -195,8 +171,15 @@ impl NodeExtractor {
                                         node_content,
                                     );
 
-                                    for (key, value) in metadata {
-                                        code_node.add_metadata(key, value);
+                                    if code_node.node_type == NodeType::Method {
+                                        code_node.parent_class =
+                                            self.find_parent_class(captured_node);
+                                    }
+
+                                    if code_node.node_type == NodeType::Variable {
+                                        code_node.declared_type =
+                                            self.find_variable_type(captured_node);
+                                        code_node.scope = self.find_variable_scope(captured_node);
                                     }
 
                                     code_units.push(code_node);
@@ -528,9 +511,7 @@ impl NodeExtractor {
                 );
 
                 if actual_node_type == NodeType::Method {
-                    if let Some(parent_class) = self.find_parent_class(node) {
-                        code_node.add_metadata("parent_class".to_string(), parent_class);
-                    }
+                    code_node.parent_class = self.find_parent_class(node);
                 }
 
                 trace!(
@@ -557,14 +538,11 @@ impl NodeExtractor {
     }
 
     pub fn get_node_text(&self, node: Node) -> String {
-        let start_byte = node.start_byte();
-        let end_byte = node.end_byte();
-
-        if start_byte >= self.source_code.len() || end_byte > self.source_code.len() {
-            return String::new();
-        }
-
-        self.source_code[start_byte..end_byte].to_string()
+        self.source_code
+            .as_bytes()
+            .get(node.start_byte()..node.end_byte())
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+            .unwrap_or_default()
     }
 
     pub fn extract_function_calls(&self, target_name: &str) -> Vec<(usize, usize)> {
@@ -17,6 +17,12 @@ pub fn get_language_parsers() -> HashMap<String, Language> {
     parsers.insert("go".to_string(), bindings::go_language());
     parsers.insert("ruby".to_string(), bindings::ruby_language());
     parsers.insert("php".to_string(), bindings::php_language());
+    parsers.insert("bash".to_string(), bindings::bash_language());
+    parsers.insert("ocaml".to_string(), bindings::ocaml_language());
+    parsers.insert("r".to_string(), bindings::r_language());
+    parsers.insert("julia".to_string(), bindings::julia_language());
+    parsers.insert("erlang".to_string(), bindings::erlang_language());
+    parsers.insert("groovy".to_string(), bindings::groovy_language());
 
     parsers
 }
@@ -37,6 +43,16 @@ pub fn detect_language_from_extension(extension: &str) -> Option<String> {
         "go" => Some("go".to_string()),
         "rb" => Some("ruby".to_string()),
         "php" => Some("php".to_string()),
+        "sh" | "bash" => Some("bash".to_string()),
+        "ml" => Some("ocaml".to_string()),
+        "r" | "R" => Some("r".to_string()),
+        "pl" | "pm" => Some("perl".to_string()),
+        "jl" => Some("julia".to_string()),
+        "erl" | "hrl" => Some("erlang".to_string()),
+        "groovy" | "gvy" | "gradle" => Some("groovy".to_string()),
+        "graphql" | "gql" => Some("graphql".to_string()),
+        "proto" => Some("proto".to_string()),
+        "md" | "markdown" => Some("markdown".to_string()),
         _ => None,
     }
 }
@@ -44,6 +60,7 @@ pub fn detect_language_from_extension(extension: &str) -> Option<String> {
 pub fn get_supported_extensions() -> Vec<&'static str> {
     vec![
         "rs", "py", "js", "jsx", "ts", "tsx", "java", "c", "cpp", "cc", "cxx", "hpp", "h", "go",
-        "rb", "php",
+        "rb", "php", "sh", "bash", "ml", "r", "R", "pl", "pm", "jl", "erl", "hrl", "groovy", "gvy",
+        "gradle", "graphql", "gql", "proto", "md", "markdown",
     ]
 }
@@ -0,0 +1,24 @@
+pub const FUNCTION_QUERY: &str = "(function_definition name: (word)) @node";
+
+pub const VARIABLE_QUERY: &str = "(variable_assignment name: (variable_name) @node)";
+
+pub const GLOBAL_VARIABLE_QUERY: &str = "
+    (program (variable_assignment name: (variable_name) @name) @node)
+    (program (declaration_command (variable_assignment name: (variable_name) @name) @node))
+";
+
+// Every command invocation; only names of script functions resolve to calls
+pub const CALL_QUERY: &str = "(command name: (command_name) @func_name)";
+
+pub const REFERENCE_QUERY: &str = "
+    (variable_name) @reference
+    (command_name) @reference
+";
+
+// `source lib.sh` and `. lib.sh`: a command and its first argument
+pub const IMPORT_QUERY: &str = "
+    (command
+        name: (command_name) @command
+        .
+        argument: (_) @import_path)
+";
@@ -1,11 +1,22 @@
 pub const FUNCTION_QUERY: &str = "(function_definition) @node";
 
+// Function prototypes such as `int add(int a, int b);` or `char *name(void);`
+pub const PROTOTYPE_QUERY: &str = "
+    (declaration declarator: (function_declarator declarator: (identifier))) @node
+    (declaration declarator: (pointer_declarator declarator: (function_declarator
+        declarator: (identifier)))) @node
+";
+
 pub const CLASS_QUERY: &str = "[(class_specifier) (struct_specifier)] @node";
 
 pub const VARIABLE_QUERY: &str = "
     (declaration) @node
 ";
 
+pub const GLOBAL_VARIABLE_QUERY: &str = "
+    (translation_unit (declaration declarator: (init_declarator declarator: (identifier) @name)) @node)
+";
+
 pub const CALL_QUERY: &str = "
     (call_expression function: [
         (identifier) @func_name
@@ -16,6 +27,7 @@ pub const CALL_QUERY: &str = "
 pub const REFERENCE_QUERY: &str = "
     (identifier) @reference
     (field_identifier) @reference
+    (type_identifier) @reference
 ";
 
 pub const IMPORT_QUERY: &str = "
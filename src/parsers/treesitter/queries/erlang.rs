@@ -0,0 +1,31 @@
+pub const MODULE_QUERY: &str = "(module_attribute name: (atom) @name) @node";
+
+pub const FUNCTION_QUERY: &str = "(fun_decl) @node";
+
+pub const EXPORT_QUERY: &str = "(export_attribute funs: (fa) @export)";
+
+pub const TYPE_QUERY: &str = "
+    (record_decl name: (atom) @name) @node
+    (type_alias name: (type_name name: (atom) @name)) @node
+    (opaque name: (type_name name: (atom) @name)) @node
+";
+
+// `f(...)` and `m:f(...)` calls and `fun f/1` and `fun m:f/1` references
+pub const CALL_QUERY: &str = "
+    (call expr: (atom) @func_name)
+    (internal_fun fun: (atom) @func_name)
+    (external_fun fun: (atom) @func_name)
+";
+
+// Modules a file depends on: remote calls, `-import`, `-behaviour`,
+// `-include` and `-include_lib`
+pub const IMPORT_QUERY: &str = "
+    (remote_module module: (atom) @module)
+    (external_fun module: (module name: (atom) @module))
+    (import_attribute module: (atom) @module)
+    (behaviour_attribute name: (atom) @module)
+    (pp_include file: (string) @include)
+    (pp_include_lib file: (string) @include)
+";
+
+pub const IMPORTED_FUNCTION_QUERY: &str = "(import_attribute) @node";
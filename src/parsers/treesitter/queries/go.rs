@@ -4,6 +4,18 @@ pub const METHOD_QUERY: &str = "(method_declaration) @node";
 
 pub const CLASS_QUERY: &str = "(type_spec type: (struct_type)) @node";
 
+pub const INTERFACE_QUERY: &str = "(type_spec type: (interface_type)) @node";
+
+pub const INTERFACE_METHOD_QUERY: &str = "
+    (type_spec
+        name: (type_identifier) @interface
+        type: (interface_type (method_elem name: (field_identifier) @name) @node))
+";
+
+pub const FIELD_QUERY: &str = "
+    (field_declaration name: (field_identifier) @name type: (_) @type) @node
+";
+
 pub const VARIABLE_QUERY: &str = "
     (var_declaration) @node
     (const_declaration) @node
@@ -11,6 +23,12 @@ pub const VARIABLE_QUERY: &str = "
     (const_spec) @node
 ";
 
+pub const GLOBAL_VARIABLE_QUERY: &str = "
+    (source_file (const_declaration (const_spec name: (identifier) @name) @node))
+    (source_file (var_declaration (var_spec name: (identifier) @name) @node))
+    (source_file (var_declaration (var_spec_list (var_spec name: (identifier) @name) @node)))
+";
+
 pub const CALL_QUERY: &str = "
     (call_expression function: [
         (identifier) @func_name
@@ -21,6 +39,7 @@ pub const CALL_QUERY: &str = "
 pub const REFERENCE_QUERY: &str = "
     (identifier) @reference
     (field_identifier) @reference
+    (type_identifier) @reference
 ";
 
 pub const IMPORT_QUERY: &str = "
@@ -0,0 +1,23 @@
+pub const CLASS_QUERY: &str = "[(class_declaration) (interface_declaration)] @node";
+
+pub const METHOD_QUERY: &str = "
+    (method_declaration) @node
+    (constructor_declaration) @node
+";
+
+// Script-level `def name(...) { ... }`
+pub const FUNCTION_QUERY: &str = "(function_definition) @node";
+
+// `f(x)`, `obj.f(x)` and command-style `f x`
+pub const CALL_QUERY: &str = "
+    (method_invocation name: (identifier) @func_name)
+    (juxt_function_call name: (identifier) @func_name)
+";
+
+pub const REFERENCE_QUERY: &str = "
+    (identifier) @reference
+    (field_access field: (identifier) @reference)
+    (type_identifier) @reference
+";
+
+pub const IMPORT_QUERY: &str = "(import_declaration (_) @import_path)";
@@ -1,6 +1,17 @@
 pub const METHOD_QUERY: &str = "(method_declaration) @node";
 
-pub const CLASS_QUERY: &str = "[(class_declaration) (interface_declaration)] @node";
+pub const CLASS_QUERY: &str = "
+    [(class_declaration) (interface_declaration) (annotation_type_declaration)] @node
+";
+
+pub const FIELD_QUERY: &str = "
+    (field_declaration
+        type: (_) @type
+        declarator: (variable_declarator name: (identifier) @name)) @node
+    (constant_declaration
+        type: (_) @type
+        declarator: (variable_declarator name: (identifier) @name)) @node
+";
 
 pub const VARIABLE_QUERY: &str = "
     (variable_declarator) @node
@@ -9,14 +20,20 @@ pub const VARIABLE_QUERY: &str = "
 
 pub const CALL_QUERY: &str = "
     (method_invocation name: (identifier) @func_name)
-    (method_reference name: (identifier) @func_name)
+    (method_reference (identifier) @func_name .)
 ";
 
 pub const REFERENCE_QUERY: &str = "
     (identifier) @reference
     (field_access field: (identifier) @reference)
+    (type_identifier) @reference
 ";
 
 pub const IMPORT_QUERY: &str = "
     (import_declaration name: (_) @import_path)
 ";
+
+pub const SOURCE_HEADER_QUERY: &str = "
+    (package_declaration) @statement
+    (import_declaration) @statement
+";
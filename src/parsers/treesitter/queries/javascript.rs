@@ -1,6 +1,6 @@
 pub const FUNCTION_QUERY: &str = "
     (function_declaration) @node
-    (function) @node
+    (function_expression) @node
     (arrow_function) @node
 ";
 
@@ -8,12 +8,28 @@ pub const METHOD_QUERY: &str = "(method_definition) @node";
 
 pub const CLASS_QUERY: &str = "(class_declaration) @node";
 
+pub const FIELD_QUERY: &str = "(field_definition property: (_) @name) @node";
+
 pub const VARIABLE_QUERY: &str = "
     (variable_declarator) @node
     (lexical_declaration) @node
     (variable_declaration) @node
 ";
 
+pub const GLOBAL_VARIABLE_QUERY: &str = "
+    (program (lexical_declaration (variable_declarator name: (identifier) @name) @node))
+    (program (variable_declaration (variable_declarator name: (identifier) @name) @node))
+    (program (export_statement declaration: (lexical_declaration
+        (variable_declarator name: (identifier) @name) @node)))
+";
+
+// Jest/Mocha style `it("...", fn)` and `test("...", fn)` blocks
+pub const TEST_QUERY: &str = "
+    (call_expression
+        function: (identifier) @callee
+        arguments: (arguments . (string) @name)) @node
+";
+
 pub const CALL_QUERY: &str = "
     (call_expression
         function: [
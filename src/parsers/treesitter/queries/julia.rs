@@ -0,0 +1,41 @@
+pub const FUNCTION_QUERY: &str = "(function_definition (signature) @signature) @node";
+
+// `f(x) = ...` and `f(x)::T = ...` short-form definitions
+pub const SHORT_FUNCTION_QUERY: &str = "
+    (assignment . (call_expression) @signature) @node
+    (assignment . (typed_expression . (call_expression) @signature)) @node
+    (assignment . (where_expression . (call_expression) @signature)) @node
+";
+
+pub const MACRO_QUERY: &str = "(macro_definition (signature) @signature) @node";
+
+pub const STRUCT_QUERY: &str = "(struct_definition (type_head) @head) @node";
+
+pub const ABSTRACT_TYPE_QUERY: &str = "(abstract_definition (type_head) @head) @node";
+
+pub const MODULE_QUERY: &str = "(module_definition name: (identifier) @name) @node";
+
+pub const GLOBAL_VARIABLE_QUERY: &str = "
+    (source_file (assignment . (identifier) @name) @node)
+    (module_definition (assignment . (identifier) @name) @node)
+    (source_file (const_statement (assignment . (identifier) @name) @node))
+    (module_definition (const_statement (assignment . (identifier) @name) @node))
+";
+
+// `f(x)`, `Module.f(x)` and `@macro x`
+pub const CALL_QUERY: &str = "
+    (call_expression . (identifier) @func_name)
+    (call_expression . (field_expression (identifier) @func_name .))
+    (macrocall_expression (macro_identifier (identifier) @func_name))
+";
+
+pub const REFERENCE_QUERY: &str = "(identifier) @reference";
+
+pub const IMPORT_QUERY: &str = "
+    (using_statement) @import
+    (import_statement) @import
+    (call_expression
+        . (identifier) @function
+        (argument_list . (string_literal) @import_path)
+        (#eq? @function \"include\"))
+";
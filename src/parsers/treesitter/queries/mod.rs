@@ -1,8 +1,14 @@
+pub mod bash;
 pub mod cpp;
+pub mod erlang;
 pub mod go;
+pub mod groovy;
 pub mod java;
 pub mod javascript;
+pub mod julia;
+pub mod ocaml;
 pub mod python;
+pub mod r;
 pub mod ruby;
 pub mod rust;
 pub mod typescript;
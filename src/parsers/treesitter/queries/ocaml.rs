@@ -0,0 +1,21 @@
+// Every `let` binding of a name; functions and values are told apart by the
+// extractor from the parameters and the bound expression
+pub const LET_BINDING_QUERY: &str = "(let_binding pattern: (value_name) @name) @node";
+
+pub const MODULE_QUERY: &str = "(module_binding (module_name) @name) @node";
+
+pub const MODULE_TYPE_QUERY: &str = "(module_type_definition (module_type_name) @name) @node";
+
+pub const TYPE_QUERY: &str = "(type_binding name: (type_constructor) @name) @node";
+
+pub const CALL_QUERY: &str = "
+    (application_expression function: (value_path (value_name) @func_name))
+";
+
+pub const REFERENCE_QUERY: &str = "(value_path (value_name) @reference)";
+
+// `open M`, `include M`, functor arguments and qualified names like `M.f`
+pub const IMPORT_QUERY: &str = "
+    (module_path) @import_path
+    (extended_module_path) @import_path
+";
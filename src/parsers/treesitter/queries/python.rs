@@ -2,11 +2,37 @@ pub const FUNCTION_QUERY: &str = "(function_definition) @node";
 
 pub const CLASS_QUERY: &str = "(class_definition) @node";
 
+// Class attributes, and the attributes `__init__` assigns on `self`
+pub const FIELD_QUERY: &str = r#"
+    (class_definition body: (block (expression_statement
+        (assignment left: (identifier) @name type: (type) @type) @node)))
+    (class_definition body: (block (expression_statement
+        (assignment left: (identifier) @name !type) @node)))
+    (function_definition
+        name: (identifier) @init
+        body: (block (expression_statement (assignment
+            left: (attribute object: (identifier) @self attribute: (identifier) @name)
+            type: (type) @type) @node))
+        (#eq? @init "__init__")
+        (#eq? @self "self"))
+    (function_definition
+        name: (identifier) @init
+        body: (block (expression_statement (assignment
+            left: (attribute object: (identifier) @self attribute: (identifier) @name)
+            !type) @node))
+        (#eq? @init "__init__")
+        (#eq? @self "self"))
+"#;
+
 pub const VARIABLE_QUERY: &str = "
     (assignment) @node
     (global_statement) @node
 ";
 
+pub const GLOBAL_VARIABLE_QUERY: &str = "
+    (module (expression_statement (assignment left: (identifier) @name) @node))
+";
+
 pub const CALL_QUERY: &str = "
     (call function: [
         (identifier) @func_name
@@ -23,3 +49,8 @@ pub const IMPORT_QUERY: &str = "
     (import_statement name: (_) @import_path)
     (import_from_statement module_name: (_) @import_path)
 ";
+
+pub const IMPORT_STATEMENT_QUERY: &str = "
+    (import_statement) @import
+    (import_from_statement) @import
+";
@@ -0,0 +1,38 @@
+pub const FUNCTION_QUERY: &str = "
+    (binary_operator
+        lhs: [(identifier) (string)] @name
+        operator: [\"<-\" \"<<-\" \"=\"]
+        rhs: (function_definition)) @node
+";
+
+pub const GLOBAL_VARIABLE_QUERY: &str = "
+    (program
+        (binary_operator
+            lhs: (identifier) @name
+            operator: [\"<-\" \"<<-\" \"=\"]) @node)
+";
+
+// R6, S4 and reference class definitions; S4 methods and generics are
+// registered with calls as well
+pub const CLASS_QUERY: &str = "
+    (call
+        function: (identifier) @constructor
+        (#any-of? @constructor \"R6Class\" \"setClass\" \"setRefClass\" \"setGeneric\" \"setMethod\")) @node
+";
+
+// Plain calls, `pkg::f()` and `self$f()`
+pub const CALL_QUERY: &str = "
+    (call function: (identifier) @func_name)
+    (call function: (namespace_operator rhs: (identifier) @func_name))
+    (call function: (extract_operator rhs: (identifier) @func_name))
+";
+
+pub const REFERENCE_QUERY: &str = "(identifier) @reference";
+
+// `library(pkg)`, `require(\"pkg\")`, `source(\"file.R\")` and `pkg::f`
+pub const IMPORT_QUERY: &str = "
+    (call
+        function: (identifier) @function
+        arguments: (arguments . (argument value: [(identifier) (string)] @import_path)))
+    (namespace_operator lhs: (identifier) @import_path)
+";
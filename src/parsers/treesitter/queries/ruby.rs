@@ -9,6 +9,10 @@ pub const VARIABLE_QUERY: &str = "
     (constant) @node
 ";
 
+pub const GLOBAL_VARIABLE_QUERY: &str = "
+    (program (assignment left: (constant) @name) @node)
+";
+
 pub const CALL_QUERY: &str = "
     (call method: (identifier) @func_name)
     (method_call method: (identifier) @func_name)
@@ -1,6 +1,29 @@
-pub const FUNCTION_QUERY: &str = "(function_item) @node";
+pub const FUNCTION_QUERY: &str = "
+    (function_item) @node
+    (function_signature_item) @node
+";
+
+pub const CLASS_QUERY: &str = "
+    (struct_item) @node
+    (enum_item) @node
+";
 
-pub const CLASS_QUERY: &str = "(struct_item) @node";
+pub const TRAIT_QUERY: &str = "(trait_item) @node";
+
+pub const FIELD_QUERY: &str = "
+    (field_declaration name: (field_identifier) @name type: (_) @type) @node
+";
+
+pub const MACRO_QUERY: &str = "(macro_definition) @node";
+
+pub const MACRO_INVOCATION_QUERY: &str = "
+    (macro_invocation
+        macro: [
+            (identifier) @name
+            (scoped_identifier name: (identifier) @name)
+        ]
+    )
+";
 
 pub const VARIABLE_QUERY: &str = "
     (let_declaration) @node
@@ -8,6 +31,11 @@ pub const VARIABLE_QUERY: &str = "
     (static_item) @node
 ";
 
+pub const GLOBAL_VARIABLE_QUERY: &str = "
+    (const_item name: (identifier) @name) @node
+    (static_item name: (identifier) @name) @node
+";
+
 pub const CALL_QUERY: &str = "
     (call_expression
         function: [
@@ -22,9 +50,12 @@ pub const REFERENCE_QUERY: &str = "
     (identifier) @reference
     (field_expression field: (field_identifier) @reference)
     (scoped_identifier name: (identifier) @reference)
+    (type_identifier) @reference
 ";
 
 pub const IMPORT_QUERY: &str = r#"
     (use_declaration
         path: (_) @import_path)
 "#;
+
+pub const USE_QUERY: &str = "(use_declaration) @use";
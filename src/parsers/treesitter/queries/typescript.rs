@@ -1,10 +1,14 @@
 pub const FUNCTION_QUERY: &str = "
     (function_declaration) @node
-    (function) @node
+    (function_expression) @node
     (arrow_function) @node
 ";
 
-pub const METHOD_QUERY: &str = "(method_definition) @node";
+pub const METHOD_QUERY: &str = "
+    (method_definition) @node
+    (method_signature) @node
+    (abstract_method_signature) @node
+";
 
 pub const CLASS_QUERY: &str = "(class_declaration) @node";
 
@@ -12,12 +16,34 @@ pub const INTERFACE_QUERY: &str = "(interface_declaration) @node";
 
 pub const TYPE_QUERY: &str = "(type_alias_declaration) @node";
 
+// Class properties and the properties of interfaces
+pub const FIELD_QUERY: &str = "
+    (public_field_definition name: (_) @name type: (type_annotation (_) @type)) @node
+    (public_field_definition name: (_) @name !type) @node
+    (property_signature name: (_) @name type: (type_annotation (_) @type)) @node
+    (property_signature name: (_) @name !type) @node
+";
+
 pub const VARIABLE_QUERY: &str = "
     (variable_declarator) @node
     (lexical_declaration) @node
     (variable_declaration) @node
 ";
 
+pub const GLOBAL_VARIABLE_QUERY: &str = "
+    (program (lexical_declaration (variable_declarator name: (identifier) @name) @node))
+    (program (variable_declaration (variable_declarator name: (identifier) @name) @node))
+    (program (export_statement declaration: (lexical_declaration
+        (variable_declarator name: (identifier) @name) @node)))
+";
+
+// Jest/Mocha style `it("...", fn)` and `test("...", fn)` blocks
+pub const TEST_QUERY: &str = "
+    (call_expression
+        function: (identifier) @callee
+        arguments: (arguments . (string) @name)) @node
+";
+
 pub const CALL_QUERY: &str = "
     (call_expression
         function: [
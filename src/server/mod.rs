@@ -0,0 +1,242 @@
+use crate::code_graph::{CodeGraph, CodeNode, Relationship, RelationshipType};
+use crate::indexing::{LanguageCapabilities, language_capabilities};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+// Upper bound on results returned by list endpoints
+const DEFAULT_LIMIT: usize = 100;
+// Hops searched by /paths when no max_depth is given
+const DEFAULT_PATH_DEPTH: usize = 5;
+// Tokens of context returned by /nodes/{id}/context when no budget is given
+const DEFAULT_CONTEXT_BUDGET: usize = 2000;
+
+type SharedGraph = Arc<CodeGraph>;
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+#[derive(Deserialize)]
+struct SearchParams {
+    name: String,
+    node_type: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct NeighborhoodParams {
+    depth: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ContextParams {
+    budget: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct PathParams {
+    from: String,
+    to: String,
+    max_depth: Option<usize>,
+    relationship: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct FileParams {
+    path: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FileEntry<'a> {
+    path: &'a str,
+    node_count: usize,
+}
+
+// Serve the graph over HTTP until the process is stopped
+pub fn serve(graph: CodeGraph, addr: SocketAddr) -> io::Result<()> {
+    let mut graph = graph;
+    graph.compact();
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+
+    runtime.block_on(async move {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        info!(
+            "Serving graph with {} nodes on http://{}",
+            graph.node_count(),
+            addr
+        );
+        axum::serve(listener, router(graph)).await
+    })
+}
+
+pub fn router(graph: CodeGraph) -> Router {
+    Router::new()
+        .route("/health", get(|| async { "ok" }))
+        .route("/nodes/{id}", get(get_node))
+        .route("/nodes/{id}/callers", get(get_callers))
+        .route("/nodes/{id}/callees", get(get_callees))
+        .route("/nodes/{id}/neighborhood", get(get_neighborhood))
+        .route("/nodes/{id}/context", get(get_context))
+        .route("/paths", get(find_paths))
+        .route("/search", get(search))
+        .route("/files", get(list_files))
+        .route("/languages", get(list_languages))
+        .with_state(Arc::new(graph))
+}
+
+fn not_found(id: &str) -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, format!("Node not found: {}", id))
+}
+
+async fn get_node(State(graph): State<SharedGraph>, Path(id): Path<String>) -> ApiResult<CodeNode> {
+    graph
+        .get_node(&id)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| not_found(&id))
+}
+
+async fn get_callers(
+    State(graph): State<SharedGraph>,
+    Path(id): Path<String>,
+) -> ApiResult<Vec<CodeNode>> {
+    graph.get_node(&id).ok_or_else(|| not_found(&id))?;
+    Ok(Json(graph.find_callers(&id).into_iter().cloned().collect()))
+}
+
+async fn get_callees(
+    State(graph): State<SharedGraph>,
+    Path(id): Path<String>,
+) -> ApiResult<Vec<CodeNode>> {
+    graph.get_node(&id).ok_or_else(|| not_found(&id))?;
+    Ok(Json(
+        graph
+            .find_called_functions(&id)
+            .into_iter()
+            .cloned()
+            .collect(),
+    ))
+}
+
+async fn get_neighborhood(
+    State(graph): State<SharedGraph>,
+    Path(id): Path<String>,
+    Query(params): Query<NeighborhoodParams>,
+) -> ApiResult<Vec<CodeNode>> {
+    graph.get_node(&id).ok_or_else(|| not_found(&id))?;
+    let depth = params.depth.unwrap_or(1);
+    Ok(Json(
+        graph
+            .find_related_nodes(&id, depth)
+            .into_iter()
+            .cloned()
+            .collect(),
+    ))
+}
+
+// Prompt-ready text explaining a node, as plain text
+async fn get_context(
+    State(graph): State<SharedGraph>,
+    Path(id): Path<String>,
+    Query(params): Query<ContextParams>,
+) -> Result<String, (StatusCode, String)> {
+    graph
+        .context_for(&id, params.budget.unwrap_or(DEFAULT_CONTEXT_BUDGET))
+        .ok_or_else(|| not_found(&id))
+}
+
+async fn find_paths(
+    State(graph): State<SharedGraph>,
+    Query(params): Query<PathParams>,
+) -> ApiResult<Vec<Vec<Relationship>>> {
+    graph
+        .get_node(&params.from)
+        .ok_or_else(|| not_found(&params.from))?;
+    graph
+        .get_node(&params.to)
+        .ok_or_else(|| not_found(&params.to))?;
+
+    let rel_filter: Vec<RelationshipType> = match &params.relationship {
+        Some(name) => {
+            let relationship_type = RelationshipType::parse(name).ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Unknown relationship type: {}", name),
+                )
+            })?;
+            vec![relationship_type]
+        }
+        None => Vec::new(),
+    };
+
+    let mut paths = graph.find_paths(
+        &params.from,
+        &params.to,
+        params.max_depth.unwrap_or(DEFAULT_PATH_DEPTH),
+        &rel_filter,
+    );
+    paths.truncate(params.limit.unwrap_or(DEFAULT_LIMIT));
+
+    Ok(Json(
+        paths
+            .into_iter()
+            .map(|path| path.into_iter().cloned().collect())
+            .collect(),
+    ))
+}
+
+async fn search(
+    State(graph): State<SharedGraph>,
+    Query(params): Query<SearchParams>,
+) -> ApiResult<Vec<CodeNode>> {
+    let mut nodes: Vec<&CodeNode> = graph
+        .search_nodes_by_name(&params.name)
+        .into_iter()
+        .filter(|node| {
+            params
+                .node_type
+                .as_ref()
+                .is_none_or(|t| format!("{:?}", node.node_type).eq_ignore_ascii_case(t))
+        })
+        .collect();
+
+    nodes.sort_by(|a, b| (&a.name, &a.file_path).cmp(&(&b.name, &b.file_path)));
+    nodes.truncate(params.limit.unwrap_or(DEFAULT_LIMIT));
+
+    Ok(Json(nodes.into_iter().cloned().collect()))
+}
+
+async fn list_files(
+    State(graph): State<SharedGraph>,
+    Query(params): Query<FileParams>,
+) -> Json<serde_json::Value> {
+    // With a path, list the nodes in that file; otherwise list every file
+    if let Some(path) = params.path {
+        let mut nodes = graph.find_nodes_in_file(&path);
+        nodes.sort_by_key(|node| node.line_range);
+        return Json(serde_json::json!(nodes));
+    }
+
+    let mut files: Vec<FileEntry> = graph
+        .files()
+        .map(|path| FileEntry {
+            path,
+            node_count: graph.find_nodes_in_file(path).len(),
+        })
+        .collect();
+    files.sort_by_key(|entry| entry.path);
+
+    Json(serde_json::json!(files))
+}
+
+async fn list_languages() -> Json<Vec<LanguageCapabilities>> {
+    Json(language_capabilities())
+}
@@ -0,0 +1,150 @@
+use crate::code_graph::{CodeGraph, CodeNode, NodeType};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+
+// Directories and files of the graph, each drawn as a D2 container
+#[derive(Default)]
+struct Container<'a> {
+    children: BTreeMap<String, Container<'a>>,
+    // Nodes with their key inside the container
+    nodes: Vec<(String, &'a CodeNode)>,
+}
+
+fn shape(node_type: &NodeType) -> &'static str {
+    match node_type {
+        NodeType::Function | NodeType::Method | NodeType::Macro => "oval",
+        NodeType::Class => "rectangle",
+        NodeType::Interface => "hexagon",
+        NodeType::Module => "package",
+        NodeType::TypeDefinition => "parallelogram",
+        NodeType::Variable | NodeType::Field => "circle",
+        NodeType::Dependency => "cylinder",
+        NodeType::Documentation => "document",
+        NodeType::File => "page",
+        NodeType::Directory => "package",
+        NodeType::ExternalSymbol => "cloud",
+        NodeType::Endpoint => "step",
+        NodeType::Unknown => "rectangle",
+    }
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Longest directory shared by every file, so containers start below it
+fn common_directory(nodes: &[&CodeNode]) -> PathBuf {
+    let mut files = nodes
+        .iter()
+        .filter(|node| !node.file_path.is_empty())
+        .map(|node| Path::new(&node.file_path));
+    let Some(first) = files.next() else {
+        return PathBuf::new();
+    };
+    let mut common = first.parent().map(Path::to_path_buf).unwrap_or_default();
+    for file in files {
+        while !file.starts_with(&common) {
+            if !common.pop() {
+                break;
+            }
+        }
+    }
+    common
+}
+
+// Render the graph as a D2 diagram: every directory and file becomes a
+// container holding the nodes defined in it, and relationships become
+// labelled connections between them
+pub fn graph_to_d2(graph: &CodeGraph) -> String {
+    let mut nodes: Vec<&CodeNode> = graph.all_nodes().collect();
+    nodes.sort_by(|a, b| {
+        (&a.file_path, a.line_range, &a.id).cmp(&(&b.file_path, b.line_range, &b.id))
+    });
+    let common = common_directory(&nodes);
+
+    let mut root = Container::default();
+    let mut keys: HashMap<&str, String> = HashMap::new();
+    for (index, node) in nodes.iter().enumerate() {
+        let relative = Path::new(&node.file_path)
+            .strip_prefix(&common)
+            .unwrap_or(Path::new(&node.file_path));
+        let segments: Vec<String> = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+
+        let mut container = &mut root;
+        for segment in &segments {
+            container = container.children.entry(segment.clone()).or_default();
+        }
+        let key = format!("n{}", index);
+        container.nodes.push((key.clone(), node));
+
+        let path: Vec<String> = segments.iter().map(|segment| quote(segment)).collect();
+        let qualified = if path.is_empty() {
+            key
+        } else {
+            format!("{}.{}", path.join("."), key)
+        };
+        keys.insert(node.id.as_str(), qualified);
+    }
+
+    let mut d2 = String::new();
+    // D2 has no document metadata, so provenance goes in comments
+    if let Some(provenance) = graph.provenance() {
+        let _ = writeln!(
+            d2,
+            "# {} {}, indexed at {}",
+            provenance.tool, provenance.tool_version, provenance.indexed_at
+        );
+        for root in &provenance.roots {
+            let _ = writeln!(
+                d2,
+                "# root: {} ({})",
+                root.path,
+                root.git_commit.as_deref().unwrap_or("no git commit")
+            );
+        }
+    }
+    d2.push_str("direction: right\n");
+    write_container(&mut d2, &root, 0);
+
+    let mut relationships: Vec<_> = graph
+        .all_relationships()
+        .filter_map(|rel| {
+            let from = keys.get(rel.from_id.as_str())?;
+            let to = keys.get(rel.to_id.as_str())?;
+            Some((from, to, format!("{:?}", rel.relationship_type)))
+        })
+        .collect();
+    relationships.sort();
+    relationships.dedup();
+    for (from, to, label) in relationships {
+        let _ = writeln!(d2, "{} -> {}: {}", from, to, label);
+    }
+
+    d2
+}
+
+fn write_container(d2: &mut String, container: &Container, depth: usize) {
+    let indent = "  ".repeat(depth);
+    for (key, node) in &container.nodes {
+        let _ = writeln!(
+            d2,
+            "{}{}: {} {{ shape: {} }}",
+            indent,
+            key,
+            quote(&node.name),
+            shape(&node.node_type)
+        );
+    }
+    for (name, child) in &container.children {
+        let _ = writeln!(d2, "{}{}: {{", indent, quote(name));
+        write_container(d2, child, depth + 1);
+        let _ = writeln!(d2, "{}}}", indent);
+    }
+}
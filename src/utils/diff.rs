@@ -0,0 +1,77 @@
+use regex::Regex;
+
+// Lines touched by one diff hunk, on the new side of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedLines {
+    pub path: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+// Extract changed line ranges from a unified diff (as printed by `git diff`).
+// Only new-side ranges are returned, as they are matched against a graph of
+// the changed revision; deleted files yield nothing.
+pub fn parse_unified_diff(diff: &str) -> Vec<ChangedLines> {
+    let hunk_header = Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@").unwrap();
+    let mut changes = Vec::new();
+    let mut new_path: Option<String> = None;
+    // Old and new lines left in the current hunk; file headers are only
+    // recognised outside hunks, where a removed "-- " line cannot be mistaken
+    // for one
+    let mut remaining = (0usize, 0usize);
+
+    for line in diff.lines() {
+        if remaining != (0, 0) {
+            match line.as_bytes().first() {
+                Some(b'-') => remaining.0 = remaining.0.saturating_sub(1),
+                Some(b'+') => remaining.1 = remaining.1.saturating_sub(1),
+                Some(b' ') | None => {
+                    remaining.0 = remaining.0.saturating_sub(1);
+                    remaining.1 = remaining.1.saturating_sub(1);
+                }
+                Some(b'\\') => {}
+                // Anything else cannot be part of a hunk, so a miscounted
+                // one ends here
+                _ => remaining = (0, 0),
+            }
+            if line.starts_with(['-', '+', ' ', '\\']) || line.is_empty() {
+                continue;
+            }
+        }
+
+        if line.starts_with("--- ") {
+            new_path = None;
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            new_path = diff_path(path, "b/");
+        } else if let Some(captures) = hunk_header.captures(line) {
+            let number = |index: usize, default: usize| {
+                captures
+                    .get(index)
+                    .and_then(|m| m.as_str().parse().ok())
+                    .unwrap_or(default)
+            };
+            let (start, count) = (number(3, 0), number(4, 1));
+            remaining = (number(2, 1), count);
+
+            if let Some(path) = &new_path {
+                // An empty side marks the line after which lines were removed
+                let start = start.max(1);
+                changes.push(ChangedLines {
+                    path: path.clone(),
+                    start,
+                    end: start + count.max(1) - 1,
+                });
+            }
+        }
+    }
+
+    changes
+}
+
+fn diff_path(path: &str, prefix: &str) -> Option<String> {
+    let path = path.split('\t').next().unwrap_or(path).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).unwrap_or(path).to_string())
+}
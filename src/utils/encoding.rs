@@ -0,0 +1,86 @@
+// Decoding of source files that are not plain UTF-8
+
+// Characters of windows-1252 bytes 0x80..=0x9F; the rest of the code page
+// matches Latin-1. Unassigned bytes map to the replacement character.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '€', '\u{FFFD}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{FFFD}', 'Ž',
+    '\u{FFFD}', '\u{FFFD}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{FFFD}',
+    'ž', 'Ÿ',
+];
+
+// Share of zero bytes in one byte position above which text is taken to be UTF-16
+const UTF16_ZERO_RATIO: f64 = 0.4;
+
+// Decode file content, returning the text and the name of the encoding it
+// was read as. A byte order mark decides when present; otherwise text with a high/low byte pattern of zeros is read as UTF-16, valid UTF-8
+// is kept as is, and anything else is read as windows-1252 so legacy files
+// still parse.
+pub fn decode_source(bytes: &[u8]) -> (String, &'static str) {
+    if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return (String::from_utf8_lossy(rest).into_owned(), "utf-8");
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFF\xFE") {
+        return (decode_utf16(rest, u16::from_le_bytes), "utf-16le");
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFE\xFF") {
+        return (decode_utf16(rest, u16::from_be_bytes), "utf-16be");
+    }
+
+    // Checked first: ASCII text in UTF-16 is also valid UTF-8
+    match utf16_byte_order(bytes) {
+        Some("utf-16le") => return (decode_utf16(bytes, u16::from_le_bytes), "utf-16le"),
+        Some(_) => return (decode_utf16(bytes, u16::from_be_bytes), "utf-16be"),
+        None => {}
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), "utf-8"),
+        Err(_) => (decode_windows_1252(bytes), "windows-1252"),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> String {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| to_unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+fn decode_windows_1252(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            0x80..=0x9F => WINDOWS_1252_HIGH[(byte - 0x80) as usize],
+            _ => byte as char,
+        })
+        .collect()
+}
+
+// ASCII-heavy UTF-16 has a zero in every other byte: the high byte comes
+// second in little-endian text and first in big-endian text
+fn utf16_byte_order(bytes: &[u8]) -> Option<&'static str> {
+    let sample = &bytes[..bytes.len().min(1024)];
+    let pairs = sample.len() / 2;
+    if pairs < 2 {
+        return None;
+    }
+
+    let zeros_at = |offset: usize| {
+        sample
+            .chunks_exact(2)
+            .filter(|pair| pair[offset] == 0)
+            .count() as f64
+            / pairs as f64
+    };
+    let (even, odd) = (zeros_at(0), zeros_at(1));
+
+    if odd > UTF16_ZERO_RATIO && even < UTF16_ZERO_RATIO / 4.0 {
+        Some("utf-16le")
+    } else if even > UTF16_ZERO_RATIO && odd < UTF16_ZERO_RATIO / 4.0 {
+        Some("utf-16be")
+    } else {
+        None
+    }
+}
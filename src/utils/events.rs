@@ -0,0 +1,200 @@
+use crate::code_graph::GraphEvent;
+use log::debug;
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+// Destination for graph change events
+pub trait EventSink {
+    fn send(&mut self, events: &[GraphEvent]) -> io::Result<()>;
+}
+
+// Appends one JSON event per line to a file
+pub struct JsonLinesSink {
+    path: PathBuf,
+}
+
+impl JsonLinesSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        JsonLinesSink { path: path.into() }
+    }
+}
+
+impl EventSink for JsonLinesSink {
+    fn send(&mut self, events: &[GraphEvent]) -> io::Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut writer = BufWriter::new(file);
+        for event in events {
+            serde_json::to_writer(&mut writer, event)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        debug!("Appended {} events to {:?}", events.len(), self.path);
+        Ok(())
+    }
+}
+
+// POSTs events as `{"events": [...]}` JSON bodies, retrying failed requests
+#[cfg(feature = "webhook")]
+pub struct WebhookSink {
+    url: String,
+    // Events per request
+    pub batch_size: usize,
+    pub max_attempts: usize,
+}
+
+#[cfg(feature = "webhook")]
+impl WebhookSink {
+    pub fn new(url: &str) -> Self {
+        WebhookSink {
+            url: url.to_string(),
+            batch_size: 500,
+            max_attempts: 3,
+        }
+    }
+
+    fn post(&self, body: &str) -> io::Result<()> {
+        let mut attempt = 1;
+        loop {
+            let result = ureq::post(&self.url)
+                .header("Content-Type", "application/json")
+                .send(body);
+            match result {
+                Ok(_) => return Ok(()),
+                Err(e) if attempt >= self.max_attempts => {
+                    return Err(io::Error::other(format!("Webhook {}: {}", self.url, e)));
+                }
+                Err(e) => {
+                    log::warn!("Webhook attempt {} failed: {}", attempt, e);
+                    std::thread::sleep(std::time::Duration::from_millis(500 << attempt));
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "webhook")]
+impl EventSink for WebhookSink {
+    fn send(&mut self, events: &[GraphEvent]) -> io::Result<()> {
+        for batch in events.chunks(self.batch_size.max(1)) {
+            let body = serde_json::to_string(&serde_json::json!({ "events": batch }))?;
+            self.post(&body)?;
+            debug!("Posted {} events to {}", batch.len(), self.url);
+        }
+        Ok(())
+    }
+}
+
+// Produces one JSON message per event, keyed by the node ID (or the source
+// node of an edge) so a node's events stay ordered within a partition
+#[cfg(feature = "kafka")]
+pub struct KafkaSink {
+    producer: rdkafka::producer::BaseProducer,
+    topic: String,
+    // How long to wait for the broker to acknowledge each batch
+    pub flush_timeout: std::time::Duration,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaSink {
+    // `brokers` is a comma-separated bootstrap server list
+    pub fn new(brokers: &str, topic: &str) -> io::Result<Self> {
+        let producer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .create()
+            .map_err(io::Error::other)?;
+        Ok(KafkaSink {
+            producer,
+            topic: topic.to_string(),
+            flush_timeout: std::time::Duration::from_secs(30),
+        })
+    }
+}
+
+#[cfg(feature = "kafka")]
+fn event_key(event: &GraphEvent) -> &str {
+    match event {
+        GraphEvent::NodeAdded { node } | GraphEvent::NodeUpdated { node } => &node.id,
+        GraphEvent::NodeRemoved { id } => id,
+        GraphEvent::RelationshipAdded { relationship }
+        | GraphEvent::RelationshipRemoved { relationship } => &relationship.from_id,
+    }
+}
+
+#[cfg(feature = "kafka")]
+impl EventSink for KafkaSink {
+    fn send(&mut self, events: &[GraphEvent]) -> io::Result<()> {
+        use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+        use rdkafka::producer::{BaseRecord, Producer};
+
+        for event in events {
+            let payload = serde_json::to_string(event)?;
+            let key = event_key(event);
+            loop {
+                let record = BaseRecord::to(&self.topic).key(key).payload(&payload);
+                match self.producer.send(record) {
+                    Ok(()) => break,
+                    // Wait for deliveries to drain the local queue, then retry
+                    Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), _)) => {
+                        self.producer.poll(std::time::Duration::from_millis(100));
+                    }
+                    Err((e, _)) => return Err(io::Error::other(e)),
+                }
+            }
+        }
+        self.producer
+            .flush(self.flush_timeout)
+            .map_err(io::Error::other)?;
+        debug!("Produced {} events to {}", events.len(), self.topic);
+        Ok(())
+    }
+}
+
+// Build a sink from `file:<path>`, with the webhook feature `webhook:<url>`
+// (a bare http(s) URL also works), or with the kafka feature
+// `kafka:<brokers>/<topic>`
+pub fn sink_from_spec(spec: &str) -> io::Result<Box<dyn EventSink>> {
+    if let Some(path) = spec.strip_prefix("file:") {
+        return Ok(Box::new(JsonLinesSink::new(path)));
+    }
+
+    if let Some(target) = spec.strip_prefix("kafka:") {
+        return match target
+            .rsplit_once('/')
+            .filter(|(brokers, topic)| !brokers.is_empty() && !topic.is_empty())
+        {
+            #[cfg(feature = "kafka")]
+            Some((brokers, topic)) => Ok(Box::new(KafkaSink::new(brokers, topic)?)),
+            #[cfg(not(feature = "kafka"))]
+            Some(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Kafka sinks require the `kafka` feature",
+            )),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Expected kafka:<brokers>/<topic>, got {}", spec),
+            )),
+        };
+    }
+
+    let url = spec
+        .strip_prefix("webhook:")
+        .or_else(|| (spec.starts_with("http://") || spec.starts_with("https://")).then_some(spec));
+    match url {
+        #[cfg(feature = "webhook")]
+        Some(url) => Ok(Box::new(WebhookSink::new(url))),
+        #[cfg(not(feature = "webhook"))]
+        Some(_) => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Webhook sinks require the `webhook` feature",
+        )),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unknown event sink: {}", spec),
+        )),
+    }
+}
@@ -0,0 +1,4 @@
+// Stable content hash used to compare files across paths and runs
+pub fn content_hash(data: &[u8]) -> String {
+    blake3::hash(data).to_hex().to_string()
+}
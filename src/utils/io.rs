@@ -1,37 +1,445 @@
-use crate::code_graph::CodeGraph;
+use crate::code_graph::schema::{self, SchemaProbe};
+use crate::code_graph::{CodeGraph, SCHEMA_VERSION, SHARD_INDEX};
 use log::{error, info};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
 use std::fs;
-use std::io::{self};
+use std::io::{self, Read, Write};
 use std::path::Path;
 
-pub fn export_graph_to_json(graph: &CodeGraph, output_path: &Path) -> io::Result<()> {
+// Interactive viewer page; the graph data replaces the placeholder
+const VIEWER_TEMPLATE: &str = include_str!("viewer.html");
+const VIEWER_DATA_PLACEHOLDER: &str = "/*GRAPH_DATA*/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    // Self-contained page with a force-directed viewer of the graph
+    Html,
+    // D2 diagram source with a container per directory and file
+    D2,
+    // Binary MessagePack encoding of the JSON structure
+    MsgPack,
+    // Directory with `nodes.parquet` and `edges.parquet` tables
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+// Compression applied to an export; compressed graphs are recognised by
+// their magic bytes when they are imported again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    #[cfg(feature = "compression")]
+    Gzip,
+    #[cfg(feature = "compression")]
+    Zstd,
+}
+
+impl Compression {
+    // Suffix added to the file name of a compressed export
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Compression::None => "",
+            #[cfg(feature = "compression")]
+            Compression::Gzip => ".gz",
+            #[cfg(feature = "compression")]
+            Compression::Zstd => ".zst",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Compression::None),
+            #[cfg(feature = "compression")]
+            "gzip" => Some(Compression::Gzip),
+            #[cfg(feature = "compression")]
+            "zstd" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+// Output path that writes to stdout, and input path that reads from stdin,
+// so exports and imports compose in shell pipelines
+pub const STDIO_PATH: &str = "-";
+
+pub fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO_PATH
+}
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+impl ExportFormat {
+    // File extension of an export, without compression
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Html => "html",
+            ExportFormat::D2 => "d2",
+            ExportFormat::MsgPack => "msgpack",
+            #[cfg(feature = "parquet")]
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(ExportFormat::Json),
+            "html" => Some(ExportFormat::Html),
+            "d2" => Some(ExportFormat::D2),
+            "msgpack" => Some(ExportFormat::MsgPack),
+            #[cfg(feature = "parquet")]
+            "parquet" => Some(ExportFormat::Parquet),
+            _ => None,
+        }
+    }
+}
+
+pub fn export_graph(graph: &CodeGraph, output_path: &Path, format: ExportFormat) -> io::Result<()> {
+    export_graph_compressed(graph, output_path, format, Compression::None)
+}
+
+// Export a graph and write it through the given compression
+pub fn export_graph_compressed(
+    graph: &CodeGraph,
+    output_path: &Path,
+    format: ExportFormat,
+    compression: Compression,
+) -> io::Result<()> {
     info!(
-        "Exporting graph with {} nodes and {} relationships to JSON: {:?}",
+        "Exporting graph with {} nodes and {} relationships as {:?}: {:?}",
         graph.node_count(),
         graph.relationship_count(),
+        format,
         output_path
     );
 
-    let json = match serde_json::to_string_pretty(graph) {
-        Ok(json) => json,
-        Err(e) => {
-            error!("Failed to serialize graph to JSON: {}", e);
-            return Err(io::Error::new(io::ErrorKind::Other, e));
+    let bytes = match format {
+        ExportFormat::Json => graph_to_json(graph)?.into_bytes(),
+        ExportFormat::Html => graph_to_html(graph).into_bytes(),
+        ExportFormat::D2 => super::d2::graph_to_d2(graph).into_bytes(),
+        ExportFormat::MsgPack => graph_to_msgpack(graph)?,
+        #[cfg(feature = "parquet")]
+        ExportFormat::Parquet => {
+            if is_stdio(output_path) {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "Parquet output is a directory and cannot be written to stdout",
+                ));
+            }
+            // Parquet pages are compressed by the format itself
+            if compression != Compression::None {
+                log::warn!("Ignoring {:?} compression for Parquet output", compression);
+            }
+            return super::parquet::export_graph_to_parquet(graph, output_path);
         }
     };
 
-    match fs::write(output_path, json.clone()) {
-        Ok(_) => {
-            info!(
-                "Successfully wrote {} bytes to {:?}",
-                json.len(),
-                output_path
-            );
-            Ok(())
+    write_output(output_path, &bytes, compression)
+}
+
+// One entry of the `_index.json` index of a sharded export
+#[derive(Serialize)]
+struct ShardEntry {
+    file: String,
+    nodes: usize,
+    relationships: usize,
+    // File with the edges leaving the shard, if any do
+    cross_edges: Option<String>,
+    cross_edge_count: usize,
+}
+
+// Write one graph per top-level directory into `output_dir`, named after
+// the directory, with `<shard>.cross.json` holding the edges from that
+// shard into others and `_index.json` listing every shard, so consumers
+// can load only the parts of a large repository they need
+pub fn export_graph_shards(
+    graph: &CodeGraph,
+    output_dir: &Path,
+    format: ExportFormat,
+    compression: Compression,
+) -> io::Result<()> {
+    if is_stdio(output_dir) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Sharded output is a directory and cannot be written to stdout",
+        ));
+    }
+    fs::create_dir_all(output_dir)?;
+
+    let sharded = graph.shard_by_directory();
+    info!(
+        "Writing {} shards under {:?} to {:?}",
+        sharded.shards.len(),
+        sharded.root,
+        output_dir
+    );
+
+    let mut index: BTreeMap<&str, ShardEntry> = BTreeMap::new();
+    for (name, shard) in &sharded.shards {
+        let file = format!("{}.{}{}", name, format.extension(), compression.extension());
+        export_graph_compressed(shard, &output_dir.join(&file), format, compression)?;
+
+        let edges = sharded.cross_edges.get(name);
+        let cross_edges = match edges {
+            Some(edges) => {
+                let file = format!("{}.cross.json{}", name, compression.extension());
+                let json = serde_json::to_vec_pretty(edges).map_err(io::Error::other)?;
+                write_output(&output_dir.join(&file), &json, compression)?;
+                Some(file)
+            }
+            None => None,
+        };
+        index.insert(
+            name,
+            ShardEntry {
+                file,
+                nodes: shard.node_count(),
+                relationships: shard.relationship_count(),
+                cross_edges,
+                cross_edge_count: edges.map_or(0, Vec::len),
+            },
+        );
+    }
+
+    let json = serde_json::to_vec_pretty(&json!({
+        "provenance": graph.provenance(),
+        "root": sharded.root,
+        "shards": index,
+    }))
+    .map_err(io::Error::other)?;
+    write_output(
+        &output_dir.join(format!("{}.json", SHARD_INDEX)),
+        &json,
+        Compression::None,
+    )
+}
+
+fn write_output(output_path: &Path, bytes: &[u8], compression: Compression) -> io::Result<()> {
+    let sink: Box<dyn Write> = if is_stdio(output_path) {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(fs::File::create(output_path).inspect_err(|e| {
+            error!("Failed to create output file {:?}: {}", output_path, e);
+        })?)
+    };
+    let mut writer = io::BufWriter::new(sink);
+    match compression {
+        Compression::None => writer.write_all(bytes)?,
+        #[cfg(feature = "compression")]
+        Compression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+            encoder.write_all(bytes)?;
+            writer = encoder.finish()?;
         }
-        Err(e) => {
-            error!("Failed to write JSON to file {:?}: {}", output_path, e);
-            Err(e)
+        #[cfg(feature = "compression")]
+        Compression::Zstd => {
+            let mut encoder = zstd::Encoder::new(writer, 0)?;
+            encoder.write_all(bytes)?;
+            writer = encoder.finish()?;
         }
     }
+    writer.flush()?;
+
+    info!(
+        "Successfully wrote {} bytes ({:?} compression) to {:?}",
+        bytes.len(),
+        compression,
+        output_path
+    );
+    Ok(())
+}
+
+// Content of an exported graph, decompressed when it starts with the magic
+// bytes of gzip or zstd
+fn read_input(input_path: &Path) -> io::Result<Vec<u8>> {
+    let bytes = if is_stdio(input_path) {
+        let mut bytes = Vec::new();
+        io::stdin().lock().read_to_end(&mut bytes)?;
+        bytes
+    } else {
+        fs::read(input_path)?
+    };
+    if !bytes.starts_with(GZIP_MAGIC) && !bytes.starts_with(ZSTD_MAGIC) {
+        return Ok(bytes);
+    }
+    decompress(input_path, &bytes)
+}
+
+#[cfg(feature = "compression")]
+fn decompress(input_path: &Path, bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    if bytes.starts_with(GZIP_MAGIC) {
+        log::debug!("Decompressing gzip input {:?}", input_path);
+        flate2::read::GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    } else {
+        log::debug!("Decompressing zstd input {:?}", input_path);
+        zstd::Decoder::new(bytes)?.read_to_end(&mut decompressed)?;
+    }
+    Ok(decompressed)
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress(input_path: &Path, _bytes: &[u8]) -> io::Result<Vec<u8>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "{:?} is compressed; reading it requires the `compression` feature",
+            input_path
+        ),
+    ))
+}
+
+fn graph_to_json(graph: &CodeGraph) -> io::Result<String> {
+    serde_json::to_string_pretty(graph).map_err(|e| {
+        error!("Failed to serialize graph to JSON: {}", e);
+        io::Error::other(e)
+    })
+}
+
+// Field names are kept, so the graph loads the same way as its JSON form,
+// including into versions that added fields since
+fn graph_to_msgpack(graph: &CodeGraph) -> io::Result<Vec<u8>> {
+    rmp_serde::to_vec_named(graph).map_err(|e| {
+        error!("Failed to serialize graph to MessagePack: {}", e);
+        io::Error::other(e)
+    })
+}
+
+// Decode a graph of the current schema version directly, and older ones
+// through their self-describing form so they can be migrated first
+fn deserialize_graph(input_path: &Path, bytes: &[u8], msgpack: bool) -> io::Result<CodeGraph> {
+    fn decode<T: serde::de::DeserializeOwned>(bytes: &[u8], msgpack: bool) -> Result<T, String> {
+        if msgpack {
+            rmp_serde::from_slice(bytes).map_err(|e| e.to_string())
+        } else {
+            serde_json::from_slice(bytes).map_err(|e| e.to_string())
+        }
+    }
+    let invalid = |e: String| {
+        error!("Failed to deserialize graph from {:?}: {}", input_path, e);
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    };
+
+    let version = decode::<SchemaProbe>(bytes, msgpack)
+        .map_err(invalid)?
+        .schema_version;
+    schema::check_version(version)?;
+    if version == SCHEMA_VERSION {
+        decode(bytes, msgpack).map_err(invalid)
+    } else {
+        info!(
+            "{:?} has schema version {}, current is {}",
+            input_path, version, SCHEMA_VERSION
+        );
+        schema::upgrade(decode(bytes, msgpack).map_err(invalid)?, version)
+    }
+}
+
+fn log_imported(graph: &CodeGraph) {
+    info!(
+        "Imported graph with {} nodes and {} relationships",
+        graph.node_count(),
+        graph.relationship_count()
+    );
+}
+
+pub fn export_graph_to_json(graph: &CodeGraph, output_path: &Path) -> io::Result<()> {
+    export_graph(graph, output_path, ExportFormat::Json)
+}
+
+pub fn import_graph_from_json(input_path: &Path) -> io::Result<CodeGraph> {
+    info!("Importing graph from JSON: {:?}", input_path);
+
+    let json = read_input(input_path)?;
+    let graph = deserialize_graph(input_path, &json, false)?;
+    log_imported(&graph);
+
+    Ok(graph)
+}
+
+pub fn export_graph_to_msgpack(graph: &CodeGraph, output_path: &Path) -> io::Result<()> {
+    export_graph(graph, output_path, ExportFormat::MsgPack)
+}
+
+pub fn import_graph_from_msgpack(input_path: &Path) -> io::Result<CodeGraph> {
+    info!("Importing graph from MessagePack: {:?}", input_path);
+
+    let bytes = read_input(input_path)?;
+    let graph = deserialize_graph(input_path, &bytes, true)?;
+    log_imported(&graph);
+
+    Ok(graph)
+}
+
+// Load a graph exported as JSON or MessagePack, compressed or not. The
+// formats are told apart by the first byte: a JSON graph is an object, a
+// MessagePack graph a map header.
+pub fn import_graph(input_path: &Path) -> io::Result<CodeGraph> {
+    info!("Importing graph from {:?}", input_path);
+
+    let bytes = read_input(input_path)?;
+    let is_json = bytes
+        .first()
+        .is_none_or(|&first| first == b'{' || first.is_ascii_whitespace());
+    let graph = deserialize_graph(input_path, &bytes, !is_json)?;
+    log_imported(&graph);
+
+    Ok(graph)
+}
+
+// A single HTML page embedding the graph and a viewer for it, so the graph
+// can be explored in a browser without any other files
+fn graph_to_html(graph: &CodeGraph) -> String {
+    let mut nodes: Vec<_> = graph.all_nodes().collect();
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+    let mut edges: Vec<_> = graph.all_relationships().collect();
+    edges.sort_by(|a, b| {
+        (&a.relationship_type, &a.from_id, &a.to_id).cmp(&(
+            &b.relationship_type,
+            &b.from_id,
+            &b.to_id,
+        ))
+    });
+
+    let data = json!({
+        "provenance": graph.provenance(),
+        "nodes": nodes
+            .iter()
+            .map(|node| json!({
+                "id": node.id,
+                "name": node.name,
+                "type": format!("{:?}", node.node_type),
+                "file": node.file_path,
+                "language": node.language,
+                "line": node.line_range.0,
+                "summary": node.summary,
+            }))
+            .collect::<Vec<_>>(),
+        "edges": edges
+            .iter()
+            .map(|rel| json!({
+                "source": rel.from_id,
+                "target": rel.to_id,
+                "type": format!("{:?}", rel.relationship_type),
+            }))
+            .collect::<Vec<_>>(),
+    });
+    // Escaped so that no string in the data can close the script element
+    let data = data.to_string().replace("</", "<\\/");
+
+    VIEWER_TEMPLATE.replace(VIEWER_DATA_PLACEHOLDER, &data)
+}
+
+pub fn export_graph_to_html(graph: &CodeGraph, output_path: &Path) -> io::Result<()> {
+    export_graph(graph, output_path, ExportFormat::Html)
+}
+
+pub fn export_graph_to_d2(graph: &CodeGraph, output_path: &Path) -> io::Result<()> {
+    export_graph(graph, output_path, ExportFormat::D2)
 }
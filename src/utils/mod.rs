@@ -1 +1,12 @@
+pub mod d2;
+pub mod diff;
+pub mod encoding;
+pub mod events;
+pub mod hash;
 pub mod io;
+#[cfg(feature = "neo4j")]
+pub mod neo4j;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "postgres")]
+pub mod postgres;
@@ -0,0 +1,294 @@
+use crate::code_graph::{CodeGraph, CodeNode, NodeType, Relationship, RelationshipType};
+use log::{debug, info, warn};
+use neo4rs::{BoltType, ConfigBuilder, Graph, query};
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+
+// Where and how to write a graph into Neo4j or Memgraph
+#[derive(Debug, Clone)]
+pub struct Neo4jOptions {
+    // Bolt address such as `bolt://localhost:7687`
+    pub uri: String,
+    pub user: String,
+    pub password: String,
+    // Database to write to; None for the server's default
+    pub database: Option<String>,
+    // Nodes or relationships written per transaction
+    pub batch_size: usize,
+}
+
+impl Neo4jOptions {
+    pub fn new(uri: &str, user: &str, password: &str) -> Self {
+        Neo4jOptions {
+            uri: uri.to_string(),
+            user: user.to_string(),
+            password: password.to_string(),
+            database: None,
+            batch_size: 1000,
+        }
+    }
+}
+
+fn neo4j_error(error: neo4rs::Error) -> io::Error {
+    io::Error::other(format!("Neo4j: {}", error))
+}
+
+// `DependsOn` becomes `DEPENDS_ON`, the usual Cypher style for edge types
+fn relationship_label(relationship_type: &RelationshipType) -> String {
+    let mut label = String::new();
+    for (i, c) in format!("{:?}", relationship_type).chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            label.push('_');
+        }
+        label.push(c.to_ascii_uppercase());
+    }
+    label
+}
+
+fn node_row(node: &CodeNode) -> BoltType {
+    // Core properties win over metadata keys of the same name
+    let mut properties: HashMap<String, BoltType> = node
+        .flat_metadata()
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.into()))
+        .collect();
+    properties.insert("name".to_string(), node.name.as_str().into());
+    properties.insert(
+        "node_type".to_string(),
+        format!("{:?}", node.node_type).into(),
+    );
+    properties.insert("file_path".to_string(), node.file_path.as_str().into());
+    properties.insert("start_line".to_string(), (node.line_range.0 as i64).into());
+    properties.insert("end_line".to_string(), (node.line_range.1 as i64).into());
+    properties.insert("content".to_string(), node.content.as_str().into());
+    if let Some(summary) = &node.summary {
+        properties.insert("summary".to_string(), summary.as_str().into());
+    }
+    if let Some(repo) = &node.repo {
+        properties.insert("repo".to_string(), repo.as_str().into());
+    }
+    if let Some(namespace) = &node.namespace {
+        properties.insert("namespace".to_string(), namespace.as_str().into());
+    }
+    if let Some(language) = &node.language {
+        properties.insert("language".to_string(), language.as_str().into());
+    }
+    if let Some(hash) = &node.content_hash {
+        properties.insert("content_hash".to_string(), hash.as_str().into());
+    }
+
+    let mut row: HashMap<String, BoltType> = HashMap::new();
+    row.insert("id".to_string(), node.id.as_str().into());
+    row.insert("properties".to_string(), properties.into());
+    row.into()
+}
+
+fn relationship_row(relationship: &Relationship) -> BoltType {
+    let properties: HashMap<String, BoltType> = relationship
+        .metadata
+        .iter()
+        .map(|(key, value)| (key.clone(), value.as_str().into()))
+        .collect();
+
+    let mut row: HashMap<String, BoltType> = HashMap::new();
+    row.insert("from".to_string(), relationship.from_id.as_str().into());
+    row.insert("to".to_string(), relationship.to_id.as_str().into());
+    // Part of the MERGE key, which cannot hold nulls
+    row.insert(
+        "provenance".to_string(),
+        relationship
+            .provenance
+            .as_deref()
+            .unwrap_or_default()
+            .into(),
+    );
+    row.insert("properties".to_string(), properties.into());
+    row.into()
+}
+
+async fn run_batch(
+    graph: &Graph,
+    statement: &str,
+    sync_id: &str,
+    rows: Vec<BoltType>,
+) -> io::Result<()> {
+    let mut txn = graph.start_txn().await.map_err(neo4j_error)?;
+    txn.run(
+        query(statement)
+            .param("rows", rows)
+            .param("sync_id", sync_id),
+    )
+    .await
+    .map_err(neo4j_error)?;
+    txn.commit().await.map_err(neo4j_error)
+}
+
+// Run a statement that deletes up to `$limit` items and returns how many it
+// deleted as `deleted`, until nothing is left to delete
+async fn delete_in_batches(
+    graph: &Graph,
+    statement: &str,
+    sync_id: &str,
+    batch_size: usize,
+) -> io::Result<usize> {
+    let mut total = 0;
+    loop {
+        let mut rows = graph
+            .execute(
+                query(statement)
+                    .param("sync_id", sync_id)
+                    .param("limit", batch_size as i64),
+            )
+            .await
+            .map_err(neo4j_error)?;
+        let deleted = match rows.next().await.map_err(neo4j_error)? {
+            Some(row) => row.get::<i64>("deleted").map_err(io::Error::other)?,
+            None => 0,
+        };
+        if deleted <= 0 {
+            return Ok(total);
+        }
+        total += deleted as usize;
+    }
+}
+
+async fn write_graph(graph: &CodeGraph, options: &Neo4jOptions) -> io::Result<()> {
+    let mut config = ConfigBuilder::default()
+        .uri(options.uri.as_str())
+        .user(options.user.as_str())
+        .password(options.password.as_str());
+    if let Some(database) = &options.database {
+        config = config.db(database.as_str());
+    }
+    let database = Graph::connect(config.build().map_err(neo4j_error)?)
+        .await
+        .map_err(neo4j_error)?;
+
+    // The constraint syntax differs between Neo4j and Memgraph; MERGE stays
+    // correct without it, only slower
+    let constraint = "CREATE CONSTRAINT code_node_id IF NOT EXISTS \
+                      FOR (n:CodeNode) REQUIRE n.id IS UNIQUE";
+    if let Err(e) = database.run(query(constraint)).await {
+        let memgraph = "CREATE CONSTRAINT ON (n:CodeNode) ASSERT n.id IS UNIQUE";
+        if database.run(query(memgraph)).await.is_err() {
+            warn!(
+                "Could not create a uniqueness constraint on node IDs: {}",
+                e
+            );
+        }
+    }
+
+    let batch_size = options.batch_size.max(1);
+    // Everything this run writes is stamped with its ID, so whatever is left
+    // unstamped afterwards is no longer in the graph
+    let sync_id = uuid::Uuid::new_v4().to_string();
+
+    // A node whose type changed keeps no label of its old type
+    let type_labels: Vec<String> = NodeType::ALL
+        .iter()
+        .map(|node_type| format!("{:?}", node_type))
+        .collect();
+
+    // Labels cannot be parameters, so nodes are written per type
+    let mut nodes_by_type: BTreeMap<String, Vec<&CodeNode>> = BTreeMap::new();
+    for node in graph.all_nodes() {
+        nodes_by_type
+            .entry(format!("{:?}", node.node_type))
+            .or_default()
+            .push(node);
+    }
+    for (label, mut nodes) in nodes_by_type {
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        let statement = format!(
+            "UNWIND $rows AS row MERGE (n:CodeNode {{id: row.id}}) \
+             SET n = row.properties, n.id = row.id, n.sync_id = $sync_id \
+             REMOVE n:{} SET n:{}",
+            type_labels.join(":"),
+            label
+        );
+        for batch in nodes.chunks(batch_size) {
+            run_batch(
+                &database,
+                &statement,
+                &sync_id,
+                batch.iter().map(|n| node_row(n)).collect(),
+            )
+            .await?;
+        }
+        debug!("Wrote {} {} nodes", nodes.len(), label);
+    }
+
+    let mut relationships_by_type: BTreeMap<String, Vec<&Relationship>> = BTreeMap::new();
+    for relationship in graph.all_relationships() {
+        relationships_by_type
+            .entry(relationship_label(&relationship.relationship_type))
+            .or_default()
+            .push(relationship);
+    }
+    for (label, relationships) in relationships_by_type {
+        let statement = format!(
+            "UNWIND $rows AS row \
+             MATCH (a:CodeNode {{id: row.from}}), (b:CodeNode {{id: row.to}}) \
+             MERGE (a)-[r:{} {{provenance: row.provenance}}]->(b) \
+             SET r = row.properties, r.provenance = row.provenance, r.sync_id = $sync_id",
+            label
+        );
+        for batch in relationships.chunks(batch_size) {
+            run_batch(
+                &database,
+                &statement,
+                &sync_id,
+                batch.iter().map(|r| relationship_row(r)).collect(),
+            )
+            .await?;
+        }
+        debug!("Wrote {} {} relationships", relationships.len(), label);
+    }
+
+    let stale_relationships = delete_in_batches(
+        &database,
+        "MATCH (:CodeNode)-[r]->(:CodeNode) \
+         WHERE r.sync_id IS NULL OR r.sync_id <> $sync_id \
+         WITH r LIMIT $limit DELETE r RETURN count(*) AS deleted",
+        &sync_id,
+        batch_size,
+    )
+    .await?;
+    let stale_nodes = delete_in_batches(
+        &database,
+        "MATCH (n:CodeNode) WHERE n.sync_id IS NULL OR n.sync_id <> $sync_id \
+         WITH n LIMIT $limit DETACH DELETE n RETURN count(*) AS deleted",
+        &sync_id,
+        batch_size,
+    )
+    .await?;
+    info!(
+        "Deleted {} nodes and {} relationships no longer in the graph",
+        stale_nodes, stale_relationships
+    );
+
+    Ok(())
+}
+
+// Write the graph into a Neo4j or Memgraph database over Bolt in batched
+// transactions. Nodes are merged on their ID and relationships on their
+// endpoints, type and provenance, so writing the same graph again updates it
+// in place instead of duplicating it. Properties and type labels are
+// replaced, and code nodes and relationships that are not in the graph are
+// deleted, so the database mirrors the latest graph.
+pub fn export_graph_to_neo4j(graph: &CodeGraph, options: &Neo4jOptions) -> io::Result<()> {
+    info!(
+        "Writing {} nodes and {} relationships to {}",
+        graph.node_count(),
+        graph.relationship_count(),
+        options.uri
+    );
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(write_graph(graph, options))?;
+
+    info!("Graph written to {}", options.uri);
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crate::code_graph::{CodeGraph, CodeNode, Relationship};
+use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use log::info;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+// Rows per record batch, which also bounds the memory of one batch
+const BATCH_ROWS: usize = 65_536;
+
+fn parquet_error(error: impl std::fmt::Display) -> io::Error {
+    io::Error::other(format!("Parquet: {}", error))
+}
+
+// Metadata maps are stored as JSON text, readable with DuckDB's `json`
+// functions and Spark's `from_json`
+fn metadata_json(metadata: &HashMap<String, String>) -> String {
+    serde_json::to_string(&metadata.iter().collect::<BTreeMap<_, _>>())
+        .unwrap_or_else(|_| "{}".to_string())
+}
+
+fn strings<'a>(values: impl Iterator<Item = &'a str>) -> ArrayRef {
+    Arc::new(StringArray::from_iter_values(values))
+}
+
+fn optional_strings<'a>(values: impl Iterator<Item = Option<&'a str>>) -> ArrayRef {
+    Arc::new(values.collect::<StringArray>())
+}
+
+fn node_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("node_type", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("file_path", DataType::Utf8, false),
+        Field::new("start_line", DataType::Int64, false),
+        Field::new("end_line", DataType::Int64, false),
+        Field::new("language", DataType::Utf8, true),
+        Field::new("repo", DataType::Utf8, true),
+        Field::new("namespace", DataType::Utf8, true),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("content_hash", DataType::Utf8, true),
+        Field::new("summary", DataType::Utf8, true),
+        Field::new("metadata", DataType::Utf8, false),
+    ])
+}
+
+fn node_batch(schema: &Arc<Schema>, nodes: &[&CodeNode]) -> io::Result<RecordBatch> {
+    let types: Vec<String> = nodes
+        .iter()
+        .map(|node| format!("{:?}", node.node_type))
+        .collect();
+    let metadata: Vec<String> = nodes
+        .iter()
+        .map(|node| {
+            serde_json::to_string(&node.flat_metadata()).unwrap_or_else(|_| "{}".to_string())
+        })
+        .collect();
+    let columns: Vec<ArrayRef> = vec![
+        strings(nodes.iter().map(|node| node.id.as_str())),
+        strings(types.iter().map(String::as_str)),
+        strings(nodes.iter().map(|node| node.name.as_str())),
+        strings(nodes.iter().map(|node| node.file_path.as_str())),
+        Arc::new(Int64Array::from_iter_values(
+            nodes.iter().map(|node| node.line_range.0 as i64),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            nodes.iter().map(|node| node.line_range.1 as i64),
+        )),
+        optional_strings(nodes.iter().map(|node| node.language.as_deref())),
+        optional_strings(nodes.iter().map(|node| node.repo.as_deref())),
+        optional_strings(nodes.iter().map(|node| node.namespace.as_deref())),
+        strings(nodes.iter().map(|node| node.content.as_str())),
+        optional_strings(nodes.iter().map(|node| node.content_hash.as_deref())),
+        optional_strings(nodes.iter().map(|node| node.summary.as_deref())),
+        strings(metadata.iter().map(String::as_str)),
+    ];
+    RecordBatch::try_new(schema.clone(), columns).map_err(parquet_error)
+}
+
+fn edge_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("from_id", DataType::Utf8, false),
+        Field::new("to_id", DataType::Utf8, false),
+        Field::new("relationship_type", DataType::Utf8, false),
+        Field::new("provenance", DataType::Utf8, true),
+        Field::new("metadata", DataType::Utf8, false),
+    ])
+}
+
+fn edge_batch(schema: &Arc<Schema>, edges: &[&Relationship]) -> io::Result<RecordBatch> {
+    let types: Vec<String> = edges
+        .iter()
+        .map(|rel| format!("{:?}", rel.relationship_type))
+        .collect();
+    let metadata: Vec<String> = edges
+        .iter()
+        .map(|rel| metadata_json(&rel.metadata))
+        .collect();
+    let columns: Vec<ArrayRef> = vec![
+        strings(edges.iter().map(|rel| rel.from_id.as_str())),
+        strings(edges.iter().map(|rel| rel.to_id.as_str())),
+        strings(types.iter().map(String::as_str)),
+        optional_strings(edges.iter().map(|rel| rel.provenance.as_deref())),
+        strings(metadata.iter().map(String::as_str)),
+    ];
+    RecordBatch::try_new(schema.clone(), columns).map_err(parquet_error)
+}
+
+fn write_table<T>(
+    path: &Path,
+    schema: Schema,
+    rows: &[T],
+    batch: impl Fn(&Arc<Schema>, &[T]) -> io::Result<RecordBatch>,
+    file_metadata: &[KeyValue],
+) -> io::Result<()> {
+    let schema = Arc::new(schema);
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_key_value_metadata(Some(file_metadata.to_vec()))
+        .build();
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema.clone(), Some(properties))
+        .map_err(parquet_error)?;
+    for chunk in rows.chunks(BATCH_ROWS) {
+        writer
+            .write(&batch(&schema, chunk)?)
+            .map_err(parquet_error)?;
+    }
+    writer.close().map_err(parquet_error)?;
+    Ok(())
+}
+
+// Write `nodes.parquet` and `edges.parquet` into the output directory, one
+// row per node and per relationship, sorted so reruns give the same files
+pub fn export_graph_to_parquet(graph: &CodeGraph, output_dir: &Path) -> io::Result<()> {
+    info!(
+        "Exporting graph with {} nodes and {} relationships to Parquet: {:?}",
+        graph.node_count(),
+        graph.relationship_count(),
+        output_dir
+    );
+    fs::create_dir_all(output_dir)?;
+
+    // Provenance is stored as JSON in the key-value metadata of both files
+    let file_metadata: Vec<KeyValue> = graph
+        .provenance()
+        .map(|provenance| serde_json::to_string(provenance).map_err(parquet_error))
+        .transpose()?
+        .map(|json| KeyValue::new("provenance".to_string(), json))
+        .into_iter()
+        .collect();
+
+    let mut nodes: Vec<&CodeNode> = graph.all_nodes().collect();
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+    write_table(
+        &output_dir.join("nodes.parquet"),
+        node_schema(),
+        &nodes,
+        node_batch,
+        &file_metadata,
+    )?;
+
+    let mut edges: Vec<&Relationship> = graph.all_relationships().collect();
+    edges.sort_by(|a, b| {
+        (&a.from_id, &a.to_id, &a.relationship_type).cmp(&(
+            &b.from_id,
+            &b.to_id,
+            &b.relationship_type,
+        ))
+    });
+    write_table(
+        &output_dir.join("edges.parquet"),
+        edge_schema(),
+        &edges,
+        edge_batch,
+        &file_metadata,
+    )?;
+
+    info!(
+        "Successfully wrote {} node and {} edge rows to {:?}",
+        nodes.len(),
+        edges.len(),
+        output_dir
+    );
+    Ok(())
+}
@@ -0,0 +1,371 @@
+use crate::code_graph::{CodeGraph, CodeNode, Relationship};
+use log::{debug, info};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+use tokio_postgres::{Client, NoTls, Transaction};
+
+// Schema changes in the order they are applied; each runs once per schema
+// and is recorded by name in `codegraph_migrations`. `{schema}` is replaced
+// by the quoted schema name.
+const MIGRATIONS: &[(&str, &str)] = &[
+    (
+        "0001_graph_tables",
+        "CREATE TABLE {schema}.nodes (
+        id TEXT PRIMARY KEY,
+        node_type TEXT NOT NULL,
+        name TEXT NOT NULL,
+        file_path TEXT NOT NULL,
+        start_line INTEGER NOT NULL,
+        end_line INTEGER NOT NULL,
+        content TEXT NOT NULL,
+        summary TEXT,
+        repo TEXT,
+        metadata JSONB NOT NULL DEFAULT '{}'
+    );
+    CREATE INDEX nodes_file_path ON {schema}.nodes (file_path);
+    CREATE INDEX nodes_name ON {schema}.nodes (name);
+    CREATE TABLE {schema}.relationships (
+        from_id TEXT NOT NULL,
+        to_id TEXT NOT NULL,
+        relationship_type TEXT NOT NULL,
+        provenance TEXT NOT NULL DEFAULT '',
+        metadata JSONB NOT NULL DEFAULT '{}',
+        PRIMARY KEY (from_id, to_id, relationship_type, provenance)
+    );
+    CREATE INDEX relationships_to_id ON {schema}.relationships (to_id);",
+    ),
+    (
+        "0002_node_language",
+        "ALTER TABLE {schema}.nodes ADD COLUMN language TEXT;
+        CREATE INDEX nodes_language ON {schema}.nodes (language);",
+    ),
+    (
+        "0003_node_content_hash",
+        "ALTER TABLE {schema}.nodes ADD COLUMN content_hash TEXT;",
+    ),
+    (
+        "0004_node_namespace",
+        "ALTER TABLE {schema}.nodes ADD COLUMN namespace TEXT;
+        CREATE INDEX nodes_namespace ON {schema}.nodes (namespace);",
+    ),
+];
+
+// Applied only once a graph with embeddings is written, so databases without
+// pgvector can still hold graphs. Vectors may have any dimension; an HNSW
+// index needs a fixed one, e.g. `ALTER COLUMN embedding TYPE vector(384)`.
+const EMBEDDING_MIGRATIONS: &[(&str, &str)] = &[(
+    "0001_embeddings",
+    "CREATE EXTENSION IF NOT EXISTS vector;
+    CREATE TABLE {schema}.embeddings (
+        node_id TEXT PRIMARY KEY REFERENCES {schema}.nodes (id) ON DELETE CASCADE,
+        embedding vector NOT NULL
+    );",
+)];
+
+#[derive(Debug, Clone)]
+pub struct PostgresOptions {
+    // Connection string such as `postgres://user@localhost/db`
+    pub url: String,
+    // Schema holding the tables, created when missing
+    pub schema: String,
+    // Rows written per statement
+    pub batch_size: usize,
+}
+
+impl PostgresOptions {
+    pub fn new(url: &str) -> Self {
+        PostgresOptions {
+            url: url.to_string(),
+            schema: "codegraph".to_string(),
+            batch_size: 1000,
+        }
+    }
+}
+
+fn postgres_error(error: tokio_postgres::Error) -> io::Error {
+    // The server's message says more than the generic "db error"
+    match error.as_db_error() {
+        Some(db_error) => io::Error::other(format!("Postgres: {}", db_error)),
+        None => io::Error::other(format!("Postgres: {}", error)),
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn metadata_json(metadata: &HashMap<String, String>) -> String {
+    serde_json::to_string(&metadata.iter().collect::<BTreeMap<_, _>>())
+        .unwrap_or_else(|_| "{}".to_string())
+}
+
+// Typed node attributes are stored with the other metadata, so queries on
+// the `metadata` column see every attribute
+fn flat_metadata_json(node: &CodeNode) -> String {
+    serde_json::to_string(&node.flat_metadata()).unwrap_or_else(|_| "{}".to_string())
+}
+
+// pgvector's text form, cast to `vector` in the statement
+fn vector_literal(vector: &[f32]) -> String {
+    let values: Vec<String> = vector.iter().map(f32::to_string).collect();
+    format!("[{}]", values.join(","))
+}
+
+async fn migrate(client: &mut Client, schema: &str, embeddings: bool) -> io::Result<()> {
+    let transaction = client.transaction().await.map_err(postgres_error)?;
+    transaction
+        .batch_execute(&format!(
+            "CREATE SCHEMA IF NOT EXISTS {schema};
+            CREATE TABLE IF NOT EXISTS {schema}.codegraph_migrations (
+                name TEXT PRIMARY KEY,
+                applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+            );
+            LOCK TABLE {schema}.codegraph_migrations;"
+        ))
+        .await
+        .map_err(postgres_error)?;
+    let applied: HashSet<String> = transaction
+        .query(
+            &format!("SELECT name FROM {schema}.codegraph_migrations"),
+            &[],
+        )
+        .await
+        .map_err(postgres_error)?
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+
+    let embedding_migrations = if embeddings {
+        EMBEDDING_MIGRATIONS
+    } else {
+        &[]
+    };
+    for (name, migration) in MIGRATIONS.iter().chain(embedding_migrations) {
+        if applied.contains(*name) {
+            continue;
+        }
+        info!("Applying schema migration {}", name);
+        transaction
+            .batch_execute(&migration.replace("{schema}", schema))
+            .await
+            .map_err(postgres_error)?;
+        transaction
+            .execute(
+                &format!("INSERT INTO {schema}.codegraph_migrations (name) VALUES ($1)"),
+                &[name],
+            )
+            .await
+            .map_err(postgres_error)?;
+    }
+
+    transaction.commit().await.map_err(postgres_error)
+}
+
+async fn write_nodes(
+    transaction: &Transaction<'_>,
+    schema: &str,
+    nodes: &[&CodeNode],
+) -> io::Result<()> {
+    let ids: Vec<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+    let types: Vec<String> = nodes
+        .iter()
+        .map(|node| format!("{:?}", node.node_type))
+        .collect();
+    let names: Vec<&str> = nodes.iter().map(|node| node.name.as_str()).collect();
+    let files: Vec<&str> = nodes.iter().map(|node| node.file_path.as_str()).collect();
+    let starts: Vec<i32> = nodes.iter().map(|node| node.line_range.0 as i32).collect();
+    let ends: Vec<i32> = nodes.iter().map(|node| node.line_range.1 as i32).collect();
+    let contents: Vec<&str> = nodes.iter().map(|node| node.content.as_str()).collect();
+    let summaries: Vec<Option<&str>> = nodes.iter().map(|node| node.summary.as_deref()).collect();
+    let repos: Vec<Option<&str>> = nodes.iter().map(|node| node.repo.as_deref()).collect();
+    let languages: Vec<Option<&str>> = nodes.iter().map(|node| node.language.as_deref()).collect();
+    let hashes: Vec<Option<&str>> = nodes
+        .iter()
+        .map(|node| node.content_hash.as_deref())
+        .collect();
+    let namespaces: Vec<Option<&str>> =
+        nodes.iter().map(|node| node.namespace.as_deref()).collect();
+    let metadata: Vec<String> = nodes.iter().map(|node| flat_metadata_json(node)).collect();
+
+    transaction
+        .execute(
+            &format!(
+                "INSERT INTO {schema}.nodes (id, node_type, name, file_path, start_line,
+                    end_line, content, summary, repo, metadata, language, content_hash,
+                    namespace)
+                SELECT id, node_type, name, file_path, start_line, end_line, content,
+                    summary, repo, metadata::jsonb, language, content_hash, namespace
+                FROM UNNEST($1::text[], $2::text[], $3::text[], $4::text[], $5::int4[],
+                    $6::int4[], $7::text[], $8::text[], $9::text[], $10::text[],
+                    $11::text[], $12::text[], $13::text[])
+                    AS t(id, node_type, name, file_path, start_line, end_line, content,
+                        summary, repo, metadata, language, content_hash, namespace)
+                ON CONFLICT (id) DO UPDATE SET
+                    node_type = EXCLUDED.node_type, name = EXCLUDED.name,
+                    file_path = EXCLUDED.file_path, start_line = EXCLUDED.start_line,
+                    end_line = EXCLUDED.end_line, content = EXCLUDED.content,
+                    summary = EXCLUDED.summary, repo = EXCLUDED.repo,
+                    metadata = EXCLUDED.metadata, language = EXCLUDED.language,
+                    content_hash = EXCLUDED.content_hash, namespace = EXCLUDED.namespace"
+            ),
+            &[
+                &ids,
+                &types,
+                &names,
+                &files,
+                &starts,
+                &ends,
+                &contents,
+                &summaries,
+                &repos,
+                &metadata,
+                &languages,
+                &hashes,
+                &namespaces,
+            ],
+        )
+        .await
+        .map_err(postgres_error)?;
+
+    Ok(())
+}
+
+async fn write_relationships(
+    transaction: &Transaction<'_>,
+    schema: &str,
+    relationships: &[&Relationship],
+) -> io::Result<()> {
+    let from: Vec<&str> = relationships.iter().map(|r| r.from_id.as_str()).collect();
+    let to: Vec<&str> = relationships.iter().map(|r| r.to_id.as_str()).collect();
+    let types: Vec<String> = relationships
+        .iter()
+        .map(|r| format!("{:?}", r.relationship_type))
+        .collect();
+    let provenance: Vec<&str> = relationships
+        .iter()
+        .map(|r| r.provenance.as_deref().unwrap_or_default())
+        .collect();
+    let metadata: Vec<String> = relationships
+        .iter()
+        .map(|r| metadata_json(&r.metadata))
+        .collect();
+
+    transaction
+        .execute(
+            &format!(
+                "INSERT INTO {schema}.relationships
+                SELECT from_id, to_id, relationship_type, provenance, metadata::jsonb
+                FROM UNNEST($1::text[], $2::text[], $3::text[], $4::text[], $5::text[])
+                    AS t(from_id, to_id, relationship_type, provenance, metadata)
+                ON CONFLICT (from_id, to_id, relationship_type, provenance)
+                    DO UPDATE SET metadata = EXCLUDED.metadata"
+            ),
+            &[&from, &to, &types, &provenance, &metadata],
+        )
+        .await
+        .map_err(postgres_error)?;
+
+    Ok(())
+}
+
+async fn write_graph(graph: &CodeGraph, options: &PostgresOptions) -> io::Result<()> {
+    let (mut client, connection) = tokio_postgres::connect(&options.url, NoTls)
+        .await
+        .map_err(postgres_error)?;
+    let connection = tokio::spawn(connection);
+
+    let schema = quote_identifier(&options.schema);
+    let mut nodes: Vec<&CodeNode> = graph.all_nodes().collect();
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+    let embedded: Vec<(&str, String)> = nodes
+        .iter()
+        .filter_map(|node| Some((node.id.as_str(), vector_literal(graph.embedding(&node.id)?))))
+        .collect();
+    migrate(&mut client, &schema, !embedded.is_empty()).await?;
+
+    // Repeated edges of one pass collapse onto one row
+    let mut relationships: BTreeMap<(String, &str, &str, &str), &Relationship> = BTreeMap::new();
+    for rel in graph.all_relationships() {
+        relationships.insert(
+            (
+                format!("{:?}", rel.relationship_type),
+                rel.from_id.as_str(),
+                rel.to_id.as_str(),
+                rel.provenance.as_deref().unwrap_or_default(),
+            ),
+            rel,
+        );
+    }
+    let relationships: Vec<&Relationship> = relationships.into_values().collect();
+    let batch_size = options.batch_size.max(1);
+
+    // One transaction, so readers never see half a graph
+    let transaction = client.transaction().await.map_err(postgres_error)?;
+    let ids: Vec<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+    // Relationships are replaced wholesale; they have no identity of their own
+    transaction
+        .execute(&format!("DELETE FROM {schema}.relationships"), &[])
+        .await
+        .map_err(postgres_error)?;
+    transaction
+        .execute(
+            &format!("DELETE FROM {schema}.nodes WHERE NOT (id = ANY($1))"),
+            &[&ids],
+        )
+        .await
+        .map_err(postgres_error)?;
+
+    for batch in nodes.chunks(batch_size) {
+        write_nodes(&transaction, &schema, batch).await?;
+    }
+    debug!("Wrote {} nodes", nodes.len());
+    for batch in relationships.chunks(batch_size) {
+        write_relationships(&transaction, &schema, batch).await?;
+    }
+    debug!("Wrote {} relationships", relationships.len());
+
+    for batch in embedded.chunks(batch_size) {
+        let ids: Vec<&str> = batch.iter().map(|(id, _)| *id).collect();
+        let vectors: Vec<&str> = batch.iter().map(|(_, vector)| vector.as_str()).collect();
+        transaction
+            .execute(
+                &format!(
+                    "INSERT INTO {schema}.embeddings
+                    SELECT node_id, embedding::vector
+                    FROM UNNEST($1::text[], $2::text[]) AS t(node_id, embedding)
+                    ON CONFLICT (node_id) DO UPDATE SET embedding = EXCLUDED.embedding"
+                ),
+                &[&ids, &vectors],
+            )
+            .await
+            .map_err(postgres_error)?;
+    }
+    debug!("Wrote {} embeddings", embedded.len());
+
+    transaction.commit().await.map_err(postgres_error)?;
+    drop(client);
+    let _ = connection.await;
+
+    Ok(())
+}
+
+// Write the graph into Postgres tables `nodes`, `relationships` and, with
+// pgvector, `embeddings`, creating or upgrading the schema first. Rows are
+// upserted on node IDs, relationships are replaced, and rows of nodes no
+// longer in the graph are deleted, all in one transaction.
+pub fn export_graph_to_postgres(graph: &CodeGraph, options: &PostgresOptions) -> io::Result<()> {
+    info!(
+        "Writing {} nodes and {} relationships to Postgres schema {}",
+        graph.node_count(),
+        graph.relationship_count(),
+        options.schema
+    );
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(write_graph(graph, options))?;
+
+    info!("Graph written to Postgres");
+    Ok(())
+}
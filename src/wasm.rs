@@ -0,0 +1,28 @@
+// JavaScript bindings for extracting a single file in the browser. Build with
+// `make wasm`; graphs and capabilities are returned as JSON strings.
+use crate::indexing::{IndexConfig, language_capabilities};
+use std::path::PathBuf;
+use wasm_bindgen::prelude::*;
+
+// Index one file and return its graph; the language is taken from the
+// extension of `file_name`
+#[wasm_bindgen(js_name = extractFile)]
+pub fn extract_file(file_name: &str, content: &str) -> Result<String, JsError> {
+    let config = IndexConfig::new().num_threads(1);
+    let graph = crate::process_files(
+        vec![(PathBuf::from(file_name), content.to_string())],
+        &config,
+    )?;
+    Ok(serde_json::to_string(&graph)?)
+}
+
+// Supported languages and what is extracted for each
+#[wasm_bindgen(js_name = languages)]
+pub fn languages() -> Result<String, JsError> {
+    Ok(serde_json::to_string(&language_capabilities())?)
+}
+
+#[wasm_bindgen]
+pub fn version() -> String {
+    crate::version().to_string()
+}
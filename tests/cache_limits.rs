@@ -0,0 +1,26 @@
+use relik_codegraph::indexing::config::IndexConfig;
+use relik_codegraph::process_codebase_with_config;
+use std::fs;
+
+#[test]
+fn lowered_size_limit_skips_cached_files() {
+    let dir = std::env::temp_dir().join(format!("codegraph-cache-limits-{}", std::process::id()));
+    let (root, cache) = (dir.join("src"), dir.join("cache"));
+    fs::create_dir_all(&root).expect("failed to create codebase");
+    fs::write(
+        root.join("shapes.py"),
+        "def area(radius):\n    return radius * radius\n",
+    )
+    .expect("failed to write codebase");
+
+    let config = IndexConfig::new().num_threads(1).cache_dir(&cache);
+    let first = process_codebase_with_config(&root, &config).expect("first run failed");
+    assert!(!first.find_nodes_by_name("area").is_empty());
+
+    // The file is in the cache now, but no longer within the limit
+    let config = config.max_file_size(Some(10));
+    let second = process_codebase_with_config(&root, &config).expect("second run failed");
+    assert!(second.find_nodes_by_name("area").is_empty());
+
+    fs::remove_dir_all(&dir).expect("failed to remove codebase");
+}
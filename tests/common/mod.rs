@@ -0,0 +1,13 @@
+use relik_codegraph::code_graph::CodeGraph;
+use relik_codegraph::indexing::IndexConfig;
+use relik_codegraph::process_files;
+use std::path::PathBuf;
+
+// Index in-memory files on one thread
+pub fn index(files: &[(&str, &str)]) -> CodeGraph {
+    let files = files
+        .iter()
+        .map(|(path, content)| (PathBuf::from(path), content.to_string()))
+        .collect();
+    process_files(files, &IndexConfig::new().num_threads(1)).expect("indexing failed")
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use common::index;
+use relik_codegraph::analysis::{EntryPointRules, find_dead_code};
+
+#[test]
+fn callbacks_inside_a_reachable_function_are_reachable() {
+    let mut graph = index(&[(
+        "/src/api.ts",
+        "export function fetchJson(url: string) {\n  return fetch(url).then(r => r.json());\n}\n\n\
+         function unused() {\n  return [1, 2].map(x => x * 2);\n}\n",
+    )]);
+    let closures: Vec<String> = graph
+        .all_nodes()
+        .filter(|node| node.metadata.contains_key("closure"))
+        .map(|node| node.id.clone())
+        .collect();
+    assert_eq!(closures.len(), 2, "{:?}", closures);
+
+    let report = find_dead_code(&mut graph, &EntryPointRules::default());
+    let mut dead: Vec<(String, usize)> = report
+        .files
+        .values()
+        .flatten()
+        .map(|symbol| (symbol.name.clone(), symbol.line_range.0))
+        .collect();
+    dead.sort();
+    assert_eq!(dead.len(), 2, "{:?}", dead);
+    assert!(dead.iter().all(|(_, line)| *line >= 5), "{:?}", dead);
+}
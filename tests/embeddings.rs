@@ -0,0 +1,46 @@
+use relik_codegraph::code_graph::{CodeGraph, CodeNode, Embedder, HashingEmbedder, NodeType};
+
+fn function(content: &str) -> CodeNode {
+    CodeNode::new(
+        "parse".to_string(),
+        NodeType::Function,
+        "parse_config".to_string(),
+        "config.py".to_string(),
+        (1, 2),
+        content.to_string(),
+    )
+}
+
+#[test]
+fn hashing_embedder_is_stable() {
+    let embedder = HashingEmbedder { dimensions: 8 };
+    let vector = embedder.embed("parseConfig").expect("embedding failed");
+    // Fixed buckets and signs of the FNV-1a hashes of `parse` and `config`
+    let buckets: Vec<(usize, f32)> = vector
+        .iter()
+        .enumerate()
+        .filter(|(_, x)| **x != 0.0)
+        .map(|(i, x)| (i, x.signum()))
+        .collect();
+    assert_eq!(
+        vector,
+        embedder.embed("parse_config").expect("embedding failed")
+    );
+    assert_eq!(buckets, vec![(4, 1.0), (7, 1.0)]);
+}
+
+#[test]
+fn replaced_nodes_lose_their_embedding() {
+    let mut graph = CodeGraph::new();
+    graph.add_node(function("def parse_config(path):\n    return load(path)\n"));
+    assert_eq!(
+        graph.add_embeddings(&HashingEmbedder::default()).ok(),
+        Some(1)
+    );
+    assert!(graph.embedding("parse").is_some());
+
+    graph.add_node(function(
+        "def parse_config(text):\n    return decode(text)\n",
+    ));
+    assert!(graph.embedding("parse").is_none());
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::index;
+use relik_codegraph::code_graph::{CodeGraph, RelationshipType};
+
+const SCHEMA: &str = "type Query {\n  user(id: ID!): User\n  users: [User]\n}\n\n\
+                      type Mutation {\n  users: [User]\n}\n";
+
+// (resolver file:line, field) of every resolver link
+fn resolver_links(graph: &CodeGraph) -> Vec<(String, String)> {
+    let mut links: Vec<(String, String)> = graph
+        .all_nodes()
+        .flat_map(|node| graph.outgoing_relationships(&node.id))
+        .filter(|rel| rel.relationship_type == RelationshipType::Implements)
+        .filter_map(|rel| {
+            let resolver = graph.get_node(&rel.from_id)?;
+            let field = graph.get_node(&rel.to_id)?;
+            Some((
+                format!("{}:{}", resolver.file_path, resolver.line_range.0),
+                field.name.clone(),
+            ))
+        })
+        .collect();
+    links.sort();
+    links
+}
+
+#[test]
+fn resolver_maps_link_by_type_and_field() {
+    let graph = index(&[
+        ("/api/schema.graphql", SCHEMA),
+        (
+            "/api/resolvers.js",
+            "import { listUsers } from './users';\n\n\
+             export const resolvers = {\n  Query: {\n    \
+             user: (parent, args) => findUser(args.id),\n    users: listUsers,\n  },\n};\n\n\
+             function findUser(id) {\n  return id;\n}\n",
+        ),
+        (
+            "/api/users.js",
+            "export function listUsers() {\n  return [];\n}\n",
+        ),
+    ]);
+
+    assert_eq!(
+        resolver_links(&graph),
+        vec![
+            ("/api/resolvers.js:5".to_string(), "Query::user".to_string()),
+            ("/api/users.js:1".to_string(), "Query::users".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn same_named_functions_are_not_resolvers() {
+    let graph = index(&[
+        ("/api/schema.graphql", SCHEMA),
+        (
+            "/api/report.js",
+            "export function users() {\n  return 'unrelated';\n}\n\n\
+             export function user() {\n  return 'unrelated';\n}\n",
+        ),
+        (
+            "/api/handlers.py",
+            "def resolve_users(info):\n    return []\n",
+        ),
+    ]);
+
+    assert_eq!(resolver_links(&graph), Vec::new());
+}
+
+#[test]
+fn graphene_methods_link_to_their_type_only() {
+    let graph = index(&[
+        ("/api/schema.graphql", SCHEMA),
+        (
+            "/api/schema.py",
+            "import graphene\n\n\
+             class Mutation(graphene.ObjectType):\n    \
+             def resolve_users(self, info):\n        return []\n",
+        ),
+    ]);
+
+    assert_eq!(
+        resolver_links(&graph),
+        vec![(
+            "/api/schema.py:4".to_string(),
+            "Mutation::users".to_string()
+        )]
+    );
+}
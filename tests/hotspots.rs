@@ -0,0 +1,59 @@
+use relik_codegraph::analysis::{pagerank, top_n_by_centrality};
+use relik_codegraph::code_graph::{CodeGraph, NodeType};
+use relik_codegraph::indexing::IndexConfig;
+use relik_codegraph::process_files;
+use std::path::PathBuf;
+
+fn index(config: IndexConfig) -> CodeGraph {
+    let files = vec![
+        (
+            PathBuf::from("/src/app/main.py"),
+            "from lib.util import helper\n\ndef main():\n    helper()\n    helper()\n".to_string(),
+        ),
+        (
+            PathBuf::from("/src/lib/util.py"),
+            "def helper():\n    return 1\n".to_string(),
+        ),
+    ];
+    process_files(files, &config.num_threads(1).directory_hierarchy(true)).expect("indexing failed")
+}
+
+fn ranked_types(graph: &CodeGraph) -> Vec<NodeType> {
+    top_n_by_centrality(graph, 10)
+        .into_iter()
+        .map(|(node, _)| node.node_type.clone())
+        .collect()
+}
+
+#[test]
+fn hotspots_rank_only_symbols_of_a_hierarchy_graph() {
+    for graph in [
+        index(IndexConfig::new().metrics(true)),
+        index(IndexConfig::new()),
+    ] {
+        let ranked = ranked_types(&graph);
+        assert!(!ranked.is_empty());
+        assert!(
+            ranked
+                .iter()
+                .all(|node_type| !matches!(node_type, NodeType::File | NodeType::Directory)),
+            "{:?}",
+            ranked
+        );
+        let (top, _) = top_n_by_centrality(&graph, 1)[0];
+        assert_eq!(top.name, "helper");
+    }
+}
+
+#[test]
+fn pagerank_ignores_rolled_up_calls() {
+    let graph = index(IndexConfig::new());
+    let scores = pagerank(&graph);
+    let baseline = scores.values().copied().fold(f64::INFINITY, f64::min);
+    for node in graph
+        .all_nodes()
+        .filter(|node| matches!(node.node_type, NodeType::File | NodeType::Directory))
+    {
+        assert!((scores[&node.id] - baseline).abs() < 1e-9, "{}", node.id);
+    }
+}
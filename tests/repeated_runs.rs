@@ -0,0 +1,52 @@
+use relik_codegraph::code_graph::CodeGraph;
+use relik_codegraph::process_codebase;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+
+fn codebase() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("codegraph-repeated-{}", std::process::id()));
+    fs::create_dir_all(root.join("pkg")).expect("failed to create codebase");
+    fs::write(
+        root.join("pkg/shapes.py"),
+        "class Circle:\n    def area(self):\n        return scale(3)\n\n\
+         def scale(factor):\n    return factor * 2\n",
+    )
+    .expect("failed to write codebase");
+    fs::write(
+        root.join("main.rs"),
+        "fn main() {\n    run();\n}\n\nfn run() {}\n",
+    )
+    .expect("failed to write codebase");
+    root
+}
+
+fn summary(graph: &CodeGraph) -> (Vec<String>, usize) {
+    let mut ids: Vec<String> = graph.all_nodes().map(|node| node.id.clone()).collect();
+    ids.sort();
+    (ids, graph.relationship_count())
+}
+
+#[test]
+fn repeated_and_concurrent_runs() {
+    let root = codebase();
+
+    // Each run sets up its own thread pool with its own size
+    let first = process_codebase(&root, 1).expect("first run failed");
+    let second = process_codebase(&root, 2).expect("second run failed");
+    assert!(first.node_count() > 0);
+    assert_eq!(summary(&first), summary(&second));
+
+    let runs: Vec<_> = (1..=3)
+        .map(|threads| {
+            let root = root.clone();
+            thread::spawn(move || process_codebase(&root, threads).expect("concurrent run failed"))
+        })
+        .collect();
+    for run in runs {
+        let graph = run.join().expect("concurrent run panicked");
+        assert_eq!(summary(&graph), summary(&first));
+    }
+
+    fs::remove_dir_all(&root).expect("failed to remove codebase");
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::index;
+use relik_codegraph::code_graph::{CodeGraph, RelationshipType};
+
+// (importer, imported) names of every Imports edge
+fn imports(graph: &CodeGraph) -> Vec<(String, String)> {
+    let mut imports: Vec<(String, String)> = graph
+        .all_nodes()
+        .flat_map(|node| graph.outgoing_relationships(&node.id))
+        .filter(|rel| rel.relationship_type == RelationshipType::Imports)
+        .filter_map(|rel| {
+            let from = graph.get_node(&rel.from_id)?;
+            let to = graph.get_node(&rel.to_id)?;
+            Some((from.name.clone(), to.name.clone()))
+        })
+        .collect();
+    imports.sort();
+    imports.dedup();
+    imports
+}
+
+#[test]
+fn imports_resolve_under_a_dot_prefixed_root() {
+    let graph = index(&[
+        (
+            "./ts/tsconfig.json",
+            r#"{ "compilerOptions": { "baseUrl": ".", "paths": { "@app/*": ["src/*"] } } }"#,
+        ),
+        (
+            "./ts/src/utils/http.ts",
+            "export function get(url: string) {\n  return url;\n}\n",
+        ),
+        ("./ts/src/utils/index.ts", "export { get } from './http';\n"),
+        (
+            "./ts/src/format.ts",
+            "export function format(text: string) {\n  return text;\n}\n",
+        ),
+        (
+            "./ts/src/main.ts",
+            "import { get } from '@app/utils';\nimport { format } from './format';\n\n\
+             export function main() {\n  return format(get('/'));\n}\n",
+        ),
+    ]);
+
+    assert_eq!(
+        imports(&graph),
+        vec![
+            ("main".to_string(), "format".to_string()),
+            ("main".to_string(), "get".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::index;
+use relik_codegraph::utils::io::{Compression, ExportFormat, export_graph_shards};
+use std::fs;
+
+#[test]
+fn directories_named_like_export_files_get_their_own_shard() {
+    let graph = index(&[
+        ("/repo/shards/a.py", "def a():\n    return 1\n"),
+        ("/repo/_index/b.py", "def b():\n    return 1\n"),
+        ("/repo/c.cross/c.py", "def c():\n    return 1\n"),
+        ("/repo/c/d.py", "def d():\n    return 1\n"),
+    ]);
+    let output = std::env::temp_dir().join(format!("codegraph-shards-{}", std::process::id()));
+    export_graph_shards(&graph, &output, ExportFormat::Json, Compression::None)
+        .expect("export failed");
+
+    let mut files: Vec<String> = fs::read_dir(&output)
+        .expect("failed to list shards")
+        .map(|entry| entry.expect("failed to list shards").file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(
+        files,
+        vec![
+            "__index.json",
+            "_index.json",
+            "c.cross_.json",
+            "c.json",
+            "shards.json"
+        ]
+    );
+
+    fs::remove_dir_all(&output).expect("failed to remove shards");
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::index;
+use relik_codegraph::code_graph::{CodeGraph, RelationshipType};
+
+fn node_content<'a>(graph: &'a CodeGraph, name: &str) -> &'a str {
+    let nodes = graph.find_nodes_by_name(name);
+    assert_eq!(nodes.len(), 1, "expected one node named {}", name);
+    &nodes[0].content
+}
+
+#[test]
+fn cjk_identifiers_in_python() {
+    let graph = index(&[(
+        "/src/计算.py",
+        "# 🚀 计算模块\n\
+         def 计算总和(数字):\n    \"\"\"合计 🎉\"\"\"\n    return sum(数字)\n\n\
+         def 報告():\n    return 计算总和([1, 2])\n",
+    )]);
+
+    assert!(node_content(&graph, "计算总和").contains("合计 🎉"));
+    assert!(node_content(&graph, "報告").ends_with("计算总和([1, 2])"));
+
+    let caller = graph.find_nodes_by_name("報告")[0];
+    let called: Vec<&str> = graph
+        .find_called_functions(&caller.id)
+        .iter()
+        .map(|node| node.name.as_str())
+        .collect();
+    assert_eq!(called, vec!["计算总和"]);
+}
+
+#[test]
+fn emoji_before_rust_items() {
+    let graph = index(&[(
+        "/src/lib.rs",
+        "const GREETING: &str = \"👋🏽 こんにちは\";\n\n\
+         pub struct Grüße {\n    text: String,\n}\n\n\
+         pub fn 挨拶する() -> &'static str {\n    \"🙂\"\n}\n",
+    )]);
+
+    assert!(node_content(&graph, "Grüße").contains("text: String"));
+    assert!(node_content(&graph, "挨拶する").contains("\"🙂\""));
+}
+
+#[test]
+fn multibyte_call_expressions() {
+    let graph = index(&[(
+        "/src/app.js",
+        "class Ünïcode {\n  greet() {\n    return this.format('✨ 你好 ✨');\n  }\n\n\
+         format(text) {\n    return text;\n  }\n}\n",
+    )]);
+
+    let calls: Vec<&str> = graph
+        .all_relationships()
+        .filter(|rel| rel.relationship_type == RelationshipType::Calls)
+        .filter_map(|rel| rel.metadata.get("call_expression"))
+        .map(String::as_str)
+        .collect();
+    assert_eq!(calls, vec!["this.format('✨ 你好 ✨')"]);
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::index;
+use relik_codegraph::code_graph::{
+    CodeGraph, Direction, NodeType, RelationshipType, ViewDefinition,
+};
+
+fn graph() -> CodeGraph {
+    index(&[
+        (
+            "billing/charge.py",
+            "def charge(amount):\n    return round_cents(amount)\n\n\
+             def round_cents(amount):\n    return amount\n",
+        ),
+        ("web/views.py", "def checkout():\n    return 1\n"),
+    ])
+}
+
+fn function_names(graph: &CodeGraph) -> Vec<String> {
+    let mut names: Vec<String> = graph
+        .all_nodes()
+        .filter(|node| node.node_type == NodeType::Function)
+        .map(|node| node.name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn views_select_seeds_with_queries_and_follow_relationships() {
+    let graph = graph();
+    let view = ViewDefinition {
+        query: Some("type:Function name:charge".to_string()),
+        relationship_types: vec![RelationshipType::Calls],
+        direction: Direction::Outgoing,
+        depth: 1,
+        ..Default::default()
+    };
+    let selected = view.materialize(&graph).expect("view failed");
+    assert_eq!(function_names(&selected), vec!["charge", "round_cents"]);
+
+    // Shorthands narrow what the query returns
+    let view = ViewDefinition {
+        query: Some("type:Function".to_string()),
+        path_prefix: Some("web/".to_string()),
+        ..Default::default()
+    };
+    let selected = view.materialize(&graph).expect("view failed");
+    assert_eq!(function_names(&selected), vec!["checkout"]);
+
+    let view = ViewDefinition {
+        query: Some("type:Function ->".to_string()),
+        ..Default::default()
+    };
+    assert!(view.materialize(&graph).is_err());
+}
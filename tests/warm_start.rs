@@ -0,0 +1,94 @@
+use relik_codegraph::code_graph::CodeGraph;
+use relik_codegraph::indexing::IndexConfig;
+use relik_codegraph::{process_codebase_with_config, warm_start_codebase};
+use std::fs;
+use std::path::PathBuf;
+
+// Sorted node IDs with names, and (from, to, type) of every relationship
+fn summary(graph: &CodeGraph) -> (Vec<(String, String)>, Vec<(String, String, String)>) {
+    let mut nodes: Vec<(String, String)> = graph
+        .all_nodes()
+        .map(|node| (node.id.clone(), node.name.clone()))
+        .collect();
+    nodes.sort();
+    let mut relationships: Vec<(String, String, String)> = graph
+        .all_relationships()
+        .map(|rel| {
+            (
+                rel.from_id.clone(),
+                rel.to_id.clone(),
+                format!("{:?}", rel.relationship_type),
+            )
+        })
+        .collect();
+    relationships.sort();
+    (nodes, relationships)
+}
+
+fn codebase(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("codegraph-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&root).expect("failed to create codebase");
+    root
+}
+
+#[test]
+fn warm_start_of_an_unchanged_tree_matches_a_fresh_run() {
+    let root = codebase("warm-start");
+    fs::write(
+        root.join("Repo.java"),
+        "public class Repo {\n    public String find(String name) {\n        return name;\n    }\n\n\
+         \x20   public String find(String name, int limit) {\n        return name;\n    }\n}\n",
+    )
+    .expect("failed to write codebase");
+    fs::write(
+        root.join("Main.java"),
+        "public class Main {\n    public String hello(Repo repo) {\n        return repo.find(\"a\", 1);\n    }\n}\n",
+    )
+    .expect("failed to write codebase");
+
+    let config = IndexConfig::new().num_threads(1);
+    let fresh = process_codebase_with_config(&root, &config).expect("fresh run failed");
+    let overloads = fresh.find_nodes_by_name("Repo::find(String, int)");
+    assert_eq!(overloads.len(), 1);
+
+    let warm = warm_start_codebase(&root, &fresh, &config).expect("warm run failed");
+    let hello = &warm.find_nodes_by_name("Main::hello")[0].id;
+    assert!(
+        warm.outgoing_relationships(hello)
+            .iter()
+            .any(|rel| rel.to_id == overloads[0].id)
+    );
+    assert_eq!(summary(&warm), summary(&fresh));
+
+    fs::remove_dir_all(&root).expect("failed to remove codebase");
+}
+
+#[test]
+fn reused_nodes_drop_metadata_of_later_passes() {
+    let root = codebase("warm-start-metadata");
+    fs::write(
+        root.join("shapes.py"),
+        "def area(radius):\n    return scale(radius)\n\n\
+         def scale(factor):\n    return factor * 2\n",
+    )
+    .expect("failed to write codebase");
+
+    let config = IndexConfig::new().num_threads(1);
+    let previous = process_codebase_with_config(&root, &config.clone().metrics(true))
+        .expect("fresh run failed");
+    assert!(
+        previous
+            .all_nodes()
+            .any(|node| node.metadata.contains_key("fan_in"))
+    );
+
+    let warm = warm_start_codebase(&root, &previous, &config).expect("warm run failed");
+    assert_eq!(warm.find_nodes_by_name("scale").len(), 1);
+    assert!(
+        !warm
+            .all_nodes()
+            .any(|node| node.metadata.contains_key("fan_in"))
+    );
+
+    fs::remove_dir_all(&root).expect("failed to remove codebase");
+}
@@ -0,0 +1,28 @@
+// Run with `make wasm-test`; extraction must work without threads
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use relik_codegraph::wasm::{extract_file, languages};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn extracts_a_file_on_a_single_thread() {
+    let json = extract_file(
+        "lib.rs",
+        "pub fn parse_input(text: &str) -> usize {\n    text.len()\n}\n",
+    )
+    .unwrap_or_else(|_| panic!("extraction failed"));
+    let graph: serde_json::Value = serde_json::from_str(&json).expect("graph is JSON");
+    let names: Vec<&str> = graph["nodes"]
+        .as_object()
+        .expect("nodes by ID")
+        .values()
+        .filter_map(|node| node["name"].as_str())
+        .collect();
+    assert!(names.contains(&"parse_input"), "{names:?}");
+}
+
+#[wasm_bindgen_test]
+fn lists_languages() {
+    let json = languages().unwrap_or_else(|_| panic!("no capabilities"));
+    assert!(json.contains("\"rust\""));
+}